@@ -0,0 +1,109 @@
+//! Plain-text copies of an action for pasting into bug reports
+//! ANSI escape codes are stripped; parameters are sorted so repeated copies
+//! of the same action compare equal
+
+use crate::ansi_parser::strip_ansi;
+use crate::models::{ActionEntry, SerializedError};
+use crate::report::error_text;
+use std::collections::BTreeMap;
+
+/// Error message followed by its stack trace
+pub fn error_with_stack(error: &SerializedError) -> String {
+    error_text(error.message.as_deref(), error.stack.as_deref())
+}
+
+/// Parameters as pretty-printed JSON with sorted keys
+pub fn params_json(action: &ActionEntry) -> String {
+    let params: BTreeMap<_, _> = action.params.iter().collect();
+    serde_json::to_string_pretty(&params).unwrap_or_default()
+}
+
+/// One line such as `locator.click (#pay) — failed after 1200ms: Timeout 1000ms exceeded.`
+pub fn summary_line(action: &ActionEntry) -> String {
+    let label = action.summary_label();
+    let Some(duration) = action.duration_ms() else {
+        return format!("{} — did not finish", label);
+    };
+
+    match &action.error {
+        Some(error) => {
+            let message = strip_ansi(error.message.as_deref().unwrap_or_default());
+            match message.lines().map(str::trim).find(|line| !line.is_empty()) {
+                Some(line) => format!("{} — failed after {:.0}ms: {}", label, duration, line),
+                None => format!("{} — failed after {:.0}ms", label, duration),
+            }
+        }
+        None => format!("{} — passed in {:.0}ms", label, duration),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn action(error: Option<&str>, end_time: f64) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: "call@1".to_string(),
+            start_time: 100.0,
+            end_time,
+            title: Some("locator.click".to_string()),
+            class: Some("Frame".to_string()),
+            method: Some("click".to_string()),
+            params: HashMap::from([
+                ("selector".to_string(), json!("#pay")),
+                ("force".to_string(), json!(false)),
+            ]),
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: error.map(|message| SerializedError {
+                message: Some(message.to_string()),
+                stack: Some("    at pay.spec.ts:12:5".to_string()),
+            }),
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: Some("#pay".to_string()),
+            stack: vec![],
+        }
+    }
+
+    #[test]
+    fn test_summary_line() {
+        assert_eq!(
+            summary_line(&action(None, 130.0)),
+            "locator.click (#pay) — passed in 30ms"
+        );
+        assert_eq!(
+            summary_line(&action(
+                Some("\u{1b}[31mTimeout 1000ms exceeded.\u{1b}[39m\nCall log:"),
+                1300.0
+            )),
+            "locator.click (#pay) — failed after 1200ms: Timeout 1000ms exceeded."
+        );
+        assert_eq!(
+            summary_line(&action(None, 0.0)),
+            "locator.click (#pay) — did not finish"
+        );
+    }
+
+    #[test]
+    fn test_params_json_sorted() {
+        assert_eq!(
+            params_json(&action(None, 130.0)),
+            "{\n  \"force\": false,\n  \"selector\": \"#pay\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_error_with_stack() {
+        let action = action(Some("\u{1b}[31mTimeout\u{1b}[39m"), 1300.0);
+        assert_eq!(
+            error_with_stack(action.error.as_ref().unwrap()),
+            "Timeout\n\nStack trace:\n    at pay.spec.ts:12:5"
+        );
+    }
+}
@@ -0,0 +1,653 @@
+//! Analysis of test results across several loaded runs
+//! Retries of a test are stored as sibling folders suffixed `-retryN`; they
+//! are folded back into one test so each run contributes a list of attempts
+
+use crate::ansi_parser::strip_ansi;
+use crate::models::{TestCase, TestCaseCollection, TestStatus};
+use std::collections::HashMap;
+
+/// Flakiness of one test across the loaded runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFlakiness {
+    /// Test id without the retry suffix
+    pub test_id: String,
+    pub name: String,
+    pub flakiness: FlakinessScore,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FlakinessScore {
+    /// Number of runs with a pass or fail outcome
+    pub runs: usize,
+    /// Share of consecutive runs whose final outcome differs
+    pub alternation_rate: f64,
+    /// Share of runs that failed first and passed on a retry
+    pub pass_after_retry_rate: f64,
+    /// Stronger of the two signals, from 0 (stable) to 1 (always flaky)
+    pub score: f64,
+}
+
+/// All attempts of one test within a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryGroup {
+    /// Test id without the retry suffix
+    pub test_id: String,
+    /// Attempts ordered by retry number, never empty
+    pub attempts: Vec<TestCase>,
+}
+
+impl RetryGroup {
+    /// The last attempt decides the outcome of the test
+    pub fn final_attempt(&self) -> &TestCase {
+        self.attempts
+            .last()
+            .expect("retry group has at least one attempt")
+    }
+
+    /// The test failed at first but passed on a retry
+    pub fn passed_on_retry(&self) -> bool {
+        self.final_attempt().status == TestStatus::Passed
+            && self
+                .attempts
+                .iter()
+                .any(|attempt| attempt.status == TestStatus::Failed)
+    }
+}
+
+/// Fold `-retryN` test cases into one group per test, in order of first appearance
+pub fn group_retries(test_cases: &[TestCase]) -> Vec<RetryGroup> {
+    let mut groups: Vec<RetryGroup> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+
+    for test_case in test_cases {
+        let (base_id, _) = base_test_id(&test_case.id);
+        match index.get(base_id) {
+            Some(&position) => groups[position].attempts.push(test_case.clone()),
+            None => {
+                index.insert(base_id, groups.len());
+                groups.push(RetryGroup {
+                    test_id: base_id.to_string(),
+                    attempts: vec![test_case.clone()],
+                });
+            }
+        }
+    }
+
+    for group in &mut groups {
+        group
+            .attempts
+            .sort_by_key(|attempt| base_test_id(&attempt.id).1);
+    }
+
+    groups
+}
+
+/// Split a test case id into the base test id and its retry number
+pub fn base_test_id(id: &str) -> (&str, usize) {
+    if let Some(index) = id.rfind("-retry") {
+        if let Ok(retry) = id[index + "-retry".len()..].parse() {
+            return (&id[..index], retry);
+        }
+    }
+
+    (id, 0)
+}
+
+/// Score a test from the attempts of each run, in run order
+/// Skipped and pending attempts carry no signal and are ignored
+pub fn flakiness_score(runs: &[Vec<TestStatus>]) -> FlakinessScore {
+    let attempts: Vec<Vec<&TestStatus>> = runs
+        .iter()
+        .map(|attempts| {
+            attempts
+                .iter()
+                .filter(|status| matches!(status, TestStatus::Passed | TestStatus::Failed))
+                .collect::<Vec<_>>()
+        })
+        .filter(|attempts| !attempts.is_empty())
+        .collect();
+
+    let run_count = attempts.len();
+    if run_count == 0 {
+        return FlakinessScore::default();
+    }
+
+    let finals: Vec<&TestStatus> = attempts
+        .iter()
+        .filter_map(|attempts| attempts.last().copied())
+        .collect();
+
+    let alternations = finals.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    let alternation_rate = if run_count > 1 {
+        alternations as f64 / (run_count - 1) as f64
+    } else {
+        0.0
+    };
+
+    let passed_after_retry = attempts
+        .iter()
+        .filter(|attempts| {
+            attempts.first() == Some(&&TestStatus::Failed)
+                && attempts.last() == Some(&&TestStatus::Passed)
+        })
+        .count();
+    let pass_after_retry_rate = passed_after_retry as f64 / run_count as f64;
+
+    FlakinessScore {
+        runs: run_count,
+        alternation_rate,
+        pass_after_retry_rate,
+        score: alternation_rate.max(pass_after_retry_rate),
+    }
+}
+
+/// Compute the flakiness of every test across runs, most flaky first
+pub fn compute_flakiness(runs: &[TestCaseCollection]) -> Vec<TestFlakiness> {
+    // base id -> (name, attempts per run)
+    let mut tests: HashMap<String, (String, Vec<Vec<TestStatus>>)> = HashMap::new();
+
+    for (run_index, run) in runs.iter().enumerate() {
+        let mut attempts: Vec<_> = run
+            .test_cases
+            .iter()
+            .map(|test_case| {
+                let (base_id, retry) = base_test_id(&test_case.id);
+                (base_id, retry, test_case)
+            })
+            .collect();
+        attempts.sort_by_key(|(base_id, retry, _)| (*base_id, *retry));
+
+        for (base_id, retry, test_case) in attempts {
+            let (name, test_runs) = tests
+                .entry(base_id.to_string())
+                .or_insert_with(|| (test_case.name.clone(), Vec::new()));
+
+            if retry == 0 {
+                *name = test_case.name.clone();
+            }
+
+            test_runs.resize_with(run_index + 1, Vec::new);
+            test_runs[run_index].push(test_case.status.clone());
+        }
+    }
+
+    let mut results: Vec<TestFlakiness> = tests
+        .into_iter()
+        .map(|(test_id, (name, test_runs))| TestFlakiness {
+            test_id,
+            name,
+            flakiness: flakiness_score(&test_runs),
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.flakiness
+            .score
+            .total_cmp(&a.flakiness.score)
+            .then_with(|| a.test_id.cmp(&b.test_id))
+    });
+
+    results
+}
+
+/// Order of the tests in the list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestSortOrder {
+    /// Order of the report, grouped by suite
+    Default,
+    Name,
+    /// Slowest first
+    Duration,
+    /// Failures first
+    Status,
+    /// Most flaky first, needs several runs
+    Flakiness,
+}
+
+impl TestSortOrder {
+    pub const ALL: [TestSortOrder; 5] = [
+        TestSortOrder::Default,
+        TestSortOrder::Name,
+        TestSortOrder::Duration,
+        TestSortOrder::Status,
+        TestSortOrder::Flakiness,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TestSortOrder::Default => "Default",
+            TestSortOrder::Name => "Name",
+            TestSortOrder::Duration => "Slowest first",
+            TestSortOrder::Status => "Failures first",
+            TestSortOrder::Flakiness => "Flakiness",
+        }
+    }
+}
+
+/// Sort retry groups by their final attempt; ties keep the report order
+/// `flakiness` looks up the score of a base test id
+pub fn sort_groups(
+    groups: &mut [&RetryGroup],
+    order: TestSortOrder,
+    flakiness: impl Fn(&str) -> Option<f64>,
+) {
+    match order {
+        TestSortOrder::Default => {}
+        TestSortOrder::Name => {
+            groups.sort_by_cached_key(|group| group.final_attempt().name.to_lowercase())
+        }
+        TestSortOrder::Duration => groups.sort_by(|a, b| {
+            // Tests without a duration go last
+            let a = a.final_attempt().duration_ms.unwrap_or(-1.0);
+            let b = b.final_attempt().duration_ms.unwrap_or(-1.0);
+            b.total_cmp(&a)
+        }),
+        TestSortOrder::Status => {
+            groups.sort_by_key(|group| status_rank(&group.final_attempt().status))
+        }
+        TestSortOrder::Flakiness => groups.sort_by(|a, b| {
+            let a = flakiness(&a.test_id).unwrap_or_default();
+            let b = flakiness(&b.test_id).unwrap_or_default();
+            b.total_cmp(&a)
+        }),
+    }
+}
+
+/// Position of the first test whose id or name matches the query
+/// Exact matches win over tests whose name merely contains the query
+pub fn find_test(groups: &[&RetryGroup], query: &str) -> Option<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+
+    let name_of = |group: &RetryGroup| group.attempts[0].name.to_lowercase();
+
+    groups
+        .iter()
+        .position(|group| group.test_id.to_lowercase() == query || name_of(group) == query)
+        .or_else(|| {
+            groups
+                .iter()
+                .position(|group| name_of(group).contains(&query))
+        })
+}
+
+/// Failed tests that share an error signature
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureCluster<'a> {
+    /// Normalized first line of the error, see `error_signature`
+    pub signature: String,
+    pub tests: Vec<&'a RetryGroup>,
+}
+
+/// Group failed tests by the signature of their error, largest cluster first
+/// Tests that did not fail are left out; ties keep the order of the input
+pub fn cluster_failures<'a>(groups: &[&'a RetryGroup]) -> Vec<FailureCluster<'a>> {
+    let mut clusters: Vec<FailureCluster> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for group in groups {
+        let test_case = group.final_attempt();
+        if test_case.status != TestStatus::Failed {
+            continue;
+        }
+
+        let signature = test_case
+            .error_message
+            .as_deref()
+            .map(error_signature)
+            .filter(|signature| !signature.is_empty())
+            .unwrap_or_else(|| "No error message".to_string());
+
+        match index.get(&signature) {
+            Some(&position) => clusters[position].tests.push(group),
+            None => {
+                index.insert(signature.clone(), clusters.len());
+                clusters.push(FailureCluster {
+                    signature,
+                    tests: vec![group],
+                });
+            }
+        }
+    }
+
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.tests.len()));
+    clusters
+}
+
+/// First line of an error with its run-specific details replaced
+/// Quoted values such as selectors become `"…"`, hex ids `<id>` and numbers
+/// such as timeouts `<n>`, so `Timeout 5000ms exceeded waiting for
+/// locator('#a1')` and its variants share one signature
+pub fn error_signature(message: &str) -> String {
+    let plain = strip_ansi(message);
+    let line = plain
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    let mut signature = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' | '"' | '`' => {
+                // Skip to the closing quote
+                for next in chars.by_ref() {
+                    if next == ch {
+                        break;
+                    }
+                }
+                signature.push_str("\"…\"");
+            }
+            _ if ch.is_ascii_alphanumeric() || ch == '_' => {
+                let mut word = String::from(ch);
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || next == '_' || next == '.') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                signature.push_str(&normalize_word(&word));
+            }
+            _ if ch.is_whitespace() => {
+                if !signature.ends_with(' ') {
+                    signature.push(' ');
+                }
+            }
+            _ => signature.push(ch),
+        }
+    }
+
+    signature.trim().to_string()
+}
+
+fn normalize_word(word: &str) -> String {
+    // A trailing period ends the sentence rather than a number
+    let (word, period) = match word.strip_suffix('.') {
+        Some(word) => (word, "."),
+        None => (word, ""),
+    };
+
+    let is_hex_id = word.len() >= 6
+        && word.chars().all(|ch| ch.is_ascii_hexdigit())
+        && word.chars().any(|ch| ch.is_ascii_digit());
+    let is_number = word.starts_with(|ch: char| ch.is_ascii_digit());
+
+    let normalized = if word.starts_with("0x") || is_hex_id {
+        "<id>"
+    } else if is_number {
+        // Units such as `ms` or `px` are part of the number
+        "<n>"
+    } else {
+        word
+    };
+
+    format!("{}{}", normalized, period)
+}
+
+fn status_rank(status: &TestStatus) -> u8 {
+    match status {
+        TestStatus::Failed => 0,
+        TestStatus::Pending => 1,
+        TestStatus::Skipped => 2,
+        TestStatus::Passed => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TestCase;
+    use crate::resource_store::ResourceStore;
+    use TestStatus::{Failed, Passed, Skipped};
+
+    fn test_case(id: &str, status: TestStatus) -> TestCase {
+        TestCase {
+            id: id.to_string(),
+            name: id.to_string(),
+            status,
+            markdown_content: None,
+            screenshots: vec![],
+            video: None,
+            trace_file: None,
+            text_attachments: vec![],
+            duration_ms: None,
+            error_message: None,
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
+            suite_path: Vec::new(),
+            image_diffs: Vec::new(),
+            trace_contexts: Vec::new(),
+            trace_overview: None,
+        }
+    }
+
+    fn run(test_cases: Vec<TestCase>) -> TestCaseCollection {
+        TestCaseCollection {
+            test_cases,
+            warnings: Vec::new(),
+            attachment_store: ResourceStore::new(),
+        }
+    }
+
+    #[test]
+    fn test_base_test_id() {
+        assert_eq!(base_test_id("login-chromium"), ("login-chromium", 0));
+        assert_eq!(base_test_id("login-chromium-retry2"), ("login-chromium", 2));
+        assert_eq!(base_test_id("retry-logic"), ("retry-logic", 0));
+    }
+
+    #[test]
+    fn test_stable_test_scores_zero() {
+        let runs = vec![vec![Passed], vec![Passed], vec![Passed]];
+        let score = flakiness_score(&runs);
+        assert_eq!(score.runs, 3);
+        assert_eq!(score.score, 0.0);
+
+        let runs = vec![vec![Failed, Failed], vec![Failed]];
+        let score = flakiness_score(&runs);
+        assert_eq!(score.runs, 2);
+        assert_eq!(score.score, 0.0);
+    }
+
+    #[test]
+    fn test_alternating_outcomes() {
+        let runs = vec![vec![Passed], vec![Failed], vec![Passed], vec![Passed]];
+        let score = flakiness_score(&runs);
+
+        assert!((score.alternation_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(score.pass_after_retry_rate, 0.0);
+        assert_eq!(score.score, score.alternation_rate);
+        assert_eq!(score.runs, 4);
+    }
+
+    #[test]
+    fn test_pass_after_retry() {
+        let runs = vec![vec![Failed, Passed], vec![Passed], vec![Skipped], vec![]];
+        assert_eq!(
+            flakiness_score(&runs),
+            FlakinessScore {
+                runs: 2,
+                alternation_rate: 0.0,
+                pass_after_retry_rate: 0.5,
+                score: 0.5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_flakiness_folds_retries() {
+        let runs = vec![
+            run(vec![
+                test_case("stable", Passed),
+                test_case("flaky-retry1", Passed),
+                test_case("flaky", Failed),
+            ]),
+            run(vec![
+                test_case("stable", Passed),
+                test_case("flaky", Passed),
+            ]),
+        ];
+
+        let flakiness = compute_flakiness(&runs);
+
+        assert_eq!(flakiness.len(), 2);
+        assert_eq!(flakiness[0].test_id, "flaky");
+        assert_eq!(flakiness[0].name, "flaky");
+        assert_eq!(flakiness[0].flakiness.pass_after_retry_rate, 0.5);
+        assert_eq!(flakiness[0].flakiness.alternation_rate, 0.0);
+        assert_eq!(flakiness[0].flakiness.score, 0.5);
+        assert_eq!(flakiness[1].test_id, "stable");
+        assert_eq!(flakiness[1].flakiness.score, 0.0);
+    }
+
+    #[test]
+    fn test_group_retries() {
+        let groups = group_retries(&[
+            test_case("checkout-retry1", Passed),
+            test_case("login", Passed),
+            test_case("checkout", Failed),
+            test_case("checkout-retry2", Failed),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].test_id, "checkout");
+        let ids: Vec<_> = groups[0].attempts.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["checkout", "checkout-retry1", "checkout-retry2"]);
+        assert_eq!(groups[0].final_attempt().status, Failed);
+        assert!(!groups[0].passed_on_retry());
+
+        assert_eq!(groups[1].test_id, "login");
+        assert_eq!(groups[1].attempts.len(), 1);
+    }
+
+    #[test]
+    fn test_passed_on_retry() {
+        let groups = group_retries(&[
+            test_case("flaky", Failed),
+            test_case("flaky-retry1", Passed),
+        ]);
+
+        assert_eq!(groups[0].final_attempt().id, "flaky-retry1");
+        assert!(groups[0].passed_on_retry());
+    }
+
+    #[test]
+    fn test_sort_groups() {
+        let mut slow = test_case("b-slow", Passed);
+        slow.duration_ms = Some(4000.0);
+        let mut fast = test_case("A-fast", Failed);
+        fast.duration_ms = Some(200.0);
+        let groups = group_retries(&[fast, test_case("c-skipped", Skipped), slow]);
+
+        let ids = |order: TestSortOrder| {
+            let mut sorted: Vec<&RetryGroup> = groups.iter().collect();
+            sort_groups(&mut sorted, order, |_| None);
+            sorted
+                .iter()
+                .map(|group| group.test_id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(TestSortOrder::Default),
+            ["A-fast", "c-skipped", "b-slow"]
+        );
+        assert_eq!(ids(TestSortOrder::Name), ["A-fast", "b-slow", "c-skipped"]);
+        assert_eq!(
+            ids(TestSortOrder::Duration),
+            ["b-slow", "A-fast", "c-skipped"]
+        );
+        assert_eq!(
+            ids(TestSortOrder::Status),
+            ["A-fast", "c-skipped", "b-slow"]
+        );
+    }
+
+    #[test]
+    fn test_find_test() {
+        let groups = group_retries(&[
+            test_case("login-chromium", Passed),
+            test_case("login", Failed),
+            test_case("checkout", Passed),
+            test_case("checkout-retry1", Passed),
+        ]);
+        let groups: Vec<&RetryGroup> = groups.iter().collect();
+
+        assert_eq!(find_test(&groups, "LOGIN"), Some(1));
+        assert_eq!(find_test(&groups, "check"), Some(2));
+        assert_eq!(find_test(&groups, "  chromium "), Some(0));
+        assert_eq!(find_test(&groups, "signup"), None);
+        assert_eq!(find_test(&groups, ""), None);
+    }
+
+    #[test]
+    fn test_error_signature() {
+        assert_eq!(
+            error_signature("Test timeout of 30000ms exceeded."),
+            "Test timeout of <n> exceeded."
+        );
+        assert_eq!(
+            error_signature(
+                "\u{1b}[31mError: locator.click: Timeout 5000ms exceeded.\u{1b}[39m\nCall log:"
+            ),
+            "Error: locator.click: Timeout <n> exceeded."
+        );
+        assert_eq!(
+            error_signature("Error: expect(locator('#user-3f9a2c')).toHaveText(\"Welcome\")"),
+            error_signature("Error: expect(locator('.banner')).toHaveText(\"Hello\")")
+        );
+        assert_eq!(
+            error_signature("Request a1b2c3d4 failed at 0x7ff3"),
+            "Request <id> failed at <id>"
+        );
+        assert_eq!(error_signature("Decoded deadbeef"), "Decoded deadbeef");
+    }
+
+    #[test]
+    fn test_cluster_failures() {
+        let mut timeout = test_case("checkout", Failed);
+        timeout.error_message = Some("Test timeout of 30000ms exceeded.".to_string());
+        let mut other_timeout = test_case("search", Failed);
+        other_timeout.error_message = Some("Test timeout of 15000ms exceeded.".to_string());
+        let mut assertion = test_case("login", Failed);
+        assertion.error_message = Some("Error: expect(received).toBe(expected)".to_string());
+
+        let groups = group_retries(&[
+            assertion,
+            test_case("profile", Passed),
+            timeout,
+            other_timeout,
+            test_case("settings", Failed),
+        ]);
+        let groups: Vec<&RetryGroup> = groups.iter().collect();
+        let clusters = cluster_failures(&groups);
+
+        let summary: Vec<(&str, Vec<&str>)> = clusters
+            .iter()
+            .map(|cluster| {
+                (
+                    cluster.signature.as_str(),
+                    cluster
+                        .tests
+                        .iter()
+                        .map(|group| group.test_id.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Test timeout of <n> exceeded.", vec!["checkout", "search"]),
+                ("Error: expect(received).toBe(expected)", vec!["login"]),
+                ("No error message", vec!["settings"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Shapes drawn on top of screenshots to point out UI defects
+//! Coordinates are in image pixels so annotations keep their position
+//! regardless of how large the screenshot is displayed.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Rectangle,
+    Arrow,
+    Freehand,
+    Text,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 4] = [Tool::Rectangle, Tool::Arrow, Tool::Freehand, Tool::Text];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tool::Rectangle => "▭ Rectangle",
+            Tool::Arrow => "➜ Arrow",
+            Tool::Freehand => "✎ Freehand",
+            Tool::Text => "T Text",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    fn distance(&self, other: &Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rectangle { start: Point, end: Point },
+    Arrow { start: Point, end: Point },
+    Freehand { points: Vec<Point> },
+    Text { at: Point, text: String },
+}
+
+/// Drags shorter than this many pixels are treated as accidental clicks
+const MIN_DRAG: f64 = 3.0;
+
+impl Shape {
+    /// Start a shape for a drag beginning at `at`; text is placed on click
+    pub fn begin(tool: Tool, at: Point) -> Option<Shape> {
+        match tool {
+            Tool::Rectangle => Some(Shape::Rectangle { start: at, end: at }),
+            Tool::Arrow => Some(Shape::Arrow { start: at, end: at }),
+            Tool::Freehand => Some(Shape::Freehand { points: vec![at] }),
+            Tool::Text => None,
+        }
+    }
+
+    /// Continue the drag to `to`
+    pub fn extend(&mut self, to: Point) {
+        match self {
+            Shape::Rectangle { end, .. } | Shape::Arrow { end, .. } => *end = to,
+            Shape::Freehand { points } => points.push(to),
+            Shape::Text { .. } => {}
+        }
+    }
+
+    /// Whether the shape is large enough to keep
+    pub fn is_visible(&self) -> bool {
+        match self {
+            Shape::Rectangle { start, end } | Shape::Arrow { start, end } => {
+                start.distance(end) >= MIN_DRAG
+            }
+            Shape::Freehand { points } => points.len() > 1,
+            Shape::Text { text, .. } => !text.trim().is_empty(),
+        }
+    }
+}
+
+/// Rectangle spanned by two corners as `(x, y, width, height)`
+pub fn normalized_rect(start: Point, end: Point) -> (f64, f64, f64, f64) {
+    (
+        start.x.min(end.x),
+        start.y.min(end.y),
+        (end.x - start.x).abs(),
+        (end.y - start.y).abs(),
+    )
+}
+
+/// The two outer corners of the head of an arrow pointing at `end`
+pub fn arrow_head(start: Point, end: Point, size: f64) -> [Point; 2] {
+    let angle = (end.y - start.y).atan2(end.x - start.x);
+    let spread = std::f64::consts::PI / 7.0;
+
+    [angle - spread, angle + spread]
+        .map(|side| Point::new(end.x - size * side.cos(), end.y - size * side.sin()))
+}
+
+/// Stroke width that stays visible on large screenshots
+pub fn line_width(image_width: f64) -> f64 {
+    (image_width / 400.0).clamp(2.0, 8.0)
+}
+
+/// File name for the annotated copy of a screenshot, always a PNG
+pub fn annotated_name(name: &str) -> String {
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    format!("{}-annotated.png", stem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotated_name() {
+        assert_eq!(
+            annotated_name("test-failed-1.jpeg"),
+            "test-failed-1-annotated.png"
+        );
+        assert_eq!(annotated_name("screenshot"), "screenshot-annotated.png");
+    }
+
+    #[test]
+    fn test_normalized_rect_any_drag_direction() {
+        let rect = normalized_rect(Point::new(50.0, 40.0), Point::new(10.0, 100.0));
+        assert_eq!(rect, (10.0, 40.0, 40.0, 60.0));
+    }
+
+    #[test]
+    fn test_arrow_head_points_back_from_tip() {
+        let [left, right] = arrow_head(Point::new(0.0, 0.0), Point::new(100.0, 0.0), 10.0);
+
+        assert!(left.x < 100.0 && right.x < 100.0);
+        assert!((left.y + right.y).abs() < 1e-9);
+        assert!(left.y * right.y < 0.0);
+    }
+
+    #[test]
+    fn test_click_without_drag_is_discarded() {
+        let mut shape = Shape::begin(Tool::Rectangle, Point::new(5.0, 5.0)).unwrap();
+        shape.extend(Point::new(6.0, 6.0));
+        assert!(!shape.is_visible());
+
+        shape.extend(Point::new(40.0, 30.0));
+        assert!(shape.is_visible());
+    }
+
+    #[test]
+    fn test_text_is_placed_on_click() {
+        assert!(Shape::begin(Tool::Text, Point::new(0.0, 0.0)).is_none());
+        assert!(!Shape::Text {
+            at: Point::new(0.0, 0.0),
+            text: "  ".to_string()
+        }
+        .is_visible());
+    }
+}
@@ -11,6 +11,18 @@ pub enum AnsiStyle {
     FgBlue,
     FgMagenta,
     FgCyan,
+    FgBrightBlack,
+    FgBrightRed,
+    FgBrightGreen,
+    FgBrightYellow,
+    FgBrightBlue,
+    FgBrightMagenta,
+    FgBrightCyan,
+    FgBrightWhite,
+    /// Extended foreground color, rendered as an inline style
+    FgRgb(u8, u8, u8),
+    /// Extended background color, rendered as an inline style
+    BgRgb(u8, u8, u8),
 }
 
 impl AnsiStyle {
@@ -24,8 +36,83 @@ impl AnsiStyle {
             AnsiStyle::FgBlue => "ansi-blue",
             AnsiStyle::FgMagenta => "ansi-magenta",
             AnsiStyle::FgCyan => "ansi-cyan",
+            AnsiStyle::FgBrightBlack => "ansi-bright-black",
+            AnsiStyle::FgBrightRed => "ansi-bright-red",
+            AnsiStyle::FgBrightGreen => "ansi-bright-green",
+            AnsiStyle::FgBrightYellow => "ansi-bright-yellow",
+            AnsiStyle::FgBrightBlue => "ansi-bright-blue",
+            AnsiStyle::FgBrightMagenta => "ansi-bright-magenta",
+            AnsiStyle::FgBrightCyan => "ansi-bright-cyan",
+            AnsiStyle::FgBrightWhite => "ansi-bright-white",
+            AnsiStyle::FgRgb(..) | AnsiStyle::BgRgb(..) => "",
         }
     }
+
+    fn is_foreground(&self) -> bool {
+        !matches!(
+            self,
+            AnsiStyle::Bold | AnsiStyle::Dim | AnsiStyle::BgRgb(..)
+        )
+    }
+}
+
+/// Replace the foreground color, so the latest one wins
+fn set_foreground(styles: &mut Vec<AnsiStyle>, color: AnsiStyle) {
+    styles.retain(|style| !style.is_foreground());
+    styles.push(color);
+}
+
+/// Color of a `5;n` (256-color) or `2;r;g;b` (truecolor) argument list
+fn extended_color(codes: &mut impl Iterator<Item = u32>) -> Option<(u8, u8, u8)> {
+    match codes.next()? {
+        5 => palette_color(codes.next()?),
+        2 => {
+            let mut channel = || codes.next().map(|value| value.min(255) as u8);
+            Some((channel()?, channel()?, channel()?))
+        }
+        _ => None,
+    }
+}
+
+/// RGB of an entry of the xterm 256-color palette
+fn palette_color(index: u32) -> Option<(u8, u8, u8)> {
+    // The 16 basic colors, matching the ansi-* classes
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (239, 68, 68),
+        (34, 197, 94),
+        (234, 179, 8),
+        (59, 130, 246),
+        (168, 85, 247),
+        (6, 182, 212),
+        (229, 231, 235),
+        (156, 163, 175),
+        (248, 113, 113),
+        (74, 222, 128),
+        (250, 204, 21),
+        (96, 165, 250),
+        (192, 132, 252),
+        (34, 211, 238),
+        (249, 250, 251),
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => Some(BASIC[index as usize]),
+        16..=231 => {
+            let cube = index - 16;
+            Some((
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            ))
+        }
+        232..=255 => {
+            let level = (8 + (index - 232) * 10) as u8;
+            Some((level, level, level))
+        }
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,9 +130,34 @@ impl AnsiSegment {
         self.styles
             .iter()
             .map(|style| style.to_css_class())
+            .filter(|class| !class.is_empty())
             .collect::<Vec<_>>()
             .join(" ")
     }
+
+    /// Inline CSS for extended colors, which have no class
+    pub fn inline_style(&self) -> Option<String> {
+        let declarations: Vec<String> = self
+            .styles
+            .iter()
+            .filter_map(|style| match style {
+                AnsiStyle::FgRgb(r, g, b) => Some(format!("color: rgb({}, {}, {})", r, g, b)),
+                AnsiStyle::BgRgb(r, g, b) => {
+                    Some(format!("background-color: rgb({}, {}, {})", r, g, b))
+                }
+                _ => None,
+            })
+            .collect();
+        (!declarations.is_empty()).then(|| declarations.join("; "))
+    }
+}
+
+/// Text without its ANSI escape codes, for plain-text output such as exports
+pub fn strip_ansi(input: &str) -> String {
+    parse_ansi(input)
+        .into_iter()
+        .map(|segment| segment.text)
+        .collect()
 }
 
 /// Parse ANSI escape codes from a string
@@ -58,28 +170,44 @@ pub fn parse_ansi(input: &str) -> Vec<AnsiSegment> {
     while let Some(ch) = chars.next() {
         if ch == '\x1b' || ch == '[' {
             // Check for ANSI escape sequence
-            if ch == '\x1b' && chars.peek() == Some(&'[') {
+            let escaped = ch == '\x1b';
+            if escaped && chars.peek() == Some(&'[') {
                 chars.next(); // consume '['
             }
 
             // Try to parse the escape sequence
             let mut code = String::new();
+            let mut terminated = false;
             while let Some(&next_ch) = chars.peek() {
                 if next_ch.is_ascii_digit() {
                     code.push(next_ch);
                     chars.next();
                 } else if next_ch == 'm' {
                     chars.next(); // consume 'm'
+                    terminated = true;
                     break;
                 } else if next_ch == ';' {
                     chars.next(); // consume separator
                     code.push(';');
                 } else {
-                    // Not a valid ANSI sequence
+                    // Not a color sequence; other escape sequences such as
+                    // cursor movement are dropped with their final letter
+                    if escaped && next_ch.is_ascii_alphabetic() {
+                        chars.next();
+                    }
                     break;
                 }
             }
 
+            if !terminated {
+                // A bracket that starts no sequence is plain text, e.g. `[data-id]`
+                if !escaped {
+                    current_text.push('[');
+                    current_text.push_str(&code);
+                }
+                continue;
+            }
+
             // Save current segment if we have text
             if !current_text.is_empty() {
                 segments.push(AnsiSegment::new(
@@ -90,41 +218,50 @@ pub fn parse_ansi(input: &str) -> Vec<AnsiSegment> {
             }
 
             // Parse the code
-            if !code.is_empty() {
-                for code_part in code.split(';') {
-                    if let Ok(num) = code_part.parse::<u32>() {
-                        match num {
-                            0 => current_styles.clear(), // Reset
-                            1 => current_styles.push(AnsiStyle::Bold),
-                            2 => current_styles.push(AnsiStyle::Dim),
-                            22 => {
-                                // Normal intensity - remove bold and dim
-                                current_styles
-                                    .retain(|s| !matches!(s, AnsiStyle::Bold | AnsiStyle::Dim));
-                            }
-                            31 => current_styles.push(AnsiStyle::FgRed),
-                            32 => current_styles.push(AnsiStyle::FgGreen),
-                            33 => current_styles.push(AnsiStyle::FgYellow),
-                            34 => current_styles.push(AnsiStyle::FgBlue),
-                            35 => current_styles.push(AnsiStyle::FgMagenta),
-                            36 => current_styles.push(AnsiStyle::FgCyan),
-                            39 => {
-                                // Default foreground - remove color styles
-                                current_styles.retain(|s| {
-                                    !matches!(
-                                        s,
-                                        AnsiStyle::FgRed
-                                            | AnsiStyle::FgGreen
-                                            | AnsiStyle::FgYellow
-                                            | AnsiStyle::FgBlue
-                                            | AnsiStyle::FgMagenta
-                                            | AnsiStyle::FgCyan
-                                    )
-                                });
-                            }
-                            _ => {} // Ignore unsupported codes
+            let mut codes = code.split(';').filter_map(|part| part.parse::<u32>().ok());
+            while let Some(num) = codes.next() {
+                match num {
+                    0 => current_styles.clear(), // Reset
+                    1 => current_styles.push(AnsiStyle::Bold),
+                    2 => current_styles.push(AnsiStyle::Dim),
+                    22 => {
+                        // Normal intensity - remove bold and dim
+                        current_styles.retain(|s| !matches!(s, AnsiStyle::Bold | AnsiStyle::Dim));
+                    }
+                    31 => set_foreground(&mut current_styles, AnsiStyle::FgRed),
+                    32 => set_foreground(&mut current_styles, AnsiStyle::FgGreen),
+                    33 => set_foreground(&mut current_styles, AnsiStyle::FgYellow),
+                    34 => set_foreground(&mut current_styles, AnsiStyle::FgBlue),
+                    35 => set_foreground(&mut current_styles, AnsiStyle::FgMagenta),
+                    36 => set_foreground(&mut current_styles, AnsiStyle::FgCyan),
+                    // 256-color or truecolor; the arguments are consumed even
+                    // when invalid so they are not read as codes of their own
+                    38 => {
+                        if let Some((r, g, b)) = extended_color(&mut codes) {
+                            set_foreground(&mut current_styles, AnsiStyle::FgRgb(r, g, b));
                         }
                     }
+                    39 => {
+                        // Default foreground - remove color styles
+                        current_styles.retain(|s| !s.is_foreground());
+                    }
+                    48 => {
+                        if let Some((r, g, b)) = extended_color(&mut codes) {
+                            current_styles.retain(|s| !matches!(s, AnsiStyle::BgRgb(..)));
+                            current_styles.push(AnsiStyle::BgRgb(r, g, b));
+                        }
+                    }
+                    49 => current_styles.retain(|s| !matches!(s, AnsiStyle::BgRgb(..))),
+                    // Bright colors; 90 is the grey Playwright dims paths with
+                    90 => set_foreground(&mut current_styles, AnsiStyle::FgBrightBlack),
+                    91 => set_foreground(&mut current_styles, AnsiStyle::FgBrightRed),
+                    92 => set_foreground(&mut current_styles, AnsiStyle::FgBrightGreen),
+                    93 => set_foreground(&mut current_styles, AnsiStyle::FgBrightYellow),
+                    94 => set_foreground(&mut current_styles, AnsiStyle::FgBrightBlue),
+                    95 => set_foreground(&mut current_styles, AnsiStyle::FgBrightMagenta),
+                    96 => set_foreground(&mut current_styles, AnsiStyle::FgBrightCyan),
+                    97 => set_foreground(&mut current_styles, AnsiStyle::FgBrightWhite),
+                    _ => {} // Ignore unsupported codes
                 }
             }
         } else {
@@ -181,6 +318,58 @@ mod tests {
         assert!(!segments.is_empty());
     }
 
+    #[test]
+    fn test_parse_bright_colors() {
+        let input = "\x1b[31mError\x1b[90m at tests/login.spec.ts:3\x1b[39m done";
+        let segments = parse_ansi(input);
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].styles, vec![AnsiStyle::FgRed]);
+        // The bright color replaces the previous foreground
+        assert_eq!(segments[1].text, " at tests/login.spec.ts:3");
+        assert_eq!(segments[1].styles, vec![AnsiStyle::FgBrightBlack]);
+        assert_eq!(segments[1].css_classes(), "ansi-bright-black");
+        assert!(segments[2].styles.is_empty());
+    }
+
+    #[test]
+    fn test_parse_extended_colors() {
+        let input = "\x1b[38;5;208mOrange\x1b[38;2;10;20;300;48;5;244mTrue\x1b[39;49mplain";
+        let segments = parse_ansi(input);
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].styles, vec![AnsiStyle::FgRgb(255, 135, 0)]);
+        assert_eq!(
+            segments[0].inline_style().as_deref(),
+            Some("color: rgb(255, 135, 0)")
+        );
+        assert_eq!(segments[0].css_classes(), "");
+        // Channels above 255 are clamped; the arguments are not read as codes
+        assert_eq!(
+            segments[1].styles,
+            vec![
+                AnsiStyle::FgRgb(10, 20, 255),
+                AnsiStyle::BgRgb(128, 128, 128)
+            ]
+        );
+        assert!(segments[2].styles.is_empty());
+        assert_eq!(segments[2].inline_style(), None);
+    }
+
+    #[test]
+    fn test_strip_ansi_keeps_brackets() {
+        let input = "\x1b[2K\x1b[31mlocator('[data-id=\"a\"]')\x1b[39m resolved to [1] element";
+
+        assert_eq!(
+            strip_ansi(input),
+            "locator('[data-id=\"a\"]') resolved to [1] element"
+        );
+        assert_eq!(
+            strip_ansi("[2mexpect([22m[31mreceived[39m[2m)[22m"),
+            "expect(received)"
+        );
+    }
+
     #[test]
     fn test_css_classes() {
         let segment = AnsiSegment::new("test".to_string(), vec![AnsiStyle::Bold, AnsiStyle::FgRed]);
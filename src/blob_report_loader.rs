@@ -0,0 +1,578 @@
+//! Loader for Playwright blob reporter archives (`--reporter=blob`)
+//! A blob archive holds a `report.jsonl` event log plus the attachments it
+//! references under `resources/`. Sharded runs produce one archive per shard;
+//! an archive of those archives (e.g. the zipped `blob-report` folder) is
+//! loaded as a single merged collection.
+
+use crate::json_report_loader::{normalize_tag, parse_status};
+use crate::models::*;
+use crate::resource_store::ResourceStore;
+use crate::test_case_loader::{
+    capture_failure_warnings, extract_image_diffs, is_capture_failed, TestCaseLoadError,
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+#[derive(Deserialize)]
+struct BlobEvent {
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct ProjectParams {
+    project: BlobProject,
+}
+
+#[derive(Deserialize)]
+struct BlobProject {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    suites: Vec<BlobSuite>,
+}
+
+/// Suites list their children as `entries` in newer versions and as
+/// separate `suites`/`tests` in older ones
+#[derive(Deserialize)]
+struct BlobSuite {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    entries: Vec<BlobEntry>,
+    #[serde(default)]
+    suites: Vec<BlobSuite>,
+    #[serde(default)]
+    tests: Vec<BlobTest>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BlobEntry {
+    Test(BlobTest),
+    Suite(BlobSuite),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobTest {
+    test_id: String,
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    annotations: Vec<TestAnnotation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestBeginParams {
+    test_id: String,
+    result: ResultBegin,
+}
+
+#[derive(Deserialize)]
+struct ResultBegin {
+    id: String,
+    #[serde(default)]
+    retry: usize,
+}
+
+#[derive(Deserialize)]
+struct TestEndParams {
+    test: TestRef,
+    result: ResultEnd,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestRef {
+    test_id: String,
+    /// Includes annotations added while the test ran
+    #[serde(default)]
+    annotations: Option<Vec<TestAnnotation>>,
+}
+
+#[derive(Deserialize)]
+struct ResultEnd {
+    id: String,
+    #[serde(default)]
+    duration: f64,
+    status: String,
+    #[serde(default)]
+    errors: Vec<BlobError>,
+    #[serde(default)]
+    attachments: Vec<BlobAttachment>,
+}
+
+#[derive(Deserialize, Clone)]
+struct BlobError {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    stack: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct BlobAttachment {
+    name: String,
+    content_type: String,
+    #[serde(default)]
+    path: Option<String>,
+    /// Inline content; `body` in older versions
+    #[serde(default, alias = "body")]
+    base64: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachParams {
+    result_id: String,
+    attachments: Vec<BlobAttachment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StdioParams {
+    #[serde(default)]
+    result_id: Option<String>,
+    /// `stdout` or `stderr`; older versions encode it in the method name
+    #[serde(default, rename = "type")]
+    stream: Option<String>,
+    #[serde(default, alias = "data")]
+    text: Option<String>,
+    #[serde(default)]
+    buffer: Option<String>,
+    #[serde(default)]
+    is_base64: bool,
+}
+
+/// What the project tree says about a test
+#[derive(Default, Clone)]
+struct TestInfo {
+    /// Title path joined with ` › `
+    title: String,
+    /// Titles of the enclosing suites
+    suite_path: Vec<String>,
+    project: String,
+    tags: Vec<String>,
+    annotations: Vec<TestAnnotation>,
+}
+
+#[derive(Default)]
+struct ResultState {
+    test_id: String,
+    retry: usize,
+    status: Option<String>,
+    duration: f64,
+    errors: Vec<BlobError>,
+    attachments: Vec<BlobAttachment>,
+    stdout: String,
+    stderr: String,
+    annotations: Option<Vec<TestAnnotation>>,
+}
+
+/// Whether the archive contains a blob report, directly or per shard
+pub fn is_blob_report(bytes: &[u8]) -> bool {
+    let Ok(archive) = ZipArchive::new(Cursor::new(bytes)) else {
+        return false;
+    };
+
+    let found = archive
+        .file_names()
+        .any(|name| is_report_log(name) || is_shard_archive(name));
+    found
+}
+
+/// Load and merge the test results of every shard in a blob report archive
+pub fn load_test_cases_from_blob_report(
+    bytes: &[u8],
+) -> Result<TestCaseCollection, TestCaseLoadError> {
+    let mut test_cases = Vec::new();
+    let shards = load_archive(bytes, &mut test_cases)?;
+
+    if shards == 0 {
+        return Err(TestCaseLoadError::ParseError(
+            "No report.jsonl found in blob report".to_string(),
+        ));
+    }
+
+    log::info!(
+        "Loaded {} test results from {} blob report shard(s)",
+        test_cases.len(),
+        shards
+    );
+
+    let warnings = capture_failure_warnings(&test_cases);
+
+    Ok(TestCaseCollection {
+        test_cases,
+        warnings,
+        attachment_store: ResourceStore::new(),
+    })
+}
+
+fn is_report_log(name: &str) -> bool {
+    !name.contains('/') && name.ends_with(".jsonl")
+}
+
+fn is_shard_archive(name: &str) -> bool {
+    !name.contains('/') && name.starts_with("report") && name.ends_with(".zip")
+}
+
+/// Load the report logs of an archive and of the shard archives inside it
+/// Returns the number of report logs found
+fn load_archive(bytes: &[u8], test_cases: &mut Vec<TestCase>) -> Result<usize, TestCaseLoadError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| TestCaseLoadError::ZipError(e.to_string()))?;
+
+    // Shards are merged in name order so the list is stable across loads
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+
+    let mut shards = 0;
+    for name in names {
+        if is_report_log(&name) {
+            let log = read_entry(&mut archive, &name)?;
+            load_report_log(&String::from_utf8_lossy(&log), &mut archive, test_cases);
+            shards += 1;
+        } else if is_shard_archive(&name) {
+            let shard = read_entry(&mut archive, &name)?;
+            shards += load_archive(&shard, test_cases)?;
+        }
+    }
+
+    Ok(shards)
+}
+
+fn read_entry(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<Vec<u8>, TestCaseLoadError> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|e| TestCaseLoadError::ZipError(format!("Failed to read {}: {}", name, e)))?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| TestCaseLoadError::IoError(e.to_string()))?;
+
+    Ok(bytes)
+}
+
+fn load_report_log(
+    log: &str,
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    test_cases: &mut Vec<TestCase>,
+) {
+    let mut tests: HashMap<String, TestInfo> = HashMap::new();
+    let mut results: HashMap<String, ResultState> = HashMap::new();
+    let mut order = Vec::new();
+
+    for line in log.lines().filter(|line| !line.trim().is_empty()) {
+        let event: BlobEvent = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(e) => {
+                log::warn!("Skipping unparseable blob report line: {}", e);
+                continue;
+            }
+        };
+
+        match event.method.as_str() {
+            "onProject" => {
+                if let Ok(params) = serde_json::from_value::<ProjectParams>(event.params) {
+                    for suite in &params.project.suites {
+                        collect_tests(suite, &[], &params.project.name, &mut tests);
+                    }
+                }
+            }
+            "onTestBegin" => {
+                if let Ok(params) = serde_json::from_value::<TestBeginParams>(event.params) {
+                    order.push(params.result.id.clone());
+                    results.insert(
+                        params.result.id,
+                        ResultState {
+                            test_id: params.test_id,
+                            retry: params.result.retry,
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+            "onTestEnd" => {
+                if let Ok(params) = serde_json::from_value::<TestEndParams>(event.params) {
+                    let state = results.entry(params.result.id.clone()).or_insert_with(|| {
+                        order.push(params.result.id.clone());
+                        ResultState {
+                            test_id: params.test.test_id.clone(),
+                            ..Default::default()
+                        }
+                    });
+                    state.status = Some(params.result.status);
+                    state.duration = params.result.duration;
+                    state.errors = params.result.errors;
+                    state.attachments.extend(params.result.attachments);
+                    state.annotations = params.test.annotations;
+                }
+            }
+            "onAttach" => {
+                if let Ok(params) = serde_json::from_value::<AttachParams>(event.params) {
+                    if let Some(state) = results.get_mut(&params.result_id) {
+                        state.attachments.extend(params.attachments);
+                    }
+                }
+            }
+            method @ ("onStdIO" | "onStdOut" | "onStdErr") => {
+                let Ok(params) = serde_json::from_value::<StdioParams>(event.params) else {
+                    continue;
+                };
+                let Some(state) = params.result_id.as_ref().and_then(|id| results.get_mut(id))
+                else {
+                    continue;
+                };
+
+                let text = stdio_text(&params);
+                if method == "onStdErr" || params.stream.as_deref() == Some("stderr") {
+                    state.stderr.push_str(&text);
+                } else {
+                    state.stdout.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for result_id in order {
+        let Some(state) = results.remove(&result_id) else {
+            continue;
+        };
+        // Results still running when the shard was interrupted have no status
+        if state.status.is_none() {
+            continue;
+        }
+
+        let info = tests
+            .get(&state.test_id)
+            .cloned()
+            .unwrap_or_else(|| TestInfo {
+                title: state.test_id.clone(),
+                ..Default::default()
+            });
+
+        test_cases.push(build_test_case(state, info, archive));
+    }
+}
+
+fn collect_tests(
+    suite: &BlobSuite,
+    parents: &[&str],
+    project: &str,
+    tests: &mut HashMap<String, TestInfo>,
+) {
+    let mut path = parents.to_vec();
+    if !suite.title.is_empty() {
+        path.push(&suite.title);
+    }
+
+    let mut add_test = |test: &BlobTest| {
+        let mut title_path = path.clone();
+        title_path.push(&test.title);
+        tests.insert(
+            test.test_id.clone(),
+            TestInfo {
+                title: title_path.join(" › "),
+                suite_path: path.iter().map(|title| title.to_string()).collect(),
+                project: project.to_string(),
+                tags: test.tags.iter().map(|tag| normalize_tag(tag)).collect(),
+                annotations: test.annotations.clone(),
+            },
+        );
+    };
+
+    for test in &suite.tests {
+        add_test(test);
+    }
+    for entry in &suite.entries {
+        if let BlobEntry::Test(test) = entry {
+            add_test(test);
+        }
+    }
+
+    for child in suite
+        .suites
+        .iter()
+        .chain(suite.entries.iter().filter_map(|entry| match entry {
+            BlobEntry::Suite(suite) => Some(suite),
+            BlobEntry::Test(_) => None,
+        }))
+    {
+        collect_tests(child, &path, project, tests);
+    }
+}
+
+fn stdio_text(params: &StdioParams) -> String {
+    if let Some(text) = &params.text {
+        if params.is_base64 {
+            return general_purpose::STANDARD
+                .decode(text)
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .unwrap_or_default();
+        }
+        return text.clone();
+    }
+
+    params
+        .buffer
+        .as_ref()
+        .and_then(|buffer| general_purpose::STANDARD.decode(buffer).ok())
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default()
+}
+
+fn build_test_case(
+    state: ResultState,
+    info: TestInfo,
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+) -> TestCase {
+    let mut id = state.test_id.clone();
+    let mut name = if info.project.is_empty() {
+        info.title.clone()
+    } else {
+        format!("{} ({})", info.title, info.project)
+    };
+    if state.retry > 0 {
+        id.push_str(&format!("-retry{}", state.retry));
+        name.push_str(&format!(" – retry {}", state.retry));
+    }
+
+    let mut screenshots = Vec::new();
+    let mut video = None;
+    let mut trace_file = None;
+    let mut text_attachments = Vec::new();
+
+    let error_text = state
+        .errors
+        .iter()
+        .map(|error| {
+            [error.message.as_deref(), error.stack.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if !error_text.is_empty() {
+        text_attachments.push(TextAttachment {
+            name: "error.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            content: error_text,
+        });
+    }
+
+    for (stream, output) in [("stdout", &state.stdout), ("stderr", &state.stderr)] {
+        if !output.is_empty() {
+            text_attachments.push(TextAttachment {
+                name: stream.to_string(),
+                mime_type: "text/plain".to_string(),
+                content: output.clone(),
+            });
+        }
+    }
+
+    for attachment in &state.attachments {
+        let Some(bytes) = attachment_bytes(attachment, archive) else {
+            continue;
+        };
+
+        let file_name = attachment
+            .path
+            .as_deref()
+            .and_then(|path| path.rsplit('/').next())
+            .unwrap_or(&attachment.name)
+            .to_string();
+        let content_type = attachment.content_type.as_str();
+
+        if content_type.starts_with("text/") || content_type == "application/json" {
+            text_attachments.push(TextAttachment {
+                name: attachment.name.clone(),
+                mime_type: content_type.to_string(),
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            });
+            continue;
+        }
+
+        let binary = TestAttachment {
+            name: file_name,
+            mime_type: content_type.to_string(),
+            data_url: format!(
+                "data:{};base64,{}",
+                content_type,
+                general_purpose::STANDARD.encode(&bytes)
+            ),
+            size_bytes: Some(bytes.len()),
+            capture_failed: is_capture_failed(content_type, &bytes),
+            archive_entry: None,
+        };
+
+        if content_type.starts_with("image/") {
+            screenshots.push(binary);
+        } else if content_type.starts_with("video/") {
+            video = Some(binary);
+        } else if attachment.name == "trace" {
+            trace_file = Some(binary);
+        }
+    }
+
+    let error_message = state
+        .errors
+        .iter()
+        .filter_map(|error| error.message.as_deref())
+        .flat_map(str::lines)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.trim().to_string());
+
+    let image_diffs = extract_image_diffs(&mut screenshots);
+
+    TestCase {
+        id,
+        name,
+        status: parse_status(state.status.as_deref().unwrap_or_default()),
+        markdown_content: None,
+        screenshots,
+        video,
+        trace_file,
+        text_attachments,
+        duration_ms: Some(state.duration),
+        error_message,
+        annotations: state.annotations.unwrap_or(info.annotations),
+        tags: info.tags,
+        project: (!info.project.is_empty()).then_some(info.project),
+        suite_path: info.suite_path,
+        image_diffs,
+        trace_contexts: Vec::new(),
+        trace_overview: None,
+    }
+}
+
+fn attachment_bytes(
+    attachment: &BlobAttachment,
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+) -> Option<Vec<u8>> {
+    if let Some(base64) = &attachment.base64 {
+        return general_purpose::STANDARD.decode(base64).ok();
+    }
+
+    // Attachments are stored next to the log; absolute paths point outside it
+    let path = attachment.path.as_deref()?;
+    let mut file = archive.by_name(path).ok()?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
@@ -0,0 +1,135 @@
+//! Type-aware previews of network response bodies
+//! The declared content type picks the renderer; bodies without one are
+//! sniffed so JSON APIs served as `text/plain` still get pretty-printed
+
+use base64::{engine::general_purpose, Engine as _};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodyPreview {
+    /// Pretty-printed JSON document
+    Json(String),
+    /// HTML source, shown as text rather than rendered
+    Html(String),
+    Text(String),
+    Image {
+        data_url: String,
+    },
+    /// Body that cannot be shown, with its size in bytes
+    Binary(usize),
+    Empty,
+}
+
+/// Build a preview for a response body with the given content type
+pub fn preview_body(content_type: Option<&str>, body: &[u8]) -> BodyPreview {
+    if body.is_empty() {
+        return BodyPreview::Empty;
+    }
+
+    let mime_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .unwrap_or_default();
+
+    if mime_type.starts_with("image/") {
+        let data = general_purpose::STANDARD.encode(body);
+        return BodyPreview::Image {
+            data_url: format!("data:{};base64,{}", mime_type, data),
+        };
+    }
+
+    if is_binary(&mime_type) {
+        return BodyPreview::Binary(body.len());
+    }
+
+    let text = match std::str::from_utf8(body) {
+        Ok(text) => text,
+        Err(_) => return BodyPreview::Binary(body.len()),
+    };
+
+    if is_json(&mime_type) || mime_type.is_empty() || mime_type == "text/plain" {
+        if let Some(pretty) = pretty_json(text) {
+            return BodyPreview::Json(pretty);
+        }
+    }
+
+    if mime_type == "text/html" || mime_type == "application/xhtml+xml" {
+        BodyPreview::Html(text.to_string())
+    } else {
+        BodyPreview::Text(text.to_string())
+    }
+}
+
+fn is_json(mime_type: &str) -> bool {
+    mime_type == "application/json" || mime_type.ends_with("+json")
+}
+
+fn is_binary(mime_type: &str) -> bool {
+    matches!(mime_type, "application/octet-stream" | "application/wasm")
+        || ["font/", "audio/", "video/"]
+            .iter()
+            .any(|prefix| mime_type.starts_with(prefix))
+}
+
+fn pretty_json(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_pretty_printed() {
+        let preview = preview_body(
+            Some("application/json; charset=utf-8"),
+            br#"{"ok":true,"items":[1,2]}"#,
+        );
+
+        match preview {
+            BodyPreview::Json(text) => {
+                assert!(text.contains("\n"));
+                assert!(text.contains("\"ok\": true"));
+            }
+            other => panic!("Expected JSON preview, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_json_falls_back_to_text() {
+        let preview = preview_body(Some("application/json"), b"{not json");
+        assert_eq!(preview, BodyPreview::Text("{not json".to_string()));
+    }
+
+    #[test]
+    fn test_untyped_json_sniffed() {
+        let preview = preview_body(None, b"[1]");
+        assert!(matches!(preview, BodyPreview::Json(_)));
+    }
+
+    #[test]
+    fn test_html_source() {
+        let preview = preview_body(Some("text/html"), b"<h1>Hi</h1>");
+        assert_eq!(preview, BodyPreview::Html("<h1>Hi</h1>".to_string()));
+    }
+
+    #[test]
+    fn test_image_data_url() {
+        let preview = preview_body(Some("image/png"), &[0x89, 0x50, 0x4E, 0x47]);
+        assert_eq!(
+            preview,
+            BodyPreview::Image {
+                data_url: "data:image/png;base64,iVBORw==".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_binary_and_empty() {
+        assert_eq!(
+            preview_body(Some("application/wasm"), b"\0asm"),
+            BodyPreview::Binary(4)
+        );
+        assert_eq!(preview_body(Some("text/plain"), b""), BodyPreview::Empty);
+    }
+}
@@ -0,0 +1,486 @@
+//! Playwright test code from recorded actions
+//! The parameters of each supported method are read into a typed call first,
+//! then printed as TypeScript; selectors recorded in Playwright's internal
+//! syntax are turned back into `getBy*` locators where possible
+
+use crate::models::{ActionEntry, ActionParams, ContextEntry, ExpectParams, ExpectedText};
+use crate::steps::is_step;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A recorded call that can be replayed from a test
+#[derive(Debug, Clone, PartialEq)]
+enum Call {
+    Goto {
+        url: String,
+    },
+    /// `click`, `dblclick`, `check`, `hover`, ... on a locator, without arguments
+    Locator {
+        selector: String,
+        method: &'static str,
+    },
+    Fill {
+        selector: String,
+        value: String,
+    },
+    Press {
+        selector: String,
+        key: String,
+    },
+    SelectOption {
+        selector: String,
+        values: Vec<String>,
+    },
+    WaitForTimeout {
+        timeout: f64,
+    },
+    Expect {
+        selector: Option<String>,
+        matcher: String,
+        /// Matcher argument, already printed
+        expected: Option<String>,
+        is_not: bool,
+    },
+}
+
+/// Locator methods replayed as-is
+const LOCATOR_METHODS: &[&str] = &[
+    "click", "dblclick", "check", "uncheck", "hover", "focus", "tap", "clear",
+];
+
+/// Export the actions of a context as a Playwright test
+pub fn export_test_code(context: &ContextEntry) -> String {
+    let title = context.title.as_deref().unwrap_or("recorded test");
+    let mut output = String::new();
+
+    output.push_str("import { test, expect } from '@playwright/test';\n\n");
+    output.push_str(&format!(
+        "test({}, async ({{ page }}) => {{\n",
+        js_string(title)
+    ));
+
+    for action in context.actions.iter().filter(|action| !is_step(action)) {
+        if let Some(call) = parse_call(action) {
+            output.push_str("  ");
+            output.push_str(&render_call(&call));
+            output.push('\n');
+        }
+    }
+
+    output.push_str("});\n");
+    output
+}
+
+fn parse_call(action: &ActionEntry) -> Option<Call> {
+    let params = &action.params;
+    let method = action.method.as_deref()?;
+    let selector = || action.locator.clone();
+
+    let call = match (method, action.typed_params()) {
+        ("goto", Some(ActionParams::Goto(goto))) => Call::Goto { url: goto.url },
+        ("fill", Some(ActionParams::Fill(fill))) => Call::Fill {
+            selector: fill.selector,
+            value: fill.value,
+        },
+        ("expect", Some(ActionParams::Expect(expect))) => expect_call(expect),
+        ("goto" | "fill" | "expect", _) => return None,
+        ("press", _) => Call::Press {
+            selector: selector()?,
+            key: string_param(params, "key")?,
+        },
+        ("selectOption", _) => Call::SelectOption {
+            selector: selector()?,
+            values: option_values(params.get("options")?),
+        },
+        ("waitForTimeout", _) => Call::WaitForTimeout {
+            timeout: params.get("timeout")?.as_f64()?,
+        },
+        _ => Call::Locator {
+            method: LOCATOR_METHODS
+                .iter()
+                .find(|known| **known == method)
+                .copied()?,
+            selector: selector()?,
+        },
+    };
+
+    Some(call)
+}
+
+/// `expect` calls record the matcher as an expression such as `to.have.text`
+fn expect_call(expect: ExpectParams) -> Call {
+    let matcher = expect
+        .expression
+        .split('.')
+        .enumerate()
+        .map(|(idx, word)| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) if idx > 0 => first.to_uppercase().chain(chars).collect(),
+                _ => word.to_string(),
+            }
+        })
+        .collect::<String>();
+
+    let expected = if let Some(number) = expect.expected_number {
+        Some(number.to_string())
+    } else if !expect.expected_text.is_empty() {
+        let texts: Vec<String> = expect
+            .expected_text
+            .iter()
+            .filter_map(expected_text)
+            .collect();
+        match texts.as_slice() {
+            [] => None,
+            [text] => Some(text.clone()),
+            texts => Some(format!("[{}]", texts.join(", "))),
+        }
+    } else {
+        expect
+            .expected_value
+            .as_ref()
+            .and_then(|value| value.get("value"))
+            .map(Value::to_string)
+    };
+
+    Call::Expect {
+        selector: expect.selector,
+        matcher,
+        expected,
+        is_not: expect.is_not,
+    }
+}
+
+/// Expected text as a string or regex literal
+fn expected_text(text: &ExpectedText) -> Option<String> {
+    if let Some(string) = &text.string {
+        return Some(js_string(string));
+    }
+    let source = text.regex_source.as_deref()?;
+    let flags = text.regex_flags.as_deref().unwrap_or_default();
+    Some(format!("/{}/{}", source, flags))
+}
+
+fn option_values(options: &Value) -> Vec<String> {
+    options
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|option| {
+            option
+                .get("valueOrLabel")
+                .or_else(|| option.get("value"))
+                .or_else(|| option.get("label"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn string_param(params: &HashMap<String, Value>, name: &str) -> Option<String> {
+    params.get(name)?.as_str().map(str::to_string)
+}
+
+fn render_call(call: &Call) -> String {
+    match call {
+        Call::Goto { url } => format!("await page.goto({});", js_string(url)),
+        Call::Locator { selector, method } => {
+            format!("await {}.{}();", locator(selector), method)
+        }
+        Call::Fill { selector, value } => {
+            format!("await {}.fill({});", locator(selector), js_string(value))
+        }
+        Call::Press { selector, key } => {
+            format!("await {}.press({});", locator(selector), js_string(key))
+        }
+        Call::SelectOption { selector, values } => {
+            let values: Vec<String> = values.iter().map(|value| js_string(value)).collect();
+            let argument = match values.as_slice() {
+                [value] => value.clone(),
+                values => format!("[{}]", values.join(", ")),
+            };
+            format!("await {}.selectOption({});", locator(selector), argument)
+        }
+        Call::WaitForTimeout { timeout } => format!("await page.waitForTimeout({});", timeout),
+        Call::Expect {
+            selector,
+            matcher,
+            expected,
+            is_not,
+        } => format!(
+            "await expect({}){}.{}({});",
+            selector.as_deref().map_or("page".to_string(), locator),
+            if *is_not { ".not" } else { "" },
+            matcher,
+            expected.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+/// Locator expression for a recorded selector, e.g.
+/// `internal:role=button[name="Save"i]` becomes `page.getByRole('button', { name: 'Save' })`
+pub fn locator(selector: &str) -> String {
+    let mut expression = "page".to_string();
+    for part in split_selector(selector) {
+        expression.push('.');
+        expression.push_str(&locator_part(part.trim()));
+    }
+    expression
+}
+
+fn locator_part(part: &str) -> String {
+    let Some((engine, body)) = part.split_once('=') else {
+        return format!("locator({})", js_string(part));
+    };
+
+    match engine {
+        "internal:role" => {
+            // `button[name="Save"i]`; other attributes are left out
+            let role = body.split('[').next().unwrap_or(body);
+            let name = body
+                .split('[')
+                .skip(1)
+                .find_map(|attribute| attribute.strip_prefix("name="));
+            match name.map(text_argument) {
+                Some(name) => format!(
+                    "getByRole({}, {{ name: {}{} }})",
+                    js_string(role),
+                    name.0,
+                    if name.1 { ", exact: true" } else { "" }
+                ),
+                None => format!("getByRole({})", js_string(role)),
+            }
+        }
+        "internal:testid" => {
+            // `[data-testid="submit"s]`
+            let value = body.split_once('=').map_or(body, |(_, value)| value);
+            format!(
+                "getByTestId({})",
+                text_argument(value.trim_end_matches(']')).0
+            )
+        }
+        "internal:text" => by_text("getByText", body),
+        "internal:label" => by_text("getByLabel", body),
+        "internal:placeholder" => by_text("getByPlaceholder", body),
+        "internal:alt" => by_text("getByAltText", body),
+        "internal:title" => by_text("getByTitle", body),
+        "internal:control" if body == "enter-frame" => "contentFrame()".to_string(),
+        "nth" => match body {
+            "0" => "first()".to_string(),
+            "-1" => "last()".to_string(),
+            index => format!("nth({})", index),
+        },
+        _ => format!("locator({})", js_string(part)),
+    }
+}
+
+fn by_text(method: &str, body: &str) -> String {
+    match text_argument(body) {
+        (text, true) => format!("{}({}, {{ exact: true }})", method, text),
+        (text, false) => format!("{}({})", method, text),
+    }
+}
+
+/// Argument for a quoted selector value such as `"Save"i` or `"Save"s`, and
+/// whether it matches exactly; regex values are kept as regex literals
+fn text_argument(value: &str) -> (String, bool) {
+    let value = value.trim_end_matches(']');
+    if let Some(quoted) = value
+        .strip_suffix('s')
+        .or_else(|| value.strip_suffix('i'))
+        .filter(|quoted| quoted.starts_with('"') && quoted.ends_with('"'))
+    {
+        let text: String =
+            serde_json::from_str(quoted).unwrap_or_else(|_| quoted.trim_matches('"').to_string());
+        return (js_string(&text), value.ends_with('s'));
+    }
+    if value.starts_with('"') && value.ends_with('"') {
+        let text: String =
+            serde_json::from_str(value).unwrap_or_else(|_| value.trim_matches('"').to_string());
+        return (js_string(&text), false);
+    }
+    (value.to_string(), false)
+}
+
+/// Parts of a selector chained with `>>`, ignoring separators inside quotes
+fn split_selector(selector: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let bytes = selector.as_bytes();
+
+    for (idx, &byte) in bytes.iter().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, byte) {
+            (Some(_), b'\\') => escaped = true,
+            (Some(open), _) if byte == open => quote = None,
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'>') if bytes.get(idx + 1) == Some(&b'>') && idx >= start => {
+                parts.push(&selector[start..idx]);
+                start = idx + 2;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&selector[start..]);
+    parts.retain(|part| !part.trim().is_empty());
+    parts
+}
+
+/// Single-quoted TypeScript string literal
+fn js_string(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('\'');
+    for c in text.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn action(method: &str, params: Value) -> ActionEntry {
+        let params: HashMap<String, Value> = serde_json::from_value(params).unwrap();
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: format!("call@{}", method),
+            start_time: 0.0,
+            end_time: 1.0,
+            title: None,
+            class: Some("Frame".to_string()),
+            method: Some(method.to_string()),
+            locator: string_param(&params, "selector"),
+            params,
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: None,
+            log: vec![],
+            frame_id: None,
+            input: None,
+            stack: vec![],
+        }
+    }
+
+    #[test]
+    fn test_locator_from_internal_selectors() {
+        assert_eq!(
+            locator(r#"internal:role=button[name="Save"i]"#),
+            "page.getByRole('button', { name: 'Save' })"
+        );
+        assert_eq!(
+            locator(r#"internal:testid=[data-testid="cart"s] >> internal:text="It's done"s"#),
+            "page.getByTestId('cart').getByText('It\\'s done', { exact: true })"
+        );
+        assert_eq!(
+            locator(
+                r#"iframe >> internal:control=enter-frame >> internal:label="Email"i >> nth=0"#
+            ),
+            "page.locator('iframe').contentFrame().getByLabel('Email').first()"
+        );
+        assert_eq!(
+            locator(r#"text="a >> b""#),
+            "page.locator('text=\"a >> b\"')"
+        );
+    }
+
+    #[test]
+    fn test_expect_matchers() {
+        let visible = action(
+            "expect",
+            json!({"selector": "#done", "expression": "to.be.visible", "isNot": false}),
+        );
+        let text = action(
+            "expect",
+            json!({
+                "selector": "h1",
+                "expression": "to.have.text",
+                "isNot": true,
+                "expectedText": [{"string": "Error"}]
+            }),
+        );
+        let count = action(
+            "expect",
+            json!({"selector": "li", "expression": "to.have.count", "expectedNumber": 3}),
+        );
+
+        let render = |action: &ActionEntry| render_call(&parse_call(action).unwrap());
+        assert_eq!(
+            render(&visible),
+            "await expect(page.locator('#done')).toBeVisible();"
+        );
+        assert_eq!(
+            render(&text),
+            "await expect(page.locator('h1')).not.toHaveText('Error');"
+        );
+        assert_eq!(
+            render(&count),
+            "await expect(page.locator('li')).toHaveCount(3);"
+        );
+    }
+
+    #[test]
+    fn test_export_test_code() {
+        let mut step = action("test.step", json!({}));
+        step.class = Some("Test".to_string());
+
+        let context = ContextEntry {
+            id: String::new(),
+            start_time: 0.0,
+            end_time: 10.0,
+            browser_name: "chromium".to_string(),
+            platform: None,
+            playwright_version: None,
+            wall_time: 0.0,
+            monotonic_time: 0.0,
+            title: Some("logs in".to_string()),
+            pages: vec![],
+            actions: vec![
+                action("goto", json!({"url": "https://app.test/login"})),
+                step,
+                action(
+                    "fill",
+                    json!({"selector": r#"internal:label="Email"i"#, "value": "me@app.test"}),
+                ),
+                action(
+                    "click",
+                    json!({"selector": r#"internal:role=button[name="Sign in"s]"#}),
+                ),
+                action("evaluateExpression", json!({"expression": "1"})),
+            ],
+            resources: vec![],
+            events: vec![],
+            errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        };
+
+        assert_eq!(
+            export_test_code(&context),
+            "import { test, expect } from '@playwright/test';\n\
+             \n\
+             test('logs in', async ({ page }) => {\n\
+             \x20 await page.goto('https://app.test/login');\n\
+             \x20 await page.getByLabel('Email').fill('me@app.test');\n\
+             \x20 await page.getByRole('button', { name: 'Sign in', exact: true }).click();\n\
+             });\n"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Shortening of exported logs and stacks
+//! Retried actions log the same waiting messages over and over, differing only
+//! in attempt numbers and timeouts, and stacks run through test-runner and
+//! library frames before reaching the test. Both are cut down when an export
+//! has to fit a budget, e.g. for pasting into an AI assistant
+
+use crate::analysis::error_signature;
+use crate::models::LogEntry;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Roughly how many characters one token of English text or code takes
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Token budget of compact exports, small enough to paste into a chat
+pub const DEFAULT_TOKEN_BUDGET: usize = 8_000;
+
+/// Longest parameter string kept by compact exports
+pub const COMPACT_PARAM_LENGTH: usize = 200;
+
+/// Deepest parameter nesting kept by compact exports
+pub const COMPACT_PARAM_DEPTH: usize = 3;
+
+/// Marks where a value was cut
+pub const ELLIPSIS: &str = "…";
+
+/// Log lines kept from each end of a long action log
+const LOG_EDGE_LINES: usize = 5;
+
+/// Character budget for a token budget
+pub fn chars_for_tokens(tokens: usize) -> usize {
+    tokens * CHARS_PER_TOKEN
+}
+
+/// Log line standing for all lines with the same message up to attempt
+/// numbers, timeouts and quoted values
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine<'a> {
+    pub entry: &'a LogEntry,
+    pub repeats: usize,
+}
+
+/// Repeated messages merged into their first occurrence, and long logs cut
+/// to their first and last lines; the count of lines left out is returned
+/// alongside
+pub fn compact_log(log: &[LogEntry]) -> (Vec<LogLine<'_>>, usize) {
+    let mut lines: Vec<LogLine> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for entry in log {
+        let signature = error_signature(&entry.message);
+        match index.get(&signature) {
+            Some(&position) => lines[position].repeats += 1,
+            None => {
+                index.insert(signature, lines.len());
+                lines.push(LogLine { entry, repeats: 1 });
+            }
+        }
+    }
+
+    if lines.len() <= 2 * LOG_EDGE_LINES {
+        return (lines, 0);
+    }
+
+    let omitted = lines.len() - 2 * LOG_EDGE_LINES;
+    lines.drain(LOG_EDGE_LINES..lines.len() - LOG_EDGE_LINES);
+    (lines, omitted)
+}
+
+/// Value with strings cut to `max_length` characters and objects or arrays
+/// nested below `max_depth` replaced by a marker; the number of values cut
+/// is returned alongside
+pub fn truncate_value(
+    value: &Value,
+    max_length: Option<usize>,
+    max_depth: Option<usize>,
+) -> (Value, usize) {
+    let mut truncated = 0;
+    let value = truncate_at(value, max_length, max_depth, 0, &mut truncated);
+    (value, truncated)
+}
+
+fn truncate_at(
+    value: &Value,
+    max_length: Option<usize>,
+    max_depth: Option<usize>,
+    depth: usize,
+    truncated: &mut usize,
+) -> Value {
+    let too_deep = max_depth.is_some_and(|max_depth| depth >= max_depth);
+
+    match value {
+        Value::String(text) => match max_length {
+            Some(max_length) if text.chars().count() > max_length => {
+                *truncated += 1;
+                let kept: String = text.chars().take(max_length).collect();
+                Value::String(format!("{}{}", kept, ELLIPSIS))
+            }
+            _ => value.clone(),
+        },
+        Value::Array(items) if too_deep && !items.is_empty() => {
+            *truncated += 1;
+            Value::String(format!("[{}]", ELLIPSIS))
+        }
+        Value::Object(fields) if too_deep && !fields.is_empty() => {
+            *truncated += 1;
+            Value::String(format!("{{{}}}", ELLIPSIS))
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| truncate_at(item, max_length, max_depth, depth + 1, truncated))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, field)| {
+                    let field = truncate_at(field, max_length, max_depth, depth + 1, truncated);
+                    (name.clone(), field)
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// First frame of a stack in the test's own code, or the first frame when
+/// every frame is in a dependency or the runtime
+pub fn first_app_frame(stack: &str) -> Option<&str> {
+    let mut frames = stack
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("at "));
+    let first = frames.clone().next()?;
+    Some(frames.find(|frame| is_app_frame(frame)).unwrap_or(first))
+}
+
+/// Whether a stack frame or file is a source file outside dependencies and
+/// the runtime
+pub(crate) fn is_app_frame(frame: &str) -> bool {
+    !(frame.contains("node_modules")
+        || frame.contains("node:")
+        || frame.contains("internal/")
+        || frame.contains("<anonymous>"))
+        && is_file_path(frame_location(frame))
+}
+
+/// Location of a stack frame line, e.g. `/app/a.ts:1:2` of
+/// `at run (/app/a.ts:1:2)`; files are returned as they are
+fn frame_location(frame: &str) -> &str {
+    let frame = frame.trim();
+    let frame = frame.strip_prefix("at ").unwrap_or(frame);
+    match (frame.rfind('('), frame.strip_suffix(')')) {
+        (Some(open), Some(inner)) => &inner[open + 1..],
+        _ => frame,
+    }
+}
+
+/// Whether a location names a file rather than a pseudo-location such as
+/// `project#chromium` that runners put in place of one
+fn is_file_path(location: &str) -> bool {
+    // Drop `:line:column`
+    let path = location.trim_end_matches(|c: char| c.is_ascii_digit() || c == ':');
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+
+    !path.contains('#')
+        && (path.contains('/')
+            || path.contains('\\')
+            || name
+                .rsplit_once('.')
+                .is_some_and(|(stem, extension)| !stem.is_empty() && !extension.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: f64, message: &str) -> LogEntry {
+        LogEntry {
+            time,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compact_log_merges_retries() {
+        let log = vec![
+            entry(0.0, "waiting for locator('#pay')"),
+            entry(1.0, "attempting click action"),
+            entry(2.0, "  - element is not visible"),
+            entry(3.0, "retrying click action, attempt #2"),
+            entry(4.0, "  waiting 20ms"),
+            entry(5.0, "attempting click action"),
+            entry(6.0, "  - element is not visible"),
+            entry(7.0, "retrying click action, attempt #3"),
+            entry(8.0, "  waiting 100ms"),
+        ];
+
+        let (lines, omitted) = compact_log(&log);
+        let summary: Vec<(f64, usize)> = lines
+            .iter()
+            .map(|line| (line.entry.time, line.repeats))
+            .collect();
+
+        assert_eq!(omitted, 0);
+        assert_eq!(summary, [(0.0, 1), (1.0, 2), (2.0, 2), (3.0, 2), (4.0, 2)]);
+    }
+
+    #[test]
+    fn test_compact_log_cuts_long_logs() {
+        let log: Vec<LogEntry> = (0..30)
+            .map(|i| entry(i as f64, &format!("step {}", "x".repeat(i + 1))))
+            .collect();
+
+        let (lines, omitted) = compact_log(&log);
+
+        assert_eq!(omitted, 20);
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[4].entry.time, 4.0);
+        assert_eq!(lines[5].entry.time, 25.0);
+    }
+
+    #[test]
+    fn test_truncate_value() {
+        let params = serde_json::json!({
+            "selector": "#email",
+            "value": "x".repeat(50),
+            "arg": {"items": [{"id": 1}, {"id": 2}], "empty": {}},
+        });
+
+        let (value, truncated) = truncate_value(&params, Some(10), Some(2));
+
+        assert_eq!(truncated, 2);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "selector": "#email",
+                "value": format!("{}…", "x".repeat(10)),
+                "arg": {"items": "[…]", "empty": {}},
+            })
+        );
+        assert_eq!(truncate_value(&params, None, None), (params.clone(), 0));
+    }
+
+    #[test]
+    fn test_first_app_frame() {
+        let stack = "Error: Timeout\n\
+            \x20   at Frame.click (/app/node_modules/playwright-core/lib/frame.js:10:5)\n\
+            \x20   at process.processTicksAndRejections (node:internal/process/task_queues:95:5)\n\
+            \x20   at /app/tests/login.spec.ts:12:3\n\
+            \x20   at /app/tests/helpers.ts:4:1";
+
+        assert_eq!(
+            first_app_frame(stack),
+            Some("at /app/tests/login.spec.ts:12:3")
+        );
+        assert_eq!(
+            first_app_frame("    at Frame.click (/app/node_modules/x.js:1:1)"),
+            Some("at Frame.click (/app/node_modules/x.js:1:1)")
+        );
+        assert_eq!(first_app_frame("Error: no frames"), None);
+    }
+
+    #[test]
+    fn test_is_app_frame() {
+        assert!(is_app_frame("/app/tests/login.spec.ts"));
+        assert!(is_app_frame("login.spec.ts"));
+        assert!(is_app_frame("C:\\app\\login.spec.ts"));
+        assert!(is_app_frame("at run (/app/tests/login.spec.ts:12:3)"));
+        assert!(is_app_frame("at /app/tests/login.spec.ts:12:3"));
+
+        assert!(!is_app_frame("project#chromium"));
+        assert!(!is_app_frame("at project#chromium:1:1"));
+        assert!(!is_app_frame("at run (project#chromium:1:1)"));
+        assert!(!is_app_frame("setup"));
+        assert!(!is_app_frame("/app/node_modules/playwright/lib/index.js"));
+    }
+}
@@ -1,20 +1,110 @@
-use super::AnsiText;
-use crate::models::ActionEntry;
+use super::screencast_image::{DecodeQuality, ScreencastImage};
+use super::{AnsiText, CopyButton};
+use crate::action_copy::{error_with_stack, params_json, summary_line};
+use crate::codegen::locator;
+use crate::models::{
+    ActionEntry, ConsoleMessage, FrameEntry, InputDetail, InputPoint, NetworkEntry,
+    ScreencastFrame, StdioEntry,
+};
+use crate::paths::shorten_paths;
+use crate::report::format_clock_time;
+use crate::resource_store::ResourceStore;
+use std::collections::HashMap;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct ActionDetailsProps {
     pub action: ActionEntry,
+    /// Frames from the top-level frame down to the targeted one
+    #[prop_or_default]
+    pub frame_path: Vec<FrameEntry>,
+    /// Wall-clock minus trace time; times are shown as clock times when set
+    #[prop_or_default]
+    pub wall_clock_offset: Option<f64>,
+    /// Last screencast frame captured before the action started
+    #[prop_or_default]
+    pub before_frame: Option<ScreencastFrame>,
+    /// First screencast frame captured after the action finished
+    #[prop_or_default]
+    pub after_frame: Option<ScreencastFrame>,
+    #[prop_or_default]
+    pub store: ResourceStore,
+    /// Workspace root to collapse in titles, parameters and stacks
+    #[prop_or_default]
+    pub path_root: Option<String>,
+    /// Failed requests started while the action ran, with their network panel index
+    #[prop_or_default]
+    pub failed_requests: Vec<(usize, NetworkEntry)>,
+    /// Stderr output written while the action ran, with its output panel index
+    #[prop_or_default]
+    pub stderr: Vec<(usize, StdioEntry)>,
+    /// Console errors and warnings logged while the action ran, with their
+    /// console panel index
+    #[prop_or_default]
+    pub console: Vec<(usize, ConsoleMessage)>,
+    #[prop_or_default]
+    pub on_request_selected: Callback<usize>,
+    #[prop_or_default]
+    pub on_output_selected: Callback<usize>,
+    #[prop_or_default]
+    pub on_console_selected: Callback<usize>,
+}
+
+/// Sections of the details pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DetailsTab {
+    Call,
+    Log,
+    Errors,
+    Network,
+    Source,
+    Attachments,
+}
+
+impl DetailsTab {
+    const ALL: [DetailsTab; 6] = [
+        DetailsTab::Call,
+        DetailsTab::Log,
+        DetailsTab::Errors,
+        DetailsTab::Network,
+        DetailsTab::Source,
+        DetailsTab::Attachments,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DetailsTab::Call => "Call",
+            DetailsTab::Log => "Log",
+            DetailsTab::Errors => "Errors",
+            DetailsTab::Network => "Network",
+            DetailsTab::Source => "Source",
+            DetailsTab::Attachments => "Attachments",
+        }
+    }
+
+    /// Badge shown next to the label; the call tab has none
+    fn count(&self, props: &ActionDetailsProps) -> Option<usize> {
+        let action = &props.action;
+        match self {
+            DetailsTab::Call => None,
+            DetailsTab::Log => Some(action.log.len() + props.stderr.len() + props.console.len()),
+            DetailsTab::Errors => Some(usize::from(action.error.is_some())),
+            DetailsTab::Network => Some(props.failed_requests.len()),
+            DetailsTab::Source => Some(stack_frame_count(props)),
+            DetailsTab::Attachments => Some(screenshots(props).len()),
+        }
+    }
 }
 
 #[function_component(ActionDetails)]
 pub fn action_details(props: &ActionDetailsProps) -> Html {
     let action = &props.action;
-    let duration = if action.end_time > 0.0 {
-        action.end_time - action.start_time
-    } else {
-        0.0
-    };
+    // Tab chosen per action, kept while switching between actions
+    let selected_tabs = use_state(HashMap::<String, DetailsTab>::new);
+    let active = selected_tabs
+        .get(&action.call_id)
+        .copied()
+        .unwrap_or(DetailsTab::Call);
 
     html! {
         <div class="action-details">
@@ -28,21 +118,107 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
                         }
                     }
                 </h3>
+                <div class="details-header-actions">
+                    <CopyButton
+                        text={shorten_paths(&summary_line(action), props.path_root.as_deref())}
+                        label="Copy summary"
+                        title="Copy a one-line summary for a bug report"
+                    />
+                    {
+                        if action.error.is_some() {
+                            html! { <span class="status-badge error">{ "Failed" }</span> }
+                        } else {
+                            html! { <span class="status-badge success">{ "Success" }</span> }
+                        }
+                    }
+                </div>
+            </div>
+
+            { action.locator.as_deref().map(render_locator).unwrap_or_default() }
+
+            <div class="details-tabs" role="tablist">
                 {
-                    if action.error.is_some() {
-                        html! { <span class="status-badge error">{ "Failed" }</span> }
-                    } else {
-                        html! { <span class="status-badge success">{ "Success" }</span> }
+                    DetailsTab::ALL.iter().map(|tab| {
+                        let tab = *tab;
+                        let count = tab.count(props);
+                        let onclick = {
+                            let selected_tabs = selected_tabs.clone();
+                            let call_id = action.call_id.clone();
+                            Callback::from(move |_| {
+                                let mut tabs = (*selected_tabs).clone();
+                                tabs.insert(call_id.clone(), tab);
+                                selected_tabs.set(tabs);
+                            })
+                        };
+
+                        html! {
+                            <button
+                                class={classes!(
+                                    "details-tab",
+                                    (tab == active).then_some("details-tab-active"),
+                                    (count == Some(0)).then_some("details-tab-empty")
+                                )}
+                                role="tab"
+                                aria-selected={(tab == active).to_string()}
+                                {onclick}
+                            >
+                                { tab.label() }
+                                if let Some(count) = count.filter(|count| *count > 0) {
+                                    <span class={classes!(
+                                        "details-tab-badge",
+                                        (tab == DetailsTab::Errors).then_some("error")
+                                    )}>{ count }</span>
+                                }
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+
+            <div class="details-tab-content" role="tabpanel">
+                {
+                    match active {
+                        DetailsTab::Call => render_call(props),
+                        DetailsTab::Log => render_log(props),
+                        DetailsTab::Errors => render_error(props),
+                        DetailsTab::Network => render_network(props),
+                        DetailsTab::Source => render_source(props),
+                        DetailsTab::Attachments => render_screenshots(props),
                     }
                 }
             </div>
+        </div>
+    }
+}
+
+/// The targeted selector as a Playwright locator, with a copy button
+fn render_locator(selector: &str) -> Html {
+    let expression = locator(selector);
+
+    html! {
+        <div class="details-locator">
+            <span class="details-locator-label">{ "Locator" }</span>
+            <code class="details-locator-value" title={selector.to_string()}>{ expression.clone() }</code>
+            <CopyButton text={expression} label="Copy locator" title="Copy the Playwright locator" />
+        </div>
+    }
+}
+
+fn render_call(props: &ActionDetailsProps) -> Html {
+    let action = &props.action;
+    let root = props.path_root.as_deref();
+    let duration = action
+        .duration_ms()
+        .map_or_else(|| "Did not finish".to_string(), |d| format!("{:.2}ms", d));
 
+    html! {
+        <>
             {
                 if let Some(title) = &action.title {
                     html! {
                         <div class="detail-section">
                             <div class="detail-label">{ "Description" }</div>
-                            <div class="detail-value">{ title }</div>
+                            <div class="detail-value">{ shorten_paths(title, root) }</div>
                         </div>
                     }
                 } else {
@@ -50,11 +226,13 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
                 }
             }
 
+            { render_frame(&props.frame_path) }
+
             <div class="detail-section">
                 <div class="detail-row">
                     <div class="detail-column">
                         <div class="detail-label">{ "Duration" }</div>
-                        <div class="detail-value">{ format!("{:.2}ms", duration) }</div>
+                        <div class="detail-value">{ duration }</div>
                     </div>
                     <div class="detail-column">
                         <div class="detail-label">{ "Call ID" }</div>
@@ -67,11 +245,17 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
                 <div class="detail-row">
                     <div class="detail-column">
                         <div class="detail-label">{ "Start Time" }</div>
-                        <div class="detail-value">{ format!("{:.2}ms", action.start_time) }</div>
+                        <div class="detail-value">{ format_time(action.start_time, props.wall_clock_offset) }</div>
                     </div>
                     <div class="detail-column">
                         <div class="detail-label">{ "End Time" }</div>
-                        <div class="detail-value">{ format!("{:.2}ms", action.end_time) }</div>
+                        <div class="detail-value">{
+                            if action.did_not_finish() {
+                                "—".to_string()
+                            } else {
+                                format_time(action.end_time, props.wall_clock_offset)
+                            }
+                        }</div>
                     </div>
                 </div>
             </div>
@@ -93,7 +277,13 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
                 if !action.params.is_empty() {
                     html! {
                         <div class="detail-section">
-                            <div class="detail-label">{ "Parameters" }</div>
+                            <div class="detail-label-row">
+                                <div class="detail-label">{ "Parameters" }</div>
+                                <CopyButton
+                                    text={shorten_paths(&params_json(action), root)}
+                                    label="Copy as JSON"
+                                />
+                            </div>
                             <div class="params-list">
                                 {
                                     action.params.iter().map(|(key, value)| {
@@ -101,7 +291,7 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
                                             <div class="param-item" key={key.clone()}>
                                                 <span class="param-key">{ key }{ ": " }</span>
                                                 <span class="param-value code">
-                                                    { format!("{}", value) }
+                                                    { shorten_paths(&value.to_string(), root) }
                                                 </span>
                                             </div>
                                         }
@@ -115,58 +305,107 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
                 }
             }
 
-            {
-                if let Some(error) = &action.error {
-                    html! {
-                        <div class="detail-section error-section">
-                            <div class="detail-label">{ "Error" }</div>
-                            {
-                                if let Some(message) = &error.message {
-                                    html! {
-                                        <div class="error-message">
-                                            <AnsiText text={message.clone()} />
-                                        </div>
-                                    }
-                                } else {
-                                    html! {}
+            { action.input_detail().map(|input| render_input(&input)).unwrap_or_default() }
+        </>
+    }
+}
+
+/// Action log followed by the stderr written while it ran
+fn render_log(props: &ActionDetailsProps) -> Html {
+    let action = &props.action;
+    if action.log.is_empty() && props.stderr.is_empty() {
+        return render_empty("No log entries");
+    }
+
+    html! {
+        <>
+            if !action.log.is_empty() {
+                <div class="detail-section">
+                    <div class="detail-label">{ "Logs" }</div>
+                    <div class="log-list">
+                        {
+                            action.log.iter().map(|log| {
+                                html! {
+                                    <div class="log-entry">
+                                        <span class="log-time">{ format!("{:.2}ms", log.time) }</span>
+                                        <span class="log-message">{ &log.message }</span>
+                                    </div>
                                 }
-                            }
-                            {
-                                if let Some(stack) = &error.stack {
-                                    html! {
-                                        <details class="error-stack" open={true}>
-                                            <summary>{ "Stack Trace" }</summary>
-                                            <pre class="ansi-pre"><AnsiText text={stack.clone()} /></pre>
-                                        </details>
-                                    }
-                                } else {
-                                    html! {}
+                            }).collect::<Html>()
+                        }
+                    </div>
+                </div>
+            }
+            if !props.stderr.is_empty() {
+                <div class="detail-section related-section">
+                    <div class="detail-label">{ "Stderr during this action" }</div>
+                    <ul class="related-list">
+                        {
+                            props.stderr.iter().map(|(index, entry)| {
+                                let index = *index;
+                                let onclick = props.on_output_selected.reform(move |_| index);
+
+                                html! {
+                                    <li>
+                                        <button class="related-item stderr" {onclick} title="Show in Output">
+                                            <span class="related-kind">{ "stderr" }</span>
+                                            <span class="related-text"><AnsiText text={entry.text.trim_end().to_string()} /></span>
+                                        </button>
+                                    </li>
                                 }
-                            }
-                        </div>
-                    }
-                } else {
-                    html! {}
-                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </div>
+            }
+            if !props.console.is_empty() {
+                <div class="detail-section related-section">
+                    <div class="detail-label">{ "Console during this action" }</div>
+                    <ul class="related-list">
+                        {
+                            props.console.iter().map(|(index, message)| {
+                                let index = *index;
+                                let onclick = props.on_console_selected.reform(move |_| index);
+                                let severity = message.severity.label();
+
+                                html! {
+                                    <li>
+                                        <button class={classes!("related-item", severity)} {onclick} title="Show in Console">
+                                            <span class="related-kind">{ severity }</span>
+                                            <span class="related-text"><AnsiText text={message.text.trim_end().to_string()} /></span>
+                                        </button>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </div>
             }
+        </>
+    }
+}
+
+fn render_error(props: &ActionDetailsProps) -> Html {
+    let Some(error) = &props.action.error else {
+        return render_empty("The action succeeded");
+    };
+    let root = props.path_root.as_deref();
 
+    html! {
+        <div class="detail-section error-section">
+            <div class="detail-label-row">
+                <div class="detail-label">{ "Error" }</div>
+                <CopyButton
+                    text={shorten_paths(&error_with_stack(error), root)}
+                    label="Copy error"
+                    title="Copy the error message with its stack trace"
+                />
+            </div>
             {
-                if !action.log.is_empty() {
+                if let Some(message) = &error.message {
                     html! {
-                        <div class="detail-section">
-                            <div class="detail-label">{ "Logs" }</div>
-                            <div class="log-list">
-                                {
-                                    action.log.iter().map(|log| {
-                                        html! {
-                                            <div class="log-entry">
-                                                <span class="log-time">{ format!("{:.2}ms", log.time) }</span>
-                                                <span class="log-message">{ &log.message }</span>
-                                            </div>
-                                        }
-                                    }).collect::<Html>()
-                                }
-                            </div>
+                        <div class="error-message">
+                            <AnsiText text={shorten_paths(message, root)} />
                         </div>
                     }
                 } else {
@@ -176,3 +415,233 @@ pub fn action_details(props: &ActionDetailsProps) -> Html {
         </div>
     }
 }
+
+/// Failed requests started while the action ran, each jumping to the
+/// network panel
+fn render_network(props: &ActionDetailsProps) -> Html {
+    if props.failed_requests.is_empty() {
+        return render_empty("No failed requests during this action");
+    }
+
+    html! {
+        <div class="detail-section related-section">
+            <div class="detail-label">{ "Failed requests during this action" }</div>
+            <ul class="related-list">
+                {
+                    props.failed_requests.iter().map(|(index, resource)| {
+                        let index = *index;
+                        let onclick = props.on_request_selected.reform(move |_| index);
+
+                        html! {
+                            <li>
+                                <button class="related-item request" {onclick} title="Show in Network">
+                                    <span class="related-kind">
+                                        { resource.status().map(|status| status.to_string()).unwrap_or_default() }
+                                    </span>
+                                    <span class="related-text">
+                                        { format!("{} {}", resource.method(), resource.url()) }
+                                    </span>
+                                </button>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+        </div>
+    }
+}
+
+/// Stack of the failure, pointing at the test source
+fn render_source(props: &ActionDetailsProps) -> Html {
+    let Some(stack) = props
+        .action
+        .error
+        .as_ref()
+        .and_then(|error| error.stack.as_ref())
+    else {
+        return render_empty("No source location recorded");
+    };
+
+    html! {
+        <div class="detail-section">
+            <div class="detail-label">{ "Stack Trace" }</div>
+            <pre class="ansi-pre error-stack"><AnsiText text={shorten_paths(stack, props.path_root.as_deref())} /></pre>
+        </div>
+    }
+}
+
+/// Frames of the error stack, counted by their `at` lines
+fn stack_frame_count(props: &ActionDetailsProps) -> usize {
+    props
+        .action
+        .error
+        .as_ref()
+        .and_then(|error| error.stack.as_deref())
+        .map_or(0, |stack| {
+            stack
+                .lines()
+                .filter(|line| line.trim_start().starts_with("at "))
+                .count()
+        })
+}
+
+fn render_empty(message: &str) -> Html {
+    html! { <div class="details-tab-empty-message">{ message.to_string() }</div> }
+}
+
+/// Screenshots before and after the action that are present in the trace
+fn screenshots(props: &ActionDetailsProps) -> Vec<(&'static str, &ScreencastFrame)> {
+    [
+        ("Before", props.before_frame.as_ref()),
+        ("After", props.after_frame.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(label, frame)| frame.map(|frame| (label, frame)))
+    .filter(|(_, frame)| props.store.contains(&frame.sha1))
+    .collect()
+}
+
+fn render_screenshots(props: &ActionDetailsProps) -> Html {
+    let frames = screenshots(props);
+
+    if frames.is_empty() {
+        return render_empty("No screenshots around this action");
+    }
+
+    html! {
+        <div class="detail-section">
+            <div class="detail-label">{ "Screenshots" }</div>
+            <div class="action-screenshots">
+                {
+                    frames.into_iter().map(|(label, frame)| {
+                        html! {
+                            <figure class="action-screenshot" key={label}>
+                                <ScreencastImage
+                                    store={props.store.clone()}
+                                    sha1={frame.sha1.clone()}
+                                    width={frame.width}
+                                    height={frame.height}
+                                    quality={DecodeQuality::Thumbnail}
+                                    alt={format!("{} {}", label, props.action.call_id)}
+                                />
+                                <figcaption>{ label }</figcaption>
+                            </figure>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
+
+/// Keys pressed and where the pointer acted
+fn render_input(input: &InputDetail) -> Html {
+    let coordinates = |point: &InputPoint| format!("({:.0}, {:.0})", point.x, point.y);
+    let mut rows: Vec<(&str, Html)> = Vec::new();
+
+    if input.keys.is_some() || !input.modifiers.is_empty() {
+        let keys = input.modifiers.iter().chain(input.keys.iter());
+        rows.push((
+            "Keys",
+            html! {
+                <span class="input-keys">
+                    {
+                        keys.enumerate().map(|(index, key)| html! {
+                            <>
+                                if index > 0 {
+                                    { "+" }
+                                }
+                                <kbd>{ key }</kbd>
+                            </>
+                        }).collect::<Html>()
+                    }
+                </span>
+            },
+        ));
+    }
+
+    if input.button.is_some() || input.click_count.is_some() {
+        let mut mouse = input.button.clone().unwrap_or_else(|| "left".to_string());
+        if let Some(count) = input.click_count.filter(|count| *count > 1) {
+            mouse.push_str(&format!(" ×{}", count));
+        }
+        rows.push(("Button", html! { { mouse } }));
+    }
+
+    if let Some(point) = &input.point {
+        rows.push(("Page point", html! { { coordinates(point) } }));
+    }
+
+    if let Some(position) = &input.position {
+        rows.push(("In element", html! { { coordinates(position) } }));
+    }
+
+    if let Some(snapshot) = &input.snapshot {
+        rows.push(("Snapshot", html! { <span class="code">{ snapshot }</span> }));
+    }
+
+    html! {
+        <div class="detail-section input-section">
+            <div class="detail-label">{ "Input" }</div>
+            <div class="input-list">
+                {
+                    rows.into_iter().map(|(name, value)| html! {
+                        <div class="input-row">
+                            <span class="input-name">{ name }</span>
+                            <span class="input-value">{ value }</span>
+                        </div>
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
+
+/// Main frame, or the chain of frames down to the targeted iframe
+fn render_frame(frame_path: &[FrameEntry]) -> Html {
+    let Some(target) = frame_path.last() else {
+        return html! {};
+    };
+
+    let url = |frame: &FrameEntry| frame.url.clone().unwrap_or_else(|| frame.frame_id.clone());
+    let kind = if target.is_main_frame || frame_path.len() == 1 {
+        "Main frame"
+    } else {
+        "iframe"
+    };
+
+    html! {
+        <div class="detail-section">
+            <div class="detail-label">{ "Frame" }</div>
+            <div class="detail-value frame-path">
+                <span class={classes!("frame-kind", (kind == "iframe").then_some("iframe"))}>
+                    { kind }
+                </span>
+                {
+                    frame_path.iter().enumerate().map(|(index, frame)| {
+                        html! {
+                            <>
+                                if index > 0 {
+                                    <span class="frame-separator">{ "›" }</span>
+                                }
+                                <span class="frame-url code" title={frame.frame_id.clone()}>
+                                    { url(frame) }
+                                    if frame.detached {
+                                        { " (detached)" }
+                                    }
+                                </span>
+                            </>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
+
+fn format_time(time: f64, wall_clock_offset: Option<f64>) -> String {
+    match wall_clock_offset {
+        Some(offset) => format_clock_time(offset + time),
+        None => format!("{:.2}ms", time),
+    }
+}
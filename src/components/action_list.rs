@@ -1,85 +1,446 @@
-use crate::models::ActionEntry;
+use crate::models::{ActionEntry, PageEntry, PageEvent};
+use crate::paths::shorten_paths;
+use crate::report::format_clock_time;
+use crate::steps::{filter_nodes, group_actions_by_step, group_nodes_by_page, PageGroup, StepNode};
+use web_sys::{Element, HtmlInputElement, ScrollIntoViewOptions, ScrollLogicalPosition};
 use yew::prelude::*;
 
+/// Characters of a selector or URL shown under the method name
+const PREVIEW_MAX_CHARS: usize = 80;
+
 #[derive(Properties, PartialEq)]
 pub struct ActionListProps {
     pub actions: Vec<ActionEntry>,
     pub on_action_selected: Callback<ActionEntry>,
     #[prop_or_default]
     pub selected_action: Option<ActionEntry>,
+    /// Workspace root to collapse in titles
+    #[prop_or_default]
+    pub path_root: Option<String>,
+    /// Dialogs, downloads, popups and crashes, shown between the actions
+    #[prop_or_default]
+    pub page_events: Vec<PageEvent>,
+    /// Trace time offsets are measured from
+    #[prop_or_default]
+    pub start_time: f64,
+    /// Wall-clock minus trace time; times are shown as clock times when set
+    #[prop_or_default]
+    pub wall_clock_offset: Option<f64>,
+    /// Pages of the context, labelling the groups when grouped by page
+    #[prop_or_default]
+    pub pages: Vec<PageEntry>,
+    /// Errors-only mode of the viewer; hides passing actions on top of the
+    /// list's own switch
+    #[prop_or_default]
+    pub errors_only: bool,
 }
 
 #[function_component(ActionList)]
 pub fn action_list(props: &ActionListProps) -> Html {
+    let group_by_page = use_state(|| false);
+    let filter_text = use_state(String::new);
+    let errors_only = use_state(|| false);
+    let content_ref = use_node_ref();
     let selected_id = props.selected_action.as_ref().map(|a| a.call_id.as_str());
 
+    // Bring the selection on screen however it changed: keyboard, deep link
+    // or jumping to an error
+    {
+        let content_ref = content_ref.clone();
+        use_effect_with(selected_id.map(str::to_string), move |selected| {
+            if selected.is_some() {
+                if let Some(content) = content_ref.cast::<Element>() {
+                    scroll_to_selected(&content);
+                }
+            }
+        });
+    }
+
+    let mut nodes = group_actions_by_step(&props.actions);
+
+    let mut page_events: Vec<&PageEvent> = props.page_events.iter().collect();
+    page_events.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let multi_page = group_nodes_by_page(nodes.clone())
+        .iter()
+        .filter(|group| group.page_id.is_some())
+        .count()
+        > 1;
+
+    let query = filter_text.trim().to_lowercase();
+    let only_errors = props.errors_only || *errors_only;
+    let filtering = !query.is_empty() || only_errors;
+    let matches = |action: &ActionEntry| {
+        (!only_errors || action.error.is_some()) && matches_text(action, &query)
+    };
+    let count = if filtering {
+        nodes = filter_nodes(&nodes, &matches);
+        let shown = props
+            .actions
+            .iter()
+            .filter(|action| matches(action))
+            .count();
+        format!(
+            "{} of {} actions, {} hidden",
+            shown,
+            props.actions.len(),
+            props.actions.len() - shown
+        )
+    } else {
+        format!("{} actions", props.actions.len())
+    };
+    let groups = group_nodes_by_page(nodes.clone());
+
+    let on_toggle_group = {
+        let group_by_page = group_by_page.clone();
+        Callback::from(move |_| group_by_page.set(!*group_by_page))
+    };
+    let on_filter_input = {
+        let filter_text = filter_text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            filter_text.set(input.value());
+        })
+    };
+    let on_toggle_errors = {
+        let errors_only = errors_only.clone();
+        Callback::from(move |_| errors_only.set(!*errors_only))
+    };
+
     html! {
         <div class="action-list">
             <div class="action-list-header">
                 <h3>{ "Actions" }</h3>
-                <span class="action-count">{ format!("{} actions", props.actions.len()) }</span>
+                if multi_page {
+                    <label class="group-by-page-toggle">
+                        <input type="checkbox" checked={*group_by_page} onchange={on_toggle_group} />
+                        { "Group by page" }
+                    </label>
+                }
+                <span class="action-count">{ count }</span>
             </div>
-            <div class="action-list-content">
+            <div class="action-list-filter">
+                <input
+                    type="search"
+                    class="action-filter-input"
+                    placeholder="Filter actions"
+                    value={(*filter_text).clone()}
+                    oninput={on_filter_input}
+                />
+                <label class="action-filter-errors">
+                    <input
+                        type="checkbox"
+                        checked={only_errors}
+                        disabled={props.errors_only}
+                        onchange={on_toggle_errors}
+                    />
+                    { "Errors only" }
+                </label>
+            </div>
+            <div class="action-list-content" ref={content_ref}>
                 {
-                    props.actions.iter().map(|action| {
-                        let action_clone = action.clone();
-                        let on_action_selected = props.on_action_selected.clone();
-                        let is_selected = selected_id == Some(action.call_id.as_str());
-                        let has_error = action.error.is_some();
-
-                        let onclick = Callback::from(move |_| {
-                            on_action_selected.emit(action_clone.clone());
-                        });
-
-                        let class = classes!(
-                            "action-item",
-                            is_selected.then_some("selected"),
-                            has_error.then_some("error"),
-                        );
-
-                        let duration = if action.end_time > 0.0 {
-                            action.end_time - action.start_time
-                        } else {
-                            0.0
-                        };
+                    if filtering && nodes.is_empty() {
+                        html! { <div class="action-filter-empty">{ "No matching actions" }</div> }
+                    } else if multi_page && *group_by_page {
+                        groups.iter().enumerate().map(|(index, group)| {
+                            render_page_group(index, group, props, selected_id, &page_events)
+                        }).collect::<Html>()
+                    } else {
+                        let mut page_events = page_events.as_slice();
+                        html! {
+                            <>
+                                { render_nodes(&nodes, props, selected_id, &mut page_events) }
+                                { render_markers(page_events, props) }
+                            </>
+                        }
+                    }
+                }
+            </div>
+        </div>
+    }
+}
+
+/// Open the steps and page groups around the selected row and scroll it
+/// into view, leaving the list alone when the row is already visible
+fn scroll_to_selected(content: &Element) {
+    let Some(row) = content
+        .query_selector(".action-item.selected")
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+
+    let mut parent = row.parent_element();
+    while let Some(element) = parent {
+        if element.tag_name().eq_ignore_ascii_case("details") {
+            let _ = element.set_attribute("open", "");
+        }
+        parent = element.parent_element();
+    }
+
+    let options = ScrollIntoViewOptions::new();
+    options.set_block(ScrollLogicalPosition::Nearest);
+    row.scroll_into_view_with_scroll_into_view_options(&options);
+}
+
+/// Collapsible group of the nodes that ran on one page, with its page events
+fn render_page_group(
+    index: usize,
+    group: &PageGroup,
+    props: &ActionListProps,
+    selected_id: Option<&str>,
+    page_events: &[&PageEvent],
+) -> Html {
+    let events: Vec<&PageEvent> = page_events
+        .iter()
+        .filter(|event| event.page_id.as_deref() == group.page_id)
+        .copied()
+        .collect();
+    let mut events = events.as_slice();
+
+    let (label, title) = match group.page_id {
+        Some(page_id) => {
+            let url = props
+                .pages
+                .iter()
+                .find(|page| page.page_id == page_id)
+                .and_then(PageEntry::url);
+            (
+                url.map_or_else(|| format!("Page {}", index + 1), str::to_string),
+                page_id.to_string(),
+            )
+        }
+        None => (
+            "No page".to_string(),
+            "Actions not tied to a page".to_string(),
+        ),
+    };
+    let has_error = group.nodes.iter().any(StepNode::has_error);
 
+    html! {
+        <details
+            key={group.page_id.unwrap_or_default().to_string()}
+            class={classes!("page-group", has_error.then_some("error"))}
+            open={true}
+        >
+            <summary class="page-group-header" {title}>
+                <span class="page-group-title">{ label }</span>
+                if has_error {
+                    <span class="error-indicator">{ "⚠" }</span>
+                }
+                <span class="action-count">{ group.nodes.len() }</span>
+            </summary>
+            <div class="page-group-children">
+                { render_nodes(&group.nodes, props, selected_id, &mut events) }
+                { render_markers(events, props) }
+            </div>
+        </details>
+    }
+}
+
+/// Render nodes with the page events that happened before each of them;
+/// events within a step are rendered among its children. Rendered events are
+/// taken off the front of `page_events`
+fn render_nodes(
+    nodes: &[StepNode],
+    props: &ActionListProps,
+    selected_id: Option<&str>,
+    page_events: &mut &[&PageEvent],
+) -> Html {
+    nodes
+        .iter()
+        .map(|node| {
+            let before = take_events(page_events, |time| time < node.action().start_time);
+            html! {
+                <>
+                    { render_markers(before, props) }
+                    { render_node(node, props, selected_id, page_events) }
+                </>
+            }
+        })
+        .collect::<Html>()
+}
+
+fn render_node(
+    node: &StepNode,
+    props: &ActionListProps,
+    selected_id: Option<&str>,
+    page_events: &mut &[&PageEvent],
+) -> Html {
+    match node {
+        StepNode::Action(action) => render_action(action, props, selected_id),
+        StepNode::Step { step, children } => {
+            let mut within = take_events(page_events, |time| {
+                step.did_not_finish() || time <= step.end_time
+            });
+            let class = classes!("step-group", node.has_error().then_some("error"));
+
+            html! {
+                <details key={step.call_id.clone()} {class} open={true}>
+                    <summary class="step-header">
+                        <span class="step-title">
+                            {
+                                shorten_paths(
+                                    step.title.as_deref().unwrap_or(&step.call_id),
+                                    props.path_root.as_deref(),
+                                )
+                            }
+                        </span>
+                        {
+                            if node.has_error() {
+                                html! { <span class="error-indicator">{ "⚠" }</span> }
+                            } else {
+                                html! {}
+                            }
+                        }
+                        { render_duration(step) }
+                    </summary>
+                    <div class="step-children">
+                        { render_nodes(children, props, selected_id, &mut within) }
+                        { render_markers(within, props) }
+                    </div>
+                </details>
+            }
+        }
+    }
+}
+
+/// Split off the leading events whose time matches
+fn take_events<'a, 'b>(
+    page_events: &mut &'a [&'b PageEvent],
+    matches: impl Fn(f64) -> bool,
+) -> &'a [&'b PageEvent] {
+    let count = page_events
+        .iter()
+        .take_while(|event| matches(event.time))
+        .count();
+    let (taken, rest) = page_events.split_at(count);
+    *page_events = rest;
+    taken
+}
+
+fn render_markers(page_events: &[&PageEvent], props: &ActionListProps) -> Html {
+    page_events
+        .iter()
+        .map(|event| {
+            html! {
+                <div class={classes!("page-event-marker", event.kind_name())} title={event.label()}>
+                    <span class="page-event-icon">{ event.icon() }</span>
+                    <span class="page-event-label">{ event.label() }</span>
+                    <span class="action-time">{ format_time(event.time, props) }</span>
+                </div>
+            }
+        })
+        .collect::<Html>()
+}
+
+fn render_action(action: &ActionEntry, props: &ActionListProps, selected_id: Option<&str>) -> Html {
+    let action_clone = action.clone();
+    let on_action_selected = props.on_action_selected.clone();
+    let is_selected = selected_id == Some(action.call_id.as_str());
+    let has_error = action.error.is_some();
+    let unfinished = action.did_not_finish();
+
+    let onclick = Callback::from(move |_| {
+        on_action_selected.emit(action_clone.clone());
+    });
+
+    // The test line that made the call, so rows can be told apart by source
+    let source = action
+        .user_frame()
+        .map(|frame| shorten_paths(&frame.location(), props.path_root.as_deref()));
+
+    let category = action.category();
+    let class = classes!(
+        "action-item",
+        category.kind_name(),
+        is_selected.then_some("selected"),
+        has_error.then_some("error"),
+        unfinished.then_some("unfinished"),
+    );
+
+    html! {
+        <div key={action.call_id.clone()} {class} {onclick} title={source}>
+            <div class="action-header">
+                <span class="action-icon" title={category.kind_name()}>{ category.icon() }</span>
+                <span class="action-method">
+                    {
+                        if let Some(method) = &action.method {
+                            method.clone()
+                        } else {
+                            action.action_type.clone()
+                        }
+                    }
+                </span>
+                <span class="action-time">{ format_time(action.start_time, props) }</span>
+                {
+                    if has_error {
+                        html! { <span class="error-indicator">{ "⚠" }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+            if let Some(target) = action.target() {
+                <div class="action-target code" title={target.to_string()}>{ preview(target) }</div>
+            }
+            <div class="action-info">
+                {
+                    if let Some(title) = &action.title {
                         html! {
-                            <div key={action.call_id.clone()} {class} {onclick}>
-                                <div class="action-header">
-                                    <span class="action-method">
-                                        {
-                                            if let Some(method) = &action.method {
-                                                method.clone()
-                                            } else {
-                                                action.action_type.clone()
-                                            }
-                                        }
-                                    </span>
-                                    {
-                                        if has_error {
-                                            html! { <span class="error-indicator">{ "⚠" }</span> }
-                                        } else {
-                                            html! {}
-                                        }
-                                    }
-                                </div>
-                                <div class="action-info">
-                                    {
-                                        if let Some(title) = &action.title {
-                                            html! { <span class="action-title">{ title }</span> }
-                                        } else {
-                                            html! {}
-                                        }
-                                    }
-                                    <span class="action-duration">
-                                        { format!("{:.0}ms", duration) }
-                                    </span>
-                                </div>
-                            </div>
+                            <span class="action-title">
+                                { shorten_paths(title, props.path_root.as_deref()) }
+                            </span>
                         }
-                    }).collect::<Html>()
+                    } else {
+                        html! {}
+                    }
                 }
+                { render_duration(action) }
             </div>
         </div>
     }
 }
+
+/// Case-insensitive match of the method, title or target; `query` is lowercase
+fn matches_text(action: &ActionEntry, query: &str) -> bool {
+    query.is_empty()
+        || [
+            action.method.as_deref(),
+            action.title.as_deref(),
+            action.target(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|text| text.to_lowercase().contains(query))
+}
+
+/// Selector or URL cut down to fit a row; the full text is in the tooltip
+fn preview(target: &str) -> String {
+    match target.char_indices().nth(PREVIEW_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &target[..end]),
+        None => target.to_string(),
+    }
+}
+
+/// Clock time, or the offset from the start of the trace
+fn format_time(time: f64, props: &ActionListProps) -> String {
+    match props.wall_clock_offset {
+        Some(offset) => format_clock_time(offset + time),
+        None => format!("+{:.0}ms", time - props.start_time),
+    }
+}
+
+fn render_duration(action: &ActionEntry) -> Html {
+    match action.duration_ms() {
+        Some(duration) => html! {
+            <span class="action-duration">{ format!("{:.0}ms", duration) }</span>
+        },
+        None => html! {
+            <span class="action-duration unfinished" title="No after event was recorded">
+                { "did not finish" }
+            </span>
+        },
+    }
+}
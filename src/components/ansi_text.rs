@@ -1,6 +1,11 @@
 use crate::ansi_parser::parse_ansi;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use yew::prelude::*;
 
+/// Segments rendered before the output is collapsed behind "show full output"
+const MAX_SEGMENTS: usize = 2000;
+
 #[derive(Properties, PartialEq)]
 pub struct AnsiTextProps {
     pub text: String,
@@ -8,20 +13,56 @@ pub struct AnsiTextProps {
 
 #[function_component(AnsiText)]
 pub fn ansi_text(props: &AnsiTextProps) -> Html {
-    let segments = parse_ansi(&props.text);
+    let expanded = use_state(|| false);
+
+    // Reparse only when the text changes, not on every render of the details pane
+    let segments = {
+        let text = props.text.clone();
+        use_memo(text_hash(&props.text), move |_| parse_ansi(&text))
+    };
+
+    let visible = if *expanded {
+        segments.len()
+    } else {
+        segments.len().min(MAX_SEGMENTS)
+    };
+    let hidden = segments.len() - visible;
 
     html! {
         <>
             {
-                segments.into_iter().map(|segment| {
+                segments.iter().take(visible).map(|segment| {
                     let classes = segment.css_classes();
-                    if classes.is_empty() {
-                        html! { <span>{ segment.text }</span> }
+                    let style = segment.inline_style();
+                    if classes.is_empty() && style.is_none() {
+                        html! { <span>{ &segment.text }</span> }
                     } else {
-                        html! { <span class={classes}>{ segment.text }</span> }
+                        html! { <span class={(!classes.is_empty()).then_some(classes)} {style}>{ &segment.text }</span> }
                     }
                 }).collect::<Html>()
             }
+            {
+                if hidden > 0 {
+                    let onclick = {
+                        let expanded = expanded.clone();
+                        Callback::from(move |_| expanded.set(true))
+                    };
+
+                    html! {
+                        <button class="ansi-show-more" {onclick}>
+                            { format!("Show full output ({} more segments)", hidden) }
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }
+            }
         </>
     }
 }
+
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
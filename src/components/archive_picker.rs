@@ -0,0 +1,115 @@
+use crate::file_probe::format_size;
+use crate::trace_loader::NestedArchive;
+use std::collections::HashSet;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ArchivePickerProps {
+    pub archives: Vec<NestedArchive>,
+    /// Emits the names of the archives to import
+    pub on_confirm: Callback<Vec<String>>,
+}
+
+/// Lets users pick which nested traces of a report archive to import
+#[function_component(ArchivePicker)]
+pub fn archive_picker(props: &ArchivePickerProps) -> Html {
+    let selected = {
+        let archives = props.archives.clone();
+        use_state(move || {
+            archives
+                .into_iter()
+                .map(|archive| archive.name)
+                .collect::<HashSet<_>>()
+        })
+    };
+
+    let set_all = |select: bool| {
+        let selected = selected.clone();
+        let archives = props.archives.clone();
+        Callback::from(move |_: MouseEvent| {
+            if select {
+                selected.set(archives.iter().map(|a| a.name.clone()).collect());
+            } else {
+                selected.set(HashSet::new());
+            }
+        })
+    };
+
+    let onconfirm = {
+        let selected = selected.clone();
+        let archives = props.archives.clone();
+        let on_confirm = props.on_confirm.clone();
+        Callback::from(move |_: MouseEvent| {
+            // Keep the archive order rather than the set's
+            let names = archives
+                .iter()
+                .filter(|archive| selected.contains(&archive.name))
+                .map(|archive| archive.name.clone())
+                .collect();
+            on_confirm.emit(names);
+        })
+    };
+
+    let selected_size: u64 = props
+        .archives
+        .iter()
+        .filter(|archive| selected.contains(&archive.name))
+        .map(|archive| archive.size)
+        .sum();
+
+    html! {
+        <div class="archive-picker">
+            <h2>{ "Select traces to import" }</h2>
+            <p class="archive-picker-hint">
+                { format!("This report contains {} traces. Import only the ones you need.", props.archives.len()) }
+            </p>
+            <div class="archive-picker-actions">
+                <button class="filter-button" onclick={set_all(true)}>{ "Select all" }</button>
+                <button class="filter-button" onclick={set_all(false)}>{ "Select none" }</button>
+            </div>
+            <ul class="archive-picker-list">
+                {
+                    props.archives.iter().map(|archive| {
+                        let name = archive.name.clone();
+                        let checked = selected.contains(&archive.name);
+                        let onchange = {
+                            let selected = selected.clone();
+                            Callback::from(move |e: Event| {
+                                let Some(input) = e.target_dyn_into::<HtmlInputElement>() else {
+                                    return;
+                                };
+                                let mut names = (*selected).clone();
+                                if input.checked() {
+                                    names.insert(name.clone());
+                                } else {
+                                    names.remove(&name);
+                                }
+                                selected.set(names);
+                            })
+                        };
+
+                        html! {
+                            <li key={archive.name.clone()} class="archive-picker-item">
+                                <label class="checkbox-label">
+                                    <input type="checkbox" {checked} {onchange} />
+                                    <span class="archive-name">
+                                        { archive.name.strip_prefix("data/").unwrap_or(&archive.name) }
+                                    </span>
+                                </label>
+                                <span class="archive-size">{ format_size(archive.size) }</span>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+            <button
+                class="export-button"
+                disabled={selected.is_empty()}
+                onclick={onconfirm}
+            >
+                { format!("Import {} trace(s) ({})", selected.len(), format_size(selected_size)) }
+            </button>
+        </div>
+    }
+}
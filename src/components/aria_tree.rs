@@ -0,0 +1,60 @@
+use crate::error_context::AriaNode;
+use yew::prelude::*;
+
+/// Nesting depth up to which nodes start expanded
+const EXPANDED_DEPTH: usize = 3;
+
+#[derive(Properties, PartialEq)]
+pub struct AriaTreeProps {
+    pub nodes: Vec<AriaNode>,
+}
+
+/// Collapsible accessibility tree of an ARIA snapshot
+#[function_component(AriaTree)]
+pub fn aria_tree(props: &AriaTreeProps) -> Html {
+    html! {
+        <div class="aria-tree">
+            { for props.nodes.iter().map(|node| render_node(node, 0)) }
+        </div>
+    }
+}
+
+fn render_node(node: &AriaNode, depth: usize) -> Html {
+    let label = html! {
+        <>
+            <span class="aria-role">{ &node.role }</span>
+            {
+                if let Some(name) = &node.name {
+                    html! { <span class="aria-name">{ format!(" \"{}\"", name) }</span> }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                for node.attributes.iter().map(|attribute| html! {
+                    <span class="aria-attribute">{ format!(" [{}]", attribute) }</span>
+                })
+            }
+            {
+                if let Some(value) = &node.value {
+                    html! { <span class="aria-value">{ format!(": {}", value) }</span> }
+                } else {
+                    html! {}
+                }
+            }
+        </>
+    };
+
+    if node.children.is_empty() {
+        return html! { <div class="aria-leaf">{ label }</div> };
+    }
+
+    html! {
+        <details class="aria-node" open={depth < EXPANDED_DEPTH}>
+            <summary>{ label }</summary>
+            <div class="aria-children">
+                { for node.children.iter().map(|child| render_node(child, depth + 1)) }
+            </div>
+        </details>
+    }
+}
@@ -0,0 +1,76 @@
+use super::AnsiText;
+use crate::models::{ConsoleMessage, ConsoleSeverity};
+use web_sys::Element;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ConsolePanelProps {
+    pub messages: Vec<ConsoleMessage>,
+    /// Trace time message offsets are measured from
+    pub start_time: f64,
+    /// Message to open the panel at, when jumping here from an action
+    #[prop_or_default]
+    pub focused: Option<usize>,
+}
+
+#[function_component(ConsolePanel)]
+pub fn console_panel(props: &ConsolePanelProps) -> Html {
+    let details_ref = use_node_ref();
+
+    {
+        let details_ref = details_ref.clone();
+        use_effect_with(props.focused, move |focused| {
+            if focused.is_some() {
+                if let Some(details) = details_ref.cast::<Element>() {
+                    let _ = details.set_attribute("open", "");
+                    details.scroll_into_view();
+                }
+            }
+        });
+    }
+
+    if props.messages.is_empty() {
+        return html! {};
+    }
+
+    let error_count = props
+        .messages
+        .iter()
+        .filter(|message| message.severity == ConsoleSeverity::Error)
+        .count();
+
+    html! {
+        <details class="console-panel" ref={details_ref}>
+            <summary class="console-header">
+                { format!("Console ({})", props.messages.len()) }
+                if error_count > 0 {
+                    <span class="console-error-count">{ format!("{} errors", error_count) }</span>
+                }
+            </summary>
+            <div class="console-content">
+                {
+                    props.messages.iter().enumerate().map(|(index, message)| {
+                        render_message(message, props.focused == Some(index), props.start_time)
+                    }).collect::<Html>()
+                }
+            </div>
+        </details>
+    }
+}
+
+fn render_message(message: &ConsoleMessage, focused: bool, start_time: f64) -> Html {
+    let severity = message.severity.label();
+
+    html! {
+        <div class={classes!("console-row", severity, focused.then_some("focused"))}>
+            <span class="console-time">
+                { format!("{:.0}ms", message.timestamp - start_time) }
+            </span>
+            <span class="console-severity">{ severity }</span>
+            <pre class="console-text"><AnsiText text={message.text.clone()} /></pre>
+            <span class="console-location">
+                { message.location.as_ref().map(|location| location.label()).unwrap_or_default() }
+            </span>
+        </div>
+    }
+}
@@ -0,0 +1,50 @@
+use gloo::timers::callback::Timeout;
+use yew::prelude::*;
+
+/// How long the button shows its confirmation
+const COPIED_FEEDBACK_MS: u32 = 2000;
+
+#[derive(Properties, PartialEq)]
+pub struct CopyButtonProps {
+    /// Text put on the clipboard
+    pub text: String,
+    pub label: AttrValue,
+    #[prop_or_default]
+    pub title: Option<AttrValue>,
+}
+
+/// Small button copying text to the clipboard, confirming for a moment
+#[function_component(CopyButton)]
+pub fn copy_button(props: &CopyButtonProps) -> Html {
+    let copied = use_state(|| false);
+
+    let onclick = {
+        let copied = copied.clone();
+        let text = props.text.clone();
+        Callback::from(move |event: MouseEvent| {
+            // Copy buttons sit inside clickable rows and summaries
+            event.stop_propagation();
+            let Some(window) = web_sys::window() else {
+                return;
+            };
+
+            let promise = window.navigator().clipboard().write_text(&text);
+            let copied = copied.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match wasm_bindgen_futures::JsFuture::from(promise).await {
+                    Ok(_) => {
+                        copied.set(true);
+                        Timeout::new(COPIED_FEEDBACK_MS, move || copied.set(false)).forget();
+                    }
+                    Err(e) => log::error!("Failed to copy to clipboard: {:?}", e),
+                }
+            });
+        })
+    };
+
+    html! {
+        <button class="copy-text-button" {onclick} title={props.title.clone()}>
+            { if *copied { "✓ Copied!".into() } else { props.label.clone() } }
+        </button>
+    }
+}
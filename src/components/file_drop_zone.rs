@@ -1,22 +1,32 @@
+use crate::file_reader::DroppedItems;
+use crate::url_loader::UrlLoad;
 use wasm_bindgen::{closure::Closure, JsCast};
-use web_sys::{DragEvent, Event, File, HtmlInputElement};
+use web_sys::{DragEvent, Event, File, HtmlInputElement, SubmitEvent};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct FileDropZoneProps {
-    pub on_files_dropped: Callback<Vec<File>>,
-    pub on_file_selected: Callback<File>,
+    pub on_files_dropped: Callback<DroppedItems>,
+    pub on_url_submitted: Callback<UrlLoad>,
+    pub on_sample_requested: Callback<()>,
 }
 
 pub struct FileDropZone {
     drag_over: bool,
+    url: String,
+    token: String,
+    show_token: bool,
 }
 
 pub enum FileDropZoneMsg {
     DragOver,
     DragLeave,
-    Drop(Vec<File>),
-    FileSelected(File),
+    Drop(DroppedItems),
+    FilesSelected(Vec<File>),
+    UrlChanged(String),
+    TokenChanged(String),
+    ToggleToken,
+    SubmitUrl,
 }
 
 impl Component for FileDropZone {
@@ -24,7 +34,12 @@ impl Component for FileDropZone {
     type Properties = FileDropZoneProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self { drag_over: false }
+        Self {
+            drag_over: false,
+            url: String::new(),
+            token: String::new(),
+            show_token: false,
+        }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -37,13 +52,41 @@ impl Component for FileDropZone {
                 self.drag_over = false;
                 true
             }
-            FileDropZoneMsg::Drop(files) => {
+            FileDropZoneMsg::Drop(items) => {
                 self.drag_over = false;
-                ctx.props().on_files_dropped.emit(files);
+                ctx.props().on_files_dropped.emit(items);
+                true
+            }
+            FileDropZoneMsg::FilesSelected(files) => {
+                ctx.props().on_files_dropped.emit(DroppedItems {
+                    files,
+                    folders: Vec::new(),
+                });
+                false
+            }
+            FileDropZoneMsg::UrlChanged(url) => {
+                self.url = url;
                 true
             }
-            FileDropZoneMsg::FileSelected(file) => {
-                ctx.props().on_file_selected.emit(file);
+            FileDropZoneMsg::TokenChanged(token) => {
+                self.token = token;
+                false
+            }
+            FileDropZoneMsg::ToggleToken => {
+                self.show_token = !self.show_token;
+                true
+            }
+            FileDropZoneMsg::SubmitUrl => {
+                let url = self.url.trim();
+                if url.is_empty() {
+                    return false;
+                }
+
+                let token = self.token.trim();
+                ctx.props().on_url_submitted.emit(UrlLoad {
+                    url: url.to_string(),
+                    token: (self.show_token && !token.is_empty()).then(|| token.to_string()),
+                });
                 false
             }
         }
@@ -61,21 +104,7 @@ impl Component for FileDropZone {
 
         let ondrop = link.callback(|e: DragEvent| {
             e.prevent_default();
-            let files = e
-                .data_transfer()
-                .and_then(|dt| dt.files())
-                .map(|file_list| {
-                    let mut files = Vec::new();
-                    for i in 0..file_list.length() {
-                        if let Some(file) = file_list.get(i) {
-                            files.push(file);
-                        }
-                    }
-                    files
-                })
-                .unwrap_or_default();
-
-            FileDropZoneMsg::Drop(files)
+            FileDropZoneMsg::Drop(DroppedItems::from_event(&e))
         });
 
         let onclick = {
@@ -86,14 +115,18 @@ impl Component for FileDropZone {
                         let input = document.create_element("input").unwrap();
                         let input: HtmlInputElement = input.dyn_into().unwrap();
                         input.set_type("file");
-                        input.set_accept(".zip");
+                        input.set_accept(".zip,.json,.trace,.network");
+                        input.set_multiple(true);
 
                         let link = link.clone();
                         let onchange = Closure::wrap(Box::new(move |e: Event| {
                             let input: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
-                            if let Some(files) = input.files() {
-                                if let Some(file) = files.get(0) {
-                                    link.send_message(FileDropZoneMsg::FileSelected(file));
+                            if let Some(file_list) = input.files() {
+                                let files: Vec<File> = (0..file_list.length())
+                                    .filter_map(|i| file_list.get(i))
+                                    .collect();
+                                if !files.is_empty() {
+                                    link.send_message(FileDropZoneMsg::FilesSelected(files));
                                 }
                             }
                         })
@@ -108,6 +141,20 @@ impl Component for FileDropZone {
             })
         };
 
+        let onsubmit = link.callback(|e: SubmitEvent| {
+            e.prevent_default();
+            FileDropZoneMsg::SubmitUrl
+        });
+        let onurlinput = link.callback(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            FileDropZoneMsg::UrlChanged(input.value())
+        });
+        let ontokeninput = link.callback(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            FileDropZoneMsg::TokenChanged(input.value())
+        });
+        let ontoggletoken = link.callback(|_| FileDropZoneMsg::ToggleToken);
+
         let class = if self.drag_over {
             "drop-zone drag-over"
         } else {
@@ -126,10 +173,46 @@ impl Component for FileDropZone {
                     <h2>{ "Drop Playwright Trace to load" }</h2>
                     <p>{ "or" }</p>
                     <button {onclick} class="select-file-button">
-                        { "Select File" }
+                        { "Select Files" }
+                    </button>
+                    <form class="url-load-form" {onsubmit}>
+                        <div class="url-load-row">
+                            <input
+                                type="url"
+                                class="url-load-input"
+                                placeholder="https://…/trace.zip"
+                                aria-label="Trace or report URL"
+                                value={self.url.clone()}
+                                oninput={onurlinput}
+                            />
+                            <button type="submit" class="url-load-button" disabled={self.url.trim().is_empty()}>
+                                { "Load from URL" }
+                            </button>
+                        </div>
+                        <button type="button" class="url-token-toggle" onclick={ontoggletoken}>
+                            { if self.show_token { "− Authorization" } else { "+ Authorization" } }
+                        </button>
+                        if self.show_token {
+                            <input
+                                type="password"
+                                class="url-load-input url-token-input"
+                                placeholder="Token, user:password or a full header value"
+                                aria-label="Authorization header"
+                                autocomplete="off"
+                                value={self.token.clone()}
+                                oninput={ontokeninput}
+                            />
+                        }
+                    </form>
+                    <button
+                        class="sample-trace-button"
+                        onclick={ctx.props().on_sample_requested.reform(|_| ())}
+                        title="Open a small trace bundled with the viewer"
+                    >
+                        { "Try a sample trace" }
                     </button>
                     <p class="info">
-                        { "Drop a Playwright trace .zip file here to view the test execution timeline, screenshots, and logs." }
+                        { "Drop a Playwright trace .zip file, an unzipped trace folder, a raw .trace file or a JSON reporter results.json here to view the test execution timeline, screenshots, and logs." }
                     </p>
                     <p class="privacy">
                         { "Your trace data is processed locally in your browser and never sent to any server." }
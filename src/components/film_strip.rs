@@ -0,0 +1,132 @@
+use super::screencast_image::{DecodeQuality, ScreencastImage};
+use crate::models::{PageEvent, ScreencastFrame};
+use crate::resource_store::ResourceStore;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct FilmStripProps {
+    pub frames: Vec<ScreencastFrame>,
+    pub store: ResourceStore,
+    /// Trace time the strip offsets are measured from
+    pub start_time: f64,
+    /// Trace time the marker track ends at
+    #[prop_or_default]
+    pub end_time: f64,
+    /// Dialogs, downloads, popups and crashes marked on the track
+    #[prop_or_default]
+    pub markers: Vec<PageEvent>,
+}
+
+#[function_component(FilmStrip)]
+pub fn film_strip(props: &FilmStripProps) -> Html {
+    let selected = use_state(|| None::<usize>);
+
+    // Only frames whose image is in the archive can be shown
+    let frames: Vec<(usize, &ScreencastFrame)> = props
+        .frames
+        .iter()
+        .enumerate()
+        .filter(|(_, frame)| props.store.contains(&frame.sha1))
+        .collect();
+
+    if frames.is_empty() && props.markers.is_empty() {
+        return html! {};
+    }
+
+    let preview = selected.and_then(|index| {
+        frames
+            .iter()
+            .find(|(frame_index, _)| *frame_index == index)
+            .copied()
+    });
+
+    html! {
+        <div class="film-strip">
+            { render_marker_track(props) }
+            <div class="film-strip-frames">
+                {
+                    frames.iter().map(|(index, frame)| {
+                        let index = *index;
+                        let is_selected = *selected == Some(index);
+                        let onclick = {
+                            let selected = selected.clone();
+                            Callback::from(move |_| {
+                                if *selected == Some(index) {
+                                    selected.set(None);
+                                } else {
+                                    selected.set(Some(index));
+                                }
+                            })
+                        };
+
+                        html! {
+                            <div
+                                key={frame.sha1.clone()}
+                                class={classes!("film-strip-frame", is_selected.then_some("selected"))}
+                                title={format!("{:.0}ms", frame.timestamp - props.start_time)}
+                                {onclick}
+                            >
+                                <ScreencastImage
+                                    store={props.store.clone()}
+                                    sha1={frame.sha1.clone()}
+                                    width={frame.width}
+                                    height={frame.height}
+                                    quality={DecodeQuality::Thumbnail}
+                                    alt={frame.sha1.clone()}
+                                />
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+            {
+                if let Some((_, frame)) = preview {
+                    html! {
+                        <div class="film-strip-preview">
+                            <div class="film-strip-preview-header">
+                                <span>{ format!("{:.0}ms", frame.timestamp - props.start_time) }</span>
+                                <span>{ format!("{}×{}", frame.width, frame.height) }</span>
+                            </div>
+                            <ScreencastImage
+                                store={props.store.clone()}
+                                sha1={frame.sha1.clone()}
+                                width={frame.width}
+                                height={frame.height}
+                                quality={DecodeQuality::Full}
+                                alt={frame.sha1.clone()}
+                            />
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
+/// Page events placed along the trace duration
+fn render_marker_track(props: &FilmStripProps) -> Html {
+    let duration = props.end_time - props.start_time;
+    if props.markers.is_empty() || duration <= 0.0 {
+        return html! {};
+    }
+
+    html! {
+        <div class="film-strip-markers">
+            {
+                props.markers.iter().map(|marker| {
+                    let offset = ((marker.time - props.start_time) / duration).clamp(0.0, 1.0);
+                    let style = format!("left: {:.2}%", offset * 100.0);
+                    let title = format!("{:.0}ms: {}", marker.time - props.start_time, marker.label());
+
+                    html! {
+                        <span class={classes!("film-strip-marker", marker.kind_name())} {style} {title}>
+                            { marker.icon() }
+                        </span>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
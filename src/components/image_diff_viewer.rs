@@ -0,0 +1,152 @@
+use crate::models::{ImageDiff, TestAttachment};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Clone, Copy, PartialEq)]
+enum DiffMode {
+    SideBySide,
+    /// Actual image faded over the expected one
+    OnionSkin,
+    /// Expected left of the divider, actual right of it
+    Slider,
+}
+
+impl DiffMode {
+    const ALL: [DiffMode; 3] = [DiffMode::SideBySide, DiffMode::OnionSkin, DiffMode::Slider];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DiffMode::SideBySide => "Side by side",
+            DiffMode::OnionSkin => "Onion skin",
+            DiffMode::Slider => "Slider",
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ImageDiffViewerProps {
+    pub diff: ImageDiff,
+}
+
+#[function_component(ImageDiffViewer)]
+pub fn image_diff_viewer(props: &ImageDiffViewerProps) -> Html {
+    let mode = use_state(|| DiffMode::SideBySide);
+    // Onion skin opacity or slider position, in percent
+    let position = use_state(|| 50.0_f64);
+
+    let diff = &props.diff;
+    let overlay = diff.expected.as_ref().zip(diff.actual.as_ref());
+
+    let oninput = {
+        let position = position.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                position.set(input.value_as_number());
+            }
+        })
+    };
+
+    let body = match (*mode, overlay) {
+        (DiffMode::OnionSkin, Some((expected, actual))) => html! {
+            <div class="image-diff-stack">
+                <img src={expected.data_url.clone()} alt={expected.name.clone()} />
+                <img
+                    class="image-diff-overlay"
+                    src={actual.data_url.clone()}
+                    alt={actual.name.clone()}
+                    style={format!("opacity: {}", *position / 100.0)}
+                />
+            </div>
+        },
+        (DiffMode::Slider, Some((expected, actual))) => html! {
+            <div class="image-diff-stack">
+                <img src={expected.data_url.clone()} alt={expected.name.clone()} />
+                <img
+                    class="image-diff-overlay"
+                    src={actual.data_url.clone()}
+                    alt={actual.name.clone()}
+                    style={format!("clip-path: inset(0 0 0 {}%)", *position)}
+                />
+                <div class="image-diff-divider" style={format!("left: {}%", *position)} />
+                <span class="image-diff-tag expected">{ "Expected" }</span>
+                <span class="image-diff-tag actual">{ "Actual" }</span>
+            </div>
+        },
+        _ => html! {
+            <div class="image-diff-side-by-side">
+                { render_image("Expected", diff.expected.as_ref()) }
+                { render_image("Actual", diff.actual.as_ref()) }
+                { render_image("Diff", diff.diff.as_ref()) }
+            </div>
+        },
+    };
+
+    html! {
+        <div class="image-diff">
+            <div class="image-diff-header">
+                <span class="image-diff-name">{ &diff.name }</span>
+                {
+                    // Overlay modes need both images
+                    if overlay.is_some() {
+                        html! {
+                            <div class="image-diff-modes">
+                                {
+                                    DiffMode::ALL.iter().map(|option| {
+                                        let option = *option;
+                                        let onclick = {
+                                            let mode = mode.clone();
+                                            Callback::from(move |_| mode.set(option))
+                                        };
+                                        html! {
+                                            <button
+                                                class={classes!("filter-button", (*mode == option).then_some("active"))}
+                                                {onclick}
+                                            >
+                                                { option.label() }
+                                            </button>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+            { body }
+            {
+                if overlay.is_some() && *mode != DiffMode::SideBySide {
+                    html! {
+                        <div class="image-diff-range">
+                            <span>{ if *mode == DiffMode::OnionSkin { "Expected" } else { "" } }</span>
+                            <input
+                                type="range"
+                                min="0"
+                                max="100"
+                                value={position.to_string()}
+                                {oninput}
+                            />
+                            <span>{ if *mode == DiffMode::OnionSkin { "Actual" } else { "" } }</span>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
+fn render_image(caption: &str, image: Option<&TestAttachment>) -> Html {
+    let Some(image) = image else {
+        return html! {};
+    };
+
+    html! {
+        <figure class="image-diff-figure">
+            <img src={image.data_url.clone()} alt={image.name.clone()} title={image.name.clone()} />
+            <figcaption>{ caption }</figcaption>
+        </figure>
+    }
+}
@@ -0,0 +1,87 @@
+use serde_json::Value;
+use yew::prelude::*;
+
+/// Nesting depth up to which objects and arrays start expanded
+const EXPANDED_DEPTH: usize = 1;
+
+#[derive(Properties, PartialEq)]
+pub struct JsonTreeProps {
+    pub content: String,
+}
+
+/// Collapsible tree view of a JSON document
+/// Falls back to the raw text when the content is not valid JSON
+#[function_component(JsonTree)]
+pub fn json_tree(props: &JsonTreeProps) -> Html {
+    let parsed = use_memo(props.content.clone(), |content| {
+        serde_json::from_str::<Value>(content).ok()
+    });
+
+    match parsed.as_ref() {
+        Some(value) => html! {
+            <div class="json-tree">
+                { render_value(None, value, 0) }
+            </div>
+        },
+        None => html! {
+            <pre class="json-tree-raw">{ &props.content }</pre>
+        },
+    }
+}
+
+fn render_value(key: Option<&str>, value: &Value, depth: usize) -> Html {
+    let key_html = match key {
+        Some(key) => html! { <><span class="json-key">{ key }</span>{ ": " }</> },
+        None => html! {},
+    };
+
+    match value {
+        Value::Object(map) => render_container(
+            key_html,
+            format!("{{{}}}", map.len()),
+            map.iter()
+                .map(|(key, value)| render_value(Some(key), value, depth + 1))
+                .collect(),
+            depth,
+        ),
+        Value::Array(items) => render_container(
+            key_html,
+            format!("[{}]", items.len()),
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, value)| render_value(Some(&index.to_string()), value, depth + 1))
+                .collect(),
+            depth,
+        ),
+        scalar => html! {
+            <div class="json-leaf">
+                { key_html }
+                <span class={classes!("json-value", scalar_class(scalar))}>
+                    { scalar.to_string() }
+                </span>
+            </div>
+        },
+    }
+}
+
+fn render_container(key_html: Html, summary: String, children: Html, depth: usize) -> Html {
+    html! {
+        <details class="json-node" open={depth < EXPANDED_DEPTH}>
+            <summary>
+                { key_html }
+                <span class="json-summary">{ summary }</span>
+            </summary>
+            <div class="json-children">{ children }</div>
+        </details>
+    }
+}
+
+fn scalar_class(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "json-string",
+        Value::Number(_) => "json-number",
+        Value::Bool(_) => "json-bool",
+        _ => "json-null",
+    }
+}
@@ -1,7 +1,19 @@
 mod action_details;
 mod action_list;
 mod ansi_text;
+mod archive_picker;
+mod aria_tree;
+mod console_panel;
+mod copy_button;
 mod file_drop_zone;
+mod film_strip;
+mod image_diff_viewer;
+mod json_tree;
+mod network_panel;
+mod output_panel;
+mod screencast_image;
+mod screenshot_lightbox;
+mod stats_panel;
 mod test_case_card;
 mod test_case_list;
 mod trace_viewer;
@@ -9,6 +21,17 @@ mod trace_viewer;
 pub use action_details::ActionDetails;
 pub use action_list::ActionList;
 pub use ansi_text::AnsiText;
+pub use archive_picker::ArchivePicker;
+pub use aria_tree::AriaTree;
+pub use console_panel::ConsolePanel;
+pub use copy_button::CopyButton;
 pub use file_drop_zone::FileDropZone;
+pub use film_strip::FilmStrip;
+pub use image_diff_viewer::ImageDiffViewer;
+pub use json_tree::JsonTree;
+pub use network_panel::NetworkPanel;
+pub use output_panel::OutputPanel;
+pub use screenshot_lightbox::ScreenshotLightbox;
+pub use stats_panel::StatsPanel;
 pub use test_case_list::TestCaseList;
 pub use trace_viewer::TraceViewer;
@@ -0,0 +1,451 @@
+use crate::body_preview::{preview_body, BodyPreview};
+use crate::models::{NetworkEntry, NetworkHeader, NetworkTimings};
+use crate::network_filter::{domains, NetworkFilter, ResourceKind, StatusClass};
+use crate::resource_store::ResourceStore;
+use crate::route_mock_exporter::export_route_mocks;
+use gloo::timers::callback::Timeout;
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// How long the copy button shows its confirmation
+const COPIED_FEEDBACK_MS: u32 = 2000;
+
+#[derive(Properties, PartialEq)]
+pub struct NetworkPanelProps {
+    pub resources: Vec<NetworkEntry>,
+    pub store: ResourceStore,
+    /// Trace time request offsets are measured from
+    pub start_time: f64,
+    /// Request to open the panel at, when jumping here from an action
+    #[prop_or_default]
+    pub focused: Option<usize>,
+}
+
+#[function_component(NetworkPanel)]
+pub fn network_panel(props: &NetworkPanelProps) -> Html {
+    let selected = use_state(|| None::<usize>);
+    let filter = use_state(NetworkFilter::default);
+    let details_ref = use_node_ref();
+    // Responses to turn into route mocks, in recorded order
+    let checked = use_state(Vec::<usize>::new);
+    let mocks_copied = use_state(|| false);
+
+    {
+        let selected = selected.clone();
+        let filter = filter.clone();
+        let details_ref = details_ref.clone();
+        let hidden = props
+            .focused
+            .and_then(|index| props.resources.get(index))
+            .is_some_and(|resource| !filter.matches(resource));
+        use_effect_with(props.focused, move |focused| {
+            if let Some(index) = *focused {
+                selected.set(Some(index));
+                if hidden {
+                    filter.set(NetworkFilter::default());
+                }
+                if let Some(details) = details_ref.cast::<Element>() {
+                    let _ = details.set_attribute("open", "");
+                    details.scroll_into_view();
+                }
+            }
+        });
+    }
+
+    if props.resources.is_empty() {
+        return html! {};
+    }
+
+    let resource = selected.and_then(|index| props.resources.get(index));
+    let visible: Vec<(usize, &NetworkEntry)> = props
+        .resources
+        .iter()
+        .enumerate()
+        .filter(|(_, resource)| filter.matches(resource))
+        .collect();
+    let count = if filter.is_active() {
+        format!("{}/{}", visible.len(), props.resources.len())
+    } else {
+        props.resources.len().to_string()
+    };
+
+    html! {
+        <details class="network-panel" ref={details_ref}>
+            <summary class="network-header">
+                { format!("Network ({})", count) }
+            </summary>
+            { render_filter_bar(&filter, &props.resources, render_mock_button(props, &checked, &mocks_copied)) }
+            <div class="network-content">
+                <div class="network-list">
+                    {
+                        visible.into_iter().map(|(index, resource)| {
+                            let is_selected = *selected == Some(index);
+                            let onclick = {
+                                let selected = selected.clone();
+                                Callback::from(move |_| selected.set(Some(index)))
+                            };
+                            let is_error = resource.is_failed();
+                            let is_checked = checked.contains(&index);
+                            let on_check = {
+                                let checked = checked.clone();
+                                Callback::from(move |e: MouseEvent| {
+                                    // Checking a response does not select it
+                                    e.stop_propagation();
+                                    let mut next = (*checked).clone();
+                                    match next.binary_search(&index) {
+                                        Ok(position) => {
+                                            next.remove(position);
+                                        }
+                                        Err(position) => next.insert(position, index),
+                                    }
+                                    checked.set(next);
+                                })
+                            };
+
+                            html! {
+                                <div
+                                    class={classes!("network-row", is_selected.then_some("selected"))}
+                                    {onclick}
+                                >
+                                    <input
+                                        type="checkbox"
+                                        class="network-check"
+                                        checked={is_checked}
+                                        onclick={on_check}
+                                        title="Include in route mocks"
+                                    />
+                                    <span class="network-method">
+                                        { resource.method() }
+                                    </span>
+                                    <span class={classes!("network-status", is_error.then_some("error"))}>
+                                        { resource.status().map(|status| status.to_string()).unwrap_or_default() }
+                                    </span>
+                                    <span class="network-url" title={resource.url().to_string()}>
+                                        { resource.url() }
+                                    </span>
+                                    <span class="network-type">
+                                        { resource.content_type().unwrap_or("") }
+                                    </span>
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+                {
+                    if let Some(resource) = resource {
+                        html! {
+                            <NetworkDetails
+                                resource={resource.clone()}
+                                store={props.store.clone()}
+                                start_time={props.start_time}
+                            />
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        </details>
+    }
+}
+
+/// Copies TypeScript route mocks for the checked responses
+fn render_mock_button(
+    props: &NetworkPanelProps,
+    checked: &UseStateHandle<Vec<usize>>,
+    copied: &UseStateHandle<bool>,
+) -> Html {
+    let onclick = {
+        let checked = checked.clone();
+        let copied = copied.clone();
+        let resources = props.resources.clone();
+        let store = props.store.clone();
+        Callback::from(move |_| {
+            let selection: Vec<&NetworkEntry> = checked
+                .iter()
+                .filter_map(|&index| resources.get(index))
+                .collect();
+            let code = export_route_mocks(&selection, |sha1| store.get(sha1));
+            let Some(window) = web_sys::window() else {
+                return;
+            };
+
+            let promise = window.navigator().clipboard().write_text(&code);
+            let copied = copied.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match wasm_bindgen_futures::JsFuture::from(promise).await {
+                    Ok(_) => {
+                        copied.set(true);
+                        Timeout::new(COPIED_FEEDBACK_MS, move || copied.set(false)).forget();
+                    }
+                    Err(e) => log::error!("Failed to copy to clipboard: {:?}", e),
+                }
+            });
+        })
+    };
+
+    let label = if **copied {
+        "✓ Copied!".to_string()
+    } else {
+        format!("Copy route mocks ({})", checked.len())
+    };
+
+    html! {
+        <button
+            class="network-mock-button"
+            disabled={checked.is_empty()}
+            {onclick}
+            title="Copy page.route() mocks serving the checked responses"
+        >
+            { label }
+        </button>
+    }
+}
+
+fn render_filter_bar(
+    filter: &UseStateHandle<NetworkFilter>,
+    resources: &[NetworkEntry],
+    actions: Html,
+) -> Html {
+    let status_chips = StatusClass::ALL.iter().map(|&status| {
+        let active = filter.statuses.contains(&status);
+        let onclick = {
+            let filter = filter.clone();
+            Callback::from(move |_| {
+                let mut next = (*filter).clone();
+                next.toggle_status(status);
+                filter.set(next);
+            })
+        };
+
+        html! {
+            <button class={classes!("network-chip", active.then_some("active"))} {onclick}>
+                { status.label() }
+            </button>
+        }
+    });
+
+    let kind_chips = ResourceKind::ALL.iter().map(|&kind| {
+        let active = filter.kinds.contains(&kind);
+        let onclick = {
+            let filter = filter.clone();
+            Callback::from(move |_| {
+                let mut next = (*filter).clone();
+                next.toggle_kind(kind);
+                filter.set(next);
+            })
+        };
+
+        html! {
+            <button class={classes!("network-chip", active.then_some("active"))} {onclick}>
+                { kind.label() }
+            </button>
+        }
+    });
+
+    let domains = domains(resources);
+    let on_domain_change = {
+        let filter = filter.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let value = select.value();
+                filter.set(NetworkFilter {
+                    domain: (!value.is_empty()).then_some(value),
+                    ..(*filter).clone()
+                });
+            }
+        })
+    };
+
+    let on_text_input = {
+        let filter = filter.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                filter.set(NetworkFilter {
+                    text: input.value(),
+                    ..(*filter).clone()
+                });
+            }
+        })
+    };
+
+    html! {
+        <div class="network-filter-bar">
+            <div class="network-chips">{ for status_chips }</div>
+            <div class="network-chips">{ for kind_chips }</div>
+            {
+                // A single domain filters nothing
+                if domains.len() > 1 {
+                    html! {
+                        <select class="network-domain" onchange={on_domain_change}>
+                            <option value="" selected={filter.domain.is_none()}>{ "All domains" }</option>
+                            {
+                                domains.into_iter().map(|domain| {
+                                    let selected = filter.domain.as_deref() == Some(domain.as_str());
+                                    html! { <option value={domain.clone()} {selected}>{ domain }</option> }
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            <input
+                class="network-search"
+                type="search"
+                placeholder="Filter URLs"
+                value={filter.text.clone()}
+                oninput={on_text_input}
+            />
+            { actions }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct NetworkDetailsProps {
+    resource: NetworkEntry,
+    store: ResourceStore,
+    start_time: f64,
+}
+
+#[function_component(NetworkDetails)]
+fn network_details(props: &NetworkDetailsProps) -> Html {
+    let resource = &props.resource;
+
+    // Inflate the body only when a different response is selected
+    let preview = {
+        let store = props.store.clone();
+        use_memo(
+            (
+                resource.body_sha1().map(str::to_string),
+                resource.content_type().map(str::to_string),
+            ),
+            move |(sha1, content_type)| {
+                let body = sha1.as_deref().and_then(|sha1| store.get(sha1))?;
+                Some(preview_body(content_type.as_deref(), &body))
+            },
+        )
+    };
+
+    html! {
+        <div class="network-details">
+            <div class="detail-section">
+                <div class="detail-label">{ "URL" }</div>
+                <div class="detail-value code">{ resource.url() }</div>
+            </div>
+            <div class="network-details-fields">
+                {
+                    if let Some(start_time) = resource.start_time {
+                        html! { <span>{ format!("Started: {:.0}ms", start_time - props.start_time) }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(duration) = resource.duration {
+                        html! { <span>{ format!("Duration: {:.0}ms", duration) }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(size) = resource.body_size() {
+                        html! { <span>{ format!("Size: {} bytes", size) }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
+                if let Some(status) = resource.status() {
+                    <span>{ format!("Status: {} {}", status, resource.response.status_text) }</span>
+                }
+                if let Some(failure) = &resource.response.failure_text {
+                    <span class="network-failure">{ format!("Failed: {}", failure) }</span>
+                }
+            </div>
+            { render_timings(&resource.timings) }
+            { render_headers("Request Headers", &resource.request.headers) }
+            { render_headers("Response Headers", &resource.response.headers) }
+            <div class="detail-section">
+                <div class="detail-label">{ "Response Body" }</div>
+                { render_preview(preview.as_ref().as_ref()) }
+            </div>
+        </div>
+    }
+}
+
+/// Phases that took time, in the order they happen
+fn render_timings(timings: &NetworkTimings) -> Html {
+    let phases = [
+        ("Blocked", timings.blocked),
+        ("DNS", timings.dns),
+        ("Connect", timings.connect),
+        ("TLS", timings.ssl),
+        ("Send", timings.send),
+        ("Wait", timings.wait),
+        ("Receive", timings.receive),
+    ];
+    let phases: Vec<(&str, f64)> = phases
+        .into_iter()
+        .filter_map(|(name, time)| Some((name, time.filter(|time| *time > 0.0)?)))
+        .collect();
+    if phases.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="network-details-fields network-timings">
+            {
+                for phases.into_iter().map(|(name, time)| html! {
+                    <span>{ format!("{}: {:.1}ms", name, time) }</span>
+                })
+            }
+        </div>
+    }
+}
+
+fn render_headers(label: &str, headers: &[NetworkHeader]) -> Html {
+    if headers.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <details class="detail-section network-headers">
+            <summary class="detail-label">{ format!("{} ({})", label, headers.len()) }</summary>
+            <div class="params-list">
+                {
+                    for headers.iter().map(|header| html! {
+                        <div class="param-item">
+                            <span class="param-key">{ &header.name }{ ": " }</span>
+                            <span class="param-value code">{ &header.value }</span>
+                        </div>
+                    })
+                }
+            </div>
+        </details>
+    }
+}
+
+fn render_preview(preview: Option<&BodyPreview>) -> Html {
+    match preview {
+        Some(BodyPreview::Json(text)) => html! {
+            <pre class="body-preview json">{ text }</pre>
+        },
+        Some(BodyPreview::Html(text)) | Some(BodyPreview::Text(text)) => html! {
+            <pre class="body-preview">{ text }</pre>
+        },
+        Some(BodyPreview::Image { data_url }) => html! {
+            <img class="body-preview image" src={data_url.clone()} alt="Response body" />
+        },
+        Some(BodyPreview::Binary(size)) => html! {
+            <div class="body-preview-empty">{ format!("Binary content ({} bytes)", size) }</div>
+        },
+        Some(BodyPreview::Empty) => html! {
+            <div class="body-preview-empty">{ "Empty response body" }</div>
+        },
+        None => html! {
+            <div class="body-preview-empty">{ "Response body not recorded" }</div>
+        },
+    }
+}
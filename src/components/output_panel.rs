@@ -0,0 +1,106 @@
+use super::AnsiText;
+use crate::models::{ActionEntry, StdioEntry, StdioStream};
+use web_sys::Element;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct OutputPanelProps {
+    pub entries: Vec<StdioEntry>,
+    /// Actions the entries are attributed to
+    pub actions: Vec<ActionEntry>,
+    /// Trace time entry offsets are measured from
+    pub start_time: f64,
+    pub on_action_selected: Callback<ActionEntry>,
+    /// Entry to open the panel at, when jumping here from an action
+    #[prop_or_default]
+    pub focused: Option<usize>,
+}
+
+#[function_component(OutputPanel)]
+pub fn output_panel(props: &OutputPanelProps) -> Html {
+    let details_ref = use_node_ref();
+
+    {
+        let details_ref = details_ref.clone();
+        use_effect_with(props.focused, move |focused| {
+            if focused.is_some() {
+                if let Some(details) = details_ref.cast::<Element>() {
+                    let _ = details.set_attribute("open", "");
+                    details.scroll_into_view();
+                }
+            }
+        });
+    }
+
+    if props.entries.is_empty() {
+        return html! {};
+    }
+
+    let stderr_count = props
+        .entries
+        .iter()
+        .filter(|entry| entry.stream == StdioStream::Stderr)
+        .count();
+
+    html! {
+        <details class="output-panel" ref={details_ref}>
+            <summary class="output-header">
+                { format!("Output ({})", props.entries.len()) }
+                {
+                    if stderr_count > 0 {
+                        html! { <span class="output-stderr-count">{ format!("{} stderr", stderr_count) }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
+            </summary>
+            <div class="output-content">
+                {
+                    props.entries.iter().enumerate().map(|(index, entry)| {
+                        render_entry(entry, props.focused == Some(index), props)
+                    }).collect::<Html>()
+                }
+            </div>
+        </details>
+    }
+}
+
+fn render_entry(entry: &StdioEntry, focused: bool, props: &OutputPanelProps) -> Html {
+    let action = entry.call_id.as_deref().and_then(|call_id| {
+        props
+            .actions
+            .iter()
+            .find(|action| action.call_id == call_id)
+    });
+    let stream = match entry.stream {
+        StdioStream::Stdout => "stdout",
+        StdioStream::Stderr => "stderr",
+    };
+
+    html! {
+        <div class={classes!("output-row", stream, focused.then_some("focused"))}>
+            <span class="output-time">
+                { format!("{:.0}ms", entry.timestamp - props.start_time) }
+            </span>
+            <span class="output-stream">{ stream }</span>
+            {
+                if let Some(action) = action {
+                    let onclick = {
+                        let action = action.clone();
+                        let on_action_selected = props.on_action_selected.clone();
+                        Callback::from(move |_| on_action_selected.emit(action.clone()))
+                    };
+
+                    html! {
+                        <button class="output-action" {onclick} title="Select action">
+                            { action.summary_label() }
+                        </button>
+                    }
+                } else {
+                    html! { <span class="output-action"></span> }
+                }
+            }
+            <pre class="output-text"><AnsiText text={entry.text.clone()} /></pre>
+        </div>
+    }
+}
@@ -0,0 +1,133 @@
+use crate::resource_store::ResourceStore;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, BlobPropertyBag, CanvasRenderingContext2d, HtmlCanvasElement, ImageBitmap,
+    ImageBitmapOptions, ResizeQuality,
+};
+use yew::prelude::*;
+
+/// Width in pixels that thumbnails are decoded at
+pub const THUMBNAIL_WIDTH: u32 = 160;
+
+/// How much of the source resolution to decode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecodeQuality {
+    /// Downscaled while decoding, for film strips and lists
+    Thumbnail,
+    /// Full source resolution, for the preview pane
+    Full,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ScreencastImageProps {
+    pub store: ResourceStore,
+    pub sha1: AttrValue,
+    pub width: u32,
+    pub height: u32,
+    pub quality: DecodeQuality,
+    #[prop_or(AttrValue::Static("image/jpeg"))]
+    pub mime_type: AttrValue,
+    #[prop_or_default]
+    pub alt: AttrValue,
+}
+
+#[function_component(ScreencastImage)]
+pub fn screencast_image(props: &ScreencastImageProps) -> Html {
+    let canvas_ref = use_node_ref();
+
+    {
+        let canvas_ref = canvas_ref.clone();
+        let store = props.store.clone();
+        let mime_type = props.mime_type.clone();
+        let target_size = decode_size(props.width, props.height, props.quality);
+
+        use_effect_with((props.sha1.clone(), props.quality), move |(sha1, _)| {
+            let sha1 = sha1.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() else {
+                    return;
+                };
+
+                // Inflated from the archive only once the image is mounted
+                let Some(data) = store.get(&sha1) else {
+                    log::warn!("Screencast frame {} not found in archive", sha1);
+                    return;
+                };
+
+                if let Err(e) = draw_image(&canvas, &data, &mime_type, target_size).await {
+                    log::error!("Failed to decode screencast frame: {:?}", e);
+                }
+            });
+        });
+    }
+
+    let class = match props.quality {
+        DecodeQuality::Thumbnail => "screencast-image thumbnail",
+        DecodeQuality::Full => "screencast-image full",
+    };
+
+    html! {
+        <canvas
+            ref={canvas_ref}
+            {class}
+            aria-label={props.alt.clone()}
+            data-sha1={props.sha1.clone()}
+        />
+    }
+}
+
+/// Compute the size to decode at, preserving the aspect ratio for thumbnails
+pub fn decode_size(width: u32, height: u32, quality: DecodeQuality) -> Option<(u32, u32)> {
+    match quality {
+        DecodeQuality::Full => None,
+        DecodeQuality::Thumbnail if width <= THUMBNAIL_WIDTH || width == 0 => None,
+        DecodeQuality::Thumbnail => {
+            let scaled_height = (height as f64 * THUMBNAIL_WIDTH as f64 / width as f64).round();
+            Some((THUMBNAIL_WIDTH, (scaled_height as u32).max(1)))
+        }
+    }
+}
+
+async fn draw_image(
+    canvas: &HtmlCanvasElement,
+    data: &[u8],
+    mime_type: &str,
+    target_size: Option<(u32, u32)>,
+) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+
+    let array = js_sys::Array::new();
+    array.push(&js_sys::Uint8Array::from(data));
+
+    let blob_options = BlobPropertyBag::new();
+    blob_options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&array, &blob_options)?;
+
+    // Let the browser downscale while decoding instead of holding full frames in memory
+    let promise = match target_size {
+        Some((width, height)) => {
+            let options = ImageBitmapOptions::new();
+            options.set_resize_width(width);
+            options.set_resize_height(height);
+            options.set_resize_quality(ResizeQuality::Low);
+            window.create_image_bitmap_with_blob_and_image_bitmap_options(&blob, &options)?
+        }
+        None => window.create_image_bitmap_with_blob(&blob)?,
+    };
+
+    let bitmap: ImageBitmap = JsFuture::from(promise).await?.dyn_into()?;
+
+    canvas.set_width(bitmap.width());
+    canvas.set_height(bitmap.height());
+
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()?;
+
+    context.draw_image_with_image_bitmap(&bitmap, 0.0, 0.0)?;
+    bitmap.close();
+
+    Ok(())
+}
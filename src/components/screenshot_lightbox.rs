@@ -0,0 +1,336 @@
+use crate::annotations::{
+    annotated_name, arrow_head, line_width, normalized_rect, Point, Shape, Tool,
+};
+use crate::models::TestAttachment;
+use crate::test_case_loader::decode_data_url;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlAnchorElement, HtmlCanvasElement, HtmlImageElement};
+use yew::prelude::*;
+
+const ANNOTATION_COLOR: &str = "#ff3b30";
+
+#[derive(Properties, PartialEq)]
+pub struct ScreenshotLightboxProps {
+    pub screenshot: TestAttachment,
+    pub on_close: Callback<()>,
+    /// Receives the annotated image as a new PNG attachment
+    pub on_attach: Callback<TestAttachment>,
+}
+
+#[function_component(ScreenshotLightbox)]
+pub fn screenshot_lightbox(props: &ScreenshotLightboxProps) -> Html {
+    let image_ref = use_node_ref();
+    let canvas_ref = use_node_ref();
+    // No tool selected means the screenshot is only viewed
+    let tool = use_state(|| None::<Tool>);
+    let shapes = use_state(Vec::<Shape>::new);
+    let draft = use_state(|| None::<Shape>);
+    let image_size = use_state(|| None::<(u32, u32)>);
+
+    {
+        let canvas_ref = canvas_ref.clone();
+        use_effect_with(
+            ((*shapes).clone(), (*draft).clone(), *image_size),
+            move |(shapes, draft, image_size)| {
+                if let (Some(canvas), Some((width, height))) =
+                    (canvas_ref.cast::<HtmlCanvasElement>(), image_size)
+                {
+                    // Resizing also clears the previous drawing
+                    canvas.set_width(*width);
+                    canvas.set_height(*height);
+                    if let Err(e) = draw_shapes(&canvas, shapes.iter().chain(draft.iter())) {
+                        log::error!("Failed to draw annotations: {:?}", e);
+                    }
+                }
+            },
+        );
+    }
+
+    let onload = {
+        let image_size = image_size.clone();
+        Callback::from(move |e: Event| {
+            if let Some(image) = e.target_dyn_into::<HtmlImageElement>() {
+                image_size.set(Some((image.natural_width(), image.natural_height())));
+            }
+        })
+    };
+
+    let onmousedown = {
+        let tool = tool.clone();
+        let shapes = shapes.clone();
+        let draft = draft.clone();
+        let canvas_ref = canvas_ref.clone();
+        Callback::from(move |e: MouseEvent| {
+            let (Some(tool), Some(at)) = (*tool, image_point(&canvas_ref, &e)) else {
+                return;
+            };
+
+            if tool == Tool::Text {
+                let text = web_sys::window()
+                    .and_then(|window| window.prompt_with_message("Annotation text").ok())
+                    .flatten()
+                    .unwrap_or_default();
+                let shape = Shape::Text { at, text };
+                if shape.is_visible() {
+                    let mut next = (*shapes).clone();
+                    next.push(shape);
+                    shapes.set(next);
+                }
+            } else {
+                draft.set(Shape::begin(tool, at));
+            }
+        })
+    };
+
+    let onmousemove = {
+        let draft = draft.clone();
+        let canvas_ref = canvas_ref.clone();
+        Callback::from(move |e: MouseEvent| {
+            let (Some(mut shape), Some(to)) = ((*draft).clone(), image_point(&canvas_ref, &e))
+            else {
+                return;
+            };
+            shape.extend(to);
+            draft.set(Some(shape));
+        })
+    };
+
+    let finish_shape = {
+        let shapes = shapes.clone();
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(shape) = (*draft).clone() {
+                if shape.is_visible() {
+                    let mut next = (*shapes).clone();
+                    next.push(shape);
+                    shapes.set(next);
+                }
+                draft.set(None);
+            }
+        })
+    };
+
+    let on_undo = {
+        let shapes = shapes.clone();
+        Callback::from(move |_| {
+            let mut next = (*shapes).clone();
+            next.pop();
+            shapes.set(next);
+        })
+    };
+
+    let on_clear = {
+        let shapes = shapes.clone();
+        Callback::from(move |_| shapes.set(Vec::new()))
+    };
+
+    let on_download = {
+        let image_ref = image_ref.clone();
+        let shapes = shapes.clone();
+        let name = annotated_name(&props.screenshot.name);
+        Callback::from(move |_| match render_annotated(&image_ref, &shapes) {
+            Ok(data_url) => download(&data_url, &name),
+            Err(e) => log::error!("Failed to export annotated screenshot: {:?}", e),
+        })
+    };
+
+    let on_attach = {
+        let image_ref = image_ref.clone();
+        let shapes = shapes.clone();
+        let name = annotated_name(&props.screenshot.name);
+        let on_attach = props.on_attach.clone();
+        Callback::from(move |_| match render_annotated(&image_ref, &shapes) {
+            Ok(data_url) => on_attach.emit(TestAttachment {
+                name: name.clone(),
+                mime_type: "image/png".to_string(),
+                size_bytes: decode_data_url(&data_url).map(|bytes| bytes.len()),
+                data_url,
+                capture_failed: false,
+                archive_entry: None,
+            }),
+            Err(e) => log::error!("Failed to export annotated screenshot: {:?}", e),
+        })
+    };
+
+    let on_close = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| on_close.emit(()))
+    };
+
+    let annotating = tool.is_some();
+
+    html! {
+        <div class="lightbox-overlay" onclick={on_close.clone()}>
+            <div class="lightbox" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                <div class="lightbox-toolbar">
+                    <span class="lightbox-title">{ &props.screenshot.name }</span>
+                    <div class="annotation-tools">
+                        {
+                            Tool::ALL.iter().map(|option| {
+                                let option = *option;
+                                let selected = *tool == Some(option);
+                                let onclick = {
+                                    let tool = tool.clone();
+                                    Callback::from(move |_| {
+                                        tool.set(if selected { None } else { Some(option) });
+                                    })
+                                };
+                                html! {
+                                    <button
+                                        class={classes!("annotation-tool", selected.then_some("active"))}
+                                        {onclick}
+                                    >
+                                        { option.label() }
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                        <button class="annotation-tool" onclick={on_undo} disabled={shapes.is_empty()}>
+                            { "↶ Undo" }
+                        </button>
+                        <button class="annotation-tool" onclick={on_clear} disabled={shapes.is_empty()}>
+                            { "Clear" }
+                        </button>
+                    </div>
+                    <div class="lightbox-actions">
+                        <button class="annotation-tool" onclick={on_download} disabled={shapes.is_empty()}>
+                            { "📥 PNG" }
+                        </button>
+                        <button
+                            class="annotation-tool"
+                            onclick={on_attach}
+                            disabled={shapes.is_empty()}
+                            title="Add the annotated image to this test's screenshots"
+                        >
+                            { "📎 Attach to test" }
+                        </button>
+                        <button class="lightbox-close" onclick={on_close} title="Close">{ "✕" }</button>
+                    </div>
+                </div>
+                <div class={classes!("lightbox-stage", annotating.then_some("annotating"))}>
+                    <img
+                        ref={image_ref}
+                        src={props.screenshot.data_url.clone()}
+                        alt={props.screenshot.name.clone()}
+                        {onload}
+                    />
+                    <canvas
+                        ref={canvas_ref}
+                        class="annotation-canvas"
+                        {onmousedown}
+                        {onmousemove}
+                        onmouseup={finish_shape.clone()}
+                        onmouseleave={finish_shape}
+                    />
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Position of the mouse in image pixels
+fn image_point(canvas_ref: &NodeRef, e: &MouseEvent) -> Option<Point> {
+    let canvas = canvas_ref.cast::<HtmlCanvasElement>()?;
+    let rect = canvas.get_bounding_client_rect();
+    if rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return None;
+    }
+
+    Some(Point::new(
+        (e.client_x() as f64 - rect.left()) * canvas.width() as f64 / rect.width(),
+        (e.client_y() as f64 - rect.top()) * canvas.height() as f64 / rect.height(),
+    ))
+}
+
+fn context_2d(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, JsValue> {
+    canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()
+        .map_err(JsValue::from)
+}
+
+fn draw_shapes<'a>(
+    canvas: &HtmlCanvasElement,
+    shapes: impl Iterator<Item = &'a Shape>,
+) -> Result<(), JsValue> {
+    let context = context_2d(canvas)?;
+    let width = line_width(canvas.width() as f64);
+
+    context.set_stroke_style_str(ANNOTATION_COLOR);
+    context.set_fill_style_str(ANNOTATION_COLOR);
+    context.set_line_width(width);
+    context.set_line_cap("round");
+    context.set_line_join("round");
+    context.set_font(&format!("bold {}px sans-serif", (width * 8.0).round()));
+
+    for shape in shapes {
+        match shape {
+            Shape::Rectangle { start, end } => {
+                let (x, y, w, h) = normalized_rect(*start, *end);
+                context.stroke_rect(x, y, w, h);
+            }
+            Shape::Arrow { start, end } => {
+                context.begin_path();
+                context.move_to(start.x, start.y);
+                context.line_to(end.x, end.y);
+                for corner in arrow_head(*start, *end, width * 6.0) {
+                    context.move_to(end.x, end.y);
+                    context.line_to(corner.x, corner.y);
+                }
+                context.stroke();
+            }
+            Shape::Freehand { points } => {
+                context.begin_path();
+                for (index, point) in points.iter().enumerate() {
+                    if index == 0 {
+                        context.move_to(point.x, point.y);
+                    } else {
+                        context.line_to(point.x, point.y);
+                    }
+                }
+                context.stroke();
+            }
+            Shape::Text { at, text } => {
+                context.fill_text(text, at.x, at.y)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Flatten the screenshot and its annotations into a PNG data URL
+fn render_annotated(image_ref: &NodeRef, shapes: &[Shape]) -> Result<String, JsValue> {
+    let image = image_ref
+        .cast::<HtmlImageElement>()
+        .ok_or_else(|| JsValue::from_str("image not mounted"))?;
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+
+    let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+    canvas.set_width(image.natural_width());
+    canvas.set_height(image.natural_height());
+
+    context_2d(&canvas)?.draw_image_with_html_image_element(&image, 0.0, 0.0)?;
+    draw_shapes(&canvas, shapes.iter())?;
+
+    canvas.to_data_url_with_type("image/png")
+}
+
+fn download(data_url: &str, file_name: &str) {
+    let anchor = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+
+    match anchor {
+        Some(anchor) => {
+            anchor.set_href(data_url);
+            anchor.set_download(file_name);
+            anchor.click();
+        }
+        None => log::error!("Failed to create download link"),
+    }
+}
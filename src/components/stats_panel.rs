@@ -0,0 +1,145 @@
+use crate::file_probe::format_size;
+use crate::models::ActionEntry;
+use crate::stats::ContextStats;
+use yew::prelude::*;
+
+/// API methods listed in the time-by-method table
+const MAX_METHODS: usize = 15;
+
+#[derive(Properties, PartialEq)]
+pub struct StatsPanelProps {
+    pub stats: ContextStats,
+    /// Actions the slowest entries refer to
+    pub actions: Vec<ActionEntry>,
+    pub on_action_selected: Callback<ActionEntry>,
+}
+
+#[function_component(StatsPanel)]
+pub fn stats_panel(props: &StatsPanelProps) -> Html {
+    let stats = &props.stats;
+    if stats.is_empty() {
+        return html! {};
+    }
+
+    let max_total = stats
+        .method_totals
+        .iter()
+        .map(|total| total.total_ms)
+        .fold(0.0, f64::max);
+    let max_count = stats
+        .histogram
+        .iter()
+        .map(|bucket| bucket.count)
+        .max()
+        .unwrap_or(0);
+
+    html! {
+        <details class="stats-panel">
+            <summary class="stats-header">{ "Stats" }</summary>
+            <div class="stats-content">
+                <div class="stats-section">
+                    <h4>{ "Totals" }</h4>
+                    <table class="stats-table">
+                        { total_row("Actions", stats.action_count.to_string()) }
+                        { total_row("Failed", stats.failed_count.to_string()) }
+                        if stats.unfinished_count > 0 {
+                            { total_row("Unfinished", stats.unfinished_count.to_string()) }
+                        }
+                        { total_row("Time in actions", format!("{:.0}ms", stats.total_ms)) }
+                        if stats.failed_ms > 0.0 {
+                            { total_row("Time in failed actions", format!("{:.0}ms", stats.failed_ms)) }
+                        }
+                        if stats.error_count > 0 {
+                            { total_row("Context errors", stats.error_count.to_string()) }
+                        }
+                        if stats.network.requests > 0 {
+                            { total_row("Requests", format!("{} ({} failed)", stats.network.requests, stats.network.failed)) }
+                            { total_row("Transferred", format_size(stats.network.transferred_bytes)) }
+                        }
+                    </table>
+                </div>
+                <div class="stats-section">
+                    <h4>{ "Time by API method" }</h4>
+                    <table class="stats-table">
+                        {
+                            stats.method_totals.iter().take(MAX_METHODS).map(|total| {
+                                html! {
+                                    <tr>
+                                        <td class="stats-method code">{ &total.method }</td>
+                                        <td class="stats-count">{ format!("×{}", total.count) }</td>
+                                        <td class="stats-bar-cell">
+                                            <span class="stats-bar" style={bar_width(total.total_ms, max_total)}></span>
+                                        </td>
+                                        <td class="stats-value">{ format!("{:.0}ms", total.total_ms) }</td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </table>
+                </div>
+                <div class="stats-section">
+                    <h4>{ "Duration histogram" }</h4>
+                    <table class="stats-table">
+                        {
+                            stats.histogram.iter().map(|bucket| {
+                                html! {
+                                    <tr>
+                                        <td class="stats-bucket">{ &bucket.label }</td>
+                                        <td class="stats-bar-cell">
+                                            <span
+                                                class="stats-bar"
+                                                style={bar_width(bucket.count as f64, max_count as f64)}
+                                            ></span>
+                                        </td>
+                                        <td class="stats-value">{ bucket.count }</td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </table>
+                </div>
+                <div class="stats-section">
+                    <h4>{ "Slowest actions" }</h4>
+                    <ol class="stats-slowest">
+                        {
+                            stats.slowest.iter().map(|slow| {
+                                let action = props.actions.iter().find(|a| a.call_id == slow.call_id).cloned();
+                                let onclick = {
+                                    let on_action_selected = props.on_action_selected.clone();
+                                    Callback::from(move |_| {
+                                        if let Some(action) = &action {
+                                            on_action_selected.emit(action.clone());
+                                        }
+                                    })
+                                };
+
+                                html! {
+                                    <li>
+                                        <button class="stats-action" {onclick} title="Select action">
+                                            { &slow.label }
+                                        </button>
+                                        <span class="stats-value">{ format!("{:.0}ms", slow.duration_ms) }</span>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ol>
+                </div>
+            </div>
+        </details>
+    }
+}
+
+fn total_row(label: &str, value: String) -> Html {
+    html! {
+        <tr>
+            <td class="stats-label">{ label }</td>
+            <td class="stats-value">{ value }</td>
+        </tr>
+    }
+}
+
+fn bar_width(value: f64, max: f64) -> String {
+    let percent = if max > 0.0 { value / max * 100.0 } else { 0.0 };
+    format!("width: {:.1}%", percent)
+}
@@ -1,41 +1,216 @@
-use crate::models::{TestCase, TestStatus};
-use pulldown_cmark::{html, Options, Parser};
+use super::test_case_list::download_bytes;
+use super::{AnsiText, AriaTree, ImageDiffViewer, JsonTree, ScreenshotLightbox};
+use crate::analysis::{base_test_id, RetryGroup};
+use crate::content_security::render_markdown_offline;
+use crate::error_context::parse_error_context;
+use crate::models::{TestAttachment, TestCase, TestStatus, TraceContextLink};
+use crate::resource_store::ResourceStore;
+use crate::screenshot_archive::{build_screenshot_zip, test_case_images};
+use crate::test_case_loader::load_attachments;
+use crate::trace_links::TraceTarget;
+use crate::triage::{load_triage, save_triage, Triage, TriageStatus};
+use web_sys::{
+    Blob, BlobPropertyBag, HtmlMediaElement, HtmlSelectElement, HtmlTextAreaElement, Url,
+};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct TestCaseCardProps {
     pub test_case: TestCase,
+    /// Flakiness score across loaded runs, when several runs are loaded
+    #[prop_or_default]
+    pub flakiness: Option<f64>,
+    /// All attempts when the test was retried; `test_case` is the last one
+    #[prop_or_default]
+    pub retries: Option<RetryGroup>,
+    /// Target of the list's jump-to-test control
+    #[prop_or_default]
+    pub highlighted: bool,
+    /// Attachments too large to inline, decoded when requested
+    #[prop_or_default]
+    pub attachments: ResourceStore,
+    /// Opens the test's trace in the viewer
+    #[prop_or_default]
+    pub on_open_trace: Option<Callback<TraceTarget>>,
 }
 
 pub enum TestCaseCardMessage {
     ToggleExpanded,
+    SeekVideo(f64),
+    VideoDurationLoaded(f64),
+    LoadVideo,
+    SelectAttempt(usize),
+    OpenScreenshot(usize),
+    CloseScreenshot,
+    AttachAnnotated(TestAttachment),
+    SetTriageStatus(Option<TriageStatus>),
+    SetTriageNote(String),
+    DownloadScreenshots,
 }
 
 pub struct TestCaseCard {
     expanded: bool,
+    video_ref: NodeRef,
+    /// Shown attempt with its attachments read from the archive, on first expansion
+    loaded_attempt: Option<TestCase>,
+    video_duration: Option<f64>,
+    /// Object URL of a video decoded from the archive on request
+    video_url: Option<String>,
+    /// Attempt shown in the body; the last one when unset
+    selected_attempt: Option<usize>,
+    /// Index into the gallery of the screenshot shown in the lightbox
+    lightbox: Option<usize>,
+    /// Annotated copies made in the lightbox, shown after the originals
+    annotated_screenshots: Vec<TestAttachment>,
+    /// Triage notes of the test, kept in localStorage
+    triage: Triage,
 }
 
 impl Component for TestCaseCard {
     type Message = TestCaseCardMessage;
     type Properties = TestCaseCardProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self { expanded: false }
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            expanded: false,
+            video_ref: NodeRef::default(),
+            loaded_attempt: None,
+            video_duration: None,
+            video_url: None,
+            selected_attempt: None,
+            lightbox: None,
+            annotated_screenshots: Vec::new(),
+            triage: load_triage(triage_id(ctx)).unwrap_or_default(),
+        }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             TestCaseCardMessage::ToggleExpanded => {
                 self.expanded = !self.expanded;
+                if self.expanded && self.loaded_attempt.is_none() {
+                    self.load_attempt(ctx);
+                }
+                true
+            }
+            TestCaseCardMessage::SeekVideo(seconds) => {
+                if let Some(video) = self.video_ref.cast::<HtmlMediaElement>() {
+                    video.set_current_time(seconds);
+                    let _ = video.play();
+                }
+                false
+            }
+            TestCaseCardMessage::VideoDurationLoaded(duration) => {
+                // Recorded webm files often report an infinite duration
+                if duration.is_finite() && duration > 0.0 {
+                    self.video_duration = Some(duration);
+                    true
+                } else {
+                    false
+                }
+            }
+            TestCaseCardMessage::LoadVideo => {
+                let video = self.shown_attempt(ctx).video.as_ref();
+                let Some(entry) = video.and_then(|video| video.archive_entry.as_deref()) else {
+                    return false;
+                };
+                let mime_type = video.map_or("video/webm", |video| video.mime_type.as_str());
+
+                match ctx.props().attachments.get(entry) {
+                    Some(bytes) => match object_url(&bytes, mime_type) {
+                        Ok(url) => {
+                            self.revoke_video_url();
+                            self.video_url = Some(url);
+                        }
+                        Err(e) => log::error!("Failed to create video URL: {:?}", e),
+                    },
+                    None => log::error!("Video {} is not in the archive", entry),
+                }
+                true
+            }
+            TestCaseCardMessage::SelectAttempt(index) => {
+                self.selected_attempt = Some(index);
+                // Trace, video and screenshots all belong to the attempt
+                self.load_attempt(ctx);
+                self.video_duration = None;
+                self.revoke_video_url();
+                self.lightbox = None;
+                self.annotated_screenshots.clear();
+                true
+            }
+            TestCaseCardMessage::OpenScreenshot(index) => {
+                self.lightbox = Some(index);
+                true
+            }
+            TestCaseCardMessage::CloseScreenshot => {
+                self.lightbox = None;
                 true
             }
+            TestCaseCardMessage::AttachAnnotated(attachment) => {
+                self.annotated_screenshots.push(attachment);
+                self.lightbox = None;
+                true
+            }
+            TestCaseCardMessage::DownloadScreenshots => {
+                let test_case = self.shown_attempt(ctx);
+                let diffs = test_case_images(test_case)
+                    .into_iter()
+                    .filter(|image| !test_case.screenshots.contains(image));
+                let images = self
+                    .gallery(test_case)
+                    .into_iter()
+                    .filter(|image| !image.capture_failed)
+                    .chain(diffs)
+                    .map(|image| (image.name.clone(), image));
+
+                let result = build_screenshot_zip(images, &ctx.props().attachments)
+                    .map_err(wasm_bindgen::JsValue::from)
+                    .and_then(|zip| {
+                        let file_name = format!("{}-screenshots.zip", test_case.id);
+                        download_bytes(&zip, "application/zip", &file_name)
+                    });
+                if let Err(e) = result {
+                    log::error!("Failed to download screenshots: {:?}", e);
+                }
+                false
+            }
+            TestCaseCardMessage::SetTriageStatus(status) => {
+                self.triage.status = status;
+                save_triage(triage_id(ctx), &self.triage);
+                true
+            }
+            TestCaseCardMessage::SetTriageNote(note) => {
+                self.triage.note = note;
+                save_triage(triage_id(ctx), &self.triage);
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        let props = ctx.props();
+        if props.test_case != old_props.test_case || props.retries != old_props.retries {
+            // A reload or new run; drop what was read for the old attempt
+            self.loaded_attempt = None;
+            self.revoke_video_url();
+            if self.expanded {
+                self.load_attempt(ctx);
+            }
+        }
+        if props.test_case.id != old_props.test_case.id {
+            self.triage = load_triage(triage_id(ctx)).unwrap_or_default();
         }
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let test_case = &ctx.props().test_case;
+        let retries = ctx.props().retries.as_ref();
         let expanded = self.expanded;
 
+        // Later attempts are titled after the first one
+        let name = retries.map_or(&test_case.name, |group| &group.attempts[0].name);
+
         let status_class = match test_case.status {
             TestStatus::Passed => "status-passed",
             TestStatus::Failed => "status-failed",
@@ -46,7 +221,8 @@ impl Component for TestCaseCard {
         let card_class = classes!(
             "test-case-card",
             status_class,
-            expanded.then_some("expanded")
+            expanded.then_some("expanded"),
+            ctx.props().highlighted.then_some("highlighted")
         );
 
         let onclick = ctx.link().callback(|_| TestCaseCardMessage::ToggleExpanded);
@@ -61,9 +237,23 @@ impl Component for TestCaseCard {
                         <span class={classes!("test-status-badge", status_class)}>
                             { test_case.status.to_string() }
                         </span>
-                        <h3 class="test-case-name">{ &test_case.name }</h3>
+                        <h3 class="test-case-name">{ name }</h3>
+                        { render_chips(test_case) }
                     </div>
                     <div class="test-case-header-right">
+                        { render_triage_badge(&self.triage) }
+                        { render_retry_badge(retries) }
+                        {
+                            if let Some(score) = ctx.props().flakiness.filter(|score| *score > 0.0) {
+                                html! {
+                                    <span class="flakiness-badge" title="Flakiness across loaded runs">
+                                        { format!("Flaky {:.0}%", score * 100.0) }
+                                    </span>
+                                }
+                            } else {
+                                html! {}
+                            }
+                        }
                         {
                             if let Some(duration) = test_case.duration_ms {
                                 html! {
@@ -80,13 +270,19 @@ impl Component for TestCaseCard {
 
                 {
                     if expanded {
+                        let test_case = self.shown_attempt(ctx);
                         html! {
                             <div class="test-case-content">
+                                { self.render_attempt_picker(ctx) }
+                                { self.render_triage(ctx) }
                                 { self.render_error_message(test_case) }
                                 { self.render_markdown(test_case) }
-                                { self.render_screenshots(test_case) }
-                                { self.render_video(test_case) }
-                                { self.render_trace_link(test_case) }
+                                { render_image_diffs(test_case) }
+                                { self.render_screenshots(ctx, test_case) }
+                                { self.render_lightbox(ctx, test_case) }
+                                { self.render_video(ctx, test_case) }
+                                { self.render_text_attachments(test_case) }
+                                { self.render_trace_link(ctx, test_case) }
                             </div>
                         }
                     } else {
@@ -96,9 +292,117 @@ impl Component for TestCaseCard {
             </div>
         }
     }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.revoke_video_url();
+    }
 }
 
 impl TestCaseCard {
+    fn revoke_video_url(&mut self) {
+        if let Some(url) = self.video_url.take() {
+            let _ = Url::revoke_object_url(&url);
+        }
+    }
+
+    fn render_triage(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let value = e
+                .target_dyn_into::<HtmlSelectElement>()
+                .map(|select| select.value())
+                .unwrap_or_default();
+            let status = TriageStatus::ALL
+                .into_iter()
+                .find(|status| status.label() == value);
+            TestCaseCardMessage::SetTriageStatus(status)
+        });
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            let note = e
+                .target_dyn_into::<HtmlTextAreaElement>()
+                .map(|textarea| textarea.value())
+                .unwrap_or_default();
+            TestCaseCardMessage::SetTriageNote(note)
+        });
+
+        html! {
+            <div class="test-triage">
+                <label class="attempt-picker">
+                    { "Triage: " }
+                    <select {onchange}>
+                        <option value="" selected={self.triage.status.is_none()}>{ "Not triaged" }</option>
+                        {
+                            for TriageStatus::ALL.iter().map(|status| html! {
+                                <option
+                                    value={status.label()}
+                                    selected={self.triage.status == Some(*status)}
+                                >
+                                    { status.label() }
+                                </option>
+                            })
+                        }
+                    </select>
+                </label>
+                <textarea
+                    class="triage-note"
+                    placeholder="Note, e.g. a link to the issue"
+                    rows="2"
+                    value={self.triage.note.clone()}
+                    {oninput}
+                />
+            </div>
+        }
+    }
+
+    /// Attempt picked in the body, as passed in without its attachments
+    fn picked_attempt<'a>(&self, ctx: &'a Context<Self>) -> &'a TestCase {
+        let props = ctx.props();
+        self.selected_attempt
+            .zip(props.retries.as_ref())
+            .and_then(|(index, group)| group.attempts.get(index))
+            .unwrap_or(&props.test_case)
+    }
+
+    fn shown_attempt<'a>(&'a self, ctx: &'a Context<Self>) -> &'a TestCase {
+        self.loaded_attempt
+            .as_ref()
+            .unwrap_or_else(|| self.picked_attempt(ctx))
+    }
+
+    fn load_attempt(&mut self, ctx: &Context<Self>) {
+        let attempt = load_attachments(self.picked_attempt(ctx), &ctx.props().attachments);
+        self.loaded_attempt = Some(attempt);
+    }
+
+    fn render_attempt_picker(&self, ctx: &Context<Self>) -> Html {
+        let Some(attempts) = ctx.props().retries.as_ref().map(|group| &group.attempts) else {
+            return html! {};
+        };
+
+        let selected = self.selected_attempt.unwrap_or(attempts.len() - 1);
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            e.target_dyn_into::<HtmlSelectElement>()
+                .and_then(|select| select.value().parse().ok())
+                .map(TestCaseCardMessage::SelectAttempt)
+        });
+
+        html! {
+            <div class="attempt-picker">
+                <label>
+                    { "Attempt: " }
+                    <select {onchange}>
+                        {
+                            attempts.iter().enumerate().map(|(index, attempt)| html! {
+                                <option value={index.to_string()} selected={index == selected}>
+                                    { format!("{} – {}", attempt_label(index), attempt.status.to_string()) }
+                                </option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </label>
+            </div>
+        }
+    }
+
     fn render_error_message(&self, test_case: &TestCase) -> Html {
         if let Some(error_msg) = &test_case.error_message {
             html! {
@@ -113,47 +417,144 @@ impl TestCaseCard {
     }
 
     fn render_markdown(&self, test_case: &TestCase) -> Html {
-        if let Some(markdown_content) = &test_case.markdown_content {
-            // Parse markdown to HTML
-            let mut options = Options::empty();
-            options.insert(Options::ENABLE_STRIKETHROUGH);
-            options.insert(Options::ENABLE_TABLES);
-            options.insert(Options::ENABLE_TASKLISTS);
-
-            let parser = Parser::new_ext(markdown_content, options);
-            let mut html_output = String::new();
-            html::push_html(&mut html_output, parser);
+        let Some(markdown_content) = &test_case.markdown_content else {
+            return html! {};
+        };
 
-            html! {
+        let context = parse_error_context(markdown_content);
+        if !context.is_structured() {
+            return html! {
                 <div class="test-markdown-content">
-                    <div class="markdown-rendered">
-                        { Html::from_html_unchecked(AttrValue::from(html_output)) }
-                    </div>
+                    { render_offline_markdown(test_case, markdown_content) }
                 </div>
-            }
-        } else {
-            html! {}
+            };
         }
+
+        html! {
+            <div class="test-markdown-content error-context">
+                {
+                    if context.test_info.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <dl class="error-context-info">
+                                {
+                                    for context.test_info.iter().map(|(name, value)| html! {
+                                        <>
+                                            <dt>{ name }</dt>
+                                            <dd>{ value }</dd>
+                                        </>
+                                    })
+                                }
+                            </dl>
+                        }
+                    }
+                }
+                {
+                    if let Some(error) = &context.error {
+                        html! { <pre class="ansi-pre error-context-error"><AnsiText text={error.clone()} /></pre> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(snapshot) = &context.page_snapshot {
+                        html! {
+                            <details class="error-context-section" open={true}>
+                                <summary>{ "Page snapshot" }</summary>
+                                <AriaTree nodes={snapshot.clone()} />
+                            </details>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(source) = &context.test_source {
+                        html! {
+                            <details class="error-context-section">
+                                <summary>{ "Test source" }</summary>
+                                <pre class="error-context-source">{ source }</pre>
+                            </details>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    for context.other_sections.iter().map(|(title, body)| html! {
+                        <details class="error-context-section">
+                            <summary>{ title }</summary>
+                            { render_offline_markdown(test_case, body) }
+                        </details>
+                    })
+                }
+            </div>
+        }
+    }
+
+    /// Screenshots from the archive followed by annotated copies
+    fn gallery<'a>(&'a self, test_case: &'a TestCase) -> Vec<&'a TestAttachment> {
+        test_case
+            .screenshots
+            .iter()
+            .chain(self.annotated_screenshots.iter())
+            .collect()
     }
 
-    fn render_screenshots(&self, test_case: &TestCase) -> Html {
-        if test_case.screenshots.is_empty() {
+    fn render_screenshots(&self, ctx: &Context<Self>, test_case: &TestCase) -> Html {
+        let gallery = self.gallery(test_case);
+        if gallery.is_empty() {
             return html! {};
         }
 
         html! {
             <div class="test-screenshots">
-                <h4>{ "Screenshots" }</h4>
+                <div class="test-screenshots-header">
+                    <h4>{ "Screenshots" }</h4>
+                    {
+                        if gallery.len() + test_case.image_diffs.len() > 1 {
+                            html! {
+                                <button
+                                    class="download-screenshots-button"
+                                    onclick={ctx.link().callback(|_| TestCaseCardMessage::DownloadScreenshots)}
+                                    title="Download the screenshots and comparison images as a zip"
+                                >
+                                    { "📦 Download all screenshots" }
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
                 <div class="screenshot-gallery">
                     {
-                        test_case.screenshots.iter().map(|screenshot| {
+                        gallery.iter().enumerate().map(|(index, screenshot)| {
+                            let onclick = (!screenshot.capture_failed).then(|| {
+                                ctx.link().callback(move |_| TestCaseCardMessage::OpenScreenshot(index))
+                            });
                             html! {
-                                <div class="screenshot-item">
-                                    <img
-                                        src={screenshot.data_url.clone()}
-                                        alt={screenshot.name.clone()}
-                                        title={screenshot.name.clone()}
-                                    />
+                                <div
+                                    class={classes!(
+                                        "screenshot-item",
+                                        screenshot.capture_failed.then_some("capture-failed")
+                                    )}
+                                    {onclick}
+                                >
+                                    {
+                                        if screenshot.capture_failed {
+                                            render_capture_failed()
+                                        } else {
+                                            html! {
+                                                <img
+                                                    src={screenshot.data_url.clone()}
+                                                    alt={screenshot.name.clone()}
+                                                    title={screenshot.name.clone()}
+                                                />
+                                            }
+                                        }
+                                    }
                                     <div class="screenshot-name">
                                         { &screenshot.name }
                                     </div>
@@ -166,30 +567,91 @@ impl TestCaseCard {
         }
     }
 
-    fn render_video(&self, test_case: &TestCase) -> Html {
-        if let Some(video) = &test_case.video {
+    fn render_lightbox(&self, ctx: &Context<Self>, test_case: &TestCase) -> Html {
+        let Some(screenshot) = self
+            .lightbox
+            .and_then(|index| self.gallery(test_case).get(index).copied())
+        else {
+            return html! {};
+        };
+
+        html! {
+            <ScreenshotLightbox
+                screenshot={screenshot.clone()}
+                on_close={ctx.link().callback(|_| TestCaseCardMessage::CloseScreenshot)}
+                on_attach={ctx.link().callback(TestCaseCardMessage::AttachAnnotated)}
+            />
+        }
+    }
+
+    fn render_video(&self, ctx: &Context<Self>, test_case: &TestCase) -> Html {
+        if let Some(video) = test_case
+            .video
+            .as_ref()
+            .filter(|video| video.capture_failed)
+        {
+            html! {
+                <div class="test-video">
+                    <h4>{ "Video Recording" }</h4>
+                    { render_capture_failed() }
+                    <div class="video-info">
+                        <span class="video-name">{ &video.name }</span>
+                    </div>
+                </div>
+            }
+        } else if let Some(video) = test_case
+            .video
+            .as_ref()
+            .filter(|video| video.is_deferred() && self.video_url.is_none())
+        {
+            // Decoding is left to the user so large runs stay responsive
+            html! {
+                <div class="test-video">
+                    <h4>{ "Video Recording" }</h4>
+                    <div class="video-deferred">
+                        <button
+                            class="load-video-button"
+                            onclick={ctx.link().callback(|_| TestCaseCardMessage::LoadVideo)}
+                        >
+                            { "▶ Load video" }
+                        </button>
+                        <span class="video-deferred-note">{ "Too large to load up front" }</span>
+                    </div>
+                    <div class="video-info">
+                        <span class="video-name">{ &video.name }</span>
+                        { render_video_size(video) }
+                    </div>
+                </div>
+            }
+        } else if let Some(video) = &test_case.video {
+            let src = self
+                .video_url
+                .clone()
+                .unwrap_or_else(|| video.data_url.clone());
             html! {
                 <div class="test-video">
                     <h4>{ "Video Recording" }</h4>
                     <div class="video-player">
-                        <video controls={true} preload="metadata">
-                            <source src={video.data_url.clone()} type={video.mime_type.clone()} />
+                        <video
+                            ref={self.video_ref.clone()}
+                            controls={true}
+                            preload="metadata"
+                            onloadedmetadata={ctx.link().callback(|e: Event| {
+                                let duration = e
+                                    .target_dyn_into::<HtmlMediaElement>()
+                                    .map(|video| video.duration())
+                                    .unwrap_or(f64::NAN);
+                                TestCaseCardMessage::VideoDurationLoaded(duration)
+                            })}
+                        >
+                            <source {src} type={video.mime_type.clone()} />
                             { "Your browser does not support the video tag." }
                         </video>
                     </div>
+                    { self.render_video_chapters(ctx, test_case) }
                     <div class="video-info">
                         <span class="video-name">{ &video.name }</span>
-                        {
-                            if let Some(size) = video.size_bytes {
-                                html! {
-                                    <span class="video-size">
-                                        { format!(" ({:.1} MB)", size as f64 / 1024.0 / 1024.0) }
-                                    </span>
-                                }
-                            } else {
-                                html! {}
-                            }
-                        }
+                        { render_video_size(video) }
                     </div>
                 </div>
             }
@@ -198,11 +660,137 @@ impl TestCaseCard {
         }
     }
 
-    fn render_trace_link(&self, test_case: &TestCase) -> Html {
+    fn render_video_chapters(&self, ctx: &Context<Self>, test_case: &TestCase) -> Html {
+        let chapters = match &test_case.trace_overview {
+            Some(overview) if !overview.video_chapters.is_empty() => &overview.video_chapters,
+            _ => return html! {},
+        };
+
+        // Fall back to the traced span when the video does not report its length
+        let traced_span = chapters
+            .iter()
+            .map(|chapter| chapter.offset_seconds + chapter.duration_seconds)
+            .fold(0.0, f64::max);
+        let span = self.video_duration.unwrap_or(traced_span).max(traced_span);
+        let position = |seconds: f64| {
+            if span > 0.0 {
+                (seconds / span * 100.0).min(100.0)
+            } else {
+                0.0
+            }
+        };
+
+        html! {
+            <div class="video-chapters">
+                <div class="video-chapter-track">
+                    {
+                        chapters.iter().map(|chapter| {
+                            let offset = chapter.offset_seconds;
+                            let onclick = ctx.link().callback(move |_| TestCaseCardMessage::SeekVideo(offset));
+                            let style = format!("left: {:.2}%", position(offset));
+
+                            html! {
+                                <span
+                                    class={classes!("video-chapter-marker", chapter.failed.then_some("failed"))}
+                                    {style}
+                                    title={format!("{:.1}s {}", offset, chapter.title)}
+                                    {onclick}
+                                />
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+                <ul class="video-chapter-list">
+                    {
+                        chapters.iter().map(|chapter| {
+                            let offset = chapter.offset_seconds;
+                            let onclick = ctx.link().callback(move |_| TestCaseCardMessage::SeekVideo(offset));
+
+                            html! {
+                                <li
+                                    class={classes!("video-chapter", chapter.failed.then_some("failed"))}
+                                    {onclick}
+                                >
+                                    <span class="video-chapter-time">{ format!("{:.1}s", offset) }</span>
+                                    <span class="video-chapter-title">{ &chapter.title }</span>
+                                </li>
+                            }
+                        }).collect::<Html>()
+                    }
+                </ul>
+            </div>
+        }
+    }
+
+    fn render_text_attachments(&self, test_case: &TestCase) -> Html {
+        if test_case.text_attachments.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <div class="test-text-attachments">
+                <h4>{ format!("Attachments ({})", test_case.text_attachments.len()) }</h4>
+                {
+                    test_case.text_attachments.iter().map(|attachment| {
+                        html! {
+                            <details key={attachment.name.clone()} class="text-attachment">
+                                <summary class="text-attachment-name">{ &attachment.name }</summary>
+                                {
+                                    if attachment.mime_type == "application/json" {
+                                        html! {
+                                            <div class="text-attachment-content">
+                                                <JsonTree content={attachment.content.clone()} />
+                                            </div>
+                                        }
+                                    } else {
+                                        // Playwright stdout is often colored
+                                        html! {
+                                            <pre class="ansi-pre text-attachment-content">
+                                                <AnsiText text={attachment.content.clone()} />
+                                            </pre>
+                                        }
+                                    }
+                                }
+                            </details>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    fn render_trace_summary(&self, test_case: &TestCase) -> Html {
+        let Some(summary) = test_case
+            .trace_overview
+            .as_ref()
+            .map(|overview| &overview.summary)
+        else {
+            return html! {};
+        };
+
+        let outcome = match &summary.failed_action {
+            Some(action) => format!(", failed at {}", action),
+            None => String::new(),
+        };
+
+        html! {
+            <p class={classes!("trace-summary", summary.failed_action.is_some().then_some("failed"))}>
+                { format!(
+                    "Trace: {} actions in {:.1}s{}",
+                    summary.action_count,
+                    summary.duration_ms / 1000.0,
+                    outcome
+                ) }
+            </p>
+        }
+    }
+
+    fn render_trace_link(&self, ctx: &Context<Self>, test_case: &TestCase) -> Html {
         if let Some(trace) = &test_case.trace_file {
             html! {
                 <div class="test-trace-link">
                     <h4>{ "Trace File" }</h4>
+                    { self.render_trace_summary(test_case) }
                     <div class="trace-download">
                         <a
                             href={trace.data_url.clone()}
@@ -223,6 +811,12 @@ impl TestCaseCard {
                             }
                         </a>
                     </div>
+                    {
+                        match &ctx.props().on_open_trace {
+                            Some(on_open) => render_trace_contexts(test_case, on_open),
+                            None => html! {},
+                        }
+                    }
                 </div>
             }
         } else {
@@ -230,3 +824,201 @@ impl TestCaseCard {
         }
     }
 }
+
+/// Buttons opening the trace at each context and failed action
+fn render_trace_contexts(test_case: &TestCase, on_open: &Callback<TraceTarget>) -> Html {
+    let open = |link: &TraceContextLink, call_id: Option<&str>| {
+        let on_open = on_open.clone();
+        let target = TraceTarget {
+            test_case_id: test_case.id.clone(),
+            context_id: link.context_id.clone(),
+            call_id: call_id.map(str::to_string),
+        };
+        Callback::from(move |_| on_open.emit(target.clone()))
+    };
+
+    html! {
+        <ul class="trace-contexts">
+            {
+                test_case.trace_contexts.iter().map(|link| {
+                    // The test runner's context has no browser
+                    let name = if link.browser_name.is_empty() {
+                        "test runner"
+                    } else {
+                        link.browser_name.as_str()
+                    };
+
+                    html! {
+                        <li class="trace-context">
+                            <button class="trace-open-button" onclick={open(link, None)}>
+                                { format!("🎬 Open {} context", name) }
+                            </button>
+                            {
+                                link.failed_actions.iter().map(|failed| {
+                                    html! {
+                                        <button
+                                            class="trace-failed-action"
+                                            onclick={open(link, Some(&failed.call_id))}
+                                            title="Open the trace at this action"
+                                        >
+                                            { format!("⚠️ {}", failed.label) }
+                                        </button>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </li>
+                    }
+                }).collect::<Html>()
+            }
+        </ul>
+    }
+}
+
+fn attempt_label(index: usize) -> String {
+    if index == 0 {
+        "First run".to_string()
+    } else {
+        format!("Retry {}", index)
+    }
+}
+
+/// Outcome across retries, e.g. "Passed on retry 2"
+fn render_retry_badge(retries: Option<&RetryGroup>) -> Html {
+    let Some(group) = retries else {
+        return html! {};
+    };
+
+    let text = if group.passed_on_retry() {
+        format!("Passed on retry {}", group.attempts.len() - 1)
+    } else {
+        format!("{} attempts", group.attempts.len())
+    };
+
+    html! {
+        <span class="retry-badge" title="Retries of this test in the run">{ text }</span>
+    }
+}
+
+/// Triage is shared by the attempts of a test and by later runs of it
+fn triage_id(ctx: &Context<TestCaseCard>) -> &str {
+    base_test_id(&ctx.props().test_case.id).0
+}
+
+fn render_triage_badge(triage: &Triage) -> Html {
+    let Some(status) = triage.status else {
+        return html! {};
+    };
+    let class = match status {
+        TriageStatus::Investigating => "investigating",
+        TriageStatus::KnownIssue => "known-issue",
+        TriageStatus::Fixed => "fixed",
+    };
+
+    html! {
+        <span class={classes!("triage-badge", class)} title={triage.note.clone()}>
+            { status.label() }
+        </span>
+    }
+}
+
+fn render_video_size(video: &TestAttachment) -> Html {
+    match video.size_bytes {
+        Some(size) => html! {
+            <span class="video-size">
+                { format!(" ({:.1} MB)", size as f64 / 1024.0 / 1024.0) }
+            </span>
+        },
+        None => html! {},
+    }
+}
+
+/// Object URL for bytes decoded from the archive; revoke it when done
+fn object_url(bytes: &[u8], mime_type: &str) -> Result<String, wasm_bindgen::JsValue> {
+    let array = js_sys::Array::new();
+    array.push(&js_sys::Uint8Array::from(bytes));
+
+    let blob_options = BlobPropertyBag::new();
+    blob_options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&array, &blob_options)?;
+    Url::create_object_url_with_blob(&blob)
+}
+
+/// Project, tags and annotations from the report metadata
+fn render_chips(test_case: &TestCase) -> Html {
+    if test_case.project.is_none() && test_case.tags.is_empty() && test_case.annotations.is_empty()
+    {
+        return html! {};
+    }
+
+    html! {
+        <span class="test-chips">
+            {
+                if let Some(project) = &test_case.project {
+                    html! { <span class="test-chip project" title="Project">{ project }</span> }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                test_case.tags.iter().map(|tag| html! {
+                    <span class="test-chip tag">{ tag }</span>
+                }).collect::<Html>()
+            }
+            {
+                test_case.annotations.iter().map(|annotation| {
+                    let text = match &annotation.description {
+                        Some(description) => format!("{}: {}", annotation.annotation_type, description),
+                        None => annotation.annotation_type.clone(),
+                    };
+                    html! {
+                        <span class="test-chip annotation" title={text.clone()}>{ text }</span>
+                    }
+                }).collect::<Html>()
+            }
+        </span>
+    }
+}
+
+/// Shown instead of an attachment that cannot be displayed
+fn render_offline_markdown(test_case: &TestCase, markdown: &str) -> Html {
+    // Images may only come from the test's own attachments
+    let html_output = render_markdown_offline(markdown, |name| {
+        test_case
+            .screenshots
+            .iter()
+            .find(|screenshot| screenshot.name == name)
+            .map(|screenshot| screenshot.data_url.clone())
+    });
+
+    html! {
+        <div class="markdown-rendered">
+            { Html::from_html_unchecked(AttrValue::from(html_output)) }
+        </div>
+    }
+}
+
+fn render_image_diffs(test_case: &TestCase) -> Html {
+    if test_case.image_diffs.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="test-image-diffs">
+            <h4>{ "Screenshot Comparisons" }</h4>
+            {
+                for test_case.image_diffs.iter().map(|diff| html! {
+                    <ImageDiffViewer key={diff.name.clone()} diff={diff.clone()} />
+                })
+            }
+        </div>
+    }
+}
+
+fn render_capture_failed() -> Html {
+    html! {
+        <div class="capture-failed-placeholder" title="The file is empty or corrupt">
+            <span class="capture-failed-icon">{ "⚠️" }</span>
+            <span>{ "Capture failed" }</span>
+        </div>
+    }
+}
@@ -1,14 +1,55 @@
+use crate::analysis::{
+    base_test_id, cluster_failures, compute_flakiness, find_test, group_retries, sort_groups,
+    FailureCluster, RetryGroup, TestFlakiness, TestSortOrder,
+};
 use crate::components::test_case_card::TestCaseCard;
 use crate::models::{TestCaseCollection, TestStatus};
+use crate::screenshot_archive::{build_screenshot_zip, test_case_images};
+use crate::test_report_exporter::{export_failed_summary, export_test_run_to_markdown};
+use crate::test_tree::{build_suite_tree, has_suites, SuiteNode};
+use crate::trace_links::TraceTarget;
+use crate::triage::load_triage;
+use gloo::timers::callback::Timeout;
+use std::collections::{BTreeSet, HashMap};
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, HtmlInputElement, HtmlSelectElement, Url};
 use yew::prelude::*;
 
+/// Number of tests listed in the "Most Flaky" section
+const MOST_FLAKY_LIMIT: usize = 5;
+
+/// How long the copy button confirms a copy
+const COPIED_FEEDBACK_MS: u32 = 2000;
+
+/// Number of tests rendered per page
+const PAGE_SIZE: usize = 50;
+
 #[derive(Properties, PartialEq)]
 pub struct TestCaseListProps {
     pub test_cases: TestCaseCollection,
+    /// All loaded runs of the suite, oldest first, for trend analysis
+    #[prop_or_default]
+    pub runs: Vec<TestCaseCollection>,
+    /// Test to jump to when the list opens, e.g. when coming back from its trace
+    #[prop_or_default]
+    pub initial_test: Option<AttrValue>,
+    /// Opens a test's trace in the viewer
+    #[prop_or_default]
+    pub on_open_trace: Option<Callback<TraceTarget>>,
 }
 
 pub enum TestCaseListMessage {
     FilterChanged(TestStatusFilter),
+    SortChanged(TestSortOrder),
+    FilterByTag(Option<String>),
+    FilterByProject(Option<String>),
+    ToggleGroupByError,
+    SetPage(usize),
+    JumpToTest(String),
+    ExportReport,
+    DownloadFailedScreenshots,
+    CopyFailedSummary,
+    SetSummaryCopied(bool),
 }
 
 #[derive(Clone, PartialEq)]
@@ -21,60 +62,298 @@ pub enum TestStatusFilter {
 
 pub struct TestCaseList {
     filter: TestStatusFilter,
+    sort_order: TestSortOrder,
+    /// Only show tests with this tag
+    tag_filter: Option<String>,
+    /// Only show tests of this project, e.g. `chromium`
+    project_filter: Option<String>,
+    /// Show failures clustered by error signature instead of one card each
+    group_by_error: bool,
+    page: usize,
+    /// Query of the jump-to-test control; its match decides the page
+    jump_query: Option<String>,
+    /// Scroll the jump target into view after the next render
+    scroll_to_target: bool,
+    summary_copied: bool,
 }
 
 impl Component for TestCaseList {
     type Message = TestCaseListMessage;
     type Properties = TestCaseListProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let jump_query = ctx.props().initial_test.as_ref().map(AttrValue::to_string);
+
         Self {
             filter: TestStatusFilter::All,
+            sort_order: TestSortOrder::Default,
+            tag_filter: None,
+            project_filter: None,
+            group_by_error: false,
+            page: 0,
+            scroll_to_target: jump_query.is_some(),
+            jump_query,
+            summary_copied: false,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             TestCaseListMessage::FilterChanged(filter) => {
                 self.filter = filter;
+                self.reset_page();
+                true
+            }
+            TestCaseListMessage::SortChanged(sort_order) => {
+                self.sort_order = sort_order;
+                self.reset_page();
+                true
+            }
+            TestCaseListMessage::FilterByTag(tag) => {
+                self.tag_filter = tag;
+                self.reset_page();
+                true
+            }
+            TestCaseListMessage::FilterByProject(project) => {
+                self.project_filter = project;
+                self.reset_page();
                 true
             }
+            TestCaseListMessage::ToggleGroupByError => {
+                self.group_by_error = !self.group_by_error;
+                self.reset_page();
+                true
+            }
+            TestCaseListMessage::SetPage(page) => {
+                self.page = page;
+                self.jump_query = None;
+                true
+            }
+            TestCaseListMessage::JumpToTest(query) => {
+                self.jump_query = (!query.trim().is_empty()).then_some(query);
+                self.scroll_to_target = self.jump_query.is_some();
+                true
+            }
+            TestCaseListMessage::ExportReport => {
+                let markdown = export_test_run_to_markdown(&ctx.props().test_cases, load_triage);
+                if let Err(e) = download_markdown(&markdown, "test-report.md") {
+                    log::error!("Failed to export test report: {:?}", e);
+                }
+                false
+            }
+            TestCaseListMessage::DownloadFailedScreenshots => {
+                // One folder per failed test
+                let collection = &ctx.props().test_cases;
+                let groups = group_retries(&collection.test_cases);
+                let images = groups
+                    .iter()
+                    .map(RetryGroup::final_attempt)
+                    .filter(|test_case| test_case.status == TestStatus::Failed)
+                    .flat_map(|test_case| {
+                        test_case_images(test_case)
+                            .into_iter()
+                            .map(|image| (format!("{}/{}", test_case.id, image.name), image))
+                    });
+
+                let result = build_screenshot_zip(images, &collection.attachment_store)
+                    .map_err(wasm_bindgen::JsValue::from)
+                    .and_then(|zip| {
+                        download_bytes(&zip, "application/zip", "failed-screenshots.zip")
+                    });
+                if let Err(e) = result {
+                    log::error!("Failed to download screenshots: {:?}", e);
+                }
+                false
+            }
+            TestCaseListMessage::CopyFailedSummary => {
+                let summary = export_failed_summary(&ctx.props().test_cases, load_triage);
+                let Some(window) = web_sys::window() else {
+                    return false;
+                };
+
+                let promise = window.navigator().clipboard().write_text(&summary);
+                let link = ctx.link().clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match wasm_bindgen_futures::JsFuture::from(promise).await {
+                        Ok(_) => {
+                            link.send_message(TestCaseListMessage::SetSummaryCopied(true));
+                            Timeout::new(COPIED_FEEDBACK_MS, move || {
+                                link.send_message(TestCaseListMessage::SetSummaryCopied(false))
+                            })
+                            .forget();
+                        }
+                        Err(e) => log::error!("Failed to copy to clipboard: {:?}", e),
+                    }
+                });
+                false
+            }
+            TestCaseListMessage::SetSummaryCopied(copied) => {
+                self.summary_copied = copied;
+                true
+            }
+        }
+    }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        if !std::mem::take(&mut self.scroll_to_target) {
+            return;
+        }
+
+        let target = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| {
+                document
+                    .query_selector(".test-case-card.highlighted")
+                    .ok()
+                    .flatten()
+            });
+        if let Some(target) = target {
+            target.scroll_into_view();
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let test_cases = &ctx.props().test_cases.test_cases;
 
+        // Flakiness needs more than one run to compare
+        let runs = &ctx.props().runs;
+        let flakiness = if runs.len() > 1 {
+            compute_flakiness(runs)
+        } else {
+            Vec::new()
+        };
+        let scores: HashMap<&str, f64> = flakiness
+            .iter()
+            .map(|test| (test.test_id.as_str(), test.flakiness.score))
+            .collect();
+        let score_of = |id: &str| scores.get(base_test_id(id).0).copied();
+
+        // Retries are shown as one test with the outcome of its last attempt
+        let groups = group_retries(test_cases);
+
         // Filter test cases based on current filter
-        let filtered_cases: Vec<_> = test_cases
+        let mut filtered_groups: Vec<_> = groups
             .iter()
-            .filter(|tc| match self.filter {
-                TestStatusFilter::All => true,
-                TestStatusFilter::Failed => tc.status == TestStatus::Failed,
-                TestStatusFilter::Passed => tc.status == TestStatus::Passed,
-                TestStatusFilter::Skipped => tc.status == TestStatus::Skipped,
+            .filter(|group| {
+                let tc = group.final_attempt();
+                match self.filter {
+                    TestStatusFilter::All => true,
+                    TestStatusFilter::Failed => tc.status == TestStatus::Failed,
+                    TestStatusFilter::Passed => tc.status == TestStatus::Passed,
+                    TestStatusFilter::Skipped => tc.status == TestStatus::Skipped,
+                }
+            })
+            .filter(|group| match &self.tag_filter {
+                Some(tag) => group.final_attempt().tags.contains(tag),
+                None => true,
+            })
+            .filter(|group| match &self.project_filter {
+                Some(project) => group.final_attempt().project.as_ref() == Some(project),
+                None => true,
             })
             .collect();
 
-        // Count test cases by status
-        let total_count = test_cases.len();
-        let failed_count = test_cases
-            .iter()
-            .filter(|tc| tc.status == TestStatus::Failed)
-            .count();
-        let passed_count = test_cases
+        let tags: BTreeSet<&str> = test_cases
             .iter()
-            .filter(|tc| tc.status == TestStatus::Passed)
-            .count();
-        let skipped_count = test_cases
+            .flat_map(|tc| tc.tags.iter().map(String::as_str))
+            .collect();
+        let projects: BTreeSet<&str> = test_cases
             .iter()
-            .filter(|tc| tc.status == TestStatus::Skipped)
-            .count();
+            .filter_map(|tc| tc.project.as_deref())
+            .collect();
+
+        sort_groups(&mut filtered_groups, self.sort_order, score_of);
+
+        // Only one page of cards is rendered; a jump shows the page of its match
+        let jump_index = self
+            .jump_query
+            .as_deref()
+            .and_then(|query| find_test(&filtered_groups, query));
+        let page_count = filtered_groups.len().div_ceil(PAGE_SIZE).max(1);
+        let page = jump_index
+            .map_or(self.page, |index| index / PAGE_SIZE)
+            .min(page_count - 1);
+        let page_start = page * PAGE_SIZE;
+        let page_groups =
+            &filtered_groups[page_start..(page_start + PAGE_SIZE).min(filtered_groups.len())];
+        let jump_target = jump_index.map(|index| filtered_groups[index].test_id.as_str());
+
+        // Count tests by final outcome
+        let count_status = |status: TestStatus| {
+            groups
+                .iter()
+                .filter(|group| group.final_attempt().status == status)
+                .count()
+        };
+        let total_count = groups.len();
+        let failed_count = count_status(TestStatus::Failed);
+        let passed_count = count_status(TestStatus::Passed);
+        let skipped_count = count_status(TestStatus::Skipped);
+        let has_failed_images = groups.iter().any(|group| {
+            let test_case = group.final_attempt();
+            test_case.status == TestStatus::Failed && !test_case_images(test_case).is_empty()
+        });
+
+        let render_card = |group: &RetryGroup| {
+            let retries = (group.attempts.len() > 1).then(|| group.clone());
+            html! {
+                <TestCaseCard
+                    key={group.test_id.clone()}
+                    test_case={group.final_attempt().clone()}
+                    {retries}
+                    flakiness={score_of(&group.test_id)}
+                    highlighted={jump_target == Some(group.test_id.as_str())}
+                    attachments={ctx.props().test_cases.attachment_store.clone()}
+                    on_open_trace={ctx.props().on_open_trace.clone()}
+                />
+            }
+        };
 
         html! {
             <div class="test-case-list">
                 <div class="test-case-list-header">
-                    <h2>{ "Test Results" }</h2>
+                    <div class="test-case-list-title">
+                        <h2>{ "Test Results" }</h2>
+                        <div class="test-case-list-actions">
+                            {
+                                if failed_count > 0 {
+                                    html! {
+                                        <button
+                                            class={classes!("copy-button", self.summary_copied.then_some("copy-success"))}
+                                            onclick={ctx.link().callback(|_| TestCaseListMessage::CopyFailedSummary)}
+                                            title="Copy the failed tests and their first error line as markdown"
+                                        >
+                                            { if self.summary_copied { "✓ Copied!" } else { "📋 Copy failures" } }
+                                        </button>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                            {
+                                if has_failed_images {
+                                    html! {
+                                        <button
+                                            class="export-button"
+                                            onclick={ctx.link().callback(|_| TestCaseListMessage::DownloadFailedScreenshots)}
+                                            title="Download the screenshots of all failed tests as a zip"
+                                        >
+                                            { "🖼️ Download failed screenshots" }
+                                        </button>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                            <button
+                                class="export-button"
+                                onclick={ctx.link().callback(|_| TestCaseListMessage::ExportReport)}
+                                title="Download a markdown summary of the test run"
+                            >
+                                { "📥 Export report" }
+                            </button>
+                        </div>
+                    </div>
                     <div class="test-summary">
                         <span class="test-summary-item">
                             { format!("Total: {}", total_count) }
@@ -99,6 +378,10 @@ impl Component for TestCaseList {
                     </div>
                 </div>
 
+                { render_load_warnings(&ctx.props().test_cases.warnings) }
+
+                { render_most_flaky(&flakiness) }
+
                 <div class="test-filter-bar">
                     <span class="filter-label">{ "Filter: " }</span>
                     { self.render_filter_button(ctx, TestStatusFilter::All, "All") }
@@ -111,34 +394,172 @@ impl Component for TestCaseList {
                             html! {}
                         }
                     }
+                    { self.render_sort_select(ctx, !flakiness.is_empty()) }
+                    {
+                        if failed_count > 0 {
+                            html! {
+                                <button
+                                    class={classes!("filter-button", "group-by-error", self.group_by_error.then_some("active"))}
+                                    onclick={ctx.link().callback(|_| TestCaseListMessage::ToggleGroupByError)}
+                                    title="Group failed tests that fail for the same reason"
+                                >
+                                    { "Group by error" }
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
                 </div>
 
+                { self.render_project_filter(ctx, &projects) }
+
+                { self.render_tag_filter(ctx, &tags) }
+
+                {
+                    if self.group_by_error {
+                        html! {}
+                    } else {
+                        self.render_jump_to_test(ctx, &filtered_groups, jump_index.is_some())
+                    }
+                }
+
                 <div class="test-case-list-content">
                     {
-                        if filtered_cases.is_empty() {
+                        if filtered_groups.is_empty() {
                             html! {
                                 <div class="empty-state">
                                     <p>{ "No test cases match the current filter." }</p>
                                 </div>
                             }
+                        } else if self.group_by_error {
+                            // Clusters are small in number; every failure is shown
+                            render_failure_clusters(&cluster_failures(&filtered_groups), &render_card)
+                        } else if self.sort_order == TestSortOrder::Default && has_suites(page_groups) {
+                            // Sorted lists stay flat; a ranking across suites reads better
+                            let tree = build_suite_tree(page_groups);
+                            render_suite_contents(&tree, &render_card)
                         } else {
-                            filtered_cases.iter().map(|test_case| {
-                                html! {
-                                    <TestCaseCard
-                                        key={test_case.id.clone()}
-                                        test_case={(*test_case).clone()}
-                                    />
-                                }
-                            }).collect::<Html>()
+                            page_groups.iter().map(|group| render_card(group)).collect::<Html>()
                         }
                     }
                 </div>
+
+                {
+                    if self.group_by_error {
+                        html! {}
+                    } else {
+                        render_pagination(ctx, page, page_count, filtered_groups.len())
+                    }
+                }
             </div>
         }
     }
 }
 
 impl TestCaseList {
+    fn reset_page(&mut self) {
+        self.page = 0;
+        self.jump_query = None;
+    }
+
+    fn render_jump_to_test(
+        &self,
+        ctx: &Context<Self>,
+        groups: &[&RetryGroup],
+        found: bool,
+    ) -> Html {
+        if groups.len() <= PAGE_SIZE {
+            return html! {};
+        }
+
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            e.target_dyn_into::<HtmlInputElement>()
+                .map(|input| TestCaseListMessage::JumpToTest(input.value()))
+        });
+        let not_found = self.jump_query.is_some() && !found;
+
+        html! {
+            <div class="test-filter-bar jump-to-test">
+                <label class="filter-label" for="jump-to-test-input">{ "Jump to test: " }</label>
+                <input
+                    id="jump-to-test-input"
+                    type="search"
+                    list="jump-to-test-names"
+                    placeholder="Test name or id"
+                    class={classes!(not_found.then_some("not-found"))}
+                    value={self.jump_query.clone().unwrap_or_default()}
+                    {onchange}
+                />
+                <datalist id="jump-to-test-names">
+                    { for groups.iter().map(|group| html! { <option value={group.attempts[0].name.clone()} /> }) }
+                </datalist>
+                {
+                    if not_found {
+                        html! { <span class="jump-to-test-hint">{ "No matching test" }</span> }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        }
+    }
+
+    fn render_project_filter(&self, ctx: &Context<Self>, projects: &BTreeSet<&str>) -> Html {
+        // A single project filters nothing
+        if projects.len() < 2 {
+            return html! {};
+        }
+
+        html! {
+            <div class="test-filter-bar project-filter-bar">
+                <span class="filter-label">{ "Projects: " }</span>
+                {
+                    projects.iter().map(|project| {
+                        let active = self.project_filter.as_deref() == Some(*project);
+                        let next = (!active).then(|| project.to_string());
+                        let onclick = ctx
+                            .link()
+                            .callback(move |_| TestCaseListMessage::FilterByProject(next.clone()));
+
+                        html! {
+                            <button class={classes!("test-chip", "project", active.then_some("active"))} {onclick}>
+                                { *project }
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    fn render_tag_filter(&self, ctx: &Context<Self>, tags: &BTreeSet<&str>) -> Html {
+        if tags.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <div class="test-filter-bar tag-filter-bar">
+                <span class="filter-label">{ "Tags: " }</span>
+                {
+                    tags.iter().map(|tag| {
+                        let active = self.tag_filter.as_deref() == Some(*tag);
+                        let next = (!active).then(|| tag.to_string());
+                        let onclick = ctx
+                            .link()
+                            .callback(move |_| TestCaseListMessage::FilterByTag(next.clone()));
+
+                        html! {
+                            <button class={classes!("test-chip", "tag", active.then_some("active"))} {onclick}>
+                                { *tag }
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
     fn render_filter_button(
         &self,
         ctx: &Context<Self>,
@@ -159,4 +580,231 @@ impl TestCaseList {
             </button>
         }
     }
+
+    fn render_sort_select(&self, ctx: &Context<Self>, has_flakiness: bool) -> Html {
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            e.target_dyn_into::<HtmlSelectElement>()
+                .and_then(|select| select.value().parse::<usize>().ok())
+                .and_then(|index| TestSortOrder::ALL.get(index).copied())
+                .map(TestCaseListMessage::SortChanged)
+        });
+
+        html! {
+            <label class="filter-label sort-label">
+                { "Sort: " }
+                <select class="sort-select" {onchange}>
+                    {
+                        TestSortOrder::ALL.iter().enumerate()
+                            // Flakiness needs more than one run to compare
+                            .filter(|(_, order)| has_flakiness || **order != TestSortOrder::Flakiness)
+                            .map(|(index, order)| html! {
+                                <option value={index.to_string()} selected={*order == self.sort_order}>
+                                    { order.label() }
+                                </option>
+                            })
+                            .collect::<Html>()
+                    }
+                </select>
+            </label>
+        }
+    }
+}
+
+fn render_suite_contents(node: &SuiteNode, render_card: &dyn Fn(&RetryGroup) -> Html) -> Html {
+    html! {
+        <>
+            { for node.tests.iter().map(|group| render_card(group)) }
+            {
+                for node.suites.iter().map(|suite| {
+                    let counts = suite.counts();
+                    html! {
+                        <details key={suite.title.clone()} class="test-suite" open={true}>
+                            <summary class="test-suite-header">
+                                <span class="test-suite-title">{ &suite.title }</span>
+                                <span class="test-suite-counts">
+                                    <span class="status-passed">{ format!("✓ {}", counts.passed) }</span>
+                                    <span class="status-failed">{ format!("✗ {}", counts.failed) }</span>
+                                    {
+                                        if counts.skipped > 0 {
+                                            html! {
+                                                <span class="status-skipped">{ format!("○ {}", counts.skipped) }</span>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                </span>
+                            </summary>
+                            <div class="test-suite-content">
+                                { render_suite_contents(suite, render_card) }
+                            </div>
+                        </details>
+                    }
+                })
+            }
+        </>
+    }
+}
+
+fn render_pagination(
+    ctx: &Context<TestCaseList>,
+    page: usize,
+    page_count: usize,
+    total: usize,
+) -> Html {
+    if page_count <= 1 {
+        return html! {};
+    }
+
+    let first = page * PAGE_SIZE + 1;
+    let last = ((page + 1) * PAGE_SIZE).min(total);
+    let previous = ctx
+        .link()
+        .callback(move |_| TestCaseListMessage::SetPage(page.saturating_sub(1)));
+    let next = ctx
+        .link()
+        .callback(move |_| TestCaseListMessage::SetPage(page + 1));
+
+    html! {
+        <div class="test-pagination">
+            <button class="filter-button" disabled={page == 0} onclick={previous}>
+                { "‹ Previous" }
+            </button>
+            <span class="test-pagination-status">
+                { format!("{}–{} of {} · page {} of {}", first, last, total, page + 1, page_count) }
+            </span>
+            <button class="filter-button" disabled={page + 1 >= page_count} onclick={next}>
+                { "Next ›" }
+            </button>
+        </div>
+    }
+}
+
+fn download_markdown(markdown: &str, file_name: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let array = js_sys::Array::new();
+    array.push(&wasm_bindgen::JsValue::from_str(markdown));
+
+    let blob_options = BlobPropertyBag::new();
+    blob_options.set_type("text/markdown");
+    let blob = Blob::new_with_str_sequence_and_options(&array, &blob_options)?;
+    download_blob(&blob, file_name)
+}
+
+/// Save bytes as a file, e.g. a zip built in the browser
+pub(super) fn download_bytes(
+    bytes: &[u8],
+    mime_type: &str,
+    file_name: &str,
+) -> Result<(), wasm_bindgen::JsValue> {
+    let array = js_sys::Array::new();
+    array.push(&js_sys::Uint8Array::from(bytes));
+
+    let blob_options = BlobPropertyBag::new();
+    blob_options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&array, &blob_options)?;
+    download_blob(&blob, file_name)
+}
+
+fn download_blob(blob: &Blob, file_name: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let url = Url::create_object_url_with_blob(blob)?;
+
+    let anchor: HtmlAnchorElement = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("no document"))?
+        .create_element("a")?
+        .dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
+
+fn render_failure_clusters(
+    clusters: &[FailureCluster],
+    render_card: &dyn Fn(&RetryGroup) -> Html,
+) -> Html {
+    if clusters.is_empty() {
+        return html! {
+            <div class="empty-state">
+                <p>{ "No failed tests match the current filter." }</p>
+            </div>
+        };
+    }
+
+    clusters
+        .iter()
+        .map(|cluster| {
+            html! {
+                <details class="error-cluster" key={cluster.signature.clone()}>
+                    <summary class="error-cluster-header">
+                        <span class="error-cluster-count">
+                            { format!("{} failed", cluster.tests.len()) }
+                        </span>
+                        <code class="error-cluster-signature">{ &cluster.signature }</code>
+                    </summary>
+                    <div class="error-cluster-tests">
+                        { for cluster.tests.iter().map(|group| render_card(group)) }
+                    </div>
+                </details>
+            }
+        })
+        .collect()
+}
+
+fn render_load_warnings(warnings: &[String]) -> Html {
+    if warnings.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <details class="load-warnings">
+            <summary>{ format!("⚠️ {} load warning(s)", warnings.len()) }</summary>
+            <ul>
+                { for warnings.iter().map(|warning| html! { <li>{ warning }</li> }) }
+            </ul>
+        </details>
+    }
+}
+
+fn render_most_flaky(flakiness: &[TestFlakiness]) -> Html {
+    let most_flaky: Vec<_> = flakiness
+        .iter()
+        .filter(|test| test.flakiness.score > 0.0)
+        .take(MOST_FLAKY_LIMIT)
+        .collect();
+
+    if most_flaky.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="most-flaky">
+            <h3>{ "Most Flaky" }</h3>
+            <ul class="most-flaky-list">
+                {
+                    most_flaky.iter().map(|test| {
+                        html! {
+                            <li key={test.test_id.clone()} class="most-flaky-item">
+                                <span class="flakiness-badge">
+                                    { format!("{:.0}%", test.flakiness.score * 100.0) }
+                                </span>
+                                <span class="most-flaky-name">{ &test.name }</span>
+                                <span class="most-flaky-detail">
+                                    {
+                                        format!(
+                                            "{} runs · {:.0}% alternating · {:.0}% passed on retry",
+                                            test.flakiness.runs,
+                                            test.flakiness.alternation_rate * 100.0,
+                                            test.flakiness.pass_after_retry_rate * 100.0
+                                        )
+                                    }
+                                </span>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+        </div>
+    }
 }
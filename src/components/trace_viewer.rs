@@ -1,14 +1,84 @@
-use super::{ActionDetails, ActionList};
-use crate::markdown_exporter::{export_to_markdown, ExportOptions};
-use crate::models::{ActionEntry, TraceModel};
+use super::test_case_list::download_bytes;
+use super::{
+    ActionDetails, ActionList, ConsolePanel, FilmStrip, NetworkPanel, OutputPanel, StatsPanel,
+};
+use crate::codegen::export_test_code;
+use crate::compact::{
+    chars_for_tokens, COMPACT_PARAM_DEPTH, COMPACT_PARAM_LENGTH, DEFAULT_TOKEN_BUDGET,
+};
+use crate::embed_events::{self, ViewerEvent};
+use crate::export_template::{
+    load_custom_template, render_template, save_custom_template, ExportTemplate,
+    BUG_REPORT_TEMPLATE, PLACEHOLDERS,
+};
+use crate::html_bundle::export_html_bundle;
+use crate::jira_exporter::export_to_jira;
+use crate::markdown_exporter::{
+    export_markdown_bundle, export_to_markdown, ExportOptions, MarkdownFlavor, ScreenshotMode,
+};
+use crate::models::{ActionEntry, ContextEntry, PageEntry, ParseWarning, TraceModel, Viewport};
+use crate::ndjson_exporter::export_actions_ndjson;
+use crate::paths::detect_workspace_root;
+use crate::slack_exporter::export_to_slack;
+use crate::stats::SLOWEST_COUNT;
+use crate::trace_links::locate;
+use gloo::timers::callback::Timeout;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+use web_sys::{
+    Blob, BlobPropertyBag, HtmlAnchorElement, HtmlSelectElement, HtmlTextAreaElement, Url,
+};
+use yew::html::Scope;
 use yew::prelude::*;
 
+/// How long the copy-as-test button shows its confirmation
+const COPIED_FEEDBACK_MS: u32 = 2000;
+
+/// Markup copies and exports are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown(MarkdownFlavor),
+    /// Slack mrkdwn for incident channels: no headings or tables, stacks cut
+    /// to their first app frame and code blocks to a few lines
+    Slack,
+    /// Jira wiki markup, for trackers that do not render markdown
+    Jira,
+}
+
+impl ExportFormat {
+    const ALL: [ExportFormat; 4] = [
+        ExportFormat::Markdown(MarkdownFlavor::Plain),
+        ExportFormat::Markdown(MarkdownFlavor::GitHub),
+        ExportFormat::Slack,
+        ExportFormat::Jira,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown(flavor) => flavor.label(),
+            ExportFormat::Slack => "Slack",
+            ExportFormat::Jira => "Jira",
+        }
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct TraceViewerProps {
     pub model: TraceModel,
+    /// Name of the loaded file, listed in export frontmatter
+    #[prop_or_default]
+    pub file_name: Option<AttrValue>,
+    /// Context shown first, by `ContextEntry::id`
+    #[prop_or_default]
+    pub initial_context: Option<AttrValue>,
+    /// Action of the initial context selected first, by call id
+    #[prop_or_default]
+    pub initial_action: Option<AttrValue>,
+    /// Returns to the test the trace was opened from, named by `back_label`
+    #[prop_or_default]
+    pub on_back: Option<Callback<()>>,
+    #[prop_or_default]
+    pub back_label: Option<AttrValue>,
 }
 
 pub struct TraceViewer {
@@ -16,48 +86,209 @@ pub struct TraceViewer {
     errors_only: bool,
     copy_success: bool,
     active_tab: usize,
+    /// Directory shared by all absolute paths in the trace
+    workspace_root: Option<String>,
+    show_full_paths: bool,
+    /// The banner about unparsable trace lines was closed
+    parse_warnings_dismissed: bool,
+    /// Page the actions and frames are filtered to; all pages when `None`
+    selected_page: Option<String>,
+    /// Show wall-clock times instead of trace offsets
+    wall_clock: bool,
+    /// Add the slowest actions to exports
+    export_performance: bool,
+    /// Add failed and slow requests to exports
+    export_network: bool,
+    /// Shorten exports to the default token budget
+    export_compact: bool,
+    /// Start exports with YAML frontmatter
+    export_frontmatter: bool,
+    /// Start exports with links to every action
+    export_contents: bool,
+    /// Markup of copies and exports
+    export_format: ExportFormat,
+    /// Export every loaded context instead of the one being viewed
+    export_all_contexts: bool,
+    /// Screenshots added to exports; linked ones make the export a zip
+    export_screenshots: ScreenshotMode,
+    /// Template copies and exports are written in; the full report when `None`
+    export_template: Option<ExportTemplate>,
+    custom_template: String,
+    /// Request the network panel was last asked to reveal
+    network_focus: Option<usize>,
+    /// Output entry the output panel was last asked to reveal
+    output_focus: Option<usize>,
+    /// Console message the console panel was last asked to reveal
+    console_focus: Option<usize>,
+    test_code_copied: bool,
 }
 
 pub enum TraceViewerMsg {
     SelectAction(Box<ActionEntry>),
     ToggleErrorsOnly,
     ExportMarkdown,
+    SaveHtmlBundle,
+    ExportActionsNdjson,
     CopyToClipboard,
     ResetCopySuccess,
     SwitchTab(usize),
+    ToggleFullPaths,
+    DismissParseWarnings,
+    SelectPage(Option<String>),
+    ToggleWallClock,
+    ToggleExportPerformance,
+    ToggleExportNetwork,
+    ToggleExportCompact,
+    ToggleExportFrontmatter,
+    ToggleExportContents,
+    SetExportFormat(ExportFormat),
+    SetExportAllContexts(bool),
+    SetExportScreenshots(ScreenshotMode),
+    SetExportTemplate(Option<ExportTemplate>),
+    SetCustomTemplate(String),
+    FocusRequest(usize),
+    FocusOutput(usize),
+    FocusConsole(usize),
+    CopyAsTest,
+    SetTestCodeCopied(bool),
 }
 
 impl Component for TraceViewer {
     type Message = TraceViewerMsg;
     type Properties = TraceViewerProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = ctx.props();
+        let (active_tab, selected_action) = props
+            .initial_context
+            .as_deref()
+            .and_then(|context_id| {
+                locate(&props.model, context_id, props.initial_action.as_deref())
+            })
+            .map(|(index, action)| (index, action.cloned()))
+            .unwrap_or_default();
+
         Self {
-            selected_action: None,
+            selected_action,
             errors_only: false,
             copy_success: false,
-            active_tab: 0,
+            active_tab,
+            workspace_root: detect_workspace_root(&ctx.props().model),
+            show_full_paths: false,
+            parse_warnings_dismissed: false,
+            selected_page: None,
+            wall_clock: false,
+            export_performance: false,
+            export_network: false,
+            export_compact: false,
+            export_frontmatter: false,
+            export_contents: false,
+            export_format: ExportFormat::Markdown(MarkdownFlavor::Plain),
+            export_all_contexts: false,
+            export_screenshots: ScreenshotMode::Omit,
+            export_template: None,
+            custom_template: load_custom_template()
+                .unwrap_or_else(|| BUG_REPORT_TEMPLATE.to_string()),
+            network_focus: None,
+            output_focus: None,
+            console_focus: None,
+            test_code_copied: false,
         }
     }
 
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.workspace_root = detect_workspace_root(&ctx.props().model);
+        self.parse_warnings_dismissed = false;
+        self.selected_page = None;
+        true
+    }
+
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             TraceViewerMsg::SelectAction(action) => {
+                embed_events::emit(ViewerEvent::ActionSelected {
+                    call_id: action.call_id.clone(),
+                    title: action.title.clone(),
+                    failed: action.error.is_some(),
+                });
                 self.selected_action = Some(*action);
                 true
             }
+            TraceViewerMsg::DismissParseWarnings => {
+                self.parse_warnings_dismissed = true;
+                true
+            }
             TraceViewerMsg::ToggleErrorsOnly => {
                 self.errors_only = !self.errors_only;
                 true
             }
             TraceViewerMsg::ExportMarkdown => {
+                embed_events::emit(ViewerEvent::ExportRequested {
+                    format: "markdown".to_string(),
+                    errors_only: self.errors_only,
+                });
                 self.export_markdown(ctx);
                 false
             }
+            TraceViewerMsg::SaveHtmlBundle => {
+                embed_events::emit(ViewerEvent::ExportRequested {
+                    format: "html".to_string(),
+                    errors_only: false,
+                });
+                self.save_html_bundle(ctx);
+                false
+            }
+            TraceViewerMsg::ExportActionsNdjson => {
+                embed_events::emit(ViewerEvent::ExportRequested {
+                    format: "ndjson".to_string(),
+                    errors_only: false,
+                });
+                if let Some(export_model) = self.export_model(&ctx.props().model) {
+                    download(
+                        &export_actions_ndjson(&export_model),
+                        "application/x-ndjson",
+                        &format!("{}.ndjson", file_stem(&export_model)),
+                    );
+                }
+                false
+            }
             TraceViewerMsg::CopyToClipboard => {
+                embed_events::emit(ViewerEvent::ExportRequested {
+                    format: "clipboard".to_string(),
+                    errors_only: self.errors_only,
+                });
                 self.copy_to_clipboard(ctx);
                 false
             }
+            TraceViewerMsg::CopyAsTest => {
+                let Some(context) = ctx.props().model.contexts.get(self.active_tab) else {
+                    return false;
+                };
+                let Some(window) = web_sys::window() else {
+                    return false;
+                };
+
+                let code = export_test_code(context);
+                let promise = window.navigator().clipboard().write_text(&code);
+                let link = ctx.link().clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match wasm_bindgen_futures::JsFuture::from(promise).await {
+                        Ok(_) => {
+                            link.send_message(TraceViewerMsg::SetTestCodeCopied(true));
+                            Timeout::new(COPIED_FEEDBACK_MS, move || {
+                                link.send_message(TraceViewerMsg::SetTestCodeCopied(false))
+                            })
+                            .forget();
+                        }
+                        Err(e) => log::error!("Failed to copy to clipboard: {:?}", e),
+                    }
+                });
+                false
+            }
+            TraceViewerMsg::SetTestCodeCopied(copied) => {
+                self.test_code_copied = copied;
+                true
+            }
             TraceViewerMsg::ResetCopySuccess => {
                 self.copy_success = false;
                 true
@@ -66,23 +297,114 @@ impl Component for TraceViewer {
                 if self.active_tab != index {
                     self.active_tab = index;
                     self.selected_action = None; // Clear selection when switching tabs
+                    self.selected_page = None;
+                    self.network_focus = None;
+                    self.output_focus = None;
+                    self.console_focus = None;
+                    true
+                } else {
+                    false
+                }
+            }
+            TraceViewerMsg::FocusRequest(index) => {
+                self.network_focus = Some(index);
+                true
+            }
+            TraceViewerMsg::FocusOutput(index) => {
+                self.output_focus = Some(index);
+                true
+            }
+            TraceViewerMsg::FocusConsole(index) => {
+                self.console_focus = Some(index);
+                true
+            }
+            TraceViewerMsg::SelectPage(page_id) => {
+                if self.selected_page != page_id {
+                    self.selected_page = page_id;
+                    self.selected_action = None;
                     true
                 } else {
                     false
                 }
             }
+            TraceViewerMsg::ToggleWallClock => {
+                self.wall_clock = !self.wall_clock;
+                true
+            }
+            TraceViewerMsg::ToggleExportPerformance => {
+                self.export_performance = !self.export_performance;
+                true
+            }
+            TraceViewerMsg::ToggleExportNetwork => {
+                self.export_network = !self.export_network;
+                true
+            }
+            TraceViewerMsg::ToggleExportCompact => {
+                self.export_compact = !self.export_compact;
+                true
+            }
+            TraceViewerMsg::ToggleExportFrontmatter => {
+                self.export_frontmatter = !self.export_frontmatter;
+                true
+            }
+            TraceViewerMsg::ToggleExportContents => {
+                self.export_contents = !self.export_contents;
+                true
+            }
+            TraceViewerMsg::SetExportFormat(format) => {
+                self.export_format = format;
+                true
+            }
+            TraceViewerMsg::SetExportAllContexts(all) => {
+                self.export_all_contexts = all;
+                true
+            }
+            TraceViewerMsg::SetExportScreenshots(mode) => {
+                self.export_screenshots = mode;
+                true
+            }
+            TraceViewerMsg::SetExportTemplate(template) => {
+                self.export_template = template;
+                true
+            }
+            TraceViewerMsg::SetCustomTemplate(template) => {
+                save_custom_template(&template);
+                self.custom_template = template;
+                false
+            }
+            TraceViewerMsg::ToggleFullPaths => {
+                self.show_full_paths = !self.show_full_paths;
+                true
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let model = &ctx.props().model;
         let link = ctx.link();
+        let back_button = ctx.props().on_back.as_ref().map(|on_back| {
+            let onclick = on_back.reform(|_| ());
+            let label = ctx.props().back_label.as_deref().unwrap_or("test");
+            html! {
+                <button class="back-to-test" {onclick}>{ format!("← Back to {}", label) }</button>
+            }
+        });
 
         // Get the active context based on the active tab
         let context = model.contexts.get(self.active_tab);
 
         html! {
             <div class="trace-viewer">
+                {
+                    if self.parse_warnings_dismissed {
+                        html! {}
+                    } else {
+                        render_parse_warnings(
+                            &model.parse_warnings,
+                            link.callback(|_| TraceViewerMsg::DismissParseWarnings),
+                        )
+                    }
+                }
                 // Render tabs if there are multiple contexts
                 {
                     if model.contexts.len() > 1 {
@@ -120,6 +442,7 @@ impl Component for TraceViewer {
                                 html! {
                                     <>
                                         <div class="header-left">
+                                            { back_button }
                                             <h2>
                                                 { ctx.title.as_deref().unwrap_or("Trace") }
                                             </h2>
@@ -139,11 +462,64 @@ impl Component for TraceViewer {
                                                         html! {}
                                                     }
                                                 }
+                                                {
+                                                    if let Some(page) = self.shown_page(ctx) {
+                                                        render_page_info(page)
+                                                    } else {
+                                                        html! {}
+                                                    }
+                                                }
+                                                {
+                                                    if let Some(root) = &self.workspace_root {
+                                                        html! {
+                                                            <span class="workspace-root" title="Workspace root, shown as …/">
+                                                                { root }
+                                                            </span>
+                                                        }
+                                                    } else {
+                                                        html! {}
+                                                    }
+                                                }
                                             </div>
                                         </div>
                                         <div class="header-right">
                                             <div class="export-controls">
-                                                <label class="checkbox-label errors-only-checkbox">
+                                                {
+                                                    if self.workspace_root.is_some() {
+                                                        html! {
+                                                            <label class="checkbox-label full-paths-checkbox">
+                                                                <input
+                                                                    type="checkbox"
+                                                                    checked={self.show_full_paths}
+                                                                    onchange={link.callback(|_| TraceViewerMsg::ToggleFullPaths)}
+                                                                />
+                                                                <span>{ "Full paths" }</span>
+                                                            </label>
+                                                        }
+                                                    } else {
+                                                        html! {}
+                                                    }
+                                                }
+                                                {
+                                                    if ctx.wall_clock_ms(ctx.start_time).is_some() {
+                                                        html! {
+                                                            <label class="checkbox-label wall-clock-checkbox" title="Show times of day instead of offsets">
+                                                                <input
+                                                                    type="checkbox"
+                                                                    checked={self.wall_clock}
+                                                                    onchange={link.callback(|_| TraceViewerMsg::ToggleWallClock)}
+                                                                />
+                                                                <span>{ "Clock time" }</span>
+                                                            </label>
+                                                        }
+                                                    } else {
+                                                        html! {}
+                                                    }
+                                                }
+                                                <label
+                                                    class="checkbox-label errors-only-checkbox"
+                                                    title="List and export only failed actions"
+                                                >
                                                     <input
                                                         type="checkbox"
                                                         checked={self.errors_only}
@@ -151,6 +527,65 @@ impl Component for TraceViewer {
                                                     />
                                                     <span>{ "Errors only" }</span>
                                                 </label>
+                                                <label
+                                                    class="checkbox-label performance-checkbox"
+                                                    title="Add the slowest actions to exports"
+                                                >
+                                                    <input
+                                                        type="checkbox"
+                                                        checked={self.export_performance}
+                                                        onchange={link.callback(|_| TraceViewerMsg::ToggleExportPerformance)}
+                                                    />
+                                                    <span>{ "Performance" }</span>
+                                                </label>
+                                                <label
+                                                    class="checkbox-label network-checkbox"
+                                                    title="Add failed and slow requests to exports"
+                                                >
+                                                    <input
+                                                        type="checkbox"
+                                                        checked={self.export_network}
+                                                        onchange={link.callback(|_| TraceViewerMsg::ToggleExportNetwork)}
+                                                    />
+                                                    <span>{ "Network" }</span>
+                                                </label>
+                                                <label
+                                                    class="checkbox-label compact-checkbox"
+                                                    title={format!("Shorten logs, stacks and parameters to fit about {} tokens, for pasting into an AI assistant", DEFAULT_TOKEN_BUDGET)}
+                                                >
+                                                    <input
+                                                        type="checkbox"
+                                                        checked={self.export_compact}
+                                                        onchange={link.callback(|_| TraceViewerMsg::ToggleExportCompact)}
+                                                    />
+                                                    <span>{ "Compact" }</span>
+                                                </label>
+                                                <label
+                                                    class="checkbox-label frontmatter-checkbox"
+                                                    title="Start exports with YAML fields for tools that index reports"
+                                                >
+                                                    <input
+                                                        type="checkbox"
+                                                        checked={self.export_frontmatter}
+                                                        onchange={link.callback(|_| TraceViewerMsg::ToggleExportFrontmatter)}
+                                                    />
+                                                    <span>{ "Frontmatter" }</span>
+                                                </label>
+                                                <label
+                                                    class="checkbox-label contents-checkbox"
+                                                    title="Start exports with a table of contents linking every action"
+                                                >
+                                                    <input
+                                                        type="checkbox"
+                                                        checked={self.export_contents}
+                                                        onchange={link.callback(|_| TraceViewerMsg::ToggleExportContents)}
+                                                    />
+                                                    <span>{ "Contents" }</span>
+                                                </label>
+                                                { self.render_export_scope(model.contexts.len(), link) }
+                                                { self.render_screenshot_mode(model, link) }
+                                                { self.render_format_picker(link) }
+                                                { self.render_template_picker(link) }
                                                 <button
                                                     class={if self.copy_success { "copy-button copy-success" } else { "copy-button" }}
                                                     onclick={link.callback(|_| TraceViewerMsg::CopyToClipboard)}
@@ -158,12 +593,33 @@ impl Component for TraceViewer {
                                                 >
                                                     { if self.copy_success { "✓ Copied!" } else { "📋 Copy" } }
                                                 </button>
+                                                <button
+                                                    class={classes!("copy-button", self.test_code_copied.then_some("copy-success"))}
+                                                    onclick={link.callback(|_| TraceViewerMsg::CopyAsTest)}
+                                                    title="Copy the recorded actions as a Playwright test"
+                                                >
+                                                    { if self.test_code_copied { "✓ Copied!" } else { "🎭 Copy as test" } }
+                                                </button>
                                                 <button
                                                     class="export-button"
                                                     onclick={link.callback(|_| TraceViewerMsg::ExportMarkdown)}
                                                 >
                                                     { "📥 Export" }
                                                 </button>
+                                                <button
+                                                    class="copy-button"
+                                                    onclick={link.callback(|_| TraceViewerMsg::SaveHtmlBundle)}
+                                                    title="Save a single HTML file that opens without hosting the viewer"
+                                                >
+                                                    { "🌐 Save as HTML" }
+                                                </button>
+                                                <button
+                                                    class="copy-button"
+                                                    onclick={link.callback(|_| TraceViewerMsg::ExportActionsNdjson)}
+                                                    title="Save one JSON object per action for analytics pipelines"
+                                                >
+                                                    { "🧾 Actions as NDJSON" }
+                                                </button>
                                             </div>
                                         </div>
                                     </>
@@ -174,36 +630,152 @@ impl Component for TraceViewer {
                         }
                     </div>
                 </div>
+                { self.render_template_editor(link) }
 
                 {
                     if let Some(ctx) = context {
                         let on_action_selected = link.callback(|a| TraceViewerMsg::SelectAction(Box::new(a)));
+                        let wall_clock_offset = self.wall_clock_offset(ctx);
+                        let on_output_action_selected = link.callback(|a| TraceViewerMsg::SelectAction(Box::new(a)));
+                        let on_stats_action_selected = link.callback(|a| TraceViewerMsg::SelectAction(Box::new(a)));
+
+                        // Context-level entries without a page stay visible on every page
+                        let selected_page = self.selected_page.as_deref();
+                        let on_page = |page_id: Option<&str>| {
+                            selected_page.is_none_or(|selected| page_id.is_none_or(|id| id == selected))
+                        };
+                        let actions: Vec<ActionEntry> = ctx
+                            .actions
+                            .iter()
+                            .filter(|action| on_page(action.page_id.as_deref()))
+                            .cloned()
+                            .collect();
+                        let page_events: Vec<_> = ctx
+                            .page_events
+                            .iter()
+                            .filter(|event| on_page(event.page_id.as_deref()))
+                            .cloned()
+                            .collect();
+
+                        let mut frames: Vec<_> = ctx
+                            .pages
+                            .iter()
+                            .filter(|page| on_page(Some(&page.page_id)))
+                            .flat_map(|page| page.screencast_frames.iter().cloned())
+                            .collect();
+                        frames.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
 
                         html! {
-                            <div class="viewer-content">
-                                <div class="left-panel">
-                                    <ActionList
-                                        actions={ctx.actions.clone()}
-                                        {on_action_selected}
-                                        selected_action={self.selected_action.clone()}
-                                    />
-                                </div>
-                                <div class="right-panel">
-                                    {
-                                        if let Some(action) = &self.selected_action {
-                                            html! {
-                                                <ActionDetails action={action.clone()} />
-                                            }
-                                        } else {
-                                            html! {
-                                                <div class="no-selection">
-                                                    <p>{ "Select an action to view details" }</p>
-                                                </div>
+                            <>
+                                {
+                                    render_page_selector(
+                                        &ctx.pages,
+                                        selected_page,
+                                        link.callback(TraceViewerMsg::SelectPage),
+                                    )
+                                }
+                                <FilmStrip
+                                    key={self.active_tab}
+                                    {frames}
+                                    store={model.resource_store.clone()}
+                                    start_time={ctx.start_time}
+                                    end_time={ctx.end_time}
+                                    markers={page_events.clone()}
+                                />
+                                <div class="viewer-content">
+                                    <div class="left-panel">
+                                        <ActionList
+                                            {actions}
+                                            {on_action_selected}
+                                            selected_action={self.selected_action.clone()}
+                                            path_root={self.path_root()}
+                                            {page_events}
+                                            start_time={ctx.start_time}
+                                            {wall_clock_offset}
+                                            pages={ctx.pages.clone()}
+                                            errors_only={self.errors_only}
+                                        />
+                                    </div>
+                                    <div class="right-panel">
+                                        {
+                                            if let Some(action) = &self.selected_action {
+                                                let (before_frame, after_frame) = ctx.action_screenshots(action);
+                                                let frame_path = ctx
+                                                    .action_frame_path(action)
+                                                    .into_iter()
+                                                    .cloned()
+                                                    .collect::<Vec<_>>();
+                                                let failed_requests = ctx
+                                                    .action_failed_requests(action)
+                                                    .into_iter()
+                                                    .map(|index| (index, ctx.resources[index].clone()))
+                                                    .collect::<Vec<_>>();
+                                                let stderr = ctx
+                                                    .action_stderr(action)
+                                                    .into_iter()
+                                                    .map(|index| (index, ctx.stdio[index].clone()))
+                                                    .collect::<Vec<_>>();
+                                                let console = ctx
+                                                    .action_console(action)
+                                                    .into_iter()
+                                                    .map(|index| (index, ctx.console[index].clone()))
+                                                    .collect::<Vec<_>>();
+
+                                                html! {
+                                                    <ActionDetails
+                                                        action={action.clone()}
+                                                        {frame_path}
+                                                        {wall_clock_offset}
+                                                        before_frame={before_frame.cloned()}
+                                                        after_frame={after_frame.cloned()}
+                                                        store={model.resource_store.clone()}
+                                                        path_root={self.path_root()}
+                                                        {failed_requests}
+                                                        {stderr}
+                                                        {console}
+                                                        on_request_selected={link.callback(TraceViewerMsg::FocusRequest)}
+                                                        on_output_selected={link.callback(TraceViewerMsg::FocusOutput)}
+                                                        on_console_selected={link.callback(TraceViewerMsg::FocusConsole)}
+                                                    />
+                                                }
+                                            } else {
+                                                html! {
+                                                    <div class="no-selection">
+                                                        <p>{ "Select an action to view details" }</p>
+                                                    </div>
+                                                }
                                             }
                                         }
-                                    }
+                                    </div>
                                 </div>
-                            </div>
+                                <NetworkPanel
+                                    key={self.active_tab}
+                                    resources={ctx.resources.clone()}
+                                    store={model.resource_store.clone()}
+                                    start_time={ctx.start_time}
+                                    focused={self.network_focus}
+                                />
+                                <OutputPanel
+                                    key={self.active_tab}
+                                    entries={ctx.stdio.clone()}
+                                    actions={ctx.actions.clone()}
+                                    start_time={ctx.start_time}
+                                    on_action_selected={on_output_action_selected}
+                                    focused={self.output_focus}
+                                />
+                                <ConsolePanel
+                                    key={self.active_tab}
+                                    messages={ctx.console.clone()}
+                                    start_time={ctx.start_time}
+                                    focused={self.console_focus}
+                                />
+                                <StatsPanel
+                                    key={self.active_tab}
+                                    stats={ctx.stats.clone()}
+                                    actions={ctx.actions.clone()}
+                                    on_action_selected={on_stats_action_selected}
+                                />
+                            </>
                         }
                     } else {
                         html! {
@@ -219,123 +791,298 @@ impl Component for TraceViewer {
 }
 
 impl TraceViewer {
-    fn export_markdown(&self, ctx: &Context<Self>) {
-        let model = &ctx.props().model;
-        let options = ExportOptions {
-            errors_only: self.errors_only,
-        };
+    /// Root to collapse in displayed and exported paths
+    fn path_root(&self) -> Option<String> {
+        if self.show_full_paths {
+            None
+        } else {
+            self.workspace_root.clone()
+        }
+    }
 
-        // Export only the active context
-        let active_context = match model.contexts.get(self.active_tab) {
-            Some(context) => context,
-            None => return,
-        };
+    /// Choice between exporting the viewed context and all of them, shown
+    /// when a report archive loaded several
+    fn render_export_scope(&self, count: usize, link: &Scope<Self>) -> Html {
+        if count < 2 {
+            return html! {};
+        }
 
-        let single_context_model = TraceModel {
-            contexts: vec![active_context.clone()],
-        };
+        let onchange = link.callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            TraceViewerMsg::SetExportAllContexts(select.value() == "all")
+        });
 
-        let markdown = export_to_markdown(&single_context_model, &options);
+        html! {
+            <select class="export-scope" {onchange} title="Contexts to copy and export">
+                <option value="active" selected={!self.export_all_contexts}>{ "This context" }</option>
+                <option value="all" selected={self.export_all_contexts}>
+                    { format!("All contexts ({})", count) }
+                </option>
+            </select>
+        }
+    }
 
-        // Create a blob with the markdown content
-        let array = js_sys::Array::new();
-        array.push(&wasm_bindgen::JsValue::from_str(&markdown));
+    fn render_format_picker(&self, link: &Scope<Self>) -> Html {
+        let onchange = link.callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            TraceViewerMsg::SetExportFormat(
+                ExportFormat::ALL
+                    .into_iter()
+                    .find(|format| format.label() == value)
+                    .unwrap_or(ExportFormat::Markdown(MarkdownFlavor::Plain)),
+            )
+        });
 
-        let blob_options = BlobPropertyBag::new();
-        blob_options.set_type("text/markdown");
+        html! {
+            <select
+                class="export-scope"
+                {onchange}
+                title="GitHub collapses parameters, logs and stacks for issues; Slack shortens the report for incident channels"
+            >
+                {
+                    for ExportFormat::ALL.iter().map(|format| html! {
+                        <option value={format.label()} selected={self.export_format == *format}>
+                            { format.label() }
+                        </option>
+                    })
+                }
+            </select>
+        }
+    }
 
-        let blob = match Blob::new_with_str_sequence_and_options(&array, &blob_options) {
-            Ok(blob) => blob,
-            Err(e) => {
-                log::error!("Failed to create blob: {:?}", e);
-                return;
-            }
-        };
+    fn render_template_picker(&self, link: &Scope<Self>) -> Html {
+        let onchange = link.callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            TraceViewerMsg::SetExportTemplate(
+                ExportTemplate::ALL
+                    .into_iter()
+                    .find(|template| template.label() == value),
+            )
+        });
 
-        // Create a download link
-        let url = match Url::create_object_url_with_blob(&blob) {
-            Ok(url) => url,
-            Err(e) => {
-                log::error!("Failed to create object URL: {:?}", e);
-                return;
-            }
-        };
+        html! {
+            <select class="export-scope" {onchange} title="Format of copies and exports">
+                <option value="" selected={self.export_template.is_none()}>{ "Full report" }</option>
+                {
+                    for ExportTemplate::ALL.iter().map(|template| html! {
+                        <option
+                            value={template.label()}
+                            selected={self.export_template == Some(*template)}
+                        >
+                            { template.label() }
+                        </option>
+                    })
+                }
+            </select>
+        }
+    }
 
-        // Create and click an anchor element to trigger download
-        let window = match web_sys::window() {
-            Some(window) => window,
-            None => {
-                log::error!("Failed to get window");
-                return;
-            }
-        };
+    /// Editor of the custom template with the placeholders it can use
+    fn render_template_editor(&self, link: &Scope<Self>) -> Html {
+        if self.export_template != Some(ExportTemplate::Custom) {
+            return html! {};
+        }
 
-        let document = match window.document() {
-            Some(doc) => doc,
-            None => {
-                log::error!("Failed to get document");
-                return;
-            }
-        };
+        let oninput = link.callback(|e: InputEvent| {
+            let textarea: HtmlTextAreaElement = e.target_unchecked_into();
+            TraceViewerMsg::SetCustomTemplate(textarea.value())
+        });
 
-        let anchor = match document.create_element("a") {
-            Ok(el) => el,
-            Err(e) => {
-                log::error!("Failed to create anchor element: {:?}", e);
-                return;
-            }
-        };
+        html! {
+            <div class="template-editor">
+                <textarea
+                    class="template-text"
+                    rows="10"
+                    spellcheck="false"
+                    value={self.custom_template.clone()}
+                    {oninput}
+                />
+                <dl class="template-placeholders">
+                    {
+                        for PLACEHOLDERS.iter().map(|(name, description)| html! {
+                            <>
+                                <dt><code>{ format!("{{{{{}}}}}", name) }</code></dt>
+                                <dd>{ *description }</dd>
+                            </>
+                        })
+                    }
+                </dl>
+            </div>
+        }
+    }
 
-        let anchor: HtmlAnchorElement = match anchor.dyn_into() {
-            Ok(a) => a,
-            Err(e) => {
-                log::error!("Failed to cast to HtmlAnchorElement: {:?}", e);
-                return;
-            }
+    /// Choice of how screenshots go into exports, shown when the trace
+    /// recorded a screencast
+    fn render_screenshot_mode(&self, model: &TraceModel, link: &Scope<Self>) -> Html {
+        let has_frames = model
+            .contexts
+            .iter()
+            .flat_map(|context| &context.pages)
+            .any(|page| !page.screencast_frames.is_empty());
+        if !has_frames {
+            return html! {};
+        }
+
+        let onchange = link.callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            TraceViewerMsg::SetExportScreenshots(match select.value().as_str() {
+                "inline" => ScreenshotMode::Inline,
+                "links" => ScreenshotMode::Links,
+                _ => ScreenshotMode::Omit,
+            })
+        });
+        let mode = self.export_screenshots;
+
+        html! {
+            <select class="export-scope" {onchange} title="Screenshots before and after each action">
+                <option value="omit" selected={mode == ScreenshotMode::Omit}>{ "No screenshots" }</option>
+                <option value="inline" selected={mode == ScreenshotMode::Inline}>{ "Inline screenshots" }</option>
+                <option value="links" selected={mode == ScreenshotMode::Links}>{ "Screenshots in zip" }</option>
+            </select>
+        }
+    }
+
+    /// Page picked in the page tabs, or the only page of the context
+    fn shown_page<'a>(&self, context: &'a ContextEntry) -> Option<&'a PageEntry> {
+        match self.selected_page.as_deref() {
+            Some(page_id) => context.pages.iter().find(|page| page.page_id == page_id),
+            None if context.pages.len() == 1 => context.pages.first(),
+            None => None,
+        }
+    }
+
+    /// Wall-clock minus trace time, when clock times are shown and known
+    fn wall_clock_offset(&self, context: &ContextEntry) -> Option<f64> {
+        if !self.wall_clock {
+            return None;
+        }
+        context.wall_clock_ms(0.0)
+    }
+
+    fn export_options(&self, ctx: &Context<Self>) -> ExportOptions {
+        ExportOptions {
+            errors_only: self.errors_only,
+            path_root: self.path_root(),
+            wall_clock: self.wall_clock,
+            slowest_actions: self.export_performance.then_some(SLOWEST_COUNT),
+            network_summary: self.export_network,
+            max_param_length: self.export_compact.then_some(COMPACT_PARAM_LENGTH),
+            max_param_depth: self.export_compact.then_some(COMPACT_PARAM_DEPTH),
+            screenshots: self.export_screenshots,
+            max_chars: self
+                .export_compact
+                .then(|| chars_for_tokens(DEFAULT_TOKEN_BUDGET)),
+            frontmatter: self.export_frontmatter,
+            trace_file: ctx.props().file_name.as_ref().map(ToString::to_string),
+            flavor: match self.export_format {
+                ExportFormat::Markdown(flavor) => flavor,
+                ExportFormat::Slack | ExportFormat::Jira => MarkdownFlavor::Plain,
+            },
+            // The Slack exporter cuts stacks itself
+            short_stacks: false,
+            table_of_contents: self.export_contents,
+        }
+    }
+
+    /// The active context, or every context when exporting all of them
+    fn export_model(&self, model: &TraceModel) -> Option<TraceModel> {
+        let contexts = if self.export_all_contexts {
+            model.contexts.clone()
+        } else {
+            vec![model.contexts.get(self.active_tab)?.clone()]
         };
 
-        anchor.set_href(&url);
+        Some(TraceModel {
+            contexts,
+            resource_store: model.resource_store.clone(),
+            ..TraceModel::new()
+        })
+    }
 
-        // Generate filename based on active context title and whether it's errors only
-        let title = active_context
-            .title
-            .as_deref()
-            .unwrap_or("trace")
-            .replace(' ', "_")
-            .to_lowercase();
+    fn export_markdown(&self, ctx: &Context<Self>) {
+        let model = &ctx.props().model;
+        let options = self.export_options(ctx);
+
+        let Some(export_model) = self.export_model(model) else {
+            return;
+        };
 
-        let filename = if self.errors_only {
-            format!("{}_errors.md", title)
+        // Generate filename based on the exported contexts and whether it's errors only
+        let title = file_stem(&export_model);
+        let title = if self.errors_only {
+            format!("{}_errors", title)
         } else {
-            format!("{}.md", title)
+            title
         };
 
-        anchor.set_download(&filename);
+        // Slack and Jira only show images they can fetch, so there is nothing
+        // to bundle for them
+        if options.screenshots == ScreenshotMode::Links
+            && matches!(self.export_format, ExportFormat::Markdown(_))
+            && self.export_template.is_none()
+        {
+            let result = export_markdown_bundle(&export_model, &options).and_then(|zip| {
+                download_bytes(&zip, "application/zip", &format!("{}.zip", title))
+                    .map_err(|e| format!("{:?}", e))
+            });
+            if let Err(e) = result {
+                log::error!("Failed to build markdown bundle: {}", e);
+            }
+            return;
+        }
+
+        let markdown = self.export_text(ctx, &export_model);
+        download(&markdown, "text/markdown", &format!("{}.md", title));
+    }
 
-        // Trigger the download
-        anchor.click();
+    /// Markdown for a single file or the clipboard, in the chosen template
+    fn export_text(&self, ctx: &Context<Self>, model: &TraceModel) -> String {
+        let mut options = self.export_options(ctx);
+        // Plain text has no folder for linked screenshots to point into
+        if options.screenshots == ScreenshotMode::Links {
+            options.screenshots = ScreenshotMode::Omit;
+        }
 
-        // Clean up the object URL
-        Url::revoke_object_url(&url).ok();
+        match self.export_template.map(|template| template.builtin()) {
+            Some(Some(template)) => render_template(template, model, &options),
+            Some(None) => render_template(&self.custom_template, model, &options),
+            None => match self.export_format {
+                ExportFormat::Markdown(_) => export_to_markdown(model, &options),
+                ExportFormat::Slack => export_to_slack(model, &options),
+                ExportFormat::Jira => export_to_jira(model, &options),
+            },
+        }
     }
 
-    fn copy_to_clipboard(&mut self, ctx: &Context<Self>) {
-        let model = &ctx.props().model;
-        let options = ExportOptions {
-            errors_only: self.errors_only,
+    fn save_html_bundle(&self, ctx: &Context<Self>) {
+        let Some(export_model) = self.export_model(&ctx.props().model) else {
+            return;
         };
 
-        // Export only the active context
-        let active_context = match model.contexts.get(self.active_tab) {
-            Some(context) => context,
-            None => return,
+        let title = match export_model.contexts.as_slice() {
+            [context] => context.title.clone().unwrap_or_else(|| "Trace".to_string()),
+            contexts => format!("{} traces", contexts.len()),
         };
+        match export_html_bundle(&export_model, &title) {
+            Ok(html) => download(
+                &html,
+                "text/html",
+                &format!("{}.html", file_stem(&export_model)),
+            ),
+            Err(e) => log::error!("Failed to build HTML bundle: {}", e),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, ctx: &Context<Self>) {
+        let model = &ctx.props().model;
 
-        let single_context_model = TraceModel {
-            contexts: vec![active_context.clone()],
+        let Some(export_model) = self.export_model(model) else {
+            return;
         };
 
-        let markdown = export_to_markdown(&single_context_model, &options);
+        let markdown = self.export_text(ctx, &export_model);
 
         // Get window and navigator
         let window = match web_sys::window() {
@@ -371,3 +1118,180 @@ impl TraceViewer {
         self.copy_success = true;
     }
 }
+
+/// Lines of the trace that were left out, e.g. from a truncated file
+fn render_parse_warnings(warnings: &[ParseWarning], on_dismiss: Callback<MouseEvent>) -> Html {
+    if warnings.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="parse-warnings-banner">
+            <details>
+                <summary>
+                    { format!("⚠️ {} event(s) could not be parsed", warnings.len()) }
+                </summary>
+                <ul>
+                    {
+                        for warnings.iter().map(|warning| html! {
+                            <li>
+                                <span class="parse-warning-location">
+                                    { format!("{}:{}", warning.file, warning.line) }
+                                </span>
+                                { format!(" {}", warning.error) }
+                                <code class="parse-warning-snippet">{ &warning.snippet }</code>
+                            </li>
+                        })
+                    }
+                </ul>
+            </details>
+            <button class="parse-warnings-dismiss" onclick={on_dismiss} title="Dismiss">
+                { "✕" }
+            </button>
+        </div>
+    }
+}
+
+/// File name without extension: the context title, or `all_contexts`
+fn file_stem(model: &TraceModel) -> String {
+    match model.contexts.as_slice() {
+        [context] => context.title.as_deref().unwrap_or("trace"),
+        _ => "all_contexts",
+    }
+    .replace(' ', "_")
+    .to_lowercase()
+}
+
+/// Offer text as a file download
+fn download(content: &str, mime_type: &str, filename: &str) {
+    // Create a blob with the content
+    let array = js_sys::Array::new();
+    array.push(&wasm_bindgen::JsValue::from_str(content));
+
+    let blob_options = BlobPropertyBag::new();
+    blob_options.set_type(mime_type);
+
+    let blob = match Blob::new_with_str_sequence_and_options(&array, &blob_options) {
+        Ok(blob) => blob,
+        Err(e) => {
+            log::error!("Failed to create blob: {:?}", e);
+            return;
+        }
+    };
+
+    // Create a download link
+    let url = match Url::create_object_url_with_blob(&blob) {
+        Ok(url) => url,
+        Err(e) => {
+            log::error!("Failed to create object URL: {:?}", e);
+            return;
+        }
+    };
+
+    // Create and click an anchor element to trigger download
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => {
+            log::error!("Failed to get window");
+            return;
+        }
+    };
+
+    let document = match window.document() {
+        Some(doc) => doc,
+        None => {
+            log::error!("Failed to get document");
+            return;
+        }
+    };
+
+    let anchor = match document.create_element("a") {
+        Ok(el) => el,
+        Err(e) => {
+            log::error!("Failed to create anchor element: {:?}", e);
+            return;
+        }
+    };
+
+    let anchor: HtmlAnchorElement = match anchor.dyn_into() {
+        Ok(a) => a,
+        Err(e) => {
+            log::error!("Failed to cast to HtmlAnchorElement: {:?}", e);
+            return;
+        }
+    };
+
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+
+    // Trigger the download
+    anchor.click();
+
+    // Clean up the object URL
+    Url::revoke_object_url(&url).ok();
+}
+
+/// Title and viewport of a page, shown next to the browser in the header
+fn render_page_info(page: &PageEntry) -> Html {
+    let title = page.title.as_deref().filter(|title| !title.is_empty());
+
+    html! {
+        <>
+            if let Some(title) = title {
+                <span class="page-title" title={page.url().map(str::to_string)}>{ title }</span>
+            }
+            if let Some(viewport) = page.viewport {
+                <span class="viewport" title="Viewport">{ viewport.label() }</span>
+            }
+        </>
+    }
+}
+
+/// Tabs for the pages of a context, shown when it opened more than one
+fn render_page_selector(
+    pages: &[PageEntry],
+    selected: Option<&str>,
+    on_select: Callback<Option<String>>,
+) -> Html {
+    if pages.len() < 2 {
+        return html! {};
+    }
+
+    let tab = |page_id: Option<&str>,
+               title: String,
+               url: Option<&str>,
+               viewport: Option<Viewport>| {
+        let is_active = selected == page_id;
+        let onclick = {
+            let on_select = on_select.clone();
+            let page_id = page_id.map(str::to_string);
+            Callback::from(move |_| on_select.emit(page_id.clone()))
+        };
+        let tooltip = viewport.map(|viewport| format!("Viewport {}", viewport.label()));
+
+        html! {
+            <button class={classes!("page-tab", is_active.then_some("active"))} title={tooltip} {onclick}>
+                <span class="page-tab-title">{ title }</span>
+                if let Some(url) = url {
+                    <span class="page-tab-url" title={url.to_string()}>{ url }</span>
+                }
+            </button>
+        }
+    };
+
+    html! {
+        <div class="page-tabs">
+            { tab(None, format!("All pages ({})", pages.len()), None, None) }
+            {
+                pages.iter().enumerate().map(|(index, page)| {
+                    let title = page
+                        .title
+                        .clone()
+                        .filter(|title| !title.is_empty())
+                        .unwrap_or_else(|| format!("Page {}", index + 1));
+                    tab(Some(&page.page_id), title, page.url(), page.viewport)
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
@@ -0,0 +1,132 @@
+//! Rendering of archive content without network access
+//! Archives may reference external images or scripts; rendering them as-is
+//! would make the browser fetch those URLs and leak that a trace was opened.
+//! Remote references are rewritten to archived resources or placeholders.
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+/// Inline image shown in place of blocked remote images
+/// Fully percent-encoded so it survives attribute escaping unchanged
+pub const BLOCKED_IMAGE_PLACEHOLDER: &str = "data:image/svg+xml,%3Csvg%20xmlns=%27http://www.w3.org/2000/svg%27%20width=%27240%27%20height=%2740%27%3E%3Crect%20width=%27240%27%20height=%2740%27%20fill=%27%23242424%27%20stroke=%27%233a3a3a%27/%3E%3Ctext%20x=%27120%27%20y=%2725%27%20fill=%27%23a0a0a0%27%20font-family=%27sans-serif%27%20font-size=%2712%27%20text-anchor=%27middle%27%3ERemote%20image%20blocked%3C/text%3E%3C/svg%3E";
+
+/// Whether loading the URL would reach out to the network
+pub fn is_remote_url(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    url.starts_with("//")
+        || ["http:", "https:", "ftp:", "ws:", "wss:"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+}
+
+/// Render markdown to HTML that cannot trigger network requests
+/// Images are resolved through `resolve_resource` (e.g. to archived
+/// attachments) and otherwise replaced with a placeholder.
+/// Raw HTML is shown as text so embedded tags cannot load anything.
+pub fn render_markdown_offline(
+    markdown: &str,
+    resolve_resource: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Start(Tag::Image(link_type, url, title)) => {
+            let url = rewrite_image_url(&url, &resolve_resource);
+            Event::Start(Tag::Image(link_type, url, title))
+        }
+        Event::End(Tag::Image(link_type, url, title)) => {
+            let url = rewrite_image_url(&url, &resolve_resource);
+            Event::End(Tag::Image(link_type, url, title))
+        }
+        Event::Html(raw) => Event::Text(raw),
+        other => other,
+    });
+
+    let mut html_output = String::new();
+    html::push_html(&mut html_output, parser);
+    html_output
+}
+
+fn rewrite_image_url<'a>(
+    url: &str,
+    resolve_resource: &impl Fn(&str) -> Option<String>,
+) -> CowStr<'a> {
+    // Archived resources are matched by file name
+    let file_name = url.rsplit('/').next().unwrap_or(url);
+    if let Some(resolved) = resolve_resource(file_name) {
+        return CowStr::from(resolved);
+    }
+
+    // Inline data never leaves the browser; anything else would be fetched,
+    // either from a remote host or from the server hosting the viewer
+    if url.trim_start().to_lowercase().starts_with("data:") {
+        CowStr::from(url.to_string())
+    } else {
+        if is_remote_url(url) {
+            log::info!("Blocked remote image: {}", url);
+        }
+        CowStr::Borrowed(BLOCKED_IMAGE_PLACEHOLDER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_remote_url() {
+        assert!(is_remote_url("https://example.com/a.png"));
+        assert!(is_remote_url("HTTP://example.com/a.png"));
+        assert!(is_remote_url("//cdn.example.com/a.png"));
+        assert!(!is_remote_url("data:image/png;base64,AAAA"));
+        assert!(!is_remote_url("test-failed-1.png"));
+    }
+
+    #[test]
+    fn test_remote_image_replaced() {
+        let html = render_markdown_offline("![logo](https://example.com/logo.png)", |_| None);
+
+        assert!(!html.contains("example.com"));
+        assert!(html.contains(BLOCKED_IMAGE_PLACEHOLDER));
+        assert!(html.contains("alt=\"logo\""));
+    }
+
+    #[test]
+    fn test_archived_image_resolved() {
+        let html = render_markdown_offline("![failure](test-failed-1.png)", |name| {
+            (name == "test-failed-1.png").then(|| "data:image/png;base64,AAAA".to_string())
+        });
+
+        assert!(html.contains("src=\"data:image/png;base64,AAAA\""));
+    }
+
+    #[test]
+    fn test_unresolved_relative_image_replaced() {
+        let html = render_markdown_offline("![missing](missing.png)", |_| None);
+
+        assert!(!html.contains("missing.png"));
+        assert!(html.contains(BLOCKED_IMAGE_PLACEHOLDER));
+    }
+
+    #[test]
+    fn test_raw_html_escaped() {
+        let html = render_markdown_offline(
+            "<img src=\"https://tracker.example.com/pixel.gif\">\n\n<script>alert(1)</script>",
+            |_| None,
+        );
+
+        assert!(!html.contains("<img"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_plain_markdown_unchanged() {
+        let html = render_markdown_offline("# Error\n\n- item", |_| None);
+
+        assert!(html.contains("<h1>Error</h1>"));
+        assert!(html.contains("<li>item</li>"));
+    }
+}
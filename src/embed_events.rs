@@ -0,0 +1,146 @@
+//! Events reported to pages embedding the viewer
+//! Hosts either register a callback through `onViewerEvent` or, when the
+//! viewer runs in an iframe, listen for `message` events on the parent
+//! window. Both receive the same JSON payload tagged with `type`.
+
+use crate::models::TraceModel;
+use serde::Serialize;
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+
+/// Value of the `source` field so hosts can tell viewer messages apart
+pub const EVENT_SOURCE: &str = "trace-viewer";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ViewerEvent {
+    #[serde(rename_all = "camelCase")]
+    TraceLoaded {
+        contexts: usize,
+        actions: usize,
+        failed_actions: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    ActionSelected {
+        call_id: String,
+        title: Option<String>,
+        failed: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    ExportRequested { format: String, errors_only: bool },
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    source: &'static str,
+    #[serde(flatten)]
+    event: &'a ViewerEvent,
+}
+
+impl ViewerEvent {
+    pub fn trace_loaded(model: &TraceModel) -> Self {
+        let actions = model.contexts.iter().flat_map(|c| c.actions.iter());
+        Self::TraceLoaded {
+            contexts: model.contexts.len(),
+            actions: actions.clone().count(),
+            failed_actions: actions.filter(|a| a.error.is_some()).count(),
+        }
+    }
+
+    /// JSON payload delivered to the host
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Envelope {
+            source: EVENT_SOURCE,
+            event: self,
+        })
+        .unwrap_or_default()
+    }
+}
+
+thread_local! {
+    static LISTENER: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+/// Register a callback receiving every viewer event; replaces the previous one
+#[wasm_bindgen(js_name = onViewerEvent)]
+pub fn on_viewer_event(callback: js_sys::Function) {
+    LISTENER.with(|listener| *listener.borrow_mut() = Some(callback));
+}
+
+/// Deliver an event to the registered callback and the embedding page
+pub fn emit(event: ViewerEvent) {
+    let payload = match js_sys::JSON::parse(&event.to_json()) {
+        Ok(payload) => payload,
+        Err(e) => {
+            log::warn!("Failed to serialize viewer event: {:?}", e);
+            return;
+        }
+    };
+
+    LISTENER.with(|listener| {
+        if let Some(callback) = listener.borrow().as_ref() {
+            if let Err(e) = callback.call1(&JsValue::NULL, &payload) {
+                log::warn!("Viewer event callback failed: {:?}", e);
+            }
+        }
+    });
+
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    // Only post when framed; a top-level window would message itself
+    if let Ok(Some(parent)) = window.parent() {
+        if parent != window {
+            if let Err(e) = parent.post_message(&payload, "*") {
+                log::warn!("Failed to post viewer event: {:?}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_payload() {
+        let event = ViewerEvent::ActionSelected {
+            call_id: "call@12".to_string(),
+            title: Some("Click".to_string()),
+            failed: true,
+        };
+
+        let payload: serde_json::Value = serde_json::from_str(&event.to_json()).unwrap();
+        assert_eq!(payload["source"], "trace-viewer");
+        assert_eq!(payload["type"], "actionSelected");
+        assert_eq!(payload["callId"], "call@12");
+        assert_eq!(payload["failed"], true);
+    }
+
+    #[test]
+    fn test_export_payload() {
+        let event = ViewerEvent::ExportRequested {
+            format: "markdown".to_string(),
+            errors_only: false,
+        };
+
+        assert_eq!(
+            event.to_json(),
+            r#"{"source":"trace-viewer","type":"exportRequested","format":"markdown","errorsOnly":false}"#
+        );
+    }
+
+    #[test]
+    fn test_trace_loaded_counts() {
+        let event = ViewerEvent::trace_loaded(&TraceModel::new());
+        assert_eq!(
+            event,
+            ViewerEvent::TraceLoaded {
+                contexts: 0,
+                actions: 0,
+                failed_actions: 0
+            }
+        );
+    }
+}
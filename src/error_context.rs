@@ -0,0 +1,332 @@
+//! Parser for the `error-context.md` files Playwright writes next to failed tests
+//! The file is a list of `# Heading` sections: test info, the error, an ARIA
+//! snapshot of the page as YAML and the test source. Sections are split apart
+//! and the snapshot is parsed into a tree so it can be browsed.
+
+/// Sections of an `error-context.md` file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ErrorContext {
+    /// `- Name: value` pairs of the test info section
+    pub test_info: Vec<(String, String)>,
+    /// Error message and stack
+    pub error: Option<String>,
+    /// ARIA snapshot of the page at the time of the failure
+    pub page_snapshot: Option<Vec<AriaNode>>,
+    /// Source around the failing line
+    pub test_source: Option<String>,
+    /// Sections this parser does not know, as `(title, markdown)`
+    pub other_sections: Vec<(String, String)>,
+}
+
+/// Node of an ARIA snapshot, e.g. `- heading "Counter" [level=1] [ref=e4]`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AriaNode {
+    /// Role such as `button`, or `text` for text content
+    pub role: String,
+    /// Accessible name in quotes
+    pub name: Option<String>,
+    /// Bracketed attributes such as `level=1`, `ref=e4` or `checked`
+    pub attributes: Vec<String>,
+    /// Inline value after the colon, e.g. text content
+    pub value: Option<String>,
+    pub children: Vec<AriaNode>,
+}
+
+impl ErrorContext {
+    /// Whether the parser recognized anything beyond free-form markdown
+    pub fn is_structured(&self) -> bool {
+        self.error.is_some() || self.page_snapshot.is_some() || !self.test_info.is_empty()
+    }
+
+    /// First non-empty line of the error, for summaries
+    pub fn error_line(&self) -> Option<&str> {
+        self.error
+            .as_deref()?
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+    }
+}
+
+/// Split `error-context.md` into its sections
+pub fn parse_error_context(markdown: &str) -> ErrorContext {
+    let mut context = ErrorContext::default();
+
+    for (title, body) in split_sections(markdown) {
+        match title.to_lowercase().as_str() {
+            "error details" | "error" => context.error = Some(code_block_content(&body)),
+            "page snapshot" => {
+                context.page_snapshot = Some(parse_aria_snapshot(&code_block_content(&body)))
+            }
+            "test source" => context.test_source = Some(code_block_content(&body)),
+            "test info" => context.test_info = parse_test_info(&body),
+            _ => context
+                .other_sections
+                .push((title, body.trim().to_string())),
+        }
+    }
+
+    context
+}
+
+/// Parse an ARIA snapshot in Playwright's YAML dialect
+pub fn parse_aria_snapshot(yaml: &str) -> Vec<AriaNode> {
+    // Stack of (indent, node) whose children are still being read
+    let mut stack: Vec<(usize, AriaNode)> = Vec::new();
+    let mut roots = Vec::new();
+
+    for line in yaml.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let Some(entry) = line.trim_start().strip_prefix("- ") else {
+            continue;
+        };
+
+        while stack.last().is_some_and(|(depth, _)| *depth >= indent) {
+            pop_into(&mut stack, &mut roots);
+        }
+
+        stack.push((indent, parse_aria_line(entry)));
+    }
+
+    while !stack.is_empty() {
+        pop_into(&mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn pop_into(stack: &mut Vec<(usize, AriaNode)>, roots: &mut Vec<AriaNode>) {
+    if let Some((_, node)) = stack.pop() {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+fn parse_aria_line(entry: &str) -> AriaNode {
+    let (head, value) = split_value(entry);
+    let mut node = AriaNode {
+        value,
+        ..AriaNode::default()
+    };
+
+    let mut rest = head.trim();
+
+    // Role runs up to the first space, quote or bracket
+    let role_end = rest.find([' ', '"', '[']).unwrap_or(rest.len());
+    node.role = rest[..role_end].to_string();
+    rest = rest[role_end..].trim_start();
+
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let end = closing_quote(quoted).unwrap_or(quoted.len());
+        node.name = Some(unescape(&quoted[..end]));
+        rest = quoted.get(end + 1..).unwrap_or_default().trim_start();
+    }
+
+    while let Some(attribute) = rest.strip_prefix('[') {
+        let end = attribute.find(']').unwrap_or(attribute.len());
+        node.attributes.push(attribute[..end].to_string());
+        rest = attribute.get(end + 1..).unwrap_or_default().trim_start();
+    }
+
+    node
+}
+
+/// Split `role "name": value` at the first colon outside quotes
+fn split_value(entry: &str) -> (&str, Option<String>) {
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (index, ch) in entry.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                let value = entry[index + 1..].trim();
+                let value = (!value.is_empty()).then(|| {
+                    value
+                        .strip_prefix('"')
+                        .and_then(|value| value.strip_suffix('"'))
+                        .map_or_else(|| value.to_string(), unescape)
+                });
+                return (&entry[..index], value);
+            }
+            _ => {}
+        }
+    }
+
+    (entry, None)
+}
+
+fn closing_quote(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, ch) in text.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+fn split_sections(markdown: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+
+        match line.strip_prefix("# ") {
+            Some(title) if !in_code => sections.push((title.trim().to_string(), String::new())),
+            _ => {
+                if let Some((_, body)) = sections.last_mut() {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+            }
+        }
+    }
+
+    sections
+}
+
+/// Content of the first fenced code block, or the trimmed text without one
+fn code_block_content(body: &str) -> String {
+    let mut lines = body
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("```"));
+
+    if lines.next().is_none() {
+        return body.trim().to_string();
+    }
+
+    lines
+        .take_while(|line| !line.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_test_info(body: &str) -> Vec<(String, String)> {
+    body.lines()
+        .filter_map(|line| line.trim().strip_prefix("- "))
+        .filter_map(|item| item.split_once(": "))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERROR_CONTEXT: &str = r#"# Test info
+
+- Name: counter › increments
+- Location: tests/counter.spec.ts:12:5
+
+# Error details
+
+```
+Error: expect(locator).toHaveText(expected) failed
+
+Expected: "Counter: 1"
+```
+
+# Page snapshot
+
+```yaml
+- generic [ref=e3]:
+  - heading "Counter \"Example\"" [level=1] [ref=e4]
+  - generic [ref=e6]: "Counter: 0"
+  - list:
+    - listitem: First
+  - button "Increment" [active]
+```
+
+# Test source
+
+```ts
+await expect(counter).toHaveText('Counter: 1');
+```
+"#;
+
+    #[test]
+    fn test_parse_sections() {
+        let context = parse_error_context(ERROR_CONTEXT);
+
+        assert!(context.is_structured());
+        assert_eq!(
+            context.test_info,
+            vec![
+                ("Name".to_string(), "counter › increments".to_string()),
+                (
+                    "Location".to_string(),
+                    "tests/counter.spec.ts:12:5".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            context.error_line(),
+            Some("Error: expect(locator).toHaveText(expected) failed")
+        );
+        assert_eq!(
+            context.test_source.as_deref(),
+            Some("await expect(counter).toHaveText('Counter: 1');")
+        );
+        assert!(context.other_sections.is_empty());
+    }
+
+    #[test]
+    fn test_parse_aria_snapshot() {
+        let context = parse_error_context(ERROR_CONTEXT);
+        let roots = context.page_snapshot.unwrap();
+
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].role, "generic");
+        assert_eq!(roots[0].attributes, vec!["ref=e3"]);
+        assert_eq!(roots[0].children.len(), 4);
+
+        let heading = &roots[0].children[0];
+        assert_eq!(heading.role, "heading");
+        assert_eq!(heading.name.as_deref(), Some("Counter \"Example\""));
+        assert_eq!(heading.attributes, vec!["level=1", "ref=e4"]);
+
+        assert_eq!(roots[0].children[1].value.as_deref(), Some("Counter: 0"));
+
+        let list = &roots[0].children[2];
+        assert_eq!(list.children[0].role, "listitem");
+        assert_eq!(list.children[0].value.as_deref(), Some("First"));
+
+        let button = &roots[0].children[3];
+        assert_eq!(button.name.as_deref(), Some("Increment"));
+        assert_eq!(button.attributes, vec!["active"]);
+    }
+
+    #[test]
+    fn test_snapshot_only_file() {
+        let context = parse_error_context("# Page snapshot\n\n```yaml\n- button \"OK\"\n```\n");
+
+        assert!(context.error.is_none());
+        assert_eq!(context.page_snapshot.unwrap()[0].role, "button");
+    }
+
+    #[test]
+    fn test_unknown_markdown_kept() {
+        let context = parse_error_context("Something went wrong\n\n# Notes\n\nSee logs");
+
+        assert!(!context.is_structured());
+        assert_eq!(
+            context.other_sections,
+            vec![("Notes".to_string(), "See logs".to_string())]
+        );
+    }
+}
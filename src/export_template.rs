@@ -0,0 +1,413 @@
+//! Markdown exports in a team's own format
+//! A template is plain text with `{{placeholder}}` markers that are replaced
+//! by values of the trace, e.g. to match an incident or bug-report form.
+//! Unknown placeholders are kept as written so typos show up in the output.
+//! A custom template is kept in the browser's localStorage
+
+use crate::ansi_parser::strip_ansi;
+use crate::markdown_exporter::{export_to_markdown, render_markdown, ExportOptions};
+use crate::models::{ActionEntry, TraceModel};
+use crate::paths::shorten_paths;
+use crate::report::{build_metadata, error_text, format_clock_time, Report};
+use crate::steps::is_step;
+use gloo::storage::{LocalStorage, Storage};
+use std::collections::BTreeMap;
+
+/// localStorage key of the custom template
+const STORAGE_KEY: &str = "trace-viewer:export-template";
+
+/// Actions listed as steps to reproduce, counting back from the failure
+const MAX_STEPS: usize = 20;
+
+/// Placeholders with what they stand for, shown next to the template editor
+pub const PLACEHOLDERS: [(&str, &str); 14] = [
+    ("title", "Test or trace title"),
+    ("browser", "Browser name"),
+    ("platform", "Operating system"),
+    ("playwright_version", "Playwright version"),
+    ("url", "Last URL of the first page"),
+    ("start_time", "Wall-clock start of the trace"),
+    ("duration", "Duration of the trace"),
+    ("total_actions", "Number of actions"),
+    ("failed_actions", "Number of failed actions"),
+    ("first_error", "First line of the first error"),
+    ("failures", "List of failed actions with their errors"),
+    ("error_details", "Message and stack of the first error"),
+    ("steps", "Numbered actions up to the first failure"),
+    ("report", "The full markdown report"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTemplate {
+    BugReport,
+    SlackUpdate,
+    /// Template written by the user
+    Custom,
+}
+
+impl ExportTemplate {
+    pub const ALL: [ExportTemplate; 3] = [
+        ExportTemplate::BugReport,
+        ExportTemplate::SlackUpdate,
+        ExportTemplate::Custom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportTemplate::BugReport => "Bug report",
+            ExportTemplate::SlackUpdate => "Slack update",
+            ExportTemplate::Custom => "Custom template",
+        }
+    }
+
+    /// Text of a built-in template
+    pub fn builtin(&self) -> Option<&'static str> {
+        match self {
+            ExportTemplate::BugReport => Some(BUG_REPORT_TEMPLATE),
+            ExportTemplate::SlackUpdate => Some(SLACK_UPDATE_TEMPLATE),
+            ExportTemplate::Custom => None,
+        }
+    }
+}
+
+pub const BUG_REPORT_TEMPLATE: &str = "## Bug: {{title}}
+
+**Environment**: {{browser}} on {{platform}}, Playwright {{playwright_version}}
+**URL**: {{url}}
+**Started**: {{start_time}} · **Duration**: {{duration}}
+
+### What happened
+
+{{first_error}}
+
+### Steps to reproduce
+
+{{steps}}
+
+### Failed actions
+
+{{failures}}
+
+### Error details
+
+{{error_details}}
+";
+
+pub const SLACK_UPDATE_TEMPLATE: &str = "*{{title}}* failed on {{browser}} ({{failed_actions}} of {{total_actions}} actions, {{duration}})
+> {{first_error}}
+
+{{failures}}
+";
+
+/// Fill a template with the values of a trace, after the frontmatter when
+/// asked for
+pub fn render_template(template: &str, model: &TraceModel, options: &ExportOptions) -> String {
+    let text = fill(template, &template_values(model, options));
+    if !options.frontmatter {
+        return text;
+    }
+
+    let frontmatter = render_markdown(&Report {
+        blocks: vec![build_metadata(model, options)],
+    });
+    frontmatter + &text
+}
+
+/// Replace `{{name}}` markers, allowing spaces inside the braces
+pub fn fill(template: &str, values: &BTreeMap<&str, String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+
+        output.push_str(&rest[..start]);
+        match values.get(rest[start + 2..end].trim()) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Value of every placeholder; contexts are summed up, and the first
+/// context gives the environment
+pub fn template_values(
+    model: &TraceModel,
+    options: &ExportOptions,
+) -> BTreeMap<&'static str, String> {
+    let root = options.path_root.as_deref();
+    let unknown = || "unknown".to_string();
+    let first = model.contexts.first();
+    let actions: Vec<&ActionEntry> = model
+        .contexts
+        .iter()
+        .flat_map(|context| &context.actions)
+        .filter(|action| !is_step(action))
+        .collect();
+    let failed: Vec<&ActionEntry> = actions
+        .iter()
+        .copied()
+        .filter(|action| action.error.is_some())
+        .collect();
+
+    let title = match model.contexts.as_slice() {
+        [] => "Trace".to_string(),
+        [context] => context.title.clone().unwrap_or_else(|| "Trace".to_string()),
+        contexts => format!("{} traces", contexts.len()),
+    };
+    let duration: f64 = model
+        .contexts
+        .iter()
+        .map(|context| context.end_time - context.start_time)
+        .sum();
+
+    // Failed actions first, then errors outside of any action
+    let errors: Vec<(Option<&str>, Option<&str>)> = failed
+        .iter()
+        .filter_map(|action| action.error.as_ref())
+        .map(|error| (error.message.as_deref(), error.stack.as_deref()))
+        .chain(
+            model
+                .contexts
+                .iter()
+                .flat_map(|context| &context.errors)
+                .map(|error| (Some(error.message.as_str()), error.stack.as_deref())),
+        )
+        .collect();
+    let first_error = errors
+        .first()
+        .and_then(|(message, _)| *message)
+        .map(first_line)
+        .unwrap_or_else(|| "No errors".to_string());
+
+    let failures = if failed.is_empty() {
+        "None".to_string()
+    } else {
+        failed
+            .iter()
+            .map(|action| {
+                let message = action
+                    .error
+                    .as_ref()
+                    .and_then(|error| error.message.as_deref())
+                    .map(first_line)
+                    .unwrap_or_default();
+                format!("- `{}`: {}", action_name(action), message)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let error_details = errors
+        .first()
+        .map(|(message, stack)| format!("```\n{}\n```", error_text(*message, *stack)))
+        .unwrap_or_else(|| "None".to_string());
+
+    // Up to and including the first failure
+    let until = actions
+        .iter()
+        .position(|action| action.error.is_some())
+        .map_or(actions.len(), |index| index + 1);
+    let skipped = until.saturating_sub(MAX_STEPS);
+    let steps = actions[skipped..until]
+        .iter()
+        .enumerate()
+        .map(|(index, action)| format!("{}. {}", skipped + index + 1, action_name(action)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut values = BTreeMap::new();
+    values.insert("title", title);
+    values.insert(
+        "browser",
+        first.map_or_else(unknown, |context| context.browser_name.clone()),
+    );
+    values.insert(
+        "platform",
+        first
+            .and_then(|context| context.platform.clone())
+            .unwrap_or_else(unknown),
+    );
+    values.insert(
+        "playwright_version",
+        first
+            .and_then(|context| context.playwright_version.clone())
+            .unwrap_or_else(unknown),
+    );
+    values.insert(
+        "url",
+        first
+            .and_then(|context| context.pages.first())
+            .and_then(|page| page.url())
+            .map_or_else(unknown, str::to_string),
+    );
+    values.insert(
+        "start_time",
+        first.map_or_else(unknown, |context| format_clock_time(context.wall_time)),
+    );
+    values.insert("duration", format!("{:.2}s", duration / 1000.0));
+    values.insert("total_actions", actions.len().to_string());
+    values.insert("failed_actions", failed.len().to_string());
+    values.insert("first_error", first_error);
+    values.insert("failures", failures);
+    values.insert("error_details", error_details);
+    values.insert("steps", steps);
+    values.insert(
+        "report",
+        export_to_markdown(
+            model,
+            &ExportOptions {
+                frontmatter: false,
+                ..options.clone()
+            },
+        ),
+    );
+
+    values
+        .into_iter()
+        .map(|(name, value)| (name, shorten_paths(&value, root)))
+        .collect()
+}
+
+fn action_name(action: &ActionEntry) -> String {
+    action
+        .title
+        .clone()
+        .or_else(|| action.method.clone())
+        .unwrap_or_else(|| action.action_type.clone())
+}
+
+fn first_line(text: &str) -> String {
+    strip_ansi(text)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Stored custom template, if any
+pub fn load_custom_template() -> Option<String> {
+    LocalStorage::get(STORAGE_KEY).ok()
+}
+
+/// Store the custom template; an empty one removes the entry
+pub fn save_custom_template(template: &str) {
+    if template.trim().is_empty() {
+        LocalStorage::delete(STORAGE_KEY);
+    } else if let Err(e) = LocalStorage::set(STORAGE_KEY, template) {
+        log::warn!("Failed to save export template: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ContextEntry, SerializedError};
+    use std::collections::HashMap;
+
+    fn action(call_id: &str, title: &str, error: Option<&str>) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: call_id.to_string(),
+            start_time: 100.0,
+            end_time: 200.0,
+            title: Some(title.to_string()),
+            class: Some("Frame".to_string()),
+            method: Some("click".to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: error.map(|message| SerializedError {
+                message: Some(message.to_string()),
+                stack: Some("    at /work/app/tests/pay.spec.ts:7:3".to_string()),
+            }),
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        }
+    }
+
+    fn model() -> TraceModel {
+        let mut model = TraceModel::new();
+        model.contexts.push(ContextEntry {
+            id: String::new(),
+            start_time: 0.0,
+            end_time: 1500.0,
+            browser_name: "firefox".to_string(),
+            platform: None,
+            playwright_version: Some("1.48.0".to_string()),
+            wall_time: 1700000000000.0,
+            monotonic_time: 0.0,
+            title: Some("pays by card".to_string()),
+            pages: vec![],
+            actions: vec![
+                action("call@1", "Navigate to \"/checkout\"", None),
+                action(
+                    "call@2",
+                    "Click \"Pay\"",
+                    Some("\u{1b}[31mTimeout 5000ms exceeded\u{1b}[39m\nwaiting"),
+                ),
+                action("call@3", "Expect toast", None),
+            ],
+            resources: vec![],
+            events: vec![],
+            errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        });
+        model
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let values = BTreeMap::from([("title", "Checkout".to_string())]);
+
+        assert_eq!(
+            fill("# {{title}} / {{ title }}", &values),
+            "# Checkout / Checkout"
+        );
+        assert_eq!(fill("{{titel}} stays", &values), "{{titel}} stays");
+        assert_eq!(fill("open {{title", &values), "open {{title");
+    }
+
+    #[test]
+    fn test_every_placeholder_has_a_value() {
+        let values = template_values(&model(), &ExportOptions::default());
+        let names: Vec<&str> = PLACEHOLDERS.iter().map(|(name, _)| *name).collect();
+
+        assert_eq!(
+            values.keys().copied().collect::<Vec<_>>().len(),
+            names.len()
+        );
+        assert!(names.iter().all(|name| values.contains_key(name)));
+    }
+
+    #[test]
+    fn test_render_bug_report() {
+        let options = ExportOptions {
+            path_root: Some("/work/app/".to_string()),
+            ..Default::default()
+        };
+        let text = render_template(BUG_REPORT_TEMPLATE, &model(), &options);
+
+        assert!(text.starts_with("## Bug: pays by card\n"));
+        assert!(text.contains("**Environment**: firefox on unknown, Playwright 1.48.0"));
+        assert!(text.contains("### What happened\n\nTimeout 5000ms exceeded\n"));
+        assert!(text.contains("1. Navigate to \"/checkout\"\n2. Click \"Pay\"\n\n"));
+        assert!(text.contains("- `Click \"Pay\"`: Timeout 5000ms exceeded"));
+        assert!(text.contains("at …/tests/pay.spec.ts:7:3"));
+        assert!(!text.contains("{{"));
+    }
+}
@@ -0,0 +1,260 @@
+//! First look at a file before it is read
+//! Only the start and the end of the file are needed: the start holds the
+//! magic bytes, the end of a zip archive its central directory, whose entry
+//! names tell traces, reports and test results apart
+
+/// Bytes read from the start of a file
+pub const HEAD_BYTES: u64 = 4 * 1024;
+
+/// Bytes read from the end of a file, enough for the central directory of
+/// archives with a few thousand entries
+pub const TAIL_BYTES: u64 = 256 * 1024;
+
+/// Files above this size get a warning before they are read
+pub const LARGE_FILE_BYTES: u64 = 500 * 1024 * 1024;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const EMPTY_ZIP_MAGIC: &[u8] = b"PK\x05\x06";
+const END_OF_DIRECTORY: &[u8] = b"PK\x05\x06";
+const DIRECTORY_ENTRY: &[u8] = b"PK\x01\x02";
+
+/// Extensions the viewer loads
+const KNOWN_EXTENSIONS: [&str; 4] = [".zip", ".json", ".trace", ".network"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Trace,
+    /// `test-results` folder with a subfolder per test
+    TestResults,
+    HtmlReport,
+    BlobReport,
+    JsonReport,
+    /// `.trace` or `.network` file that was not zipped
+    RawTrace,
+    /// Zip archive whose entries could not be listed or recognized
+    Archive,
+    Unknown,
+}
+
+impl FileKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileKind::Trace => "Playwright trace",
+            FileKind::TestResults => "Test-results archive",
+            FileKind::HtmlReport => "HTML report",
+            FileKind::BlobReport => "Blob report",
+            FileKind::JsonReport => "JSON report",
+            FileKind::RawTrace => "Uncompressed trace",
+            FileKind::Archive => "Zip archive",
+            FileKind::Unknown => "Unknown file",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileProbe {
+    pub name: String,
+    pub size: u64,
+    pub kind: FileKind,
+    pub warnings: Vec<String>,
+}
+
+/// Identify a file from its name, size and the bytes at its start and end
+/// `tail` holds the last bytes of the file, or the whole file when it is small
+pub fn probe(name: &str, size: u64, head: &[u8], tail: &[u8]) -> FileProbe {
+    let lower = name.to_lowercase();
+    let is_zip = head.starts_with(ZIP_MAGIC) || head.starts_with(EMPTY_ZIP_MAGIC);
+    let mut warnings = Vec::new();
+
+    let kind = if is_zip {
+        match directory_names(size, tail) {
+            Some(names) => archive_kind(&names),
+            None => FileKind::Archive,
+        }
+    } else if lower.ends_with(".trace") || lower.ends_with(".network") {
+        FileKind::RawTrace
+    } else if first_char(head) == Some(b'{') {
+        FileKind::JsonReport
+    } else {
+        FileKind::Unknown
+    };
+
+    if !KNOWN_EXTENSIONS
+        .iter()
+        .any(|extension| lower.ends_with(extension))
+    {
+        warnings.push(format!(
+            "Unexpected file type; the viewer opens {}",
+            KNOWN_EXTENSIONS.join(", ")
+        ));
+    }
+    if lower.ends_with(".zip") && !is_zip {
+        warnings.push("Named .zip but is not a zip archive".to_string());
+    }
+    if is_html(head) {
+        warnings.push(
+            "This is an HTML page; drop the zipped playwright-report folder instead".to_string(),
+        );
+    }
+    match kind {
+        FileKind::Unknown if size == 0 => warnings.push("The file is empty".to_string()),
+        FileKind::Unknown => {
+            warnings.push("Does not look like a Playwright trace or report".to_string())
+        }
+        FileKind::Archive => warnings.push(
+            "Zip archive without trace files or test results that could be recognized".to_string(),
+        ),
+        _ => {}
+    }
+    if size > LARGE_FILE_BYTES {
+        warnings.push(format!(
+            "{} is large; loading may take a while and use as much memory",
+            format_size(size)
+        ));
+    }
+
+    FileProbe {
+        name: name.to_string(),
+        size,
+        kind,
+        warnings,
+    }
+}
+
+fn archive_kind(names: &[String]) -> FileKind {
+    let any = |predicate: &dyn Fn(&str) -> bool| names.iter().any(|name| predicate(name));
+
+    if any(&|name| name.ends_with(".trace")) {
+        FileKind::Trace
+    } else if any(&|name| {
+        name == "index.html" || (name.starts_with("data/") && name.ends_with(".zip"))
+    }) {
+        FileKind::HtmlReport
+    } else if any(&|name| !name.contains('/') && name.ends_with(".jsonl")) {
+        FileKind::BlobReport
+    } else if any(&|name| name.contains('/')) {
+        FileKind::TestResults
+    } else {
+        FileKind::Archive
+    }
+}
+
+/// Entry names from the central directory at the end of a zip archive;
+/// `None` when the directory does not fit in `tail`
+fn directory_names(size: u64, tail: &[u8]) -> Option<Vec<String>> {
+    let end = tail
+        .windows(END_OF_DIRECTORY.len())
+        .rposition(|window| window == END_OF_DIRECTORY)?;
+    let directory_size = read_u32(tail, end + 12)? as u64;
+    let directory_offset = read_u32(tail, end + 16)? as u64;
+
+    let tail_start = size.checked_sub(tail.len() as u64)?;
+    let mut position = usize::try_from(directory_offset.checked_sub(tail_start)?).ok()?;
+    let directory_end = position.checked_add(usize::try_from(directory_size).ok()?)?;
+
+    let mut names = Vec::new();
+    while position < directory_end && tail.get(position..position + 4)? == DIRECTORY_ENTRY {
+        let name_length = read_u16(tail, position + 28)? as usize;
+        let extra_length = read_u16(tail, position + 30)? as usize;
+        let comment_length = read_u16(tail, position + 32)? as usize;
+        let name = tail.get(position + 46..position + 46 + name_length)?;
+        names.push(String::from_utf8_lossy(name).into_owned());
+        position += 46 + name_length + extra_length + comment_length;
+    }
+
+    Some(names)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn first_char(bytes: &[u8]) -> Option<u8> {
+    bytes.iter().copied().find(|b| !b.is_ascii_whitespace())
+}
+
+fn is_html(head: &[u8]) -> bool {
+    let start = String::from_utf8_lossy(&head[..head.len().min(64)]).to_lowercase();
+    let start = start.trim_start();
+    start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+/// Size such as `12.3 MB`
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe_bytes(name: &str, bytes: &[u8]) -> FileProbe {
+        let head = &bytes[..bytes.len().min(HEAD_BYTES as usize)];
+        let tail = &bytes[bytes.len().saturating_sub(TAIL_BYTES as usize)..];
+        probe(name, bytes.len() as u64, head, tail)
+    }
+
+    #[test]
+    fn test_probe_archives() {
+        let trace = probe_bytes(
+            "trace.zip",
+            include_bytes!("../tests/fixtures/sample-trace.zip"),
+        );
+        assert_eq!(trace.kind, FileKind::Trace);
+        assert!(trace.warnings.is_empty());
+
+        let results = probe_bytes(
+            "results.zip",
+            include_bytes!("../tests/fixtures/test-cases.zip"),
+        );
+        assert_eq!(results.kind, FileKind::TestResults);
+
+        let report = probe_bytes(
+            "results.json",
+            include_bytes!("../tests/fixtures/results.json"),
+        );
+        assert_eq!(report.kind, FileKind::JsonReport);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_probe_directory_beyond_tail() {
+        let bytes = include_bytes!("../tests/fixtures/sample-trace.zip");
+        let tail = &bytes[bytes.len() - 30..];
+
+        let probe = probe("trace.zip", bytes.len() as u64, &bytes[..64], tail);
+        assert_eq!(probe.kind, FileKind::Archive);
+        assert_eq!(probe.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_probe_warnings() {
+        let html = probe_bytes("index.html", b"<!DOCTYPE html><html></html>");
+        assert_eq!(html.kind, FileKind::Unknown);
+        assert_eq!(html.warnings.len(), 3);
+
+        let fake = probe_bytes("trace.zip", b"not a zip");
+        assert_eq!(fake.kind, FileKind::Unknown);
+        assert!(fake
+            .warnings
+            .contains(&"Named .zip but is not a zip archive".to_string()));
+
+        let large = probe("trace.zip", LARGE_FILE_BYTES + 1, ZIP_MAGIC, &[]);
+        assert!(large
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("500.0 MB is large")));
+
+        let raw = probe_bytes("0-trace.trace", br#"{"type":"context-options"}"#);
+        assert_eq!(raw.kind, FileKind::RawTrace);
+        assert!(raw.warnings.is_empty());
+    }
+}
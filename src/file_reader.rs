@@ -0,0 +1,316 @@
+//! Reading of dropped files and folders
+//! Files are read in slices one after another, so the loading screen shows the
+//! bytes actually read, which matters for report archives of several hundred
+//! MB. Folders, e.g. an unzipped trace, are walked through the entries API and
+//! read file by file
+
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    Blob, DragEvent, File, FileSystemDirectoryEntry, FileSystemEntry, FileSystemFileEntry,
+};
+
+/// Bytes read per slice
+pub const CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Guard of a running read; dropping it stops after the current slice or file
+pub struct ReadGuard {
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl ReadGuard {
+    pub(crate) fn new() -> (Self, Rc<Cell<bool>>) {
+        let cancelled = Rc::new(Cell::new(false));
+        let guard = Self {
+            cancelled: cancelled.clone(),
+        };
+        (guard, cancelled)
+    }
+}
+
+impl Drop for ReadGuard {
+    fn drop(&mut self) {
+        self.cancelled.set(true);
+    }
+}
+
+/// Files and folders of a drop
+/// Entries are only available while the drop event is handled, so they are
+/// taken from it right away
+#[derive(Default)]
+pub struct DroppedItems {
+    pub files: Vec<File>,
+    pub folders: Vec<FileSystemDirectoryEntry>,
+}
+
+impl DroppedItems {
+    pub fn from_event(e: &DragEvent) -> Self {
+        let Some(data_transfer) = e.data_transfer() else {
+            return Self::default();
+        };
+
+        let mut dropped = Self::default();
+        let items = data_transfer.items();
+        for item in (0..items.length()).filter_map(|i| items.get(i)) {
+            if item.kind() != "file" {
+                continue;
+            }
+
+            match item.webkit_get_as_entry() {
+                Ok(Some(entry)) if entry.is_directory() => {
+                    dropped.folders.push(entry.unchecked_into());
+                }
+                _ => dropped.files.extend(item.get_as_file().ok().flatten()),
+            }
+        }
+
+        // Browsers without the items list still report the files
+        if items.length() == 0 {
+            if let Some(file_list) = data_transfer.files() {
+                dropped
+                    .files
+                    .extend((0..file_list.length()).filter_map(|i| file_list.get(i)));
+            }
+        }
+
+        dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len() + self.folders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Names of the dropped files, folders marked with a trailing slash
+    pub fn names(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .map(File::name)
+            .chain(
+                self.folders
+                    .iter()
+                    .map(|folder| format!("{}/", folder.name())),
+            )
+            .collect()
+    }
+}
+
+/// Read the whole file slice by slice
+/// `on_progress` gets the fraction read after every slice, `on_done` the bytes
+/// or the reason the read failed; neither is called once the guard is dropped
+pub fn read_in_chunks(
+    file: File,
+    on_progress: impl Fn(f32) + 'static,
+    on_done: impl FnOnce(Result<Vec<u8>, String>) + 'static,
+) -> ReadGuard {
+    let (guard, cancelled) = ReadGuard::new();
+
+    spawn_local(async move {
+        let size = file.size() as u64;
+        let mut bytes = Vec::with_capacity(size as usize);
+
+        for (start, end) in chunk_ranges(size, CHUNK_SIZE) {
+            let chunk = read_slice(&file, start, end).await;
+            if cancelled.get() {
+                return;
+            }
+
+            match chunk {
+                Ok(chunk) => bytes.extend_from_slice(&chunk),
+                Err(e) => {
+                    on_done(Err(e));
+                    return;
+                }
+            }
+            on_progress(end as f32 / size as f32);
+        }
+
+        on_done(Ok(bytes));
+    });
+
+    guard
+}
+
+/// Read every file of a folder as (path in the folder, bytes)
+/// `on_progress` gets the fraction of bytes read after every file
+pub fn read_folder(
+    folder: FileSystemDirectoryEntry,
+    on_progress: impl Fn(f32) + 'static,
+    on_done: impl FnOnce(Result<Vec<(String, Vec<u8>)>, String>) + 'static,
+) -> ReadGuard {
+    let (guard, cancelled) = ReadGuard::new();
+
+    spawn_local(async move {
+        let files = match folder_files(folder).await {
+            Ok(files) => read_named_files(files, &cancelled, &on_progress).await,
+            Err(e) => Some(Err(e)),
+        };
+
+        if let Some(result) = files.filter(|_| !cancelled.get()) {
+            on_done(result);
+        }
+    });
+
+    guard
+}
+
+/// Read several files one after another as (name, bytes), e.g. the `.trace`
+/// and `.network` files of an uncompressed trace
+pub fn read_files(
+    files: Vec<File>,
+    on_progress: impl Fn(f32) + 'static,
+    on_done: impl FnOnce(Result<Vec<(String, Vec<u8>)>, String>) + 'static,
+) -> ReadGuard {
+    let (guard, cancelled) = ReadGuard::new();
+
+    spawn_local(async move {
+        let files = files.into_iter().map(|file| (file.name(), file)).collect();
+        if let Some(result) = read_named_files(files, &cancelled, &on_progress).await {
+            on_done(result);
+        }
+    });
+
+    guard
+}
+
+/// `None` once the read was cancelled
+async fn read_named_files(
+    files: Vec<(String, File)>,
+    cancelled: &Cell<bool>,
+    on_progress: &impl Fn(f32),
+) -> Option<Result<Vec<(String, Vec<u8>)>, String>> {
+    let total: f64 = files.iter().map(|(_, file)| file.size()).sum();
+    let mut read = 0.0;
+    let mut contents = Vec::with_capacity(files.len());
+
+    for (name, file) in files {
+        let bytes = read_blob(&file).await;
+        if cancelled.get() {
+            return None;
+        }
+
+        match bytes {
+            Ok(bytes) => contents.push((name, bytes)),
+            Err(e) => return Some(Err(format!("{}: {}", name, e))),
+        }
+        read += file.size();
+        if total > 0.0 {
+            on_progress((read / total) as f32);
+        }
+    }
+
+    Some(Ok(contents))
+}
+
+/// Files under a folder with their paths relative to it, e.g. `resources/<sha1>`
+async fn folder_files(folder: FileSystemDirectoryEntry) -> Result<Vec<(String, File)>, String> {
+    let mut files = Vec::new();
+    let mut folders = vec![(String::new(), folder)];
+
+    while let Some((prefix, folder)) = folders.pop() {
+        for entry in folder_entries(&folder).await? {
+            let path = format!("{}{}", prefix, entry.name());
+            if entry.is_directory() {
+                folders.push((format!("{}/", path), entry.unchecked_into()));
+            } else {
+                files.push((path, entry_file(entry.unchecked_ref()).await?));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Entries of a folder; the reader hands them out in batches until none are left
+async fn folder_entries(folder: &FileSystemDirectoryEntry) -> Result<Vec<FileSystemEntry>, String> {
+    let reader = folder.create_reader();
+    let mut entries = Vec::new();
+
+    loop {
+        let batch = JsFuture::from(js_sys::Promise::new(&mut |resolve, reject| {
+            if let Err(e) = reader.read_entries_with_callback_and_callback(&resolve, &reject) {
+                let _ = reject.call1(&JsValue::NULL, &e);
+            }
+        }))
+        .await
+        .map_err(js_error)?;
+
+        let batch: js_sys::Array = batch.unchecked_into();
+        if batch.length() == 0 {
+            return Ok(entries);
+        }
+        entries.extend(batch.iter().map(JsCast::unchecked_into::<FileSystemEntry>));
+    }
+}
+
+async fn entry_file(entry: &FileSystemFileEntry) -> Result<File, String> {
+    JsFuture::from(js_sys::Promise::new(&mut |resolve, reject| {
+        entry.file_with_callback_and_callback(&resolve, &reject);
+    }))
+    .await
+    .map(JsCast::unchecked_into)
+    .map_err(js_error)
+}
+
+/// First `head` and last `tail` bytes of a file, overlapping for small files
+pub async fn read_head_and_tail(
+    file: &File,
+    head: u64,
+    tail: u64,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let size = file.size() as u64;
+    let head = read_slice(file, 0, head.min(size)).await?;
+    let tail = read_slice(file, size.saturating_sub(tail), size).await?;
+    Ok((head, tail))
+}
+
+async fn read_slice(file: &File, start: u64, end: u64) -> Result<Vec<u8>, String> {
+    let slice = file
+        .slice_with_f64_and_f64(start as f64, end as f64)
+        .map_err(js_error)?;
+    read_blob(&slice).await
+}
+
+async fn read_blob(blob: &Blob) -> Result<Vec<u8>, String> {
+    let buffer = JsFuture::from(blob.array_buffer())
+        .await
+        .map_err(js_error)?;
+    let buffer: js_sys::ArrayBuffer = buffer
+        .dyn_into()
+        .map_err(|_| "Slice was not read as bytes".to_string())?;
+
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+fn js_error(error: JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .unwrap_or_else(|| format!("{:?}", error))
+}
+
+/// Byte ranges covering `size` bytes, `chunk` at a time
+fn chunk_ranges(size: u64, chunk: u64) -> impl Iterator<Item = (u64, u64)> {
+    (0..size)
+        .step_by(chunk as usize)
+        .map(move |start| (start, (start + chunk).min(size)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_ranges() {
+        let ranges: Vec<(u64, u64)> = chunk_ranges(20, 8).collect();
+        assert_eq!(ranges, [(0, 8), (8, 16), (16, 20)]);
+
+        assert_eq!(chunk_ranges(16, 8).count(), 2);
+        assert_eq!(chunk_ranges(0, 8).count(), 0);
+    }
+}
@@ -0,0 +1,286 @@
+//! Shareable single-file HTML export
+//! The parsed model is embedded as gzip-compressed, base64-encoded JSON next to
+//! a small read-only viewer written in plain JavaScript, so the file opens in
+//! any current browser without the app being hosted. Only what the viewer
+//! renders is embedded: pages, network traffic, console and stdio output stay
+//! in the archive, since headers, bodies and logs can hold credentials
+
+use crate::models::{ContextEntry, TraceModel};
+use base64::{engine::general_purpose, Engine as _};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+/// Marks the element holding the compressed model
+const PAYLOAD_ID: &str = "trace-model";
+
+/// Build the standalone HTML page for a model
+pub fn export_html_bundle(model: &TraceModel, title: &str) -> Result<String, String> {
+    let payload = encode_model(model)?;
+
+    Ok(VIEWER_TEMPLATE
+        // Braces escaped so the title cannot add placeholders of its own
+        .replace("{{TITLE}}", &escape_html(title).replace('{', "&#123;"))
+        .replace("{{PAYLOAD_ID}}", PAYLOAD_ID)
+        .replace("{{PAYLOAD}}", &payload))
+}
+
+/// Gzip-compressed, base64-encoded JSON of what the viewer renders
+pub fn encode_model(model: &TraceModel) -> Result<String, String> {
+    let model = TraceModel {
+        contexts: model.contexts.iter().map(shareable_context).collect(),
+        ..TraceModel::new()
+    };
+
+    let json =
+        serde_json::to_vec(&model).map_err(|e| format!("Failed to serialize model: {}", e))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&json)
+        .and_then(|_| encoder.finish())
+        .map(|compressed| general_purpose::STANDARD.encode(compressed))
+        .map_err(|e| format!("Failed to compress model: {}", e))
+}
+
+/// Context with the summary, actions and errors the viewer renders
+fn shareable_context(context: &ContextEntry) -> ContextEntry {
+    ContextEntry {
+        id: context.id.clone(),
+        start_time: context.start_time,
+        end_time: context.end_time,
+        browser_name: context.browser_name.clone(),
+        platform: context.platform.clone(),
+        playwright_version: context.playwright_version.clone(),
+        wall_time: context.wall_time,
+        monotonic_time: context.monotonic_time,
+        title: context.title.clone(),
+        pages: vec![],
+        actions: context.actions.clone(),
+        resources: vec![],
+        events: vec![],
+        errors: context.errors.clone(),
+        stdio: vec![],
+        page_events: vec![],
+        console: vec![],
+        stats: context.stats.clone(),
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const VIEWER_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{TITLE}}</title>
+<style>
+  body { margin: 0; font: 14px/1.4 system-ui, sans-serif; background: #1a1a1a; color: #e8e8e8; }
+  header { padding: 1rem 1.5rem; border-bottom: 1px solid #3a3a3a; }
+  header h1 { margin: 0; font-size: 1.2rem; }
+  header p { margin: 0.25rem 0 0; color: #a0a0a0; font-size: 0.85rem; }
+  section { padding: 1rem 1.5rem; border-bottom: 1px solid #3a3a3a; }
+  h2 { margin: 0 0 0.5rem; font-size: 1rem; }
+  .meta { color: #a0a0a0; font-size: 0.85rem; margin-bottom: 0.75rem; }
+  details { border-left: 3px solid transparent; margin: 0.15rem 0; }
+  details.failed { border-left-color: #e63946; }
+  summary { display: flex; gap: 0.75rem; padding: 0.3rem 0.5rem; cursor: pointer; }
+  summary:hover { background: #2d2d2d; }
+  .method { font-weight: 600; min-width: 8rem; }
+  .title { flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+  .duration { color: #a0a0a0; }
+  .failed .method, .error { color: #e63946; }
+  pre { margin: 0.25rem 0.5rem 0.5rem 1rem; padding: 0.5rem; background: #1e1e1e; overflow-x: auto; white-space: pre-wrap; }
+  footer { padding: 1rem 1.5rem; color: #a0a0a0; font-size: 0.8rem; }
+</style>
+</head>
+<body>
+<header>
+  <h1>{{TITLE}}</h1>
+  <p>Read-only trace summary. Screenshots and network bodies are not included.</p>
+</header>
+<main id="viewer"><section>Loading…</section></main>
+<footer>Generated by Playwright Trace Viewer</footer>
+<script type="application/octet-stream" id="{{PAYLOAD_ID}}">{{PAYLOAD}}</script>
+<script>
+(async () => {
+  const root = document.getElementById('viewer');
+  const el = (tag, props = {}, ...children) => {
+    const node = Object.assign(document.createElement(tag), props);
+    node.append(...children.filter((child) => child !== null && child !== undefined));
+    return node;
+  };
+  const stripAnsi = (text) => (text || '').replace(/\u001b\[[0-9;]*[A-Za-z]/g, '');
+
+  let model;
+  try {
+    const base64 = document.getElementById('{{PAYLOAD_ID}}').textContent.trim();
+    const bytes = Uint8Array.from(atob(base64), (c) => c.charCodeAt(0));
+    const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream('gzip'));
+    model = JSON.parse(await new Response(stream).text());
+  } catch (error) {
+    root.replaceChildren(el('section', { className: 'error', textContent: 'Failed to read the embedded trace: ' + error }));
+    return;
+  }
+
+  const renderAction = (action) => {
+    const duration = action.endTime > 0 ? Math.round(action.endTime - action.startTime) + 'ms' : 'did not finish';
+    const name = action.method || action.type;
+    const title = action.title || action.locator || (action.params && action.params.url) || '';
+    const details = el('details', { className: action.error ? 'failed' : '' },
+      el('summary', {},
+        el('span', { className: 'method', textContent: name }),
+        el('span', { className: 'title', textContent: title }),
+        el('span', { className: 'duration', textContent: duration })));
+    if (action.params && Object.keys(action.params).length) {
+      details.append(el('pre', { textContent: JSON.stringify(action.params, null, 2) }));
+    }
+    if (action.error) {
+      const text = [action.error.message, action.error.stack].filter(Boolean).map(stripAnsi).join('\n\n');
+      details.append(el('pre', { className: 'error', textContent: text }));
+    }
+    if (action.log && action.log.length) {
+      details.append(el('pre', { textContent: action.log.map((entry) => entry.message).join('\n') }));
+    }
+    return details;
+  };
+
+  const sections = model.contexts.map((context, index) => {
+    const failed = context.actions.filter((action) => action.error).length;
+    const meta = [
+      context.browserName,
+      context.platform,
+      context.playwrightVersion && 'Playwright ' + context.playwrightVersion,
+      Math.round(context.endTime - context.startTime) + 'ms',
+      context.actions.length + ' actions',
+      failed ? failed + ' failed' : null,
+    ].filter(Boolean).join(' · ');
+    const section = el('section', {},
+      el('h2', { textContent: context.title || 'Trace ' + (index + 1) }),
+      el('div', { className: 'meta', textContent: meta }));
+    for (const error of context.errors || []) {
+      section.append(el('pre', { className: 'error', textContent: stripAnsi([error.message, error.stack].filter(Boolean).join('\n')) }));
+    }
+    section.append(...context.actions.map(renderAction));
+    return section;
+  });
+  root.replaceChildren(...sections);
+})();
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_bundle_embeds_model() {
+        let model: TraceModel = serde_json::from_value(serde_json::json!({
+            "contexts": [{
+                "startTime": 0.0,
+                "endTime": 100.0,
+                "browserName": "chromium",
+                "wallTime": 0.0,
+                "title": "checkout <pays>",
+                "pages": [],
+                "actions": [{
+                    "type": "before",
+                    "callId": "call@1",
+                    "startTime": 10.0,
+                    "endTime": 20.0,
+                    "method": "click",
+                    "locator": "#pay"
+                }],
+                "events": [{"type": "stdout", "timestamp": 1.0, "text": "hi"}]
+            }]
+        }))
+        .unwrap();
+
+        let html = export_html_bundle(&model, "checkout <pays>").unwrap();
+        assert!(html.contains("<title>checkout &lt;pays&gt;</title>"));
+        assert!(!html.contains("{{"));
+
+        let start = html.find(r#"id="trace-model">"#).unwrap() + r#"id="trace-model">"#.len();
+        let end = start + html[start..].find("</script>").unwrap();
+        let compressed = general_purpose::STANDARD.decode(&html[start..end]).unwrap();
+        let mut json = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut json)
+            .unwrap();
+
+        let decoded: TraceModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.contexts[0].actions, model.contexts[0].actions);
+        assert_eq!(decoded.contexts[0].title, model.contexts[0].title);
+        assert!(decoded.contexts[0].events.is_empty());
+    }
+
+    #[test]
+    fn test_bundle_leaves_out_network_and_logs() {
+        let model: TraceModel = serde_json::from_value(serde_json::json!({
+            "contexts": [{
+                "startTime": 0.0,
+                "endTime": 100.0,
+                "browserName": "chromium",
+                "wallTime": 0.0,
+                "pages": [],
+                "actions": [],
+                "resources": [{
+                    "request": {
+                        "method": "POST",
+                        "url": "https://shop.test/api/pay",
+                        "headers": [
+                            {"name": "Authorization", "value": "Bearer secret-token"},
+                            {"name": "Cookie", "value": "session=secret-cookie"}
+                        ],
+                        "postData": {"text": "card=4242"}
+                    },
+                    "response": {"status": 200, "content": {"size": 0}}
+                }],
+                "stdio": [{"stream": "stdout", "timestamp": 1.0, "text": "token=secret-stdout"}],
+                "console": [{
+                    "severity": "log",
+                    "text": "secret-console",
+                    "timestamp": 2.0
+                }]
+            }]
+        }))
+        .unwrap();
+        assert_eq!(model.contexts[0].resources.len(), 1);
+
+        let json = serde_json::to_string(&model).unwrap();
+        let payload = encode_model(&model).unwrap();
+        let compressed = general_purpose::STANDARD.decode(payload).unwrap();
+        let mut embedded = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut embedded)
+            .unwrap();
+
+        for secret in [
+            "secret-token",
+            "secret-cookie",
+            "card=4242",
+            "secret-stdout",
+            "secret-console",
+        ] {
+            assert!(json.contains(secret), "{} is not in the model", secret);
+            assert!(!embedded.contains(secret), "{} is embedded", secret);
+        }
+    }
+
+    #[test]
+    fn test_title_cannot_add_placeholders() {
+        let html = export_html_bundle(&TraceModel::new(), "{{PAYLOAD}} {{TITLE}}").unwrap();
+
+        assert!(html.contains("<title>&#123;&#123;PAYLOAD}} &#123;&#123;TITLE}}</title>"));
+        assert_eq!(html.matches(r#"id="trace-model">"#).count(), 1);
+    }
+}
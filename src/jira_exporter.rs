@@ -0,0 +1,178 @@
+//! Jira wiki markup serialization of reports
+//! Mirrors the markdown renderer for trackers that do not render markdown:
+//! headings become `h2.`, code `{code}` and `{noformat}` blocks, and tables
+//! use `||` header cells. Jira has no collapsible sections in wiki markup, so
+//! collapsible captions are plain labels, and it cannot show inline or
+//! relative images
+
+use crate::models::TraceModel;
+use crate::report::{build_trace_report, Block, ExportOptions, Report};
+
+/// Export a trace model as Jira wiki markup
+pub fn export_to_jira(model: &TraceModel, options: &ExportOptions) -> String {
+    render_jira(&build_trace_report(model, options))
+}
+
+/// Serialize a report as Jira wiki markup
+pub fn render_jira(report: &Report) -> String {
+    let mut output = String::new();
+
+    for block in &report.blocks {
+        render_block(&mut output, block);
+    }
+
+    output
+}
+
+fn render_block(output: &mut String, block: &Block) {
+    match block {
+        Block::Heading { level, text } => {
+            output.push_str(&format!("h{}. {}\n\n", (*level).clamp(1, 6), escape(text)));
+        }
+        Block::Fields(fields) => {
+            for (name, value) in fields {
+                output.push_str(&format!("* *{}*: {}\n", escape(name), escape(value)));
+            }
+            output.push('\n');
+        }
+        Block::Properties(properties) => {
+            for (name, value) in properties {
+                output.push_str(&format!("*{}*: {}\n", escape(name), escape(value)));
+            }
+            output.push('\n');
+        }
+        Block::Label(text) | Block::Collapsible(text) => {
+            output.push_str(&format!("*{}*:\n", escape(text)));
+        }
+        Block::Paragraph(text) => {
+            output.push_str(&escape(text));
+            output.push_str("\n\n");
+        }
+        Block::Note(text) => {
+            output.push_str(&format!("_{}_\n\n", escape(text)));
+        }
+        Block::List(items) => {
+            for item in items {
+                output.push_str(&format!("* {}\n", escape(item)));
+            }
+            output.push('\n');
+        }
+        Block::Table { headers, rows } => {
+            output.push_str(&table_row(headers, "||"));
+            for row in rows {
+                output.push_str(&table_row(row, "|"));
+            }
+            output.push('\n');
+        }
+        Block::Code { language, text } => {
+            // Jira highlights untyped `{code}` as Java, so plain text such as
+            // error messages goes into `{noformat}`
+            let (open, close) = match language {
+                Some(language) => (format!("{{code:{}}}", language), "{code}"),
+                None => ("{noformat}".to_string(), "{noformat}"),
+            };
+            output.push_str(&open);
+            output.push('\n');
+            output.push_str(text);
+            if !text.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(close);
+            output.push_str("\n\n");
+        }
+        Block::Image { src, .. } if src.starts_with("http") => {
+            output.push_str(&format!("!{}!\n\n", src));
+        }
+        Block::Image { .. } | Block::Metadata(_) => {}
+        Block::Anchor { id, .. } => {
+            output.push_str(&format!("{{anchor:{}}}\n", id));
+        }
+        Block::Contents(entries) => {
+            for (id, title) in entries {
+                output.push_str(&format!(
+                    "* [{}|#{}]\n",
+                    escape(title).replace('|', "\\|"),
+                    id
+                ));
+            }
+            output.push('\n');
+        }
+        Block::Rule => {
+            output.push_str("----\n\n");
+        }
+    }
+}
+
+fn table_row(cells: &[String], separator: &str) -> String {
+    // Empty cells collapse into the next separator unless they hold a space
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| escape(cell).replace('|', "\\|").replace('\n', " "))
+        .map(|cell| {
+            if cell.is_empty() {
+                " ".to_string()
+            } else {
+                cell
+            }
+        })
+        .collect();
+    format!("{}{}{}\n", separator, cells.join(separator), separator)
+}
+
+/// Braces and brackets start macros and links, e.g. in locators such as
+/// `getByRole('button', { name: 'Pay' })`
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_jira() {
+        let report = Report {
+            blocks: vec![
+                Block::Heading {
+                    level: 2,
+                    text: "1. click ⚠️ FAILED".to_string(),
+                },
+                Block::Properties(vec![(
+                    "Action".to_string(),
+                    "Click getByRole('button', { name: 'Pay' })".to_string(),
+                )]),
+                Block::Table {
+                    headers: vec!["Method".to_string(), "Duration".to_string()],
+                    rows: vec![vec!["click | dblclick".to_string(), String::new()]],
+                },
+                Block::Code {
+                    language: Some("json".to_string()),
+                    text: "{\"selector\": \"#pay\"}".to_string(),
+                },
+                Block::Code {
+                    language: None,
+                    text: "Error: Timeout\n".to_string(),
+                },
+                Block::Rule,
+            ],
+        };
+
+        assert_eq!(
+            render_jira(&report),
+            "h2. 1. click ⚠️ FAILED\n\n\
+             *Action*: Click getByRole('button', \\{ name: 'Pay' \\})\n\n\
+             ||Method||Duration||\n\
+             |click \\| dblclick| |\n\n\
+             {code:json}\n{\"selector\": \"#pay\"}\n{code}\n\n\
+             {noformat}\nError: Timeout\n{noformat}\n\n\
+             ----\n\n"
+        );
+    }
+}
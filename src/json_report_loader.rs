@@ -0,0 +1,309 @@
+//! Loader for the output of Playwright's JSON reporter (`--reporter=json`)
+//! Every result of every test becomes a `TestCase`; retries get a `-retryN`
+//! id suffix like the folders in test-results archives
+
+use crate::models::*;
+use crate::resource_store::ResourceStore;
+use crate::test_case_loader::{
+    capture_failure_warnings, extract_image_diffs, is_capture_failed, TestCaseLoadError,
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+struct JsonReport {
+    #[serde(default)]
+    suites: Vec<JsonSuite>,
+}
+
+#[derive(Deserialize)]
+struct JsonSuite {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    specs: Vec<JsonSpec>,
+    #[serde(default)]
+    suites: Vec<JsonSuite>,
+}
+
+#[derive(Deserialize)]
+struct JsonSpec {
+    title: String,
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    tests: Vec<JsonTest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonTest {
+    #[serde(default)]
+    project_name: String,
+    #[serde(default)]
+    annotations: Vec<TestAnnotation>,
+    #[serde(default)]
+    results: Vec<JsonResult>,
+}
+
+#[derive(Deserialize)]
+struct JsonResult {
+    #[serde(default)]
+    retry: usize,
+    status: String,
+    #[serde(default)]
+    duration: f64,
+    #[serde(default)]
+    error: Option<JsonError>,
+    #[serde(default)]
+    attachments: Vec<JsonAttachment>,
+}
+
+#[derive(Deserialize)]
+struct JsonError {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    stack: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonAttachment {
+    name: String,
+    content_type: String,
+    /// Base64 encoded content; attachments stored as files only have a path
+    #[serde(default)]
+    body: Option<String>,
+    /// Location of the file in the test output folder
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// Whether the bytes look like a JSON document rather than an archive
+pub fn is_json_report(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|b| *b == b'{')
+}
+
+/// Load test cases from a JSON reporter `results.json`
+pub fn load_test_cases_from_json_report(
+    bytes: &[u8],
+) -> Result<TestCaseCollection, TestCaseLoadError> {
+    let report: JsonReport =
+        serde_json::from_slice(bytes).map_err(|e| TestCaseLoadError::ParseError(e.to_string()))?;
+
+    let mut test_cases = Vec::new();
+    for suite in &report.suites {
+        collect_suite(suite, &[], &mut test_cases);
+    }
+
+    log::info!("Loaded {} test results from JSON report", test_cases.len());
+
+    let warnings = capture_failure_warnings(&test_cases);
+
+    Ok(TestCaseCollection {
+        test_cases,
+        warnings,
+        attachment_store: ResourceStore::new(),
+    })
+}
+
+/// Outcome of one result as recorded by the reporter
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReportOutcome {
+    pub status: TestStatus,
+    pub duration_ms: f64,
+}
+
+/// Outcome of each result keyed by the test-results folder its attachments are in
+/// Lets a test-results archive that ships its `results.json` use real outcomes
+pub(crate) fn outcomes_by_output_folder(bytes: &[u8]) -> HashMap<String, ReportOutcome> {
+    let mut outcomes = HashMap::new();
+
+    let Ok(report) = serde_json::from_slice::<JsonReport>(bytes) else {
+        return outcomes;
+    };
+
+    let mut suites: Vec<&JsonSuite> = report.suites.iter().collect();
+    while let Some(suite) = suites.pop() {
+        suites.extend(&suite.suites);
+
+        let results = suite
+            .specs
+            .iter()
+            .flat_map(|spec| &spec.tests)
+            .flat_map(|test| &test.results);
+
+        for result in results {
+            for path in result.attachments.iter().filter_map(|a| a.path.as_deref()) {
+                let mut components = path.rsplit(['/', '\\']);
+                if let Some(folder) = components.nth(1) {
+                    outcomes.insert(
+                        folder.to_string(),
+                        ReportOutcome {
+                            status: parse_status(&result.status),
+                            duration_ms: result.duration,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    outcomes
+}
+
+fn collect_suite(suite: &JsonSuite, parents: &[&str], test_cases: &mut Vec<TestCase>) {
+    let mut path = parents.to_vec();
+    if !suite.title.is_empty() {
+        path.push(&suite.title);
+    }
+
+    for spec in &suite.specs {
+        let mut title_path = path.clone();
+        title_path.push(&spec.title);
+        let title = title_path.join(" › ");
+
+        for test in &spec.tests {
+            for result in &test.results {
+                let mut test_case = build_test_case(spec, test, result, &title);
+                test_case.suite_path = path.iter().map(|title| title.to_string()).collect();
+                test_cases.push(test_case);
+            }
+        }
+    }
+
+    for child in &suite.suites {
+        collect_suite(child, &path, test_cases);
+    }
+}
+
+fn build_test_case(spec: &JsonSpec, test: &JsonTest, result: &JsonResult, title: &str) -> TestCase {
+    let mut id = if test.project_name.is_empty() {
+        spec.id.clone()
+    } else {
+        format!("{}-{}", spec.id, test.project_name)
+    };
+    if result.retry > 0 {
+        id.push_str(&format!("-retry{}", result.retry));
+    }
+
+    let mut name = if test.project_name.is_empty() {
+        title.to_string()
+    } else {
+        format!("{} ({})", title, test.project_name)
+    };
+    if result.retry > 0 {
+        name.push_str(&format!(" – retry {}", result.retry));
+    }
+
+    let status = parse_status(&result.status);
+
+    let mut screenshots = Vec::new();
+    let mut video = None;
+    let mut text_attachments = Vec::new();
+
+    for attachment in &result.attachments {
+        let Some(body) = &attachment.body else {
+            continue;
+        };
+
+        if attachment.content_type.starts_with("image/") {
+            screenshots.push(inline_attachment(attachment, body));
+        } else if attachment.content_type.starts_with("video/") {
+            video = Some(inline_attachment(attachment, body));
+        } else if let Ok(bytes) = general_purpose::STANDARD.decode(body) {
+            text_attachments.push(TextAttachment {
+                name: attachment.name.clone(),
+                mime_type: attachment.content_type.clone(),
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            });
+        }
+    }
+
+    // The full error keeps its ANSI colors for the attachment viewer
+    if let Some(error) = &result.error {
+        let text = [error.message.as_deref(), error.stack.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !text.is_empty() {
+            text_attachments.insert(
+                0,
+                TextAttachment {
+                    name: "error.txt".to_string(),
+                    mime_type: "text/plain".to_string(),
+                    content: text,
+                },
+            );
+        }
+    }
+
+    let error_message = result
+        .error
+        .as_ref()
+        .and_then(|error| error.message.as_deref())
+        .and_then(|message| message.lines().find(|line| !line.trim().is_empty()))
+        .map(|line| line.trim().to_string());
+
+    let image_diffs = extract_image_diffs(&mut screenshots);
+
+    TestCase {
+        id,
+        name,
+        status,
+        markdown_content: None,
+        screenshots,
+        video,
+        trace_file: None,
+        text_attachments,
+        duration_ms: Some(result.duration),
+        error_message,
+        annotations: test.annotations.clone(),
+        tags: spec.tags.iter().map(|tag| normalize_tag(tag)).collect(),
+        project: (!test.project_name.is_empty()).then(|| test.project_name.clone()),
+        suite_path: Vec::new(),
+        image_diffs,
+        trace_contexts: Vec::new(),
+        trace_overview: None,
+    }
+}
+
+/// Tags are shown with their `@` prefix, which older reports omit
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    if tag.starts_with('@') {
+        tag.to_string()
+    } else {
+        format!("@{}", tag)
+    }
+}
+
+/// Map a Playwright result status to the statuses shown in the list
+pub(crate) fn parse_status(status: &str) -> TestStatus {
+    match status {
+        "passed" => TestStatus::Passed,
+        "skipped" => TestStatus::Skipped,
+        "failed" | "timedOut" | "interrupted" => TestStatus::Failed,
+        _ => TestStatus::Pending,
+    }
+}
+
+fn inline_attachment(attachment: &JsonAttachment, body: &str) -> TestAttachment {
+    let bytes = general_purpose::STANDARD.decode(body).unwrap_or_default();
+
+    TestAttachment {
+        name: attachment.name.clone(),
+        mime_type: attachment.content_type.clone(),
+        data_url: format!("data:{};base64,{}", attachment.content_type, body),
+        size_bytes: Some(bytes.len()),
+        capture_failed: is_capture_failed(&attachment.content_type, &bytes),
+        archive_entry: None,
+    }
+}
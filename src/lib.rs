@@ -1,83 +1,361 @@
-use gloo::file::{callbacks::FileReader, File as GlooFile};
 use std::collections::HashMap;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
-use web_sys::File;
+use web_sys::{DragEvent, File, FileSystemDirectoryEntry};
 use yew::html::Scope;
 use yew::prelude::*;
 
+pub mod action_copy;
+pub mod analysis;
+pub mod annotations;
 mod ansi_parser;
+pub mod blob_report_loader;
+pub mod body_preview;
+pub mod codegen;
+pub mod compact;
 mod components;
+pub mod content_security;
+pub mod embed_events;
+pub mod error_context;
+pub mod export_template;
+pub mod file_probe;
+pub mod file_reader;
+pub mod html_bundle;
+pub mod jira_exporter;
+pub mod json_report_loader;
 pub mod markdown_exporter;
 pub mod models;
+pub mod ndjson_exporter;
+pub mod network_filter;
+pub mod paths;
+pub mod report;
+pub mod resource_store;
+pub mod route_mock_exporter;
+pub mod screenshot_archive;
+pub mod session;
+pub mod slack_exporter;
+pub mod stats;
+pub mod steps;
 pub mod test_case_loader;
+pub mod test_report_exporter;
+pub mod test_tree;
+pub mod trace_format;
+pub mod trace_links;
 pub mod trace_loader;
+pub mod triage;
+pub mod url_loader;
 
-use components::{FileDropZone, TestCaseList, TraceViewer};
+use analysis::base_test_id;
+use components::{ArchivePicker, FileDropZone, TestCaseList, TraceViewer};
+use file_probe::{format_size, FileProbe, HEAD_BYTES, TAIL_BYTES};
+use file_reader::{DroppedItems, ReadGuard};
 use models::{TestCaseCollection, TraceModel};
+use session::{Session, SessionContent, TraceOrigin};
+use trace_links::TraceTarget;
+use trace_loader::NestedArchive;
+use url_loader::UrlLoad;
+
+/// Trace shipped with the viewer for trying it out
+const SAMPLE_TRACE: &[u8] = include_bytes!("../tests/fixtures/sample-trace.zip");
+const SAMPLE_TRACE_NAME: &str = "sample-trace.zip";
 
 #[derive(Clone, PartialEq)]
 pub enum LoadingState {
     Idle,
-    Loading { progress: f32 },
-    LoadedTrace { model: TraceModel },
-    LoadedTestCases { test_cases: TestCaseCollection },
-    Error { message: String },
+    Loading {
+        progress: f32,
+    },
+    /// Report archive read, waiting for the nested traces to import
+    SelectingArchives {
+        file_name: String,
+        bytes: Rc<Vec<u8>>,
+        archives: Vec<NestedArchive>,
+    },
+    /// The active session item is shown
+    Loaded,
+    Error {
+        message: String,
+    },
 }
 
 pub enum AppMessage {
-    FilesDropped(Vec<File>),
-    FileSelected(File),
-    LoadingProgress(f32),
-    TraceLoaded(TraceModel),
-    TestCasesLoaded(TestCaseCollection),
+    FilesDropped(DroppedItems),
+    UrlSubmitted(UrlLoad),
+    LoadSample,
+    /// Fraction of a file read so far
+    LoadingProgress(usize, f32),
+    ReportArchiveOpened(String, Rc<Vec<u8>>, Vec<NestedArchive>),
+    ArchivesSelected(Vec<String>),
+    TraceLoaded(String, TraceModel),
+    TestCasesLoaded(String, TestCaseCollection),
     LoadError(String),
+    /// Hide the error of a load that failed next to open items
+    DismissLoadError,
+    /// Start and end of every file of a check were read
+    FilesProbed(usize, Vec<FileProbe>),
+    /// Load checked files despite their warnings
+    ConfirmLoad,
+    CancelLoad,
+    /// A file is dragged over the window while something is open
+    WindowDragOver(bool),
+    /// Files dropped onto an open trace or report, awaiting a choice
+    WindowDrop(DroppedItems),
+    ReplaceSession,
+    AddToSession,
+    CancelDrop,
+    /// Close what is open and return to the drop zone
+    Reset,
+    SelectItem(usize),
+    CloseItem(usize),
+    ToggleSidebar,
+    /// Open the trace of a test in the active test results
+    OpenTestTrace(TraceTarget),
+    /// Return from a trace to the test it was opened from
+    BackToTest(TraceOrigin),
 }
 
 pub struct App {
     state: LoadingState,
-    file_readers: HashMap<String, FileReader>,
+    /// Reads in flight, keyed by load id; dropped files can share a name
+    file_readers: HashMap<usize, ReadGuard>,
+    /// Fraction read of every file being loaded, keyed by load id
+    read_progress: HashMap<usize, f32>,
+    next_load_id: usize,
+    /// Error of a load that failed while other items stay open
+    load_error: Option<String>,
+    window_drag_over: bool,
+    /// Files dropped while viewing, until replace or add is chosen
+    pending_drop: Option<DroppedItems>,
+    /// Files being checked before they are read
+    pending_check: Option<PendingCheck>,
+    /// Traces and test results open side by side
+    session: Session,
+    sidebar_collapsed: bool,
+    /// Test the test results jump to when shown again
+    jump_to_test: Option<String>,
+}
+
+/// Files whose start and end are read before they load
+struct PendingCheck {
+    /// Load id of the check; a later drop replaces the check
+    id: usize,
+    items: DroppedItems,
+    adding: bool,
+    /// Filled in once the files were read and some of them look off
+    probes: Option<Vec<FileProbe>>,
 }
 
 impl Component for App {
     type Message = AppMessage;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
         wasm_logger::init(wasm_logger::Config::default());
         log::info!("Playwright Trace Viewer initialized");
 
+        register_launch_consumer(ctx.link().clone());
+
         Self {
             state: LoadingState::Idle,
             file_readers: HashMap::new(),
+            read_progress: HashMap::new(),
+            next_load_id: 0,
+            load_error: None,
+            window_drag_over: false,
+            pending_drop: None,
+            pending_check: None,
+            session: Session::new(),
+            sidebar_collapsed: false,
+            jump_to_test: None,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            AppMessage::FilesDropped(files) => {
-                if let Some(file) = files.first() {
-                    self.load_file(ctx, file.clone());
-                }
+            AppMessage::FilesDropped(items) => {
+                self.load_items(ctx, items, false);
+                true
+            }
+            AppMessage::UrlSubmitted(load) => {
+                self.begin_batch(1, false);
+                self.load_url(ctx, load);
                 true
             }
-            AppMessage::FileSelected(file) => {
-                self.load_file(ctx, file);
+            AppMessage::LoadSample => {
+                self.begin_batch(1, false);
+                self.state = LoadingState::Loading { progress: 0.0 };
+                load_bytes(
+                    ctx.link(),
+                    SAMPLE_TRACE_NAME.to_string(),
+                    SAMPLE_TRACE.to_vec(),
+                );
                 true
             }
-            AppMessage::LoadingProgress(progress) => {
-                self.state = LoadingState::Loading { progress };
+            AppMessage::LoadingProgress(load_id, fraction) => {
+                if !matches!(self.state, LoadingState::Loading { .. }) {
+                    return false;
+                }
+
+                self.read_progress.insert(load_id, fraction);
+                let read: f32 = self.read_progress.values().sum();
+                self.state = LoadingState::Loading {
+                    progress: read / self.session.batch_size() as f32,
+                };
                 true
             }
-            AppMessage::TraceLoaded(model) => {
-                self.state = LoadingState::LoadedTrace { model };
+            AppMessage::ReportArchiveOpened(file_name, bytes, archives) => {
+                self.state = LoadingState::SelectingArchives {
+                    file_name,
+                    bytes,
+                    archives,
+                };
                 true
             }
-            AppMessage::TestCasesLoaded(test_cases) => {
-                self.state = LoadingState::LoadedTestCases { test_cases };
+            AppMessage::ArchivesSelected(names) => {
+                let LoadingState::SelectingArchives {
+                    file_name, bytes, ..
+                } = &self.state
+                else {
+                    return false;
+                };
+
+                match trace_loader::load_report_archive_selection(bytes, &names) {
+                    Ok(model) => {
+                        let name = file_name.clone();
+                        // Loads that finished during the selection are open already
+                        self.session.trace_loaded(name, model);
+                        self.show_when_loaded();
+                    }
+                    Err(e) => {
+                        self.session.cancel_batch();
+                        self.state = LoadingState::Error {
+                            message: format!("Could not load selected traces: {}", e),
+                        };
+                    }
+                }
                 true
             }
+            AppMessage::TraceLoaded(file_name, model) => {
+                embed_events::emit(embed_events::ViewerEvent::trace_loaded(&model));
+                self.session.trace_loaded(file_name, model);
+                self.show_when_loaded()
+            }
+            AppMessage::TestCasesLoaded(file_name, test_cases) => {
+                self.session.test_cases_loaded(file_name, test_cases);
+                self.show_when_loaded()
+            }
             AppMessage::LoadError(message) => {
-                self.state = LoadingState::Error { message };
+                // Stop the other loads of the batch
+                self.session.cancel_batch();
+                self.file_readers.clear();
+                self.read_progress.clear();
+                if self.session.items.is_empty() {
+                    self.state = LoadingState::Error { message };
+                } else {
+                    // Keep what is open and show the error above it
+                    self.load_error = Some(message);
+                    self.state = LoadingState::Loaded;
+                }
+                true
+            }
+            AppMessage::DismissLoadError => self.load_error.take().is_some(),
+            AppMessage::FilesProbed(id, probes) => {
+                let Some(check) = self.pending_check.as_mut().filter(|check| check.id == id) else {
+                    return false;
+                };
+
+                if probes.iter().any(|probe| !probe.warnings.is_empty()) {
+                    check.probes = Some(probes);
+                    return true;
+                }
+                for probe in &probes {
+                    log::info!("{} looks like a {}", probe.name, probe.kind.label());
+                }
+                self.confirm_load(ctx);
+                true
+            }
+            AppMessage::ConfirmLoad => {
+                self.confirm_load(ctx);
+                true
+            }
+            AppMessage::CancelLoad => self.pending_check.take().is_some(),
+            AppMessage::WindowDragOver(over) => {
+                let changed = self.window_drag_over != over;
+                self.window_drag_over = over;
+                changed
+            }
+            AppMessage::WindowDrop(items) => {
+                self.window_drag_over = false;
+                self.pending_drop = (!items.is_empty()).then_some(items);
+                true
+            }
+            AppMessage::ReplaceSession => {
+                if let Some(items) = self.pending_drop.take() {
+                    ctx.link().send_message(AppMessage::FilesDropped(items));
+                }
+                true
+            }
+            AppMessage::AddToSession => {
+                let Some(items) = self.pending_drop.take() else {
+                    return false;
+                };
+
+                self.load_items(ctx, items, true);
+                true
+            }
+            AppMessage::CancelDrop => {
+                self.pending_drop = None;
+                true
+            }
+            AppMessage::Reset => {
+                // Dropping the readers aborts pending reads; dropping the state
+                // frees the archive bytes, and unmounting the views revokes
+                // their object URLs
+                self.file_readers.clear();
+                self.read_progress.clear();
+                self.load_error = None;
+                self.pending_drop = None;
+                self.pending_check = None;
+                self.session = Session::new();
+                self.window_drag_over = false;
+                self.state = LoadingState::Idle;
+                true
+            }
+            AppMessage::SelectItem(index) => {
+                if index >= self.session.items.len() {
+                    return false;
+                }
+                self.session.active_item = index;
+                self.jump_to_test = None;
+                self.state = LoadingState::Loaded;
+                true
+            }
+            AppMessage::CloseItem(index) => {
+                if !self.session.close(index) {
+                    return false;
+                }
+                if self.session.items.is_empty() {
+                    self.state = LoadingState::Idle;
+                }
+                true
+            }
+            AppMessage::ToggleSidebar => {
+                self.sidebar_collapsed = !self.sidebar_collapsed;
+                true
+            }
+            AppMessage::OpenTestTrace(target) => self.open_test_trace(target),
+            AppMessage::BackToTest(origin) => {
+                let Some(index) = self
+                    .session
+                    .items
+                    .iter()
+                    .position(|item| item.id == origin.item_id)
+                else {
+                    return false;
+                };
+                self.session.active_item = index;
+                self.jump_to_test = Some(base_test_id(&origin.target.test_case_id).0.to_string());
+                self.state = LoadingState::Loaded;
                 true
             }
         }
@@ -86,16 +364,75 @@ impl Component for App {
     fn view(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
 
+        // Once something is open, files dropped anywhere can replace it or
+        // join the session; before that the drop zone takes them
+        let is_open = !self.session.items.is_empty();
+        let ondragover = link.batch_callback(move |e: DragEvent| {
+            if !is_open {
+                return None;
+            }
+            e.prevent_default();
+            Some(AppMessage::WindowDragOver(true))
+        });
+        let ondragleave = link.batch_callback(move |e: DragEvent| {
+            // Leaving for a child element still counts as over the window
+            let inside = e.related_target().is_some();
+            (is_open && !inside).then_some(AppMessage::WindowDragOver(false))
+        });
+        let ondrop = link.batch_callback(move |e: DragEvent| {
+            if !is_open {
+                return None;
+            }
+            e.prevent_default();
+            Some(AppMessage::WindowDrop(DroppedItems::from_event(&e)))
+        });
+
         html! {
-            <div class="app">
+            <div class={classes!("app", self.window_drag_over.then_some("window-drag-over"))}
+                {ondragover}
+                {ondragleave}
+                {ondrop}
+            >
+                { self.render_drop_choice(link) }
+                { self.render_file_check(link) }
                 <header class="header">
                     <div class="logo">
                         <h1>{ "Playwright Trace Viewer" }</h1>
                         <span class="subtitle">{ "Rust Edition" }</span>
                     </div>
+                    if !matches!(self.state, LoadingState::Idle) {
+                        <button
+                            class="open-another-button"
+                            onclick={link.callback(|_| AppMessage::Reset)}
+                            title="Close this file and go back to the drop zone"
+                        >
+                            { "← Open another file" }
+                        </button>
+                    }
                 </header>
                 <main class="main-content">
-                    { self.render_content(link) }
+                    if let Some(message) = &self.load_error {
+                        <div class="load-error-banner" role="alert">
+                            <span class="load-error-message">{ message }</span>
+                            <button
+                                class="load-error-dismiss"
+                                onclick={link.callback(|_| AppMessage::DismissLoadError)}
+                                title="Dismiss"
+                            >
+                                { "×" }
+                            </button>
+                        </div>
+                    }
+                    if self.session.items.len() > 1 {
+                        <div class="session-layout">
+                            { self.render_sidebar(link) }
+                            <div class="session-main">
+                                { self.render_content(link) }
+                            </div>
+                        </div>
+                    } else {
+                        { self.render_content(link) }
+                    }
                 </main>
             </div>
         }
@@ -103,16 +440,228 @@ impl Component for App {
 }
 
 impl App {
+    /// Open a test's trace next to the test results at the target
+    fn open_test_trace(&mut self, target: TraceTarget) -> bool {
+        let Some(item) = self.session.active() else {
+            return false;
+        };
+        let SessionContent::TestCases { test_cases, .. } = &item.content else {
+            return false;
+        };
+        let Some(test_case) = test_cases
+            .test_cases
+            .iter()
+            .find(|test_case| test_case.id == target.test_case_id)
+        else {
+            return false;
+        };
+        let Some(bytes) = trace_links::trace_bytes(test_case, &test_cases.attachment_store) else {
+            log::warn!("Trace of {} is not in the archive", test_case.id);
+            return false;
+        };
+
+        let model = match trace_loader::load_trace_from_zip(&bytes) {
+            Ok(model) => model,
+            Err(e) => {
+                log::error!("Failed to load trace of {}: {}", test_case.id, e);
+                return false;
+            }
+        };
+        let origin = TraceOrigin {
+            item_id: item.id,
+            test_name: test_case.name.clone(),
+            target,
+        };
+        let name = format!("{} (trace)", test_case.name);
+
+        // Opening the same test's trace again replaces the earlier view of it
+        self.session.items.retain(|item| {
+            !matches!(
+                &item.content,
+                SessionContent::Trace { origin: Some(other), .. }
+                    if other.target.test_case_id == origin.target.test_case_id
+            )
+        });
+        self.session.open(
+            name,
+            SessionContent::Trace {
+                model,
+                origin: Some(origin),
+            },
+        );
+        self.state = LoadingState::Loaded;
+        true
+    }
+
+    /// Show the loaded content once every load of the batch has finished
+    fn show_when_loaded(&mut self) -> bool {
+        if self.session.is_loading() || self.session.items.is_empty() {
+            return false;
+        }
+
+        self.state = LoadingState::Loaded;
+        true
+    }
+
+    /// Open traces and reports; the active one is highlighted
+    fn render_sidebar(&self, link: &Scope<Self>) -> Html {
+        let toggle = link.callback(|_| AppMessage::ToggleSidebar);
+
+        html! {
+            <aside class={classes!("session-sidebar", self.sidebar_collapsed.then_some("collapsed"))}>
+                <button
+                    class="session-sidebar-toggle"
+                    onclick={toggle}
+                    title={if self.sidebar_collapsed { "Show open files" } else { "Hide open files" }}
+                >
+                    { if self.sidebar_collapsed { "»" } else { "«" } }
+                </button>
+                if !self.sidebar_collapsed {
+                    <h3 class="session-sidebar-title">{ format!("Open files ({})", self.session.items.len()) }</h3>
+                    <ul class="session-items">
+                        {
+                            self.session.items.iter().enumerate().map(|(index, item)| {
+                                let active = index == self.session.active_item
+                                    && matches!(self.state, LoadingState::Loaded);
+                                let (icon, kind) = match &item.content {
+                                    SessionContent::Trace { model, .. } => {
+                                        ("🎬", format!("{} contexts", model.contexts.len()))
+                                    }
+                                    SessionContent::TestCases { test_cases, .. } => {
+                                        ("🧪", format!("{} tests", test_cases.test_cases.len()))
+                                    }
+                                };
+
+                                html! {
+                                    <li key={item.id} class={classes!("session-item", active.then_some("active"))}>
+                                        <button
+                                            class="session-item-select"
+                                            onclick={link.callback(move |_| AppMessage::SelectItem(index))}
+                                            title={item.name.clone()}
+                                        >
+                                            <span class="session-item-icon">{ icon }</span>
+                                            <span class="session-item-text">
+                                                <span class="session-item-name">{ &item.name }</span>
+                                                <span class="session-item-kind">{ kind }</span>
+                                            </span>
+                                        </button>
+                                        <button
+                                            class="session-item-close"
+                                            onclick={link.callback(move |_| AppMessage::CloseItem(index))}
+                                            title="Close"
+                                        >
+                                            { "×" }
+                                        </button>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                    <p class="session-sidebar-hint">{ "Drop files anywhere to add more" }</p>
+                }
+            </aside>
+        }
+    }
+
+    /// Replace or add, for files dropped onto an open trace or report
+    fn render_drop_choice(&self, link: &Scope<Self>) -> Html {
+        let Some(items) = &self.pending_drop else {
+            return html! {};
+        };
+
+        let names = items.names().join(", ");
+
+        html! {
+            <div class="drop-choice-backdrop">
+                <div class="drop-choice" role="dialog" aria-label="Open dropped files">
+                    <h3>{ "Open dropped files" }</h3>
+                    <p class="drop-choice-files">{ names }</p>
+                    <div class="drop-choice-actions">
+                        <button
+                            class="drop-choice-button primary"
+                            onclick={link.callback(|_| AppMessage::AddToSession)}
+                            title="Open next to the files already open"
+                        >
+                            { "Add to session" }
+                        </button>
+                        <button
+                            class="drop-choice-button"
+                            onclick={link.callback(|_| AppMessage::ReplaceSession)}
+                            title="Close what is open and load the dropped files"
+                        >
+                            { "Replace" }
+                        </button>
+                        <button
+                            class="drop-choice-button"
+                            onclick={link.callback(|_| AppMessage::CancelDrop)}
+                        >
+                            { "Cancel" }
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// What the checked files appear to be, with the reasons to double-check them
+    fn render_file_check(&self, link: &Scope<Self>) -> Html {
+        let Some(probes) = self
+            .pending_check
+            .as_ref()
+            .and_then(|check| check.probes.as_ref())
+        else {
+            return html! {};
+        };
+
+        html! {
+            <div class="drop-choice-backdrop">
+                <div class="drop-choice file-check" role="dialog" aria-label="Check the files before loading">
+                    <h3>{ "Check the files before loading" }</h3>
+                    <ul class="file-check-list">
+                        { for probes.iter().map(|probe| html! {
+                            <li class={classes!("file-check-item", (!probe.warnings.is_empty()).then_some("has-warnings"))}>
+                                <div class="file-check-header">
+                                    <span class="file-check-name">{ &probe.name }</span>
+                                    <span class="file-check-kind">{ probe.kind.label() }</span>
+                                    <span class="file-check-size">{ format_size(probe.size) }</span>
+                                </div>
+                                { for probe.warnings.iter().map(|warning| html! {
+                                    <p class="file-check-warning">{ format!("⚠ {}", warning) }</p>
+                                }) }
+                            </li>
+                        }) }
+                    </ul>
+                    <div class="drop-choice-actions">
+                        <button
+                            class="drop-choice-button primary"
+                            onclick={link.callback(|_| AppMessage::ConfirmLoad)}
+                        >
+                            { "Load anyway" }
+                        </button>
+                        <button
+                            class="drop-choice-button"
+                            onclick={link.callback(|_| AppMessage::CancelLoad)}
+                        >
+                            { "Cancel" }
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
     fn render_content(&self, link: &Scope<Self>) -> Html {
         match &self.state {
             LoadingState::Idle => {
                 let on_files_dropped = link.callback(AppMessage::FilesDropped);
-                let on_file_selected = link.callback(AppMessage::FileSelected);
+                let on_url_submitted = link.callback(AppMessage::UrlSubmitted);
+                let on_sample_requested = link.callback(|_| AppMessage::LoadSample);
 
                 html! {
                     <FileDropZone
                         {on_files_dropped}
-                        {on_file_selected}
+                        {on_url_submitted}
+                        {on_sample_requested}
                     />
                 }
             }
@@ -128,18 +677,60 @@ impl App {
                     </div>
                 }
             }
-            LoadingState::LoadedTrace { model } => {
+            LoadingState::SelectingArchives { archives, .. } => {
+                let on_confirm = link.callback(AppMessage::ArchivesSelected);
+
                 html! {
-                    <TraceViewer model={model.clone()} />
+                    <ArchivePicker archives={archives.clone()} {on_confirm} />
                 }
             }
-            LoadingState::LoadedTestCases { test_cases } => {
-                html! {
-                    <TestCaseList test_cases={test_cases.clone()} />
+            LoadingState::Loaded => {
+                let Some(item) = self.session.active() else {
+                    return html! {};
+                };
+
+                match &item.content {
+                    SessionContent::Trace { model, origin } => {
+                        let target = origin.as_ref().map(|origin| &origin.target);
+                        // The test results may have been closed since
+                        let back = origin.clone().filter(|origin| {
+                            self.session
+                                .items
+                                .iter()
+                                .any(|item| item.id == origin.item_id)
+                        });
+                        let back_label = back
+                            .as_ref()
+                            .map(|origin| AttrValue::from(origin.test_name.clone()));
+                        let on_back = back.map(|origin| {
+                            link.callback(move |_| AppMessage::BackToTest(origin.clone()))
+                        });
+
+                        html! {
+                            <TraceViewer
+                                key={item.id}
+                                model={model.clone()}
+                                file_name={AttrValue::from(item.name.clone())}
+                                initial_context={target.map(|target| AttrValue::from(target.context_id.clone()))}
+                                initial_action={target.and_then(|target| target.call_id.clone()).map(AttrValue::from)}
+                                {on_back}
+                                {back_label}
+                            />
+                        }
+                    }
+                    SessionContent::TestCases { test_cases, runs } => html! {
+                        <TestCaseList
+                            key={item.id}
+                            test_cases={test_cases.clone()}
+                            runs={runs.clone()}
+                            initial_test={self.jump_to_test.clone().map(AttrValue::from)}
+                            on_open_trace={link.callback(AppMessage::OpenTestTrace)}
+                        />
+                    },
                 }
             }
             LoadingState::Error { message } => {
-                let on_retry = link.callback(|_| AppMessage::FilesDropped(vec![]));
+                let on_retry = link.callback(|_| AppMessage::Reset);
 
                 html! {
                     <div class="error-container">
@@ -152,7 +743,161 @@ impl App {
         }
     }
 
+    /// Check dropped, picked or launched files, then load them as a batch
+    /// that replaces the open items or, when `adding`, joins them; folders
+    /// are walked when they load
+    fn load_items(&mut self, ctx: &Context<Self>, items: DroppedItems, adding: bool) {
+        let id = self.next_load_id();
+        let files = items.files.clone();
+        self.pending_check = Some(PendingCheck {
+            id,
+            items,
+            adding,
+            probes: None,
+        });
+
+        let link = ctx.link().clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut probes = Vec::new();
+            for file in files {
+                let size = file.size() as u64;
+                let probe =
+                    match file_reader::read_head_and_tail(&file, HEAD_BYTES, TAIL_BYTES).await {
+                        Ok((head, tail)) => file_probe::probe(&file.name(), size, &head, &tail),
+                        Err(e) => {
+                            log::warn!("Could not check {}: {}", file.name(), e);
+                            file_probe::probe(&file.name(), size, &[], &[])
+                        }
+                    };
+                probes.push(probe);
+            }
+            link.send_message(AppMessage::FilesProbed(id, probes));
+        });
+    }
+
+    /// Load the files of the pending check
+    fn confirm_load(&mut self, ctx: &Context<Self>) {
+        if let Some(check) = self.pending_check.take() {
+            self.load_checked_items(ctx, check.items, check.adding);
+        }
+    }
+
+    /// Load checked files as one batch
+    fn load_checked_items(&mut self, ctx: &Context<Self>, items: DroppedItems, adding: bool) {
+        // Uncompressed trace files are parsed together as one trace
+        let (raw, archives): (Vec<File>, Vec<File>) = items
+            .files
+            .into_iter()
+            .partition(|file| trace_loader::is_raw_trace_file(&file.name()));
+
+        // Test result archives of the batch are treated as runs of the same suite
+        self.begin_batch(
+            archives.len() + items.folders.len() + usize::from(!raw.is_empty()),
+            adding,
+        );
+        for file in archives {
+            self.load_file(ctx, file);
+        }
+        for folder in items.folders {
+            self.load_folder(ctx, folder);
+        }
+        if !raw.is_empty() {
+            self.load_raw_trace(ctx, raw);
+        }
+    }
+
+    /// Start loading `loads` files or folders into the session
+    fn begin_batch(&mut self, loads: usize, adding: bool) {
+        self.session.begin_batch(loads, adding);
+        self.read_progress.clear();
+        self.load_error = None;
+    }
+
+    /// Id keying the reader and progress of a new load
+    fn next_load_id(&mut self) -> usize {
+        self.next_load_id += 1;
+        self.next_load_id
+    }
+
+    /// Load an unzipped trace folder
+    fn load_folder(&mut self, ctx: &Context<Self>, folder: FileSystemDirectoryEntry) {
+        let load_id = self.next_load_id();
+        let link = ctx.link().clone();
+        let folder_name = folder.name();
+
+        log::info!("Loading folder: {}", folder_name);
+
+        self.state = LoadingState::Loading { progress: 0.0 };
+
+        let task = {
+            let on_progress = progress_callback(&link, load_id);
+            file_reader::read_folder(folder, on_progress, move |result| {
+                trace_files_read(&link, folder_name, result)
+            })
+        };
+
+        self.file_readers.insert(load_id, task);
+    }
+
+    /// Load `.trace` files, with their `.network` files, that were not zipped
+    fn load_raw_trace(&mut self, ctx: &Context<Self>, files: Vec<File>) {
+        let load_id = self.next_load_id();
+        let link = ctx.link().clone();
+        let names: Vec<String> = files.iter().map(File::name).collect();
+        let traces: Vec<&str> = names
+            .iter()
+            .map(String::as_str)
+            .filter(|name| name.ends_with(".trace"))
+            .collect();
+        let name = if traces.is_empty() {
+            names.join(", ")
+        } else {
+            traces.join(", ")
+        };
+
+        log::info!("Loading uncompressed trace: {}", name);
+
+        self.state = LoadingState::Loading { progress: 0.0 };
+
+        let task = {
+            let on_progress = progress_callback(&link, load_id);
+            file_reader::read_files(files, on_progress, move |result| {
+                trace_files_read(&link, name, result)
+            })
+        };
+
+        self.file_readers.insert(load_id, task);
+    }
+
+    /// Download a trace or report and load it like a dropped file
+    fn load_url(&mut self, ctx: &Context<Self>, load: UrlLoad) {
+        let load_id = self.next_load_id();
+        let link = ctx.link().clone();
+        let file_name = load.file_name();
+
+        log::info!("Loading URL: {}", load.url);
+
+        self.state = LoadingState::Loading { progress: 0.0 };
+
+        let task = {
+            let on_progress = progress_callback(&link, load_id);
+            url_loader::fetch_bytes(load, on_progress, move |result| match result {
+                Ok(bytes) => {
+                    log::info!("Downloaded {} bytes", bytes.len());
+                    load_bytes(&link, file_name, bytes);
+                }
+                Err(e) => {
+                    log::error!("Error downloading: {}", e);
+                    link.send_message(AppMessage::LoadError(e));
+                }
+            })
+        };
+
+        self.file_readers.insert(load_id, task);
+    }
+
     fn load_file(&mut self, ctx: &Context<Self>, file: File) {
+        let load_id = self.next_load_id();
         let link = ctx.link().clone();
         let file_name = file.name();
 
@@ -160,66 +905,203 @@ impl App {
 
         self.state = LoadingState::Loading { progress: 0.0 };
 
-        let gloo_file = GlooFile::from(file);
         let task = {
-            let link = link.clone();
-            gloo::file::callbacks::read_as_bytes(&gloo_file, move |result| {
-                match result {
-                    Ok(bytes) => {
-                        log::info!("File read successfully, {} bytes", bytes.len());
-                        link.send_message(AppMessage::LoadingProgress(0.3));
-
-                        // Try loading as test cases first
-                        match test_case_loader::load_test_cases_from_zip(&bytes) {
-                            Ok(test_cases) if !test_cases.test_cases.is_empty() => {
-                                log::info!(
-                                    "Test cases loaded successfully: {} test cases",
-                                    test_cases.test_cases.len()
-                                );
-                                link.send_message(AppMessage::TestCasesLoaded(test_cases));
-                                return;
-                            }
-                            Ok(_) => {
-                                log::info!("No test cases found, trying to load as trace...");
-                            }
-                            Err(e) => {
-                                log::info!(
-                                    "Not a test case archive ({}), trying to load as trace...",
-                                    e
-                                );
-                            }
-                        }
-
-                        // If not test cases, try loading as a trace
-                        match trace_loader::load_trace_from_zip(&bytes) {
-                            Ok(model) => {
-                                log::info!("Trace loaded successfully");
-                                link.send_message(AppMessage::TraceLoaded(model));
-                            }
-                            Err(e) => {
-                                log::error!("Error loading file: {}", e);
-                                link.send_message(AppMessage::LoadError(format!(
-                                    "Could not load file as trace or test cases: {}",
-                                    e
-                                )));
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("Error reading file: {:?}", e);
-                        link.send_message(AppMessage::LoadError(format!(
-                            "Error reading file: {:?}",
-                            e
-                        )));
-                    }
+            let on_progress = progress_callback(&link, load_id);
+            file_reader::read_in_chunks(file, on_progress, move |result| match result {
+                Ok(bytes) => {
+                    log::info!("File read successfully, {} bytes", bytes.len());
+                    load_bytes(&link, file_name, bytes);
+                }
+                Err(e) => {
+                    log::error!("Error reading file: {}", e);
+                    link.send_message(AppMessage::LoadError(format!("Error reading file: {}", e)));
                 }
             })
         };
 
-        self.file_readers.insert(file_name, task);
+        self.file_readers.insert(load_id, task);
+    }
+}
+
+/// Recognize what a read file or download holds and load it
+fn load_bytes(link: &Scope<App>, file_name: String, bytes: Vec<u8>) {
+    // JSON reporter output carries real status, duration and retries
+    if file_name.ends_with(".json") || json_report_loader::is_json_report(&bytes) {
+        match json_report_loader::load_test_cases_from_json_report(&bytes) {
+            Ok(test_cases) => {
+                link.send_message(AppMessage::TestCasesLoaded(file_name, test_cases));
+            }
+            Err(e) => {
+                log::error!("Error loading JSON report: {}", e);
+                link.send_message(AppMessage::LoadError(format!(
+                    "Could not load JSON report: {}",
+                    e
+                )));
+            }
+        }
+        return;
+    }
+
+    // Let users pick the traces to import from large reports
+    if let Ok(Some(archives)) = trace_loader::list_report_archives(&bytes) {
+        if archives.len() > 1 {
+            log::info!("Report archive with {} traces", archives.len());
+            link.send_message(AppMessage::ReportArchiveOpened(
+                file_name,
+                Rc::new(bytes),
+                archives,
+            ));
+            return;
+        }
+    }
+
+    // Blob reporter archives, possibly one per CI shard
+    if blob_report_loader::is_blob_report(&bytes) {
+        match blob_report_loader::load_test_cases_from_blob_report(&bytes) {
+            Ok(test_cases) => {
+                link.send_message(AppMessage::TestCasesLoaded(file_name, test_cases));
+            }
+            Err(e) => {
+                log::error!("Error loading blob report: {}", e);
+                link.send_message(AppMessage::LoadError(format!(
+                    "Could not load blob report: {}",
+                    e
+                )));
+            }
+        }
+        return;
+    }
+
+    // Trace archives are recognized by their .trace files; anything
+    // else is treated as a folder-per-test results archive
+    let trace_error = match trace_loader::load_trace_from_zip(&bytes) {
+        Ok(model) => {
+            log::info!("Trace loaded successfully");
+            link.send_message(AppMessage::TraceLoaded(file_name, model));
+            return;
+        }
+        Err(e) => {
+            log::info!("Not a trace archive ({}), trying test cases...", e);
+            e
+        }
+    };
+
+    match test_case_loader::load_test_cases_from_zip(&bytes) {
+        Ok(test_cases) if !test_cases.test_cases.is_empty() => {
+            log::info!(
+                "Test cases loaded successfully: {} test cases",
+                test_cases.test_cases.len()
+            );
+            link.send_message(AppMessage::TestCasesLoaded(file_name, test_cases));
+        }
+        _ => {
+            log::error!("Error loading file: {}", trace_error);
+            link.send_message(AppMessage::LoadError(format!(
+                "Could not load file as trace or test cases: {}",
+                trace_error
+            )));
+        }
+    }
+}
+
+/// Parse the files of an unzipped trace once they are read
+fn trace_files_read(
+    link: &Scope<App>,
+    name: String,
+    result: Result<Vec<(String, Vec<u8>)>, String>,
+) {
+    let files = match result {
+        Ok(files) => files,
+        Err(e) => {
+            log::error!("Error reading {}: {}", name, e);
+            link.send_message(AppMessage::LoadError(format!("Error reading files: {}", e)));
+            return;
+        }
+    };
+
+    log::info!("Read {} trace files", files.len());
+    match trace_loader::load_trace_from_files(files) {
+        Ok(model) => link.send_message(AppMessage::TraceLoaded(name, model)),
+        Err(e) => {
+            log::error!("Error loading trace files: {}", e);
+            link.send_message(AppMessage::LoadError(format!(
+                "Could not load files as a trace: {}",
+                e
+            )));
+        }
     }
 }
 
+/// Reports the fraction read of a file or folder
+fn progress_callback(link: &Scope<App>, load_id: usize) -> impl Fn(f32) + 'static {
+    let link = link.clone();
+    move |fraction| link.send_message(AppMessage::LoadingProgress(load_id, fraction))
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// `window.launchQueue` of the File Handling API
+    type LaunchQueue;
+
+    #[wasm_bindgen(method, js_name = setConsumer)]
+    fn set_consumer(this: &LaunchQueue, consumer: &Closure<dyn FnMut(LaunchParams)>);
+
+    type LaunchParams;
+
+    /// `FileSystemFileHandle`s of the files the app was opened with
+    #[wasm_bindgen(method, getter)]
+    fn files(this: &LaunchParams) -> js_sys::Array;
+
+    type LaunchFileHandle;
+
+    #[wasm_bindgen(method, js_name = getFile)]
+    fn get_file(this: &LaunchFileHandle) -> js_sys::Promise;
+}
+
+/// Open the files the installed app is launched with, e.g. by double-clicking
+/// a downloaded trace; browsers without the File Handling API have no queue
+fn register_launch_consumer(link: Scope<App>) {
+    let Some(queue) = web_sys::window()
+        .and_then(|window| js_sys::Reflect::get(&window, &JsValue::from_str("launchQueue")).ok())
+        .filter(|queue| !queue.is_undefined() && !queue.is_null())
+    else {
+        return;
+    };
+
+    let consumer = Closure::<dyn FnMut(LaunchParams)>::new(move |params: LaunchParams| {
+        let handles = params.files();
+        if handles.length() == 0 {
+            return;
+        }
+
+        let link = link.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut files = Vec::new();
+            for handle in handles.iter() {
+                let handle: LaunchFileHandle = handle.unchecked_into();
+                match wasm_bindgen_futures::JsFuture::from(handle.get_file()).await {
+                    Ok(file) => files.push(file.unchecked_into::<File>()),
+                    Err(e) => log::error!("Could not open launched file: {:?}", e),
+                }
+            }
+
+            if !files.is_empty() {
+                log::info!("Opening {} launched file(s)", files.len());
+                link.send_message(AppMessage::FilesDropped(DroppedItems {
+                    files,
+                    folders: Vec::new(),
+                }));
+            }
+        });
+    });
+
+    queue
+        .unchecked_into::<LaunchQueue>()
+        .set_consumer(&consumer);
+    // The queue keeps calling the consumer for the lifetime of the page
+    consumer.forget();
+}
+
 #[wasm_bindgen(start)]
 pub fn run_app() {
     yew::Renderer::<App>::new().render();
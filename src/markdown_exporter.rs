@@ -1,204 +1,265 @@
-use crate::models::{ActionEntry, ContextEntry, TraceModel};
-use chrono::{DateTime, Utc};
-
-/// Options for exporting traces to markdown
-#[derive(Debug, Clone, Default)]
-pub struct ExportOptions {
-    /// Only export actions with errors
-    pub errors_only: bool,
-}
+use crate::html_bundle::escape_html;
+use crate::models::TraceModel;
+use crate::report::{build_trace_report, Block, Report, SCREENSHOTS_DIR};
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub use crate::report::{ExportOptions, MarkdownFlavor, ScreenshotMode};
+
+/// Name of the markdown file in a bundle
+pub const BUNDLE_REPORT_NAME: &str = "report.md";
 
 /// Export a trace model to markdown format suitable for Claude Code
 pub fn export_to_markdown(model: &TraceModel, options: &ExportOptions) -> String {
-    let mut output = String::new();
+    render_markdown_flavored(&build_trace_report(model, options), options.flavor)
+}
 
-    output.push_str("# Playwright Trace Report\n\n");
+/// Zip of the markdown report and the screenshots it links to, which sit
+/// next to it under `screenshots/`
+pub fn export_markdown_bundle(
+    model: &TraceModel,
+    options: &ExportOptions,
+) -> Result<Vec<u8>, String> {
+    let options = ExportOptions {
+        screenshots: ScreenshotMode::Links,
+        ..options.clone()
+    };
+    let report = build_trace_report(model, &options);
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        // Images do not shrink any further
+        let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+        zip.start_file(BUNDLE_REPORT_NAME, deflated)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(render_markdown_flavored(&report, options.flavor).as_bytes())
+            .map_err(|e| e.to_string())?;
+
+        let mut written = HashSet::new();
+        for block in &report.blocks {
+            let Block::Image { src, .. } = block else {
+                continue;
+            };
+            let Some(sha1) = src.strip_prefix(SCREENSHOTS_DIR) else {
+                continue;
+            };
+            if !written.insert(src) {
+                continue;
+            }
+            let Some(bytes) = model.resource_store.get(sha1) else {
+                continue;
+            };
 
-    // Export each context
-    for (idx, context) in model.contexts.iter().enumerate() {
-        if model.contexts.len() > 1 {
-            output.push_str(&format!("## Context {}\n\n", idx + 1));
+            zip.start_file(src.as_str(), stored)
+                .map_err(|e| e.to_string())?;
+            zip.write_all(&bytes).map_err(|e| e.to_string())?;
         }
 
-        export_context(&mut output, context, options);
-
-        if idx < model.contexts.len() - 1 {
-            output.push_str("\n---\n\n");
-        }
+        zip.finish().map_err(|e| e.to_string())?;
     }
 
-    output
+    Ok(buf)
 }
 
-fn export_context(output: &mut String, context: &ContextEntry, options: &ExportOptions) {
-    // Test information
-    output.push_str("## Test Information\n\n");
-
-    if let Some(title) = &context.title {
-        output.push_str(&format!("- **Title**: {}\n", title));
-    }
-
-    output.push_str(&format!("- **Browser**: {}\n", context.browser_name));
-
-    if let Some(platform) = &context.platform {
-        output.push_str(&format!("- **Platform**: {}\n", platform));
-    }
-
-    if let Some(version) = &context.playwright_version {
-        output.push_str(&format!("- **Playwright Version**: {}\n", version));
-    }
-
-    // Convert wall time to readable date
-    let datetime = DateTime::from_timestamp_millis(context.wall_time as i64)
-        .unwrap_or(DateTime::<Utc>::MIN_UTC);
-    output.push_str(&format!(
-        "- **Start Time**: {}\n",
-        datetime.format("%Y-%m-%d %H:%M:%S UTC")
-    ));
-
-    let duration = (context.end_time - context.start_time) / 1000.0;
-    output.push_str(&format!("- **Duration**: {:.2}s\n\n", duration));
-
-    // Summary
-    let actions_to_export: Vec<&ActionEntry> = if options.errors_only {
-        context
-            .actions
-            .iter()
-            .filter(|a| a.error.is_some())
-            .collect()
-    } else {
-        context.actions.iter().collect()
-    };
-
-    let failed_actions = context.actions.iter().filter(|a| a.error.is_some()).count();
+/// Serialize a report as markdown
+pub fn render_markdown(report: &Report) -> String {
+    render_markdown_flavored(report, MarkdownFlavor::Plain)
+}
 
-    output.push_str("## Summary\n\n");
-    output.push_str(&format!("- **Total Actions**: {}\n", context.actions.len()));
-    output.push_str(&format!("- **Failed Actions**: {}\n", failed_actions));
+/// Serialize a report in the given markdown dialect
+pub fn render_markdown_flavored(report: &Report, flavor: MarkdownFlavor) -> String {
+    let mut output = String::new();
+    // A collapsible caption wraps the block after it
+    let mut collapsing = false;
+
+    for block in &report.blocks {
+        match (flavor, block) {
+            (MarkdownFlavor::GitHub, Block::Collapsible(text)) => {
+                output.push_str(&format!(
+                    "<details>\n<summary>{}</summary>\n\n",
+                    escape_html(text)
+                ));
+                collapsing = true;
+                continue;
+            }
+            (MarkdownFlavor::GitHub, Block::Fields(fields)) => {
+                let (names, values): (Vec<String>, Vec<String>) = fields.iter().cloned().unzip();
+                render_block(
+                    &mut output,
+                    &Block::Table {
+                        headers: names,
+                        rows: vec![values],
+                    },
+                );
+            }
+            _ => render_block(&mut output, block),
+        }
 
-    if !context.errors.is_empty() {
-        output.push_str(&format!("- **Context Errors**: {}\n", context.errors.len()));
+        if collapsing {
+            output.push_str("</details>\n\n");
+            collapsing = false;
+        }
     }
 
-    if options.errors_only && failed_actions == 0 && context.errors.is_empty() {
-        output.push_str("\n*No errors found in this trace.*\n\n");
-        return;
+    if collapsing {
+        output.push_str("</details>\n\n");
     }
 
-    output.push('\n');
-
-    // Export actions
-    if !actions_to_export.is_empty() {
-        output.push_str("## Actions\n\n");
+    output
+}
 
-        for (idx, action) in actions_to_export.iter().enumerate() {
-            export_action(output, action, idx + 1);
+fn render_block(output: &mut String, block: &Block) {
+    match block {
+        Block::Heading { level, text } => {
+            output.push_str(&format!(
+                "{} {}\n\n",
+                "#".repeat((*level).clamp(1, 6)),
+                text
+            ));
         }
-    }
-
-    // Export context-level errors
-    if !context.errors.is_empty() {
-        output.push_str("## Context Errors\n\n");
-
-        for (idx, error) in context.errors.iter().enumerate() {
-            output.push_str(&format!("### Error {}\n\n", idx + 1));
-            output.push_str("```\n");
-            output.push_str(&error.message);
+        Block::Fields(fields) => {
+            for (name, value) in fields {
+                output.push_str(&format!("- **{}**: {}\n", name, value));
+            }
             output.push('\n');
-
-            if let Some(stack) = &error.stack {
-                output.push_str("\nStack trace:\n");
-                output.push_str(stack);
+        }
+        Block::Properties(properties) => {
+            // Trailing double spaces force line breaks between properties
+            for (name, value) in properties {
+                output.push_str(&format!("**{}**: {}  \n", name, value));
+            }
+            output.push('\n');
+        }
+        Block::Label(text) | Block::Collapsible(text) => {
+            output.push_str(&format!("**{}**:\n\n", text));
+        }
+        Block::Paragraph(text) => {
+            output.push_str(text);
+            output.push_str("\n\n");
+        }
+        Block::Note(text) => {
+            output.push_str(&format!("*{}*\n\n", text));
+        }
+        Block::List(items) => {
+            for item in items {
+                output.push_str(&format!("- {}\n", item));
+            }
+            output.push('\n');
+        }
+        Block::Table { headers, rows } => {
+            output.push_str(&table_row(headers));
+            output.push_str(&table_row(&vec!["---".to_string(); headers.len()]));
+            for row in rows {
+                output.push_str(&table_row(row));
+            }
+            output.push('\n');
+        }
+        Block::Code { language, text } => {
+            output.push_str("```");
+            output.push_str(language.as_deref().unwrap_or_default());
+            output.push('\n');
+            output.push_str(text);
+            if !text.ends_with('\n') {
                 output.push('\n');
             }
-
             output.push_str("```\n\n");
         }
+        Block::Image { alt, src } => {
+            output.push_str(&format!("![{}]({})\n\n", alt, src));
+        }
+        Block::Metadata(fields) => {
+            // JSON scalars are valid YAML, and quoting keeps titles with
+            // colons or quotes intact
+            output.push_str("---\n");
+            for (name, value) in fields {
+                output.push_str(&format!("{}: {}\n", name, value));
+            }
+            output.push_str("---\n\n");
+        }
+        Block::Anchor { id, .. } => {
+            output.push_str(&format!("<a id=\"{}\"></a>\n\n", escape_html(id)));
+        }
+        Block::Contents(entries) => {
+            for (id, title) in entries {
+                let title = title.replace('[', "\\[").replace(']', "\\]");
+                output.push_str(&format!("- [{}](#{})\n", title, id));
+            }
+            output.push('\n');
+        }
+        Block::Rule => {
+            output.push_str("---\n\n");
+        }
     }
 }
 
-fn export_action(output: &mut String, action: &ActionEntry, index: usize) {
-    let method = action
-        .method
-        .as_deref()
-        .or(action.class.as_deref())
-        .unwrap_or(&action.action_type);
-
-    let status = if action.error.is_some() {
-        " ⚠️ FAILED"
-    } else {
-        ""
-    };
-
-    output.push_str(&format!("### {}. {}{}\n\n", index, method, status));
-
-    // Duration
-    if action.end_time > 0.0 {
-        let duration = action.end_time - action.start_time;
-        output.push_str(&format!("**Duration**: {:.0}ms  \n", duration));
-    }
-
-    output.push_str(&format!("**Start**: {:.0}ms  \n", action.start_time));
-
-    // Title if available
-    if let Some(title) = &action.title {
-        output.push_str(&format!("**Action**: {}  \n", title));
-    }
-
-    output.push('\n');
+fn table_row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+        .collect();
+    format!("| {} |\n", cells.join(" | "))
+}
 
-    // Parameters
-    if !action.params.is_empty() {
-        output.push_str("**Parameters**:\n\n");
-        output.push_str("```json\n");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        ActionEntry, ConsoleLocation, ConsoleMessage, ConsoleSeverity, ContextEntry, ErrorEvent,
+        LogEntry, SerializedError,
+    };
+    use crate::stats::compute_stats;
+    use std::collections::HashMap;
 
-        match serde_json::to_string_pretty(&action.params) {
-            Ok(json) => output.push_str(&json),
-            Err(_) => output.push_str(&format!("{:?}", action.params)),
+    fn console_message(severity: ConsoleSeverity, timestamp: f64, text: &str) -> ConsoleMessage {
+        ConsoleMessage {
+            severity,
+            text: text.to_string(),
+            location: Some(ConsoleLocation {
+                url: "http://app.test/cart.js".to_string(),
+                line: 7,
+                column: 3,
+            }),
+            timestamp,
+            page_id: Some("page@1".to_string()),
         }
-
-        output.push_str("\n```\n\n");
     }
 
-    // Error information
-    if let Some(error) = &action.error {
-        output.push_str("**Error**:\n\n");
-        output.push_str("```\n");
-
-        if let Some(message) = &error.message {
-            output.push_str(message);
-            output.push('\n');
-        }
+    #[test]
+    fn test_render_table() {
+        let report = Report {
+            blocks: vec![Block::Table {
+                headers: vec!["Method".to_string(), "Duration".to_string()],
+                rows: vec![vec!["click | dblclick".to_string(), "5ms".to_string()]],
+            }],
+        };
 
-        if let Some(stack) = &error.stack {
-            output.push_str("\nStack trace:\n");
-            output.push_str(stack);
-            output.push('\n');
-        }
+        let markdown = render_markdown(&report);
 
-        output.push_str("```\n\n");
+        assert!(markdown.contains("| Method | Duration |\n| --- | --- |\n"));
+        assert!(markdown.contains("| click \\| dblclick | 5ms |"));
     }
 
-    // Logs
-    if !action.log.is_empty() {
-        output.push_str("**Logs**:\n\n");
-
-        for log in &action.log {
-            output.push_str(&format!("- {:.0}ms: {}\n", log.time, log.message));
-        }
+    #[test]
+    fn test_render_frontmatter() {
+        let report = Report {
+            blocks: vec![Block::Metadata(vec![
+                ("title".to_string(), serde_json::json!("checkout: \"pay\"")),
+                ("platform".to_string(), serde_json::Value::Null),
+                ("failed_actions".to_string(), serde_json::json!(2)),
+            ])],
+        };
 
-        output.push('\n');
+        assert_eq!(
+            render_markdown(&report),
+            "---\ntitle: \"checkout: \\\"pay\\\"\"\nplatform: null\nfailed_actions: 2\n---\n\n"
+        );
     }
 
-    output.push_str("---\n\n");
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::{ErrorEvent, LogEntry, SerializedError};
-    use std::collections::HashMap;
-
     #[test]
     fn test_export_empty_trace() {
         let model = TraceModel::new();
@@ -223,11 +284,16 @@ mod tests {
             params: HashMap::new(),
             page_id: Some("page1".to_string()),
             parent_id: None,
+            step_id: None,
             error: Some(SerializedError {
                 message: Some("Navigation timeout".to_string()),
                 stack: Some("at Page.goto".to_string()),
             }),
             log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
         };
 
         let action_without_error = ActionEntry {
@@ -241,28 +307,43 @@ mod tests {
             params: HashMap::new(),
             page_id: Some("page1".to_string()),
             parent_id: None,
+            step_id: None,
             error: None,
             log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
         };
 
-        let context = ContextEntry {
+        let mut context = ContextEntry {
+            id: String::new(),
             start_time: 0.0,
             end_time: 1000.0,
             browser_name: "chromium".to_string(),
             platform: Some("linux".to_string()),
             playwright_version: Some("1.40.0".to_string()),
             wall_time: 1700000000000.0,
+            monotonic_time: 0.0,
             title: Some("Test".to_string()),
             pages: vec![],
             actions: vec![action_with_error, action_without_error],
             resources: vec![],
             events: vec![],
             errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
         };
+        context.stats = compute_stats(&context);
 
         model.contexts.push(context);
 
-        let options = ExportOptions { errors_only: true };
+        let options = ExportOptions {
+            errors_only: true,
+            ..Default::default()
+        };
         let markdown = export_to_markdown(&model, &options);
 
         assert!(markdown.contains("goto"));
@@ -289,6 +370,7 @@ mod tests {
             },
             page_id: Some("page1".to_string()),
             parent_id: None,
+            step_id: None,
             error: None,
             log: vec![
                 LogEntry {
@@ -300,21 +382,31 @@ mod tests {
                     message: "Click complete".to_string(),
                 },
             ],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
         };
 
         let context = ContextEntry {
+            id: String::new(),
             start_time: 0.0,
             end_time: 200.0,
             browser_name: "chromium".to_string(),
             platform: Some("linux".to_string()),
             playwright_version: Some("1.40.0".to_string()),
             wall_time: 1700000000000.0,
+            monotonic_time: 0.0,
             title: Some("Test".to_string()),
             pages: vec![],
             actions: vec![action],
             resources: vec![],
             events: vec![],
             errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
         };
 
         model.contexts.push(context);
@@ -330,16 +422,18 @@ mod tests {
     }
 
     #[test]
-    fn test_export_context_errors() {
+    fn test_export_context_and_console_errors() {
         let mut model = TraceModel::new();
 
         let context = ContextEntry {
+            id: String::new(),
             start_time: 0.0,
             end_time: 1000.0,
             browser_name: "chromium".to_string(),
             platform: Some("linux".to_string()),
             playwright_version: Some("1.40.0".to_string()),
             wall_time: 1700000000000.0,
+            monotonic_time: 0.0,
             title: Some("Test".to_string()),
             pages: vec![],
             actions: vec![],
@@ -348,7 +442,15 @@ mod tests {
             errors: vec![ErrorEvent {
                 message: "Uncaught exception".to_string(),
                 stack: Some("at test.js:10".to_string()),
+                page_error: false,
             }],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![
+                console_message(ConsoleSeverity::Log, 120.0, "Booting"),
+                console_message(ConsoleSeverity::Error, 250.0, "Failed to load cart"),
+            ],
+            stats: Default::default(),
         };
 
         model.contexts.push(context);
@@ -356,8 +458,101 @@ mod tests {
         let options = ExportOptions::default();
         let markdown = export_to_markdown(&model, &options);
 
-        assert!(markdown.contains("Context Errors"));
+        assert!(markdown.contains(
+            "## Console Errors\n\n\
+             | Time | Message | Location |\n\
+             | --- | --- | --- |\n\
+             | 250ms | Failed to load cart | http://app.test/cart.js:7:3 |\n\n\
+             ## Context Errors\n\n\
+             ### Error 1\n\n"
+        ));
+        assert!(!markdown.contains("Booting"));
         assert!(markdown.contains("Uncaught exception"));
         assert!(markdown.contains("at test.js:10"));
+
+        // Console errors alone are still errors to report
+        model.contexts[0].errors.clear();
+        let options = ExportOptions {
+            errors_only: true,
+            ..Default::default()
+        };
+        let markdown = export_to_markdown(&model, &options);
+
+        assert!(markdown.contains("- **Console Errors**: 1"));
+        assert!(markdown.contains("Failed to load cart"));
+        assert!(!markdown.contains("No errors found"));
+    }
+
+    #[test]
+    fn test_export_groups_actions_under_steps() {
+        let step = ActionEntry {
+            action_type: "before".to_string(),
+            call_id: "test.step@1".to_string(),
+            start_time: 100.0,
+            end_time: 400.0,
+            title: Some("Log in".to_string()),
+            class: Some("Test".to_string()),
+            method: Some("test.step".to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: None,
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        };
+
+        let fill = ActionEntry {
+            action_type: "before".to_string(),
+            call_id: "pw:api@2".to_string(),
+            start_time: 150.0,
+            end_time: 200.0,
+            title: Some("Fill password".to_string()),
+            class: Some("Test".to_string()),
+            method: Some("fill".to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: Some("test.step@1".to_string()),
+            step_id: None,
+            error: None,
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        };
+
+        let context = ContextEntry {
+            id: String::new(),
+            start_time: 0.0,
+            end_time: 500.0,
+            browser_name: "chromium".to_string(),
+            platform: None,
+            playwright_version: None,
+            wall_time: 1700000000000.0,
+            monotonic_time: 0.0,
+            title: Some("Test".to_string()),
+            pages: vec![],
+            actions: vec![step, fill],
+            resources: vec![],
+            events: vec![],
+            errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        };
+
+        let mut model = TraceModel::new();
+        model.contexts.push(context);
+
+        let markdown = export_to_markdown(&model, &ExportOptions::default());
+
+        let step_pos = markdown.find("### Step: Log in").expect("step heading");
+        let fill_pos = markdown.find("#### 1. fill").expect("nested action");
+        assert!(step_pos < fill_pos);
     }
 }
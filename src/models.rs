@@ -1,9 +1,30 @@
+use crate::compact::is_app_frame;
+use crate::resource_store::ResourceStore;
+use crate::stats::ContextStats;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TraceModel {
     pub contexts: Vec<ContextEntry>,
+    /// Snapshots, screencast frames and network bodies, decoded on demand
+    #[serde(skip)]
+    pub resource_store: ResourceStore,
+    /// Lines that could not be parsed and were left out
+    #[serde(default)]
+    pub parse_warnings: Vec<ParseWarning>,
+}
+
+/// Trace line that could not be parsed; the rest of the trace still loads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    /// Archive entry, e.g. `0-trace.trace`
+    pub file: String,
+    /// 1-based line number
+    pub line: usize,
+    pub error: String,
+    /// Start of the offending line
+    pub snippet: String,
 }
 
 impl Default for TraceModel {
@@ -16,13 +37,54 @@ impl TraceModel {
     pub fn new() -> Self {
         Self {
             contexts: Vec::new(),
+            resource_store: ResourceStore::new(),
+            parse_warnings: Vec::new(),
+        }
+    }
+
+    /// Overview of the whole trace, e.g. for a test case card
+    pub fn summary(&self) -> TraceSummary {
+        let actions = self.contexts.iter().flat_map(|context| &context.actions);
+
+        let start_time = self
+            .contexts
+            .iter()
+            .map(|context| context.start_time)
+            .fold(f64::INFINITY, f64::min);
+        let end_time = self
+            .contexts
+            .iter()
+            .map(|context| context.end_time)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        TraceSummary {
+            action_count: actions.clone().count(),
+            duration_ms: (end_time - start_time).max(0.0),
+            failed_action: actions
+                .clone()
+                .find(|action| action.error.is_some())
+                .map(ActionEntry::summary_label),
         }
     }
 }
 
+/// Action count, duration and first failure of a trace
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSummary {
+    pub action_count: usize,
+    pub duration_ms: f64,
+    /// Label of the first failed action, e.g. `locator.click (#pay)`
+    pub failed_action: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContextEntry {
+    /// Trace file the context was read from without its extension, e.g.
+    /// `0-trace`; prefixed with the nested archive in report archives
+    #[serde(default)]
+    pub id: String,
     pub start_time: f64,
     pub end_time: f64,
     pub browser_name: String,
@@ -31,16 +93,233 @@ pub struct ContextEntry {
     #[serde(default)]
     pub playwright_version: Option<String>,
     pub wall_time: f64,
+    /// Trace time `wall_time` was taken at
+    #[serde(default)]
+    pub monotonic_time: f64,
     #[serde(default)]
     pub title: Option<String>,
     pub pages: Vec<PageEntry>,
     pub actions: Vec<ActionEntry>,
     #[serde(default)]
-    pub resources: Vec<ResourceSnapshot>,
+    pub resources: Vec<NetworkEntry>,
     #[serde(default)]
     pub events: Vec<TraceEvent>,
     #[serde(default)]
     pub errors: Vec<ErrorEvent>,
+    /// Output of the test process, in trace order
+    #[serde(default)]
+    pub stdio: Vec<StdioEntry>,
+    /// Dialogs, downloads, popups and crashes, in trace order
+    #[serde(default)]
+    pub page_events: Vec<PageEvent>,
+    /// Messages pages logged to their console, in trace order
+    #[serde(default)]
+    pub console: Vec<ConsoleMessage>,
+    /// Duration statistics, computed at load
+    #[serde(default)]
+    pub stats: ContextStats,
+}
+
+impl ContextEntry {
+    /// Wall-clock time in epoch milliseconds of a trace time, if the trace
+    /// recorded its wall clock
+    pub fn wall_clock_ms(&self, time: f64) -> Option<f64> {
+        (self.wall_time > 0.0).then_some(self.wall_time + (time - self.monotonic_time))
+    }
+
+    /// Frames from the top-level frame down to the one the action targeted
+    pub fn action_frame_path(&self, action: &ActionEntry) -> Vec<&FrameEntry> {
+        let Some(frame_id) = action.frame_id.as_deref() else {
+            return Vec::new();
+        };
+
+        self.pages
+            .iter()
+            .find(|page| page.frame(frame_id).is_some())
+            .map(|page| page.frame_path(frame_id))
+            .unwrap_or_default()
+    }
+
+    /// Action closest in time: the innermost action running at the timestamp,
+    /// otherwise the one that started or finished nearest to it
+    pub fn nearest_action(&self, timestamp: f64) -> Option<&ActionEntry> {
+        let distance = |action: &ActionEntry| {
+            if timestamp < action.start_time {
+                action.start_time - timestamp
+            } else if action.did_not_finish() || timestamp <= action.end_time {
+                0.0
+            } else {
+                timestamp - action.end_time
+            }
+        };
+
+        // Ties go to the action that started last, i.e. the innermost one
+        self.actions.iter().min_by(|a, b| {
+            distance(a)
+                .total_cmp(&distance(b))
+                .then(b.start_time.total_cmp(&a.start_time))
+        })
+    }
+
+    /// Screencast frames closest to an action: the last frame captured before
+    /// it started and the first frame captured after it finished
+    pub fn action_screenshots(
+        &self,
+        action: &ActionEntry,
+    ) -> (Option<&ScreencastFrame>, Option<&ScreencastFrame>) {
+        let on_action_page = |page: &&PageEntry| {
+            action.page_id.is_none() || action.page_id.as_deref() == Some(page.page_id.as_str())
+        };
+
+        let mut frames: Vec<&ScreencastFrame> = self
+            .pages
+            .iter()
+            .filter(on_action_page)
+            .flat_map(|page| &page.screencast_frames)
+            .collect();
+        frames.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+
+        let end_time = if action.end_time > 0.0 {
+            action.end_time
+        } else {
+            action.start_time
+        };
+
+        let before = frames
+            .iter()
+            .rev()
+            .find(|frame| frame.timestamp <= action.start_time)
+            .copied();
+
+        // Fall back to the latest frame when nothing was captured afterwards
+        let after = frames
+            .iter()
+            .find(|frame| frame.timestamp >= end_time)
+            .or_else(|| {
+                frames
+                    .iter()
+                    .rev()
+                    .find(|frame| frame.timestamp <= end_time)
+            })
+            .copied();
+
+        (before, after)
+    }
+
+    /// Indices into `resources` of the failed requests started while the
+    /// action ran on its page
+    pub fn action_failed_requests(&self, action: &ActionEntry) -> Vec<usize> {
+        let (start, end) = self.action_window(action);
+        self.resources
+            .iter()
+            .enumerate()
+            .filter(|(_, resource)| resource.is_failed())
+            .filter(|(_, resource)| {
+                resource
+                    .start_time
+                    .is_some_and(|time| (start..=end).contains(&time))
+            })
+            // Requests of other pages ran alongside, not because of the action
+            .filter(|(_, resource)| match (&action.page_id, &resource.page_id) {
+                (Some(action_page), Some(page)) => action_page == page,
+                _ => true,
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Indices into `stdio` of the stderr output written while the action ran
+    pub fn action_stderr(&self, action: &ActionEntry) -> Vec<usize> {
+        let (start, end) = self.action_window(action);
+        self.stdio
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.stream == StdioStream::Stderr && (start..=end).contains(&entry.timestamp)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Indices into `console` of the errors and warnings the action's page
+    /// logged while the action ran
+    pub fn action_console(&self, action: &ActionEntry) -> Vec<usize> {
+        let (start, end) = self.action_window(action);
+        self.console
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| {
+                message.severity <= ConsoleSeverity::Warning
+                    && (start..=end).contains(&message.timestamp)
+                    && match (&action.page_id, &message.page_id) {
+                        (Some(action_page), Some(page)) => action_page == page,
+                        _ => true,
+                    }
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Start and end of an action; one that did not finish runs until the
+    /// trace ends
+    fn action_window(&self, action: &ActionEntry) -> (f64, f64) {
+        let end = if action.did_not_finish() {
+            self.end_time
+        } else {
+            action.end_time
+        };
+        (action.start_time, end.max(action.start_time))
+    }
+
+    /// Chapter markers for the page video, one per page-level action
+    /// Offsets are measured from the first screencast frame of the page,
+    /// which is when the recording starts
+    pub fn video_chapters(&self) -> Vec<VideoChapter> {
+        let mut chapters: Vec<VideoChapter> = self
+            .actions
+            .iter()
+            .filter_map(|action| {
+                let page_id = action.page_id.as_deref()?;
+                let video_start = self
+                    .pages
+                    .iter()
+                    .find(|page| page.page_id == page_id)
+                    .and_then(|page| {
+                        page.screencast_frames
+                            .iter()
+                            .map(|frame| frame.timestamp)
+                            .min_by(|a, b| a.total_cmp(b))
+                    })
+                    .unwrap_or(self.start_time);
+
+                let end_time = action.end_time.max(action.start_time);
+
+                Some(VideoChapter {
+                    title: action
+                        .title
+                        .clone()
+                        .or_else(|| action.method.clone())
+                        .unwrap_or_else(|| action.action_type.clone()),
+                    offset_seconds: ((action.start_time - video_start) / 1000.0).max(0.0),
+                    duration_seconds: (end_time - action.start_time) / 1000.0,
+                    failed: action.error.is_some(),
+                })
+            })
+            .collect();
+
+        chapters.sort_by(|a, b| a.offset_seconds.total_cmp(&b.offset_seconds));
+        chapters
+    }
+}
+
+/// Position of an action within the recorded video
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoChapter {
+    pub title: String,
+    pub offset_seconds: f64,
+    pub duration_seconds: f64,
+    pub failed: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -49,6 +328,80 @@ pub struct PageEntry {
     pub page_id: String,
     #[serde(default)]
     pub screencast_frames: Vec<ScreencastFrame>,
+    /// Frames seen on the page, in order of appearance
+    #[serde(default)]
+    pub frames: Vec<FrameEntry>,
+    /// Document title of the last snapshot of the top-level frame
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+}
+
+/// Size of a page's viewport in CSS pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    pub fn label(&self) -> String {
+        format!("{}×{}", self.width, self.height)
+    }
+}
+
+impl PageEntry {
+    /// Last URL of the top-level frame
+    pub fn url(&self) -> Option<&str> {
+        self.frames
+            .iter()
+            .find(|frame| frame.is_main_frame)
+            .or_else(|| {
+                self.frames
+                    .iter()
+                    .find(|frame| frame.parent_frame_id.is_none())
+            })
+            .and_then(|frame| frame.url.as_deref())
+    }
+
+    pub fn frame(&self, frame_id: &str) -> Option<&FrameEntry> {
+        self.frames.iter().find(|frame| frame.frame_id == frame_id)
+    }
+
+    /// Frames from the top-level frame down to the given one
+    pub fn frame_path(&self, frame_id: &str) -> Vec<&FrameEntry> {
+        let mut path = Vec::new();
+        let mut current = self.frame(frame_id);
+
+        // Bounded by the frame count in case parent ids form a cycle
+        while let Some(frame) = current.filter(|_| path.len() < self.frames.len()) {
+            path.push(frame);
+            current = frame
+                .parent_frame_id
+                .as_deref()
+                .and_then(|parent| self.frame(parent));
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Frame of a page; iframes have a parent frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameEntry {
+    pub frame_id: String,
+    #[serde(default)]
+    pub parent_frame_id: Option<String>,
+    /// Last URL the frame was seen at
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub is_main_frame: bool,
+    #[serde(default)]
+    pub detached: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -84,9 +437,378 @@ pub struct ActionEntry {
     #[serde(default)]
     pub parent_id: Option<String>,
     #[serde(default)]
+    pub step_id: Option<String>,
+    #[serde(default)]
     pub error: Option<SerializedError>,
     #[serde(default)]
     pub log: Vec<LogEntry>,
+    /// Frame the action's snapshots were taken in
+    #[serde(default)]
+    pub frame_id: Option<String>,
+    /// Input recorded while the action dispatched keyboard or mouse events
+    #[serde(default)]
+    pub input: Option<ActionInput>,
+    /// Selector the action targeted, taken from its `selector` param
+    #[serde(default)]
+    pub locator: Option<String>,
+    /// Call stack of the API call, innermost frame first
+    #[serde(default)]
+    pub stack: Vec<StackFrame>,
+}
+
+/// `input` event of an action
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionInput {
+    /// Page coordinates the pointer acted at
+    #[serde(default)]
+    pub point: Option<InputPoint>,
+    /// Name of the DOM snapshot taken at the moment of input
+    #[serde(default)]
+    pub snapshot: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InputPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Keyboard and mouse detail of an action, read from its parameters and its
+/// `input` event
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InputDetail {
+    /// Key pressed or text typed
+    pub keys: Option<String>,
+    pub modifiers: Vec<String>,
+    pub button: Option<String>,
+    pub click_count: Option<u64>,
+    /// Page coordinates of the pointer
+    pub point: Option<InputPoint>,
+    /// Coordinates relative to the target element
+    pub position: Option<InputPoint>,
+    pub snapshot: Option<String>,
+}
+
+/// Methods whose `key` or `text` parameter is keyboard input
+const KEYBOARD_METHODS: &[&str] = &[
+    "press",
+    "type",
+    "pressSequentially",
+    "keyboardDown",
+    "keyboardUp",
+    "keyboardPress",
+    "keyboardType",
+    "keyboardInsertText",
+];
+
+/// Parameters of the most common API calls, typed so the UI and exporters
+/// read fields instead of formatting raw JSON
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionParams {
+    Goto(GotoParams),
+    /// `click`, `dblclick`, `tap`, `hover`, `check` and `uncheck`
+    Click(ClickParams),
+    /// `fill`, `type` and `pressSequentially`
+    Fill(FillParams),
+    Expect(ExpectParams),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoParams {
+    pub url: String,
+    #[serde(default)]
+    pub wait_until: Option<String>,
+    #[serde(default)]
+    pub referer: Option<String>,
+    #[serde(default)]
+    pub timeout: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickParams {
+    pub selector: String,
+    #[serde(default)]
+    pub button: Option<String>,
+    #[serde(default)]
+    pub click_count: Option<u64>,
+    #[serde(default)]
+    pub modifiers: Vec<String>,
+    /// Coordinates relative to the target element
+    #[serde(default)]
+    pub position: Option<InputPoint>,
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default)]
+    pub trial: bool,
+    #[serde(default)]
+    pub timeout: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillParams {
+    pub selector: String,
+    /// `value` of `fill`, `text` of `type` and `pressSequentially`
+    #[serde(alias = "text")]
+    pub value: String,
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default)]
+    pub timeout: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectParams {
+    /// Page-level assertions such as `toHaveURL` have no selector
+    #[serde(default)]
+    pub selector: Option<String>,
+    /// Matcher as recorded, e.g. `to.have.text`
+    pub expression: String,
+    #[serde(default)]
+    pub is_not: bool,
+    #[serde(default)]
+    pub expected_number: Option<f64>,
+    #[serde(default)]
+    pub expected_text: Vec<ExpectedText>,
+    /// Serialized expected value of matchers such as `toHaveJSProperty`
+    #[serde(default)]
+    pub expected_value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub timeout: Option<f64>,
+}
+
+/// Expected text of a text matcher, a string or a regex
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedText {
+    #[serde(default)]
+    pub string: Option<String>,
+    #[serde(default)]
+    pub regex_source: Option<String>,
+    #[serde(default)]
+    pub regex_flags: Option<String>,
+}
+
+fn typed_params<T: serde::de::DeserializeOwned>(
+    params: &HashMap<String, serde_json::Value>,
+    method: &str,
+) -> Result<T, String> {
+    let object: serde_json::Map<String, serde_json::Value> = params
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    serde_json::from_value(serde_json::Value::Object(object))
+        .map_err(|e| format!("Unexpected {} parameters: {}", method, e))
+}
+
+impl TryFrom<&HashMap<String, serde_json::Value>> for GotoParams {
+    type Error = String;
+
+    fn try_from(params: &HashMap<String, serde_json::Value>) -> Result<Self, String> {
+        typed_params(params, "goto")
+    }
+}
+
+impl TryFrom<&HashMap<String, serde_json::Value>> for ClickParams {
+    type Error = String;
+
+    fn try_from(params: &HashMap<String, serde_json::Value>) -> Result<Self, String> {
+        typed_params(params, "click")
+    }
+}
+
+impl TryFrom<&HashMap<String, serde_json::Value>> for FillParams {
+    type Error = String;
+
+    fn try_from(params: &HashMap<String, serde_json::Value>) -> Result<Self, String> {
+        typed_params(params, "fill")
+    }
+}
+
+impl TryFrom<&HashMap<String, serde_json::Value>> for ExpectParams {
+    type Error = String;
+
+    fn try_from(params: &HashMap<String, serde_json::Value>) -> Result<Self, String> {
+        typed_params(params, "expect")
+    }
+}
+
+impl ActionParams {
+    /// Typed parameters of a call, `None` for other methods or parameters
+    /// that do not have the expected shape
+    pub fn parse(method: &str, params: &HashMap<String, serde_json::Value>) -> Option<Self> {
+        match method {
+            "goto" => params.try_into().ok().map(ActionParams::Goto),
+            "click" | "dblclick" | "tap" | "hover" | "check" | "uncheck" => {
+                params.try_into().ok().map(ActionParams::Click)
+            }
+            "fill" | "type" | "pressSequentially" => params.try_into().ok().map(ActionParams::Fill),
+            "expect" => params.try_into().ok().map(ActionParams::Expect),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of API call, used to tell rows apart when scanning a long trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionCategory {
+    Navigation,
+    Click,
+    Fill,
+    Expect,
+    Wait,
+    Other,
+}
+
+impl ActionCategory {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ActionCategory::Navigation => "🧭",
+            ActionCategory::Click => "👆",
+            ActionCategory::Fill => "⌨️",
+            ActionCategory::Expect => "✔️",
+            ActionCategory::Wait => "⏳",
+            ActionCategory::Other => "•",
+        }
+    }
+
+    /// CSS modifier of the row
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            ActionCategory::Navigation => "navigation",
+            ActionCategory::Click => "click",
+            ActionCategory::Fill => "fill",
+            ActionCategory::Expect => "expect",
+            ActionCategory::Wait => "wait",
+            ActionCategory::Other => "other",
+        }
+    }
+}
+
+impl ActionEntry {
+    /// Category of the call, from its method
+    pub fn category(&self) -> ActionCategory {
+        let Some(method) = self.method.as_deref() else {
+            return ActionCategory::Other;
+        };
+
+        match method {
+            "goto" | "goBack" | "goForward" | "reload" | "waitForURL" | "waitForNavigation" => {
+                ActionCategory::Navigation
+            }
+            "click" | "dblclick" | "tap" | "check" | "uncheck" | "setChecked" | "hover"
+            | "dragTo" | "mouseClick" | "mouseDown" | "mouseUp" => ActionCategory::Click,
+            "fill" | "clear" | "selectOption" | "selectText" | "setInputFiles" => {
+                ActionCategory::Fill
+            }
+            _ if KEYBOARD_METHODS.contains(&method) => ActionCategory::Fill,
+            "expect" => ActionCategory::Expect,
+            _ if method.starts_with("waitFor") => ActionCategory::Wait,
+            _ => ActionCategory::Other,
+        }
+    }
+
+    /// Whether the action never got its `after` event, e.g. because the run crashed
+    pub fn did_not_finish(&self) -> bool {
+        self.end_time <= 0.0
+    }
+
+    /// Duration in milliseconds, `None` for actions that did not finish
+    pub fn duration_ms(&self) -> Option<f64> {
+        (!self.did_not_finish()).then_some(self.end_time - self.start_time)
+    }
+
+    /// Parameters of the action typed by its method, if it is a common one
+    pub fn typed_params(&self) -> Option<ActionParams> {
+        ActionParams::parse(self.method.as_deref()?, &self.params)
+    }
+
+    /// Innermost frame of the call stack in the test's own code rather than
+    /// in a dependency, i.e. the line that made the call
+    pub fn user_frame(&self) -> Option<&StackFrame> {
+        self.stack.iter().find(|frame| is_app_frame(&frame.file))
+    }
+
+    /// Keyboard and mouse input of the action, if it dispatched any
+    pub fn input_detail(&self) -> Option<InputDetail> {
+        let param = |name: &str| self.params.get(name);
+        let point = |value: &serde_json::Value| {
+            Some(InputPoint {
+                x: value.get("x")?.as_f64()?,
+                y: value.get("y")?.as_f64()?,
+            })
+        };
+
+        let is_keyboard = self
+            .method
+            .as_deref()
+            .is_some_and(|method| KEYBOARD_METHODS.contains(&method));
+        let keys = is_keyboard
+            .then(|| param("key").or(param("text")))
+            .flatten()
+            .and_then(|value| value.as_str())
+            .map(str::to_string);
+
+        let detail = InputDetail {
+            keys,
+            modifiers: param("modifiers")
+                .and_then(|value| value.as_array())
+                .map(|modifiers| {
+                    modifiers
+                        .iter()
+                        .filter_map(|modifier| modifier.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            button: param("button")
+                .and_then(|value| value.as_str())
+                .map(str::to_string),
+            click_count: param("clickCount").and_then(|value| value.as_u64()),
+            // Page-level mouse calls take page coordinates directly
+            point: self
+                .input
+                .as_ref()
+                .and_then(|input| input.point)
+                .or_else(|| {
+                    Some(InputPoint {
+                        x: param("x")?.as_f64()?,
+                        y: param("y")?.as_f64()?,
+                    })
+                }),
+            position: param("position").and_then(point),
+            snapshot: self.input.as_ref().and_then(|input| input.snapshot.clone()),
+        };
+
+        (detail != InputDetail::default()).then_some(detail)
+    }
+
+    /// What the action acted on: its selector, or the URL it navigated to
+    pub fn target(&self) -> Option<&str> {
+        self.locator
+            .as_deref()
+            .or_else(|| self.params.get("url").and_then(|url| url.as_str()))
+    }
+
+    /// Short description such as `locator.click (#pay)`
+    pub fn summary_label(&self) -> String {
+        let name = self
+            .title
+            .clone()
+            .unwrap_or_else(|| match (&self.class, &self.method) {
+                (Some(class), Some(method)) => format!("{}.{}", class, method),
+                (None, Some(method)) => method.clone(),
+                _ => self.action_type.clone(),
+            });
+
+        match &self.locator {
+            Some(locator) => format!("{} ({})", name, locator),
+            None => name,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -116,10 +838,271 @@ pub enum TraceEvent {
     ScreencastFrame(ScreencastFrameEvent),
     #[serde(rename = "context-options")]
     ContextOptions(ContextOptionsEvent),
+    /// Boxed, as HAR entries are many times larger than the other events
+    #[serde(rename = "resource-snapshot")]
+    ResourceSnapshot(Box<ResourceSnapshotEvent>),
+    #[serde(rename = "frame-snapshot")]
+    FrameSnapshot(FrameSnapshotEvent),
+    #[serde(rename = "error")]
+    Error(ErrorTraceEvent),
+    #[serde(rename = "event")]
+    Event(ObjectEvent),
+    #[serde(rename = "stdout")]
+    Stdout(StdioTraceEvent),
+    #[serde(rename = "stderr")]
+    Stderr(StdioTraceEvent),
+    #[serde(rename = "console")]
+    Console(ConsoleTraceEvent),
     #[serde(other)]
     Other,
 }
 
+/// Error reported by the test runner, e.g. an exception thrown by the test
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorTraceEvent {
+    pub message: String,
+    #[serde(default)]
+    pub stack: Vec<StackFrame>,
+}
+
+/// Frame of the call stack recorded with an action or error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackFrame {
+    pub file: String,
+    #[serde(default)]
+    pub line: u32,
+    #[serde(default)]
+    pub column: u32,
+    #[serde(default)]
+    pub function: Option<String>,
+}
+
+impl StackFrame {
+    /// `file:line:column`, as printed in V8 stacks
+    pub fn location(&self) -> String {
+        format!("{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// DOM snapshot of a frame taken before or after an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameSnapshotEvent {
+    pub snapshot: FrameSnapshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameSnapshot {
+    #[serde(default)]
+    pub call_id: Option<String>,
+    pub page_id: String,
+    pub frame_id: String,
+    #[serde(default)]
+    pub frame_url: Option<String>,
+    #[serde(default)]
+    pub is_main_frame: bool,
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+    /// `<title>` of the snapshot's DOM tree; the tree itself is not kept
+    #[serde(
+        default,
+        rename = "html",
+        deserialize_with = "deserialize_document_title",
+        skip_serializing
+    )]
+    pub title: Option<String>,
+}
+
+fn deserialize_document_title<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let html = serde_json::Value::deserialize(deserializer)?;
+    Ok(document_title(&html))
+}
+
+/// Text of `<title>` in a snapshot DOM tree, where elements are
+/// `[name, attributes?, ...children]` and nodes unchanged since an earlier
+/// snapshot are `[[snapshot, node]]` references. None if the head or title
+/// is such a reference
+pub fn document_title(html: &serde_json::Value) -> Option<String> {
+    let mut head =
+        element_children(html, "HTML")?.find_map(|child| element_children(child, "HEAD"))?;
+    let title = head.find_map(|child| element_children(child, "TITLE"))?;
+    let text: String = title.filter_map(|node| node.as_str()).collect();
+    Some(text.trim().to_string())
+}
+
+/// Child nodes of an element with the given tag name
+fn element_children<'a>(
+    node: &'a serde_json::Value,
+    name: &str,
+) -> Option<impl Iterator<Item = &'a serde_json::Value>> {
+    let items = node.as_array()?;
+    let tag = items.first()?.as_str()?;
+    tag.eq_ignore_ascii_case(name)
+        .then(|| items[1..].iter().filter(|child| !child.is_object()))
+}
+
+/// Output of the test process; binary output is recorded as base64
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StdioTraceEvent {
+    pub timestamp: f64,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub base64: Option<String>,
+}
+
+/// Message a page logged through the `console` API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleTraceEvent {
+    pub time: f64,
+    /// `log`, `error`, `warning`, ... as named by the console method
+    pub message_type: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub location: Option<ConsoleTraceLocation>,
+    #[serde(default)]
+    pub page_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleTraceLocation {
+    pub url: String,
+    #[serde(default)]
+    pub line_number: u32,
+    #[serde(default)]
+    pub column_number: u32,
+}
+
+/// Event dispatched on a Playwright object, e.g. `pageError` on the browser context
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectEvent {
+    pub class: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+    #[serde(default)]
+    pub time: Option<f64>,
+    #[serde(default, rename = "pageId")]
+    pub page_id: Option<String>,
+}
+
+/// Page event shown between actions: something happened that no action
+/// directly asked for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageEvent {
+    pub time: f64,
+    #[serde(default)]
+    pub page_id: Option<String>,
+    pub kind: PageEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PageEventKind {
+    Dialog {
+        dialog_type: String,
+        message: String,
+    },
+    Download {
+        url: String,
+        suggested_filename: Option<String>,
+    },
+    /// A page opened another one, e.g. through `window.open`
+    Popup {
+        page_id: Option<String>,
+    },
+    Crash,
+}
+
+impl PageEvent {
+    /// Typed event for dialog, download, popup and crash events
+    pub fn from_event(event: &ObjectEvent) -> Option<Self> {
+        let params = &event.params;
+        // Details are either flat or nested under the object they describe
+        let param = |object: &str, key: &str| {
+            params
+                .get(key)
+                .or_else(|| params.get(object).and_then(|nested| nested.get(key)))
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+
+        let kind = match event.method.as_str() {
+            "dialog" => PageEventKind::Dialog {
+                dialog_type: param("dialog", "type").unwrap_or_else(|| "dialog".to_string()),
+                message: param("dialog", "message").unwrap_or_default(),
+            },
+            "download" => PageEventKind::Download {
+                url: param("download", "url").unwrap_or_default(),
+                suggested_filename: param("download", "suggestedFilename"),
+            },
+            "popup" => PageEventKind::Popup {
+                page_id: param("page", "pageId"),
+            },
+            "crash" => PageEventKind::Crash,
+            _ => return None,
+        };
+
+        Some(PageEvent {
+            time: event.time?,
+            page_id: event.page_id.clone(),
+            kind,
+        })
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self.kind {
+            PageEventKind::Dialog { .. } => "💬",
+            PageEventKind::Download { .. } => "⬇️",
+            PageEventKind::Popup { .. } => "🗗",
+            PageEventKind::Crash => "💥",
+        }
+    }
+
+    /// Short description such as `alert: Saved!`
+    pub fn label(&self) -> String {
+        match &self.kind {
+            PageEventKind::Dialog {
+                dialog_type,
+                message,
+            } if message.is_empty() => dialog_type.clone(),
+            PageEventKind::Dialog {
+                dialog_type,
+                message,
+            } => format!("{}: {}", dialog_type, message),
+            PageEventKind::Download {
+                url,
+                suggested_filename,
+            } => format!(
+                "Download {}",
+                suggested_filename.as_deref().unwrap_or(url.as_str())
+            ),
+            PageEventKind::Popup {
+                page_id: Some(page_id),
+            } => format!("Popup {}", page_id),
+            PageEventKind::Popup { page_id: None } => "Popup".to_string(),
+            PageEventKind::Crash => "Page crashed".to_string(),
+        }
+    }
+
+    /// CSS modifier of the marker
+    pub fn kind_name(&self) -> &'static str {
+        match self.kind {
+            PageEventKind::Dialog { .. } => "dialog",
+            PageEventKind::Download { .. } => "download",
+            PageEventKind::Popup { .. } => "popup",
+            PageEventKind::Crash => "crash",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BeforeActionEvent {
@@ -135,6 +1118,10 @@ pub struct BeforeActionEvent {
     pub page_id: Option<String>,
     #[serde(default)]
     pub parent_id: Option<String>,
+    #[serde(default)]
+    pub step_id: Option<String>,
+    #[serde(default)]
+    pub stack: Vec<StackFrame>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -154,6 +1141,8 @@ pub struct InputActionEvent {
     pub call_id: String,
     #[serde(default)]
     pub input_snapshot: Option<String>,
+    #[serde(default)]
+    pub point: Option<InputPoint>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -164,6 +1153,8 @@ pub struct ScreencastFrameEvent {
     pub width: u32,
     pub height: u32,
     pub timestamp: f64,
+    #[serde(default)]
+    pub frame_swap_wall_time: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -181,34 +1172,312 @@ pub struct ContextOptionsEvent {
     pub title: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StdioStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleSeverity {
+    Error,
+    Warning,
+    Info,
+    Log,
+    Debug,
+}
+
+impl ConsoleSeverity {
+    /// Severity of a console message type; `console.assert` failures count as
+    /// errors and `dir`, `table`, `trace` and the like as plain logs
+    pub fn from_message_type(message_type: &str) -> Self {
+        match message_type {
+            "error" | "assert" => ConsoleSeverity::Error,
+            "warning" => ConsoleSeverity::Warning,
+            "info" => ConsoleSeverity::Info,
+            "debug" => ConsoleSeverity::Debug,
+            _ => ConsoleSeverity::Log,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConsoleSeverity::Error => "error",
+            ConsoleSeverity::Warning => "warning",
+            ConsoleSeverity::Info => "info",
+            ConsoleSeverity::Log => "log",
+            ConsoleSeverity::Debug => "debug",
+        }
+    }
+}
+
+/// Script position a console message was logged from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsoleLocation {
+    pub url: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl ConsoleLocation {
+    /// `url:line:column`, as browser consoles show it
+    pub fn label(&self) -> String {
+        format!("{}:{}:{}", self.url, self.line, self.column)
+    }
+}
+
+/// Message a page logged to its console
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleMessage {
+    pub severity: ConsoleSeverity,
+    pub text: String,
+    #[serde(default)]
+    pub location: Option<ConsoleLocation>,
+    pub timestamp: f64,
+    #[serde(default)]
+    pub page_id: Option<String>,
+}
+
+impl From<&ConsoleTraceEvent> for ConsoleMessage {
+    fn from(event: &ConsoleTraceEvent) -> Self {
+        ConsoleMessage {
+            severity: ConsoleSeverity::from_message_type(&event.message_type),
+            text: event.text.clone(),
+            // Messages logged from evaluated code have no script URL
+            location: event
+                .location
+                .as_ref()
+                .filter(|location| !location.url.is_empty())
+                .map(|location| ConsoleLocation {
+                    url: location.url.clone(),
+                    line: location.line_number,
+                    column: location.column_number,
+                }),
+            timestamp: event.time,
+            page_id: event.page_id.clone(),
+        }
+    }
+}
+
+/// Chunk of text the test process wrote to stdout or stderr
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StdioEntry {
+    pub stream: StdioStream,
+    pub timestamp: f64,
+    pub text: String,
+    /// Action nearest to the timestamp
+    #[serde(default)]
+    pub call_id: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ErrorEvent {
     pub message: String,
     #[serde(default)]
     pub stack: Option<String>,
+    /// Uncaught in a page rather than reported by the test runner; a passing
+    /// test can still have page errors
+    #[serde(default)]
+    pub page_error: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Request and response recorded in `.network` files
+/// Fields follow the HAR entry the trace stores, with Playwright's `_`-prefixed
+/// extensions, so the network panel, route mocks, exports and action lookups
+/// all read the same entry the trace recorded
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ResourceSnapshot {
+pub struct NetworkEntry {
+    #[serde(default, rename = "pageref")]
+    pub page_id: Option<String>,
+    #[serde(default, rename = "_frameref")]
+    pub frame_id: Option<String>,
+    /// Wall-clock start as an ISO 8601 date
+    #[serde(default)]
+    pub started_date_time: Option<String>,
+    /// Monotonic time the request started at
+    #[serde(default, rename = "_monotonicTime")]
+    pub start_time: Option<f64>,
+    /// Time from sending the request to the end of the response in ms
+    #[serde(default, rename = "time")]
+    pub duration: Option<f64>,
+    pub request: NetworkRequest,
+    pub response: NetworkResponse,
+    #[serde(default)]
+    pub timings: NetworkTimings,
+    #[serde(default, rename = "serverIPAddress")]
+    pub server_ip_address: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkRequest {
+    pub method: String,
     pub url: String,
     #[serde(default)]
-    pub content_type: Option<String>,
+    pub http_version: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<NetworkHeader>,
+    #[serde(default)]
+    pub post_data: Option<PostData>,
+    /// Request body size in bytes
+    #[serde(default)]
+    pub body_size: Option<i64>,
+}
+
+/// Request body; large bodies are kept in the resource store by sha1
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostData {
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default, rename = "_sha1")]
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkResponse {
+    /// `-1` when no response arrived, e.g. for aborted requests
+    pub status: i32,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<NetworkHeader>,
+    pub content: NetworkContent,
+    #[serde(default, rename = "redirectURL")]
+    pub redirect_url: Option<String>,
+    /// Bytes received including headers
+    #[serde(default, rename = "_transferSize")]
+    pub transfer_size: Option<i64>,
+    /// Network error of a request that got no response
+    #[serde(default, rename = "_failureText")]
+    pub failure_text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkContent {
+    /// Response body size in bytes
+    #[serde(default)]
+    pub size: i64,
     #[serde(default)]
+    pub mime_type: Option<String>,
+    /// Key of the response body in the resource store
+    #[serde(default, rename = "_sha1")]
     pub sha1: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// Phases of a request in ms; `-1` or missing when a phase did not apply,
+/// e.g. `dns` and `connect` on a reused connection
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct NetworkTimings {
+    #[serde(default)]
+    pub blocked: Option<f64>,
+    #[serde(default)]
+    pub dns: Option<f64>,
+    #[serde(default)]
+    pub connect: Option<f64>,
+    #[serde(default)]
+    pub ssl: Option<f64>,
+    #[serde(default)]
+    pub send: Option<f64>,
+    #[serde(default)]
+    pub wait: Option<f64>,
+    #[serde(default)]
+    pub receive: Option<f64>,
+}
+
+impl NetworkEntry {
+    /// Whether the server answered with an error status or no response arrived
+    pub fn is_failed(&self) -> bool {
+        !(0..400).contains(&self.response.status)
+    }
+
+    pub fn url(&self) -> &str {
+        &self.request.url
+    }
+
+    pub fn method(&self) -> &str {
+        &self.request.method
+    }
+
+    /// Status code, `None` when no response arrived
+    pub fn status(&self) -> Option<i32> {
+        (self.response.status > 0).then_some(self.response.status)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.response.content.mime_type.as_deref()
+    }
+
+    /// Key of the response body in the resource store
+    pub fn body_sha1(&self) -> Option<&str> {
+        self.response.content.sha1.as_deref()
+    }
+
+    /// Response body size, `None` when the trace did not record it
+    pub fn body_size(&self) -> Option<i64> {
+        (self.response.content.size > 0).then_some(self.response.content.size)
+    }
+
+    /// Value of a request header, matched case-insensitively
+    pub fn request_header(&self, name: &str) -> Option<&str> {
+        header(&self.request.headers, name)
+    }
+
+    /// Value of a response header, matched case-insensitively
+    pub fn response_header(&self, name: &str) -> Option<&str> {
+        header(&self.response.headers, name)
+    }
+}
+
+fn header<'a>(headers: &'a [NetworkHeader], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str())
+}
+
+/// HAR entry recorded in `.network` files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSnapshotEvent {
+    pub snapshot: NetworkEntry,
+}
+
 // Test Case Models for displaying test results with markdown, screenshots, and video
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TestCaseCollection {
     pub test_cases: Vec<TestCase>,
+    /// Problems found while loading that did not stop the load
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Attachments too large to inline, read from the archive on demand
+    #[serde(skip)]
+    pub attachment_store: ResourceStore,
 }
 
 impl TestCaseCollection {
     pub fn new() -> Self {
         Self {
             test_cases: Vec::new(),
+            warnings: Vec::new(),
+            attachment_store: ResourceStore::new(),
         }
     }
 }
@@ -232,10 +1501,91 @@ pub struct TestCase {
     pub video: Option<TestAttachment>,
     #[serde(default)]
     pub trace_file: Option<TestAttachment>,
+    /// Plain text attachments such as captured stdout logs
+    #[serde(default)]
+    pub text_attachments: Vec<TextAttachment>,
     #[serde(default)]
     pub duration_ms: Option<f64>,
     #[serde(default)]
     pub error_message: Option<String>,
+    /// Annotations such as skip or fixme reasons, from report metadata
+    #[serde(default)]
+    pub annotations: Vec<TestAnnotation>,
+    /// Tags such as `@smoke`, from report metadata
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Playwright project the test ran in, e.g. `chromium`
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Spec file and describe blocks containing the test, outermost first
+    #[serde(default)]
+    pub suite_path: Vec<String>,
+    /// Failed screenshot comparisons, taken out of `screenshots`
+    #[serde(default)]
+    pub image_diffs: Vec<ImageDiff>,
+    /// Contexts of `trace_file`, when the loader parsed it
+    #[serde(default)]
+    pub trace_contexts: Vec<TraceContextLink>,
+    /// What the card shows from `trace_file`, when the loader parsed it
+    #[serde(default)]
+    pub trace_overview: Option<TraceOverview>,
+}
+
+/// Summary and video chapters of a test's trace, kept when the results load
+/// so expanding the card does not parse the trace again
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceOverview {
+    pub summary: TraceSummary,
+    /// Chapter markers for the test's video; empty without a video
+    #[serde(default)]
+    pub video_chapters: Vec<VideoChapter>,
+}
+
+/// Context of a test's trace, for opening the trace at it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceContextLink {
+    /// `ContextEntry::id` of the context
+    pub context_id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub browser_name: String,
+    /// Actions that failed, in trace order
+    #[serde(default)]
+    pub failed_actions: Vec<FailedActionLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedActionLink {
+    pub call_id: String,
+    /// Short description such as `locator.click (#pay)`
+    pub label: String,
+}
+
+/// Images of a failed `toHaveScreenshot` comparison
+/// Playwright writes them as `<name>-expected.png`, `<name>-actual.png` and
+/// `<name>-diff.png`; the diff is missing when the sizes differ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageDiff {
+    /// Snapshot name without the suffix
+    pub name: String,
+    #[serde(default)]
+    pub expected: Option<TestAttachment>,
+    #[serde(default)]
+    pub actual: Option<TestAttachment>,
+    #[serde(default)]
+    pub diff: Option<TestAttachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestAnnotation {
+    #[serde(rename = "type")]
+    pub annotation_type: String,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -257,6 +1607,13 @@ impl TestStatus {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextAttachment {
+    pub name: String,
+    pub mime_type: String,
+    pub content: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TestAttachment {
     pub name: String,
@@ -264,4 +1621,18 @@ pub struct TestAttachment {
     pub data_url: String, // Base64 encoded data URL
     #[serde(default)]
     pub size_bytes: Option<usize>,
+    /// The file is empty or not a valid image/video, e.g. when capture failed in CI
+    #[serde(default)]
+    pub capture_failed: bool,
+    /// Archive entry of an attachment too large to inline; `data_url` stays
+    /// empty and the bytes come from `TestCaseCollection::attachment_store`
+    #[serde(default)]
+    pub archive_entry: Option<String>,
+}
+
+impl TestAttachment {
+    /// Whether the data is still in the archive
+    pub fn is_deferred(&self) -> bool {
+        self.data_url.is_empty() && self.archive_entry.is_some()
+    }
 }
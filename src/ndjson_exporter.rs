@@ -0,0 +1,207 @@
+//! JSON Lines export of actions for analytics pipelines
+//! One flat object per action and line, with the test and browser repeated on
+//! every line, so each line can be ingested and queried on its own
+
+use crate::ansi_parser::strip_ansi;
+use crate::models::{ActionEntry, ContextEntry, StackFrame, TraceModel};
+use chrono::DateTime;
+use serde::Serialize;
+
+/// Action flattened for ingestion; field names are part of the file format
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActionRecord {
+    /// Position of the context in the export, from 1
+    pub context: usize,
+    pub test: Option<String>,
+    pub browser: Option<String>,
+    pub call_id: String,
+    pub method: String,
+    pub title: Option<String>,
+    /// Wall-clock start as RFC 3339, if the trace recorded its wall clock
+    pub started_at: Option<String>,
+    /// Milliseconds from the start of the trace
+    pub start_ms: f64,
+    pub duration_ms: Option<f64>,
+    /// `passed`, `failed` or `unfinished`
+    pub status: &'static str,
+    pub error: Option<String>,
+    pub selector: Option<String>,
+    pub page: Option<String>,
+    pub step: Option<String>,
+    /// `file:line:column` of the test code that made the call
+    pub source: Option<String>,
+}
+
+/// Export every action of the model, one JSON object per line
+pub fn export_actions_ndjson(model: &TraceModel) -> String {
+    let mut output = String::new();
+
+    for record in action_records(model) {
+        // Records only hold strings and numbers, which always serialize
+        if let Ok(line) = serde_json::to_string(&record) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Actions of all contexts in recorded order
+pub fn action_records(model: &TraceModel) -> Vec<ActionRecord> {
+    model
+        .contexts
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, context)| {
+            context
+                .actions
+                .iter()
+                .map(move |action| action_record(idx + 1, context, action))
+        })
+        .collect()
+}
+
+fn action_record(index: usize, context: &ContextEntry, action: &ActionEntry) -> ActionRecord {
+    let status = if action.error.is_some() {
+        "failed"
+    } else if action.did_not_finish() {
+        "unfinished"
+    } else {
+        "passed"
+    };
+    let started_at = context
+        .wall_clock_ms(action.start_time)
+        .and_then(|ms| DateTime::from_timestamp_millis(ms as i64))
+        .map(|datetime| datetime.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+
+    ActionRecord {
+        context: index,
+        test: context.title.clone(),
+        browser: Some(context.browser_name.clone()).filter(|name| !name.is_empty()),
+        call_id: action.call_id.clone(),
+        method: action
+            .method
+            .clone()
+            .or_else(|| action.class.clone())
+            .unwrap_or_else(|| action.action_type.clone()),
+        title: action.title.clone(),
+        started_at,
+        start_ms: action.start_time - context.start_time,
+        duration_ms: action.duration_ms(),
+        status,
+        error: action
+            .error
+            .as_ref()
+            .and_then(|error| error.message.as_deref())
+            .map(strip_ansi),
+        selector: action.locator.clone(),
+        page: action.page_id.clone(),
+        step: action.step_id.clone(),
+        source: action.user_frame().map(StackFrame::location),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SerializedError;
+    use std::collections::HashMap;
+
+    fn action(call_id: &str, end_time: f64) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: call_id.to_string(),
+            start_time: 1100.0,
+            end_time,
+            title: None,
+            class: Some("Frame".to_string()),
+            method: Some("click".to_string()),
+            params: HashMap::new(),
+            page_id: Some("page@1".to_string()),
+            parent_id: None,
+            step_id: None,
+            error: None,
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        }
+    }
+
+    #[test]
+    fn test_export_actions_ndjson() {
+        let mut failed = action("call@2", 1400.0);
+        failed.locator = Some("#pay".to_string());
+        failed.stack = vec![
+            StackFrame {
+                file: "/app/node_modules/@playwright/test/lib/fixtures.js".to_string(),
+                line: 40,
+                column: 2,
+                function: None,
+            },
+            StackFrame {
+                file: "/app/tests/checkout.spec.ts".to_string(),
+                line: 12,
+                column: 5,
+                function: Some("pay".to_string()),
+            },
+        ];
+        failed.error = Some(SerializedError {
+            message: Some("\u{1b}[31mTimeout 30000ms exceeded\u{1b}[39m".to_string()),
+            stack: None,
+        });
+        let mut model = TraceModel::new();
+        model.contexts.push(ContextEntry {
+            id: String::new(),
+            start_time: 1000.0,
+            end_time: 2000.0,
+            browser_name: "chromium".to_string(),
+            platform: None,
+            playwright_version: None,
+            wall_time: 1_700_000_000_000.0,
+            monotonic_time: 1000.0,
+            title: Some("checkout".to_string()),
+            actions: vec![action("call@1", 0.0), failed],
+            events: vec![],
+            errors: vec![],
+            pages: vec![],
+            resources: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        });
+
+        let ndjson = export_actions_ndjson(&model);
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["status"], "unfinished");
+        assert_eq!(lines[0]["duration_ms"], serde_json::Value::Null);
+        assert_eq!(
+            lines[1],
+            serde_json::json!({
+                "context": 1,
+                "test": "checkout",
+                "browser": "chromium",
+                "call_id": "call@2",
+                "method": "click",
+                "title": null,
+                "started_at": "2023-11-14T22:13:20.100Z",
+                "start_ms": 100.0,
+                "duration_ms": 300.0,
+                "status": "failed",
+                "error": "Timeout 30000ms exceeded",
+                "selector": "#pay",
+                "page": "page@1",
+                "step": null,
+                "source": "/app/tests/checkout.spec.ts:12:5",
+            })
+        );
+    }
+}
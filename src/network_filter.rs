@@ -0,0 +1,290 @@
+//! Filters of the network panel
+//! HAR entries recorded by Playwright carry no request initiator, so the
+//! resource type is inferred from the response MIME type, falling back to the
+//! file extension of the URL
+
+use crate::models::NetworkEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusClass {
+    Success,
+    ClientError,
+    ServerError,
+}
+
+impl StatusClass {
+    pub const ALL: [StatusClass; 3] = [
+        StatusClass::Success,
+        StatusClass::ClientError,
+        StatusClass::ServerError,
+    ];
+
+    pub fn of(status: i32) -> Option<StatusClass> {
+        match status {
+            200..=299 => Some(StatusClass::Success),
+            400..=499 => Some(StatusClass::ClientError),
+            500..=599 => Some(StatusClass::ServerError),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusClass::Success => "2xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Xhr,
+    Script,
+    Image,
+    Stylesheet,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 4] = [
+        ResourceKind::Xhr,
+        ResourceKind::Script,
+        ResourceKind::Image,
+        ResourceKind::Stylesheet,
+    ];
+
+    /// Kind of a resource; `None` for documents, fonts, media and the like
+    pub fn of(resource: &NetworkEntry) -> Option<ResourceKind> {
+        match resource.content_type() {
+            Some(content_type) if !content_type.is_empty() => from_mime_type(content_type),
+            _ => from_extension(resource.url()),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResourceKind::Xhr => "xhr",
+            ResourceKind::Script => "script",
+            ResourceKind::Image => "img",
+            ResourceKind::Stylesheet => "css",
+        }
+    }
+}
+
+fn from_mime_type(content_type: &str) -> Option<ResourceKind> {
+    let mime = content_type.to_ascii_lowercase();
+    if mime.contains("javascript") || mime.contains("ecmascript") {
+        Some(ResourceKind::Script)
+    } else if mime.contains("css") {
+        Some(ResourceKind::Stylesheet)
+    } else if mime.starts_with("image/") {
+        Some(ResourceKind::Image)
+    } else if mime.contains("json") || (mime.contains("xml") && !mime.contains("html")) {
+        Some(ResourceKind::Xhr)
+    } else {
+        None
+    }
+}
+
+fn from_extension(url: &str) -> Option<ResourceKind> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match extension.as_str() {
+        "js" | "mjs" | "cjs" => Some(ResourceKind::Script),
+        "css" => Some(ResourceKind::Stylesheet),
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "ico" => {
+            Some(ResourceKind::Image)
+        }
+        "json" => Some(ResourceKind::Xhr),
+        _ => None,
+    }
+}
+
+/// Host of a URL such as `api.example.com`, without credentials or port
+pub fn url_domain(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    // Keep bracketed IPv6 hosts intact
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => name,
+        _ => host,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// Distinct domains of the resources, sorted
+pub fn domains(resources: &[NetworkEntry]) -> Vec<String> {
+    let mut domains: Vec<String> = resources
+        .iter()
+        .filter_map(|resource| url_domain(resource.url()))
+        .map(str::to_string)
+        .collect();
+    domains.sort();
+    domains.dedup();
+    domains
+}
+
+/// Active filters; an empty chip group lets everything through
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NetworkFilter {
+    pub statuses: Vec<StatusClass>,
+    pub kinds: Vec<ResourceKind>,
+    pub domain: Option<String>,
+    /// Case-insensitive URL substring
+    pub text: String,
+}
+
+impl NetworkFilter {
+    pub fn is_active(&self) -> bool {
+        !self.statuses.is_empty()
+            || !self.kinds.is_empty()
+            || self.domain.is_some()
+            || !self.text.is_empty()
+    }
+
+    pub fn toggle_status(&mut self, status: StatusClass) {
+        toggle(&mut self.statuses, status);
+    }
+
+    pub fn toggle_kind(&mut self, kind: ResourceKind) {
+        toggle(&mut self.kinds, kind);
+    }
+
+    pub fn matches(&self, resource: &NetworkEntry) -> bool {
+        if !self.statuses.is_empty()
+            && !resource
+                .status()
+                .and_then(StatusClass::of)
+                .is_some_and(|status| self.statuses.contains(&status))
+        {
+            return false;
+        }
+
+        if !self.kinds.is_empty()
+            && !ResourceKind::of(resource).is_some_and(|kind| self.kinds.contains(&kind))
+        {
+            return false;
+        }
+
+        if let Some(domain) = &self.domain {
+            if url_domain(resource.url()) != Some(domain.as_str()) {
+                return false;
+            }
+        }
+
+        self.text.is_empty()
+            || resource
+                .url()
+                .to_lowercase()
+                .contains(&self.text.to_lowercase())
+    }
+}
+
+fn toggle<T: PartialEq>(values: &mut Vec<T>, value: T) {
+    match values.iter().position(|existing| *existing == value) {
+        Some(index) => {
+            values.remove(index);
+        }
+        None => values.push(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NetworkContent, NetworkRequest, NetworkResponse};
+
+    fn resource(url: &str, content_type: Option<&str>, status: i32) -> NetworkEntry {
+        NetworkEntry {
+            request: NetworkRequest {
+                method: "GET".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+            response: NetworkResponse {
+                status,
+                content: NetworkContent {
+                    mime_type: content_type.map(str::to_string),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resource_kind() {
+        let kind = |url, content_type| ResourceKind::of(&resource(url, content_type, 200));
+
+        assert_eq!(
+            kind("https://a.test/app", Some("text/javascript; charset=utf-8")),
+            Some(ResourceKind::Script)
+        );
+        assert_eq!(
+            kind("https://a.test/api/user", Some("application/json")),
+            Some(ResourceKind::Xhr)
+        );
+        assert_eq!(
+            kind("https://a.test/logo.svg?v=2", None),
+            Some(ResourceKind::Image)
+        );
+        assert_eq!(kind("https://a.test/", Some("application/xhtml+xml")), None);
+        assert_eq!(kind("https://a.test/index.html", None), None);
+    }
+
+    #[test]
+    fn test_url_domain() {
+        assert_eq!(
+            url_domain("https://user:pw@api.example.com:8443/v1?q=1"),
+            Some("api.example.com")
+        );
+        assert_eq!(url_domain("http://[::1]:3000/"), Some("[::1]"));
+        assert_eq!(url_domain("data:image/png;base64,AAAA"), None);
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let resources = vec![
+            resource(
+                "https://app.test/main.js",
+                Some("application/javascript"),
+                200,
+            ),
+            resource("https://api.test/users", Some("application/json"), 404),
+            resource("https://api.test/orders", Some("application/json"), 500),
+        ];
+        let shown = |filter: &NetworkFilter| {
+            resources
+                .iter()
+                .filter(|resource| filter.matches(resource))
+                .map(|resource| resource.url())
+                .collect::<Vec<_>>()
+        };
+
+        let mut filter = NetworkFilter::default();
+        assert!(!filter.is_active());
+        assert_eq!(shown(&filter).len(), 3);
+
+        filter.toggle_status(StatusClass::ClientError);
+        filter.toggle_status(StatusClass::ServerError);
+        filter.toggle_kind(ResourceKind::Xhr);
+        assert_eq!(
+            shown(&filter),
+            ["https://api.test/users", "https://api.test/orders"]
+        );
+
+        filter.text = "ORDER".to_string();
+        assert_eq!(shown(&filter), ["https://api.test/orders"]);
+
+        filter = NetworkFilter {
+            domain: Some("app.test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(shown(&filter), ["https://app.test/main.js"]);
+        assert_eq!(domains(&resources), ["api.test", "app.test"]);
+    }
+}
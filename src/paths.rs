@@ -0,0 +1,132 @@
+//! Workspace-relative shortening of absolute paths
+//! CI stacks and titles repeat long checkout paths such as
+//! `/home/runner/work/repo/repo/`; the directory shared by every absolute path
+//! in a trace is taken as the workspace root and collapsed to `…/`
+
+use crate::models::TraceModel;
+
+/// Replacement for the workspace root in shortened paths
+pub const SHORTENED_ROOT: &str = "…/";
+
+/// Minimum number of shared directories before a prefix counts as a root
+const MIN_ROOT_DEPTH: usize = 2;
+
+/// Absolute paths mentioned in free-form text such as stack traces
+pub fn find_absolute_paths(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || "()[]{}'\"`,<>".contains(c))
+        .map(|token| token.strip_prefix("file://").unwrap_or(token))
+        .filter(|token| token.starts_with('/') && !token.starts_with("//"))
+        .filter(|token| token.matches('/').count() > MIN_ROOT_DEPTH)
+}
+
+/// Longest directory shared by all paths, with a trailing slash
+pub fn common_root<'a>(paths: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut common: Option<Vec<&str>> = None;
+
+    for path in paths {
+        // Only directories count, never the file name itself
+        let directories: Vec<&str> = match path.rsplit_once('/') {
+            Some((directory, _)) => directory.split('/').skip(1).collect(),
+            None => continue,
+        };
+
+        common = Some(match common {
+            None => directories,
+            Some(common) => common
+                .into_iter()
+                .zip(directories)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+
+    let common = common?;
+    if common.len() < MIN_ROOT_DEPTH {
+        return None;
+    }
+
+    Some(format!("/{}/", common.join("/")))
+}
+
+/// Detect the workspace root from every path mentioned in the trace
+pub fn detect_workspace_root(model: &TraceModel) -> Option<String> {
+    let mut texts: Vec<&str> = Vec::new();
+
+    for context in &model.contexts {
+        for action in &context.actions {
+            texts.extend(action.title.as_deref());
+            texts.extend(action.params.values().filter_map(|value| value.as_str()));
+            texts.extend(action.stack.iter().map(|frame| frame.file.as_str()));
+
+            if let Some(error) = &action.error {
+                texts.extend(error.message.as_deref());
+                texts.extend(error.stack.as_deref());
+            }
+        }
+
+        for error in &context.errors {
+            texts.push(&error.message);
+            texts.extend(error.stack.as_deref());
+        }
+    }
+
+    common_root(texts.into_iter().flat_map(find_absolute_paths))
+}
+
+/// Collapse the workspace root in text, leaving it unchanged without a root
+pub fn shorten_paths(text: &str, root: Option<&str>) -> String {
+    match root {
+        Some(root) if !root.is_empty() => text.replace(root, SHORTENED_ROOT),
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STACK: &str = "Error: expected visible
+    at /home/runner/work/app/app/tests/login.spec.ts:12:5
+    at Object.run (/home/runner/work/app/app/node_modules/runner/index.js:40:3)";
+
+    #[test]
+    fn test_find_absolute_paths() {
+        let paths: Vec<_> = find_absolute_paths(STACK).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "/home/runner/work/app/app/tests/login.spec.ts:12:5",
+                "/home/runner/work/app/app/node_modules/runner/index.js:40:3",
+            ]
+        );
+        assert_eq!(find_absolute_paths("GET /api/users").count(), 0);
+        assert_eq!(
+            find_absolute_paths("http://localhost:8080/a/b/c").count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_common_root() {
+        let root = common_root(find_absolute_paths(STACK));
+        assert_eq!(root.as_deref(), Some("/home/runner/work/app/app/"));
+    }
+
+    #[test]
+    fn test_common_root_too_shallow() {
+        assert_eq!(common_root(vec!["/home/a/x.js", "/opt/b/y.js"]), None);
+        assert_eq!(common_root(Vec::<&str>::new()), None);
+    }
+
+    #[test]
+    fn test_shorten_paths() {
+        let root = Some("/home/runner/work/app/app/");
+        let shortened = shorten_paths(STACK, root);
+
+        assert!(shortened.contains("at …/tests/login.spec.ts:12:5"));
+        assert!(!shortened.contains("/home/runner"));
+        assert_eq!(shorten_paths(STACK, None), STACK);
+    }
+}
@@ -0,0 +1,1716 @@
+//! Format-independent report model shared by all exporters
+//! The trace is walked once into a list of blocks honoring the export
+//! options; each output format only has to serialize those blocks
+
+use crate::ansi_parser::strip_ansi;
+use crate::compact::{compact_log, first_app_frame, truncate_value, ELLIPSIS};
+use crate::file_probe::format_size;
+use crate::models::{
+    ActionEntry, ConsoleLocation, ConsoleMessage, ConsoleSeverity, ContextEntry, NetworkEntry,
+    TraceModel,
+};
+use crate::paths::shorten_paths;
+use crate::resource_store::ResourceStore;
+use crate::stats::{slowest_actions, ContextStats};
+use crate::steps::{group_actions_by_step, has_steps, StepNode};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+/// Options for exporting traces
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Only export actions with errors
+    pub errors_only: bool,
+    /// Workspace root collapsed to `…/` in titles, parameters and stacks
+    pub path_root: Option<String>,
+    /// Show action start times as wall-clock times instead of trace offsets
+    pub wall_clock: bool,
+    /// List this many of the slowest actions in a Performance section
+    pub slowest_actions: Option<usize>,
+    /// Summarize failed and slow requests in a Network section
+    pub network_summary: bool,
+    /// Cut parameter strings longer than this many characters, e.g. `fill`
+    /// values
+    pub max_param_length: Option<usize>,
+    /// Replace parameter objects and arrays nested deeper than this, e.g.
+    /// `evaluate` payloads
+    pub max_param_depth: Option<usize>,
+    /// How the screencast frames around each action are included
+    pub screenshots: ScreenshotMode,
+    /// Fit the report into about this many characters: logs and stacks are
+    /// shortened, then passing actions left out, then the rest cut off
+    pub max_chars: Option<usize>,
+    /// Start with machine-readable fields for tools indexing the reports
+    pub frontmatter: bool,
+    /// Name of the exported trace file, listed in the frontmatter
+    pub trace_file: Option<String>,
+    pub flavor: MarkdownFlavor,
+    /// Cut stacks to their first app frame, e.g. for chat messages
+    pub short_stacks: bool,
+    /// List the actions at the top, linked to anchors before their headings
+    pub table_of_contents: bool,
+}
+
+/// Markdown dialect exports are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkdownFlavor {
+    #[default]
+    Plain,
+    /// GitHub issues and comments: parameters, logs and stacks collapse into
+    /// `<details>` and field lists become tables
+    GitHub,
+}
+
+impl MarkdownFlavor {
+    pub const ALL: [MarkdownFlavor; 2] = [MarkdownFlavor::Plain, MarkdownFlavor::GitHub];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MarkdownFlavor::Plain => "Markdown",
+            MarkdownFlavor::GitHub => "GitHub markdown",
+        }
+    }
+}
+
+/// Screencast frames shown before and after each action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenshotMode {
+    #[default]
+    Omit,
+    /// Embedded as base64 data URLs, so the file stands on its own
+    Inline,
+    /// Linked under [`SCREENSHOTS_DIR`], for a bundle that ships the images
+    Links,
+}
+
+/// Folder of linked screenshots, relative to the report
+pub const SCREENSHOTS_DIR: &str = "screenshots/";
+
+/// Requests listed in the slowest requests table
+const SLOWEST_REQUESTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Report {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading {
+        level: usize,
+        text: String,
+    },
+    /// Bulleted list of named values
+    Fields(Vec<(String, String)>),
+    /// Named values on consecutive lines
+    Properties(Vec<(String, String)>),
+    /// Caption introducing the block that follows
+    Label(String),
+    /// Caption of a block that formats able to collapse it keep closed
+    Collapsible(String),
+    Paragraph(String),
+    /// De-emphasized remark
+    Note(String),
+    List(Vec<String>),
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    Code {
+        language: Option<String>,
+        text: String,
+    },
+    Image {
+        alt: String,
+        src: String,
+    },
+    /// Machine-readable fields heading the output, e.g. YAML frontmatter
+    Metadata(Vec<(String, serde_json::Value)>),
+    /// Link target before an action heading, with the title listed for it in
+    /// the table of contents
+    Anchor {
+        id: String,
+        title: String,
+    },
+    /// Links to the anchors of the report as (id, title)
+    Contents(Vec<(String, String)>),
+    Rule,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Approximate length of the report in any of the output formats
+    pub fn text_len(&self) -> usize {
+        self.blocks.iter().map(Block::text_len).sum()
+    }
+
+    pub fn push(&mut self, block: Block) {
+        self.blocks.push(block);
+    }
+
+    pub fn heading(&mut self, level: usize, text: impl Into<String>) {
+        self.push(Block::Heading {
+            level,
+            text: text.into(),
+        });
+    }
+
+    pub fn label(&mut self, text: impl Into<String>) {
+        self.push(Block::Label(text.into()));
+    }
+
+    pub fn collapsible(&mut self, text: impl Into<String>) {
+        self.push(Block::Collapsible(text.into()));
+    }
+
+    pub fn code(&mut self, language: Option<&str>, text: impl Into<String>) {
+        self.push(Block::Code {
+            language: language.map(str::to_string),
+            text: text.into(),
+        });
+    }
+}
+
+impl Block {
+    /// Length of the text plus a few characters of markup per line
+    pub fn text_len(&self) -> usize {
+        const MARKUP: usize = 6;
+        let pairs = |pairs: &[(String, String)]| -> usize {
+            pairs
+                .iter()
+                .map(|(name, value)| name.len() + value.len() + MARKUP)
+                .sum()
+        };
+
+        match self {
+            Block::Heading { text, .. }
+            | Block::Label(text)
+            | Block::Collapsible(text)
+            | Block::Paragraph(text)
+            | Block::Note(text) => text.len() + MARKUP,
+            Block::Fields(fields) | Block::Properties(fields) | Block::Contents(fields) => {
+                pairs(fields)
+            }
+            Block::List(items) => items.iter().map(|item| item.len() + MARKUP).sum(),
+            Block::Table { headers, rows } => std::iter::once(headers)
+                .chain(rows)
+                .flatten()
+                .map(|cell| cell.len() + 3)
+                .sum(),
+            Block::Code { text, .. } => text.len() + 2 * MARKUP,
+            Block::Image { alt, src } => alt.len() + src.len() + MARKUP,
+            Block::Anchor { id, .. } => id.len() + 2 * MARKUP,
+            Block::Metadata(fields) => fields
+                .iter()
+                .map(|(name, value)| name.len() + value.to_string().len() + MARKUP)
+                .sum(),
+            Block::Rule => MARKUP,
+        }
+    }
+}
+
+/// Build the report for a trace model
+pub fn build_trace_report(model: &TraceModel, options: &ExportOptions) -> Report {
+    let mut report = build_full_report(model, options);
+
+    if let Some(max_chars) = options.max_chars {
+        if report.text_len() + contents_len(&report, options) > max_chars {
+            report = fit_to_budget(model, options, report, max_chars);
+        }
+    }
+
+    // Contents are built last so they only link anchors that were kept
+    if options.table_of_contents {
+        add_contents(&mut report);
+    }
+
+    report
+}
+
+/// Failures are what the reader is after, so passing actions are left out
+/// first; whatever still does not fit is cut from the end, whole sections at
+/// a time
+fn fit_to_budget(
+    model: &TraceModel,
+    options: &ExportOptions,
+    report: Report,
+    max_chars: usize,
+) -> Report {
+    let contexts = || model.contexts.iter();
+    let has_failures = contexts().any(|context| {
+        !context.errors.is_empty() || context.actions.iter().any(|a| a.error.is_some())
+    });
+
+    let mut report = if has_failures && !options.errors_only {
+        let passing = contexts()
+            .flat_map(|context| &context.actions)
+            .filter(|action| action.error.is_none())
+            .count();
+        let errors_only = ExportOptions {
+            errors_only: true,
+            ..options.clone()
+        };
+
+        let mut report = build_full_report(model, &errors_only);
+        report.push(Block::Note(format!(
+            "{} passing actions were left out to fit {} characters.",
+            passing, max_chars
+        )));
+        report
+    } else {
+        report
+    };
+
+    let mut length = if options.table_of_contents {
+        contents_heading().text_len()
+    } else {
+        0
+    };
+    let kept = report
+        .blocks
+        .iter()
+        .take_while(|block| {
+            length += block.text_len() + contents_entry_len(block, options);
+            length <= max_chars
+        })
+        .count();
+    if kept < report.blocks.len() {
+        report.blocks.truncate(section_start(&report.blocks, kept));
+        report.push(Block::Note(format!(
+            "The rest of the report was cut to fit {} characters.",
+            max_chars
+        )));
+    }
+
+    report
+}
+
+fn build_full_report(model: &TraceModel, options: &ExportOptions) -> Report {
+    let mut report = Report::new();
+
+    if options.frontmatter {
+        report.push(build_metadata(model, options));
+    }
+
+    report.heading(1, "Playwright Trace Report");
+
+    for (idx, context) in model.contexts.iter().enumerate() {
+        if model.contexts.len() > 1 {
+            report.heading(2, format!("Context {}", idx + 1));
+        }
+
+        build_context(&mut report, context, &model.resource_store, options);
+
+        if idx < model.contexts.len() - 1 {
+            report.push(Block::Rule);
+        }
+    }
+
+    report
+}
+
+/// First block of the section holding `index`, so a cut there leaves no
+/// heading without its body and no anchor without its heading
+fn section_start(blocks: &[Block], index: usize) -> usize {
+    let is_heading = |block: &Block| matches!(block, Block::Heading { .. } | Block::Anchor { .. });
+
+    let Some(heading) = blocks[..=index].iter().rposition(is_heading) else {
+        return index;
+    };
+    // Parent headings right above the section have nothing left to head
+    blocks[..heading]
+        .iter()
+        .rposition(|block| !is_heading(block))
+        .map_or(0, |body| body + 1)
+}
+
+/// Length the contents of `report` will add once built
+fn contents_len(report: &Report, options: &ExportOptions) -> usize {
+    let entries: usize = report
+        .blocks
+        .iter()
+        .map(|block| contents_entry_len(block, options))
+        .sum();
+
+    match entries {
+        0 => 0,
+        entries => contents_heading().text_len() + entries,
+    }
+}
+
+/// Length of the contents entry linking `block`
+fn contents_entry_len(block: &Block, options: &ExportOptions) -> usize {
+    match block {
+        Block::Anchor { id, title } if options.table_of_contents => {
+            Block::Contents(vec![(id.clone(), title.clone())]).text_len()
+        }
+        _ => 0,
+    }
+}
+
+/// Contents below the report title, linking every anchor; anchors of actions
+/// sharing a call id across contexts get a counter so each link has one target
+fn add_contents(report: &mut Report) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut entries = Vec::new();
+
+    for block in &mut report.blocks {
+        if let Block::Anchor { id, title } = block {
+            let count = seen.entry(id.clone()).or_default();
+            *count += 1;
+            if *count > 1 {
+                *id = format!("{}-{}", id, count);
+            }
+            entries.push((id.clone(), title.clone()));
+        }
+    }
+
+    if entries.is_empty() {
+        return;
+    }
+
+    let position = report
+        .blocks
+        .iter()
+        .position(|block| matches!(block, Block::Heading { level: 1, .. }))
+        .map_or(0, |title| title + 1);
+    report.blocks.splice(
+        position..position,
+        [contents_heading(), Block::Contents(entries)],
+    );
+}
+
+fn contents_heading() -> Block {
+    Block::Heading {
+        level: 2,
+        text: "Contents".to_string(),
+    }
+}
+
+/// Anchor id of an action, stable across exports of the same trace
+pub fn action_anchor(action: &ActionEntry) -> String {
+    let call_id: String = action
+        .call_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("action-{}", call_id)
+}
+
+/// Fields for indexing the report; the environment comes from the first
+/// context that records it, counts and durations are summed over all of them
+pub fn build_metadata(model: &TraceModel, options: &ExportOptions) -> Block {
+    use serde_json::Value;
+
+    let first = model.contexts.first();
+    let total = |count: fn(&ContextStats) -> usize| -> usize {
+        model
+            .contexts
+            .iter()
+            .map(|context| count(&context.stats))
+            .sum()
+    };
+    let text =
+        |value: Option<&String>| value.map_or(Value::Null, |value| Value::from(value.as_str()));
+    // The test runner's context has no browser
+    let recorded = |field: fn(&ContextEntry) -> Option<&String>| {
+        text(
+            model
+                .contexts
+                .iter()
+                .filter_map(field)
+                .find(|value| !value.is_empty()),
+        )
+    };
+
+    let start_time = first
+        .and_then(|context| DateTime::from_timestamp_millis(context.wall_time as i64))
+        .map(|datetime| datetime.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+    // Contexts without actions keep their unset start time
+    let duration_ms: f64 = model
+        .contexts
+        .iter()
+        .filter(|context| !context.actions.is_empty())
+        .map(|context| (context.end_time - context.start_time).max(0.0))
+        .sum();
+
+    Block::Metadata(vec![
+        (
+            "title".to_string(),
+            recorded(|context| context.title.as_ref()),
+        ),
+        ("trace_file".to_string(), text(options.trace_file.as_ref())),
+        (
+            "browser".to_string(),
+            recorded(|context| Some(&context.browser_name)),
+        ),
+        (
+            "platform".to_string(),
+            recorded(|context| context.platform.as_ref()),
+        ),
+        (
+            "playwright_version".to_string(),
+            recorded(|context| context.playwright_version.as_ref()),
+        ),
+        ("start_time".to_string(), text(start_time.as_ref())),
+        (
+            "duration_ms".to_string(),
+            Value::from(duration_ms.round() as i64),
+        ),
+        ("contexts".to_string(), Value::from(model.contexts.len())),
+        (
+            "actions".to_string(),
+            Value::from(total(|stats| stats.action_count)),
+        ),
+        (
+            "failed_actions".to_string(),
+            Value::from(total(|stats| stats.failed_count)),
+        ),
+        (
+            "context_errors".to_string(),
+            Value::from(total(|stats| stats.error_count)),
+        ),
+    ])
+}
+
+fn build_context(
+    report: &mut Report,
+    context: &ContextEntry,
+    resources: &ResourceStore,
+    options: &ExportOptions,
+) {
+    // Test information
+    report.heading(2, "Test Information");
+
+    let mut info = Vec::new();
+
+    let root = options.path_root.as_deref();
+
+    if let Some(title) = &context.title {
+        info.push(("Title".to_string(), shorten_paths(title, root)));
+    }
+
+    info.push(("Browser".to_string(), context.browser_name.clone()));
+
+    if let Some(platform) = &context.platform {
+        info.push(("Platform".to_string(), platform.clone()));
+    }
+
+    if let Some(version) = &context.playwright_version {
+        info.push(("Playwright Version".to_string(), version.clone()));
+    }
+
+    // Convert wall time to readable date
+    let datetime = DateTime::from_timestamp_millis(context.wall_time as i64)
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    info.push((
+        "Start Time".to_string(),
+        datetime.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    ));
+
+    let duration = (context.end_time - context.start_time) / 1000.0;
+    info.push(("Duration".to_string(), format!("{:.2}s", duration)));
+
+    report.push(Block::Fields(info));
+
+    // Summary
+    let actions_to_export: Vec<&ActionEntry> = if options.errors_only {
+        context
+            .actions
+            .iter()
+            .filter(|a| a.error.is_some())
+            .collect()
+    } else {
+        context.actions.iter().collect()
+    };
+
+    let stats = &context.stats;
+
+    report.heading(2, "Summary");
+
+    let mut summary = vec![
+        ("Total Actions".to_string(), stats.action_count.to_string()),
+        ("Failed Actions".to_string(), stats.failed_count.to_string()),
+    ];
+
+    if stats.unfinished_count > 0 {
+        summary.push((
+            "Unfinished Actions".to_string(),
+            stats.unfinished_count.to_string(),
+        ));
+    }
+
+    if stats.error_count > 0 {
+        summary.push(("Context Errors".to_string(), stats.error_count.to_string()));
+    }
+    let console_errors = console_errors(context);
+    if !console_errors.is_empty() {
+        summary.push((
+            "Console Errors".to_string(),
+            console_errors.len().to_string(),
+        ));
+    }
+
+    report.push(Block::Fields(summary));
+
+    if let Some(count) = options.slowest_actions {
+        build_performance(report, context, count, options);
+    }
+
+    if options.network_summary {
+        build_network(report, context);
+    }
+
+    if options.errors_only
+        && stats.failed_count == 0
+        && stats.error_count == 0
+        && console_errors.is_empty()
+    {
+        report.push(Block::Note("No errors found in this trace.".to_string()));
+        return;
+    }
+
+    // Actions
+    if !actions_to_export.is_empty() {
+        report.heading(2, "Actions");
+
+        if has_steps(&context.actions) {
+            // Nest actions under their steps so the report reads like the test
+            let nodes = group_actions_by_step(&context.actions);
+            let mut index = 0;
+            build_nodes(report, context, resources, &nodes, options, 3, &mut index);
+        } else {
+            for (idx, action) in actions_to_export.iter().enumerate() {
+                build_action(report, context, resources, action, idx + 1, 3, options);
+            }
+        }
+    }
+
+    build_console_errors(report, context, &console_errors, options);
+
+    // Context-level errors
+    if !context.errors.is_empty() {
+        report.heading(2, "Context Errors");
+
+        for (idx, error) in context.errors.iter().enumerate() {
+            report.heading(3, format!("Error {}", idx + 1));
+            build_error(
+                report,
+                Some(&error.message),
+                error.stack.as_deref(),
+                options,
+            );
+        }
+    }
+}
+
+/// Messages the pages logged to their console as errors
+fn console_errors(context: &ContextEntry) -> Vec<&ConsoleMessage> {
+    context
+        .console
+        .iter()
+        .filter(|message| message.severity == ConsoleSeverity::Error)
+        .collect()
+}
+
+/// Table of the console errors, which often explain why an action failed
+fn build_console_errors(
+    report: &mut Report,
+    context: &ContextEntry,
+    console_errors: &[&ConsoleMessage],
+    options: &ExportOptions,
+) {
+    if console_errors.is_empty() {
+        return;
+    }
+
+    report.heading(2, "Console Errors");
+    report.push(Block::Table {
+        headers: ["Time", "Message", "Location"].map(str::to_string).to_vec(),
+        rows: console_errors
+            .iter()
+            .map(|message| {
+                vec![
+                    format_time(context, message.timestamp, options),
+                    strip_ansi(&message.text),
+                    message
+                        .location
+                        .as_ref()
+                        .map(ConsoleLocation::label)
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect(),
+    });
+}
+
+/// Table of the slowest actions, so the time sinks are visible without
+/// scanning every action
+fn build_performance(
+    report: &mut Report,
+    context: &ContextEntry,
+    count: usize,
+    options: &ExportOptions,
+) {
+    let slowest = slowest_actions(&context.actions, count);
+    if slowest.is_empty() {
+        return;
+    }
+
+    report.heading(2, "Performance");
+    report.push(Block::Table {
+        headers: ["#", "Action", "Duration", "Selector"]
+            .map(str::to_string)
+            .to_vec(),
+        rows: slowest
+            .iter()
+            .enumerate()
+            .map(|(idx, (action, duration))| {
+                let name = action.title.clone().unwrap_or_else(|| {
+                    action
+                        .method
+                        .clone()
+                        .unwrap_or_else(|| action.action_type.clone())
+                });
+                let selector = action
+                    .locator
+                    .as_ref()
+                    .map(|locator| format!("`{}`", locator))
+                    .unwrap_or_default();
+
+                vec![
+                    (idx + 1).to_string(),
+                    shorten_paths(&name, options.path_root.as_deref()),
+                    format!("{:.0}ms", duration),
+                    selector,
+                ]
+            })
+            .collect(),
+    });
+}
+
+/// Request count and transfer size, then the failed and the slowest requests
+fn build_network(report: &mut Report, context: &ContextEntry) {
+    let resources = &context.resources;
+    if resources.is_empty() {
+        return;
+    }
+
+    let totals = &context.stats.network;
+    let failed: Vec<&NetworkEntry> = resources.iter().filter(|r| r.is_failed()).collect();
+
+    report.heading(2, "Network");
+    report.push(Block::Fields(vec![
+        ("Requests".to_string(), totals.requests.to_string()),
+        ("Failed Requests".to_string(), totals.failed.to_string()),
+        (
+            "Transferred".to_string(),
+            format_size(totals.transferred_bytes),
+        ),
+    ]));
+
+    let cell = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let method = |resource: &NetworkEntry| resource.method().to_string();
+    let status = |resource: &NetworkEntry| cell(resource.status().map(|s| s.to_string()));
+
+    if !failed.is_empty() {
+        report.label("Failed Requests");
+        report.push(Block::Table {
+            headers: ["Status", "Method", "URL"].map(str::to_string).to_vec(),
+            rows: failed
+                .iter()
+                .map(|resource| {
+                    vec![
+                        status(resource),
+                        method(resource),
+                        resource.url().to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    let mut timed: Vec<(&NetworkEntry, f64)> = resources
+        .iter()
+        .filter_map(|resource| Some((resource, resource.duration?)))
+        .collect();
+    timed.sort_by(|a, b| b.1.total_cmp(&a.1));
+    timed.truncate(SLOWEST_REQUESTS);
+
+    if !timed.is_empty() {
+        report.label("Slowest Requests");
+        report.push(Block::Table {
+            headers: ["Duration", "Status", "Method", "URL"]
+                .map(str::to_string)
+                .to_vec(),
+            rows: timed
+                .iter()
+                .map(|(resource, duration)| {
+                    vec![
+                        format!("{:.0}ms", duration),
+                        status(resource),
+                        method(resource),
+                        resource.url().to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+}
+
+fn build_nodes(
+    report: &mut Report,
+    context: &ContextEntry,
+    resources: &ResourceStore,
+    nodes: &[StepNode],
+    options: &ExportOptions,
+    level: usize,
+    index: &mut usize,
+) {
+    for node in nodes {
+        if options.errors_only && !node.has_error() {
+            continue;
+        }
+
+        match node {
+            StepNode::Action(action) => {
+                *index += 1;
+                build_action(report, context, resources, action, *index, level, options);
+            }
+            StepNode::Step { step, children } => {
+                let title = step.title.as_deref().unwrap_or(&step.call_id);
+                let title = shorten_paths(title, options.path_root.as_deref());
+
+                report.heading(level, format!("Step: {}{}", title, status_suffix(step)));
+                let mut properties = vec![duration_property(step)];
+                if options.wall_clock {
+                    properties.push(start_property(context, step, options));
+                }
+                report.push(Block::Properties(properties));
+
+                build_nodes(
+                    report,
+                    context,
+                    resources,
+                    children,
+                    options,
+                    level + 1,
+                    index,
+                );
+            }
+        }
+    }
+}
+
+fn build_action(
+    report: &mut Report,
+    context: &ContextEntry,
+    resources: &ResourceStore,
+    action: &ActionEntry,
+    index: usize,
+    level: usize,
+    options: &ExportOptions,
+) {
+    let root = options.path_root.as_deref();
+
+    let method = action
+        .method
+        .as_deref()
+        .or(action.class.as_deref())
+        .unwrap_or(&action.action_type);
+
+    if options.table_of_contents {
+        let title = action.title.as_deref().unwrap_or(method);
+        report.push(Block::Anchor {
+            id: action_anchor(action),
+            title: format!(
+                "{}. {}{}",
+                index,
+                shorten_paths(title, root),
+                status_suffix(action)
+            ),
+        });
+    }
+    report.heading(
+        level,
+        format!("{}. {}{}", index, method, status_suffix(action)),
+    );
+
+    let mut properties = vec![duration_property(action)];
+    properties.push(start_property(context, action, options));
+
+    if let Some(title) = &action.title {
+        properties.push(("Action".to_string(), shorten_paths(title, root)));
+    }
+
+    if let Some(frame) = action.user_frame() {
+        properties.push(("Source".to_string(), shorten_paths(&frame.location(), root)));
+    }
+
+    report.push(Block::Properties(properties));
+
+    // Parameters
+    if !action.params.is_empty() {
+        report.collapsible("Parameters");
+
+        // Sorted so repeated exports of the same trace are identical
+        let mut truncated = 0;
+        let sorted: BTreeMap<_, _> = action
+            .params
+            .iter()
+            .map(|(name, value)| {
+                let (value, count) = truncate_value(
+                    value,
+                    options.max_param_length,
+                    options.max_param_depth.map(|depth| depth.saturating_sub(1)),
+                );
+                truncated += count;
+                (name, value)
+            })
+            .collect();
+        let json = if options.max_chars.is_some() {
+            serde_json::to_string(&sorted)
+        } else {
+            serde_json::to_string_pretty(&sorted)
+        };
+        let params = match json {
+            Ok(json) => json,
+            Err(_) => format!("{:?}", action.params),
+        };
+
+        report.code(Some("json"), shorten_paths(&params, root));
+        if truncated > 0 {
+            report.push(Block::Note(format!(
+                "{} parameter values cut, marked with {}",
+                truncated, ELLIPSIS
+            )));
+        }
+    }
+
+    // Error information
+    if let Some(error) = &action.error {
+        report.label("Error");
+        build_error(
+            report,
+            error.message.as_deref(),
+            error.stack.as_deref(),
+            options,
+        );
+    }
+
+    // Logs
+    if !action.log.is_empty() && options.max_chars.is_some() {
+        let (lines, omitted) = compact_log(&action.log);
+        let mut items: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                let text = format!(
+                    "{:.0}ms: {}",
+                    line.entry.time,
+                    strip_ansi(&line.entry.message)
+                );
+                match line.repeats {
+                    1 => text,
+                    repeats => format!("{} (×{})", text, repeats),
+                }
+            })
+            .collect();
+        if omitted > 0 {
+            items.insert(items.len() / 2, format!("… {} more", omitted));
+        }
+
+        report.collapsible("Logs");
+        report.push(Block::List(items));
+    } else if !action.log.is_empty() {
+        report.collapsible("Logs");
+        report.push(Block::List(
+            action
+                .log
+                .iter()
+                .map(|log| format!("{:.0}ms: {}", log.time, strip_ansi(&log.message)))
+                .collect(),
+        ));
+    }
+
+    // A single inline frame takes more than most budgets
+    if options.max_chars.is_none() || options.screenshots != ScreenshotMode::Inline {
+        build_screenshots(report, context, resources, action, options.screenshots);
+    }
+
+    report.push(Block::Rule);
+}
+
+/// Frames before and after the action; frames missing from the archive are
+/// left out
+fn build_screenshots(
+    report: &mut Report,
+    context: &ContextEntry,
+    resources: &ResourceStore,
+    action: &ActionEntry,
+    mode: ScreenshotMode,
+) {
+    if mode == ScreenshotMode::Omit {
+        return;
+    }
+
+    let (before, after) = context.action_screenshots(action);
+    let after = after.filter(|after| Some(&after.sha1) != before.map(|before| &before.sha1));
+
+    let images: Vec<Block> = [("Before", before), ("After", after)]
+        .into_iter()
+        .filter_map(|(alt, frame)| {
+            let sha1 = &frame?.sha1;
+            let src = match mode {
+                ScreenshotMode::Inline => format!(
+                    "data:{};base64,{}",
+                    image_mime_type(sha1),
+                    general_purpose::STANDARD.encode(resources.get(sha1)?)
+                ),
+                _ if resources.contains(sha1) => format!("{}{}", SCREENSHOTS_DIR, sha1),
+                _ => return None,
+            };
+            Some(Block::Image {
+                alt: alt.to_string(),
+                src,
+            })
+        })
+        .collect();
+
+    if !images.is_empty() {
+        report.label("Screenshots");
+        report.blocks.extend(images);
+    }
+}
+
+/// Screencast frames are JPEG unless their name says otherwise
+fn image_mime_type(name: &str) -> &'static str {
+    if name.ends_with(".png") {
+        "image/png"
+    } else {
+        "image/jpeg"
+    }
+}
+
+fn status_suffix(action: &ActionEntry) -> &'static str {
+    if action.error.is_some() {
+        " ⚠️ FAILED"
+    } else if action.did_not_finish() {
+        " ⏳ DID NOT FINISH"
+    } else {
+        ""
+    }
+}
+
+/// Duration, or an explicit note when the action never got its `after` event
+fn duration_property(action: &ActionEntry) -> (String, String) {
+    let value = match action.duration_ms() {
+        Some(duration) => format!("{:.0}ms", duration),
+        None => "did not finish (no after event recorded)".to_string(),
+    };
+    ("Duration".to_string(), value)
+}
+
+/// Start as a wall-clock time when asked for and known, the trace time otherwise
+fn start_property(
+    context: &ContextEntry,
+    action: &ActionEntry,
+    options: &ExportOptions,
+) -> (String, String) {
+    (
+        "Start".to_string(),
+        format_time(context, action.start_time, options),
+    )
+}
+
+/// Trace time as a wall-clock time when asked for and known
+fn format_time(context: &ContextEntry, time: f64, options: &ExportOptions) -> String {
+    match context.wall_clock_ms(time) {
+        Some(wall_ms) if options.wall_clock => format_clock_time(wall_ms),
+        _ => format!("{:.0}ms", time),
+    }
+}
+
+/// Wall-clock time of day such as `14:05:24.872 UTC`
+pub fn format_clock_time(wall_ms: f64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(wall_ms.round() as i64)
+        .map(|datetime| datetime.format("%H:%M:%S%.3f UTC").to_string())
+        .unwrap_or_else(|| format!("{:.0}ms", wall_ms))
+}
+
+/// Error message and stack as plain text; terminal colors in the message
+/// would show up as raw escape codes in exported files
+pub(crate) fn error_text(message: Option<&str>, stack: Option<&str>) -> String {
+    let mut text = strip_ansi(message.unwrap_or_default());
+
+    if let Some(stack) = stack {
+        text.push_str("\n\nStack trace:\n");
+        text.push_str(&strip_ansi(stack));
+    }
+
+    text
+}
+
+/// Error message and stack; budgeted and short-stack exports cut the stack to
+/// its first app frame, and GitHub markdown collapses it below the message
+fn build_error(
+    report: &mut Report,
+    message: Option<&str>,
+    stack: Option<&str>,
+    options: &ExportOptions,
+) {
+    let root = options.path_root.as_deref();
+    let stack = if options.max_chars.is_some() || options.short_stacks {
+        stack
+            .and_then(first_app_frame)
+            .map(|frame| format!("    {}", frame))
+    } else {
+        stack.map(str::to_string)
+    };
+
+    match stack {
+        Some(stack) if options.flavor == MarkdownFlavor::GitHub => {
+            report.code(
+                None,
+                shorten_paths(&strip_ansi(message.unwrap_or_default()), root),
+            );
+            report.collapsible("Stack trace");
+            report.code(None, shorten_paths(&strip_ansi(&stack), root));
+        }
+        stack => report.code(
+            None,
+            shorten_paths(&error_text(message, stack.as_deref()), root),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        LogEntry, NetworkContent, NetworkRequest, NetworkResponse, PageEntry, ScreencastFrame,
+        SerializedError, StackFrame,
+    };
+    use crate::stats::compute_stats;
+    use std::collections::HashMap;
+
+    fn context_with(actions: Vec<ActionEntry>) -> ContextEntry {
+        let mut context = ContextEntry {
+            id: String::new(),
+            start_time: 0.0,
+            end_time: 1000.0,
+            browser_name: "chromium".to_string(),
+            platform: None,
+            playwright_version: None,
+            wall_time: 1700000000000.0,
+            monotonic_time: 0.0,
+            title: Some("Test".to_string()),
+            pages: vec![],
+            actions,
+            resources: vec![],
+            events: vec![],
+            errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        };
+        context.stats = compute_stats(&context);
+        context
+    }
+
+    fn action(call_id: &str, error: Option<&str>) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: call_id.to_string(),
+            start_time: 100.0,
+            end_time: 200.0,
+            title: None,
+            class: Some("Frame".to_string()),
+            method: Some("click".to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: error.map(|message| SerializedError {
+                message: Some(message.to_string()),
+                stack: None,
+            }),
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        }
+    }
+
+    #[test]
+    fn test_report_source_needs_a_file() {
+        let frame = |file: &str| StackFrame {
+            file: file.to_string(),
+            line: 12,
+            column: 3,
+            function: None,
+        };
+        let mut pseudo = action("call@1", None);
+        pseudo.stack = vec![frame("project#chromium")];
+        let mut real = action("call@2", None);
+        real.stack = vec![frame("project#chromium"), frame("/app/tests/login.spec.ts")];
+
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![pseudo, real]));
+        let report = build_trace_report(&model, &ExportOptions::default());
+
+        let sources: Vec<_> = report
+            .blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Properties(properties) => Some(properties),
+                _ => None,
+            })
+            .flatten()
+            .filter(|(name, _)| name == "Source")
+            .map(|(_, value)| value.as_str())
+            .collect();
+
+        assert_eq!(sources, ["/app/tests/login.spec.ts:12:3"]);
+    }
+
+    #[test]
+    fn test_report_starts_with_title() {
+        let report = build_trace_report(&TraceModel::new(), &ExportOptions::default());
+
+        assert_eq!(
+            report.blocks[0],
+            Block::Heading {
+                level: 1,
+                text: "Playwright Trace Report".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_report_errors_only_skips_passing_actions() {
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![
+            action("call@1", None),
+            action("call@2", Some("Timeout")),
+        ]));
+
+        let options = ExportOptions {
+            errors_only: true,
+            ..Default::default()
+        };
+        let report = build_trace_report(&model, &options);
+
+        let action_headings: Vec<_> = report
+            .blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Heading { level: 3, text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(action_headings, vec!["1. click ⚠️ FAILED"]);
+    }
+
+    #[test]
+    fn test_report_marks_unfinished_actions() {
+        let mut crashed = action("call@2", None);
+        crashed.end_time = 0.0;
+
+        let mut model = TraceModel::new();
+        model
+            .contexts
+            .push(context_with(vec![action("call@1", None), crashed]));
+        let report = build_trace_report(&model, &ExportOptions::default());
+
+        assert!(report.blocks.contains(&Block::Heading {
+            level: 3,
+            text: "2. click ⏳ DID NOT FINISH".to_string()
+        }));
+        assert!(report.blocks.iter().any(|block| matches!(
+            block,
+            Block::Properties(properties)
+                if properties[0].1 == "did not finish (no after event recorded)"
+        )));
+        assert!(report.blocks.iter().any(|block| matches!(
+            block,
+            Block::Fields(fields)
+                if fields.contains(&("Unfinished Actions".to_string(), "1".to_string()))
+        )));
+    }
+
+    #[test]
+    fn test_report_frontmatter_skips_empty_contexts() {
+        let mut empty = context_with(vec![]);
+        empty.start_time = f64::MAX;
+        empty.end_time = 0.0;
+        let model = TraceModel {
+            contexts: vec![empty, context_with(vec![action("call@1", None)])],
+            ..TraceModel::new()
+        };
+        let options = ExportOptions {
+            frontmatter: true,
+            ..Default::default()
+        };
+        let report = build_trace_report(&model, &options);
+
+        let Some(Block::Metadata(fields)) = report.blocks.first() else {
+            panic!("report does not start with metadata");
+        };
+        let duration = fields
+            .iter()
+            .find(|(name, _)| name == "duration_ms")
+            .map(|(_, value)| value);
+        assert_eq!(duration, Some(&serde_json::Value::from(1000)));
+    }
+
+    #[test]
+    fn test_report_wall_clock_start_times() {
+        let mut context = context_with(vec![action("call@1", None)]);
+        // 2023-11-14 22:13:20 UTC at trace time 50ms
+        context.wall_time = 1700000000000.0;
+        context.monotonic_time = 50.0;
+
+        assert_eq!(context.wall_clock_ms(100.0), Some(1700000000050.0));
+
+        let mut model = TraceModel::new();
+        model.contexts.push(context);
+        let start = |wall_clock| {
+            let options = ExportOptions {
+                wall_clock,
+                ..Default::default()
+            };
+            build_trace_report(&model, &options)
+                .blocks
+                .into_iter()
+                .find_map(|block| match block {
+                    Block::Properties(properties) => properties
+                        .into_iter()
+                        .find(|(name, _)| name == "Start")
+                        .map(|(_, value)| value),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        assert_eq!(start(false), "100ms");
+        assert_eq!(start(true), "22:13:20.050 UTC");
+    }
+
+    #[test]
+    fn test_report_performance_section() {
+        let mut slow = action("call@2", None);
+        slow.end_time = 900.0;
+        slow.locator = Some("#submit".to_string());
+
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![
+            action("call@1", None),
+            slow,
+            action("call@3", None),
+        ]));
+
+        let without = build_trace_report(&model, &ExportOptions::default());
+        assert!(!without.blocks.contains(&Block::Heading {
+            level: 2,
+            text: "Performance".to_string()
+        }));
+
+        let options = ExportOptions {
+            slowest_actions: Some(2),
+            ..Default::default()
+        };
+        let report = build_trace_report(&model, &options);
+        let rows = report
+            .blocks
+            .iter()
+            .find_map(|block| match block {
+                Block::Table { rows, .. } => Some(rows.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec!["1", "click", "800ms", "`#submit`"],
+                vec!["2", "click", "100ms", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_strips_ansi_codes() {
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![action(
+            "call@1",
+            Some("\u{1b}[31mTimeout 5000ms exceeded\u{1b}[39m waiting for [data-test]"),
+        )]));
+
+        let report = build_trace_report(&model, &ExportOptions::default());
+
+        assert!(report.blocks.contains(&Block::Code {
+            language: None,
+            text: "Timeout 5000ms exceeded waiting for [data-test]".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_report_shortens_paths() {
+        let mut failing = action("call@1", Some("Timeout"));
+        failing.error.as_mut().unwrap().stack =
+            Some("at /home/runner/work/app/app/tests/login.spec.ts:3:1".to_string());
+
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![failing]));
+
+        let options = ExportOptions {
+            path_root: Some("/home/runner/work/app/app/".to_string()),
+            ..Default::default()
+        };
+        let report = build_trace_report(&model, &options);
+
+        assert!(report.blocks.contains(&Block::Code {
+            language: None,
+            text: "Timeout\n\nStack trace:\nat …/tests/login.spec.ts:3:1".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_report_note_when_no_errors() {
+        let mut model = TraceModel::new();
+        model
+            .contexts
+            .push(context_with(vec![action("call@1", None)]));
+
+        let options = ExportOptions {
+            errors_only: true,
+            ..Default::default()
+        };
+        let report = build_trace_report(&model, &options);
+
+        assert_eq!(
+            report.blocks.last(),
+            Some(&Block::Note("No errors found in this trace.".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_report_truncates_params() {
+        let mut fill = action("call@1", None);
+        fill.params = HashMap::from([
+            ("selector".to_string(), serde_json::json!("#bio")),
+            ("value".to_string(), serde_json::json!("lorem ipsum dolor")),
+            (
+                "arg".to_string(),
+                serde_json::json!({"user": {"name": "Ada"}}),
+            ),
+        ]);
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![fill]));
+        let options = ExportOptions {
+            max_param_length: Some(5),
+            max_param_depth: Some(2),
+            ..Default::default()
+        };
+
+        let report = build_trace_report(&model, &options);
+
+        assert!(report.blocks.contains(&Block::Code {
+            language: Some("json".to_string()),
+            text: serde_json::to_string_pretty(&serde_json::json!({
+                "arg": {"user": "{…}"},
+                "selector": "#bio",
+                "value": "lorem…",
+            }))
+            .unwrap(),
+        }));
+        assert!(report.blocks.contains(&Block::Note(
+            "2 parameter values cut, marked with …".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_report_network_section() {
+        let request = |url: &str, status: i32, duration: f64, size: i64| NetworkEntry {
+            start_time: Some(0.0),
+            duration: Some(duration),
+            request: NetworkRequest {
+                method: "GET".to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+            response: NetworkResponse {
+                status,
+                content: NetworkContent {
+                    size,
+                    ..Default::default()
+                },
+                transfer_size: Some(size + 100),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut context = context_with(vec![]);
+        context.resources = vec![
+            request("https://app.test/", 200, 40.0, 2048),
+            request("https://app.test/api/cart", 500, 900.0, 0),
+            request("https://app.test/app.js", 200, 120.0, 0),
+        ];
+        context.stats = compute_stats(&context);
+        let mut model = TraceModel::new();
+        model.contexts.push(context);
+        let options = ExportOptions {
+            network_summary: true,
+            ..Default::default()
+        };
+
+        let report = build_trace_report(&model, &options);
+
+        assert!(report.blocks.contains(&Block::Fields(vec![
+            ("Requests".to_string(), "3".to_string()),
+            ("Failed Requests".to_string(), "1".to_string()),
+            ("Transferred".to_string(), "2.3 KB".to_string()),
+        ])));
+        assert!(report.blocks.contains(&Block::Table {
+            headers: vec![
+                "Status".to_string(),
+                "Method".to_string(),
+                "URL".to_string()
+            ],
+            rows: vec![vec![
+                "500".to_string(),
+                "GET".to_string(),
+                "https://app.test/api/cart".to_string()
+            ]],
+        }));
+        let slowest = report.blocks.iter().find_map(|block| match block {
+            Block::Table { headers, rows } if headers[0] == "Duration" => Some(rows),
+            _ => None,
+        });
+        assert_eq!(
+            slowest
+                .unwrap()
+                .iter()
+                .map(|row| row[0].as_str())
+                .collect::<Vec<_>>(),
+            ["900ms", "120ms", "40ms"]
+        );
+    }
+
+    #[test]
+    fn test_report_fits_budget() {
+        let mut failed = action("call@1", Some("Timeout 5000ms exceeded"));
+        failed.error.as_mut().unwrap().stack = Some(
+            "Error: Timeout\n    at Frame.click (/app/node_modules/pw/frame.js:1:1)\n    \
+             at /app/tests/login.spec.ts:3:1\n    at /app/tests/fixtures.ts:9:1"
+                .to_string(),
+        );
+        let mut passed = action("call@2", None);
+        passed.log = (0..40)
+            .map(|attempt| LogEntry {
+                time: attempt as f64,
+                message: format!("retrying click action, attempt #{}", attempt),
+            })
+            .collect();
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(vec![failed, passed]));
+        let options = |max_chars| ExportOptions {
+            max_chars: Some(max_chars),
+            ..Default::default()
+        };
+
+        // Logs are merged and stacks cut to the test's frame
+        let roomy = build_trace_report(&model, &options(100_000));
+        assert!(roomy.blocks.contains(&Block::List(vec![
+            "0ms: retrying click action, attempt #0 (×40)".to_string()
+        ])));
+        assert!(roomy.blocks.contains(&Block::Code {
+            language: None,
+            text: "Timeout 5000ms exceeded\n\nStack trace:\n    at /app/tests/login.spec.ts:3:1"
+                .to_string(),
+        }));
+
+        // Passing actions go first, then the end is cut off
+        let tight = build_trace_report(&model, &options(450));
+        assert_eq!(
+            tight.blocks.last(),
+            Some(&Block::Note(
+                "1 passing actions were left out to fit 450 characters.".to_string()
+            ))
+        );
+        assert!(tight.text_len() <= 450);
+
+        let tiny = build_trace_report(&model, &options(200));
+        assert_eq!(
+            tiny.blocks.last(),
+            Some(&Block::Note(
+                "The rest of the report was cut to fit 200 characters.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_report_table_of_contents() {
+        let mut failing = action("call@2", Some("Timeout"));
+        failing.title = Some("Click \"Pay\"".to_string());
+        let mut model = TraceModel::new();
+        model
+            .contexts
+            .push(context_with(vec![action("call@1", None), failing]));
+        model
+            .contexts
+            .push(context_with(vec![action("call@1", None)]));
+        let options = ExportOptions {
+            table_of_contents: true,
+            ..Default::default()
+        };
+
+        let report = build_trace_report(&model, &options);
+
+        assert_eq!(
+            report.blocks[1],
+            Block::Heading {
+                level: 2,
+                text: "Contents".to_string()
+            }
+        );
+        assert_eq!(
+            report.blocks[2],
+            Block::Contents(vec![
+                ("action-call-1".to_string(), "1. click".to_string()),
+                (
+                    "action-call-2".to_string(),
+                    "2. Click \"Pay\" ⚠️ FAILED".to_string()
+                ),
+                ("action-call-1-2".to_string(), "1. click".to_string()),
+            ])
+        );
+        assert!(!build_trace_report(&model, &ExportOptions::default())
+            .blocks
+            .iter()
+            .any(|block| matches!(block, Block::Anchor { .. } | Block::Contents(_))));
+    }
+
+    #[test]
+    fn test_report_contents_fit_budget() {
+        let actions = (1..=6)
+            .map(|index| {
+                let mut action = action(&format!("call@{}", index), None);
+                action.log = vec![LogEntry {
+                    time: 0.0,
+                    message: format!("waiting for locator #{}", index),
+                }];
+                action
+            })
+            .collect();
+        let mut model = TraceModel::new();
+        model.contexts.push(context_with(actions));
+
+        for max_chars in [300, 450, 600, 800] {
+            let options = ExportOptions {
+                table_of_contents: true,
+                max_chars: Some(max_chars),
+                ..Default::default()
+            };
+            let report = build_trace_report(&model, &options);
+            let Some((Block::Note(note), kept)) = report.blocks.split_last() else {
+                panic!("report for {} characters was not cut", max_chars);
+            };
+            assert!(note.starts_with("The rest of the report was cut"));
+            assert!(
+                Report {
+                    blocks: kept.to_vec()
+                }
+                .text_len()
+                    <= max_chars
+            );
+
+            // The cut lands between sections and every entry has its heading
+            assert!(!matches!(
+                kept.last(),
+                Some(Block::Heading { .. } | Block::Anchor { .. })
+            ));
+            let anchors: Vec<&String> = kept
+                .iter()
+                .zip(&kept[1..])
+                .filter_map(|pair| match pair {
+                    (Block::Anchor { id, .. }, Block::Heading { .. }) => Some(id),
+                    _ => None,
+                })
+                .collect();
+            let entries: Vec<&String> = kept
+                .iter()
+                .find_map(|block| match block {
+                    Block::Contents(entries) => Some(entries.iter().map(|(id, _)| id).collect()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            assert_eq!(entries, anchors);
+        }
+    }
+
+    #[test]
+    fn test_report_screenshots() {
+        let frame = |sha1: &str, timestamp: f64| ScreencastFrame {
+            sha1: sha1.to_string(),
+            timestamp,
+            width: 800,
+            height: 600,
+            frame_swap_wall_time: None,
+        };
+        let mut context = context_with(vec![action("call@1", None)]);
+        context.pages.push(PageEntry {
+            page_id: "page@1".to_string(),
+            screencast_frames: vec![
+                frame("page@1-50.jpeg", 50.0),
+                frame("page@1-250.jpeg", 250.0),
+            ],
+            frames: vec![],
+            title: None,
+            viewport: None,
+        });
+        let model = TraceModel {
+            contexts: vec![context],
+            // Only the frame before the action was kept in the archive
+            resource_store: ResourceStore::from_files(HashMap::from([(
+                "page@1-50.jpeg".to_string(),
+                vec![0xFF, 0xD8, 0xFF],
+            )])),
+            ..TraceModel::new()
+        };
+        let images = |mode| {
+            let options = ExportOptions {
+                screenshots: mode,
+                ..Default::default()
+            };
+            build_trace_report(&model, &options)
+                .blocks
+                .into_iter()
+                .filter(|block| matches!(block, Block::Image { .. }))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(images(ScreenshotMode::Omit).is_empty());
+        assert_eq!(
+            images(ScreenshotMode::Inline),
+            [Block::Image {
+                alt: "Before".to_string(),
+                src: "data:image/jpeg;base64,/9j/".to_string(),
+            }]
+        );
+        assert_eq!(
+            images(ScreenshotMode::Links),
+            [Block::Image {
+                alt: "Before".to_string(),
+                src: "screenshots/page@1-50.jpeg".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use flate2::read::DeflateDecoder;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::rc::Rc;
+use zip::{CompressionMethod, ZipArchive};
+
+/// Prefix of archive entries holding trace resources
+pub const RESOURCES_PREFIX: &str = "resources/";
+
+/// Lazily decoded archive entries, e.g. trace resources keyed by sha1
+/// Only the location of each entry is recorded at load time; the bytes are
+/// inflated from the archive when a resource is requested. Files read from a
+/// folder are kept whole, as stored entries of their own
+#[derive(Clone, Default)]
+pub struct ResourceStore {
+    entries: Rc<HashMap<String, ResourceLocation>>,
+}
+
+#[derive(Clone)]
+struct ResourceLocation {
+    archive: Rc<[u8]>,
+    data_start: usize,
+    compressed_size: usize,
+    size: usize,
+    compression: CompressionMethod,
+}
+
+impl PartialEq for ResourceLocation {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.archive, &other.archive) && self.data_start == other.data_start
+    }
+}
+
+impl PartialEq for ResourceStore {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries) || self.entries == other.entries
+    }
+}
+
+impl std::fmt::Debug for ResourceStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ResourceStore")
+            .field("resources", &self.entries.len())
+            .finish()
+    }
+}
+
+impl ResourceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the location of every `resources/*` entry in the archive
+    pub fn from_archive(
+        bytes: Rc<[u8]>,
+        archive: &mut ZipArchive<Cursor<&[u8]>>,
+    ) -> Result<Self, String> {
+        Self::index(bytes, archive, |name| {
+            name.strip_prefix(RESOURCES_PREFIX)
+                .filter(|sha1| !sha1.is_empty())
+                .map(str::to_string)
+        })
+    }
+
+    /// Record the location of the named entries, keyed by their path in the archive
+    pub fn from_entries(
+        bytes: Rc<[u8]>,
+        archive: &mut ZipArchive<Cursor<&[u8]>>,
+        names: &HashSet<String>,
+    ) -> Result<Self, String> {
+        Self::index(bytes, archive, |name| {
+            names.contains(name).then(|| name.to_string())
+        })
+    }
+
+    /// Keep files read one by one, e.g. from an unzipped trace folder, keyed by sha1
+    pub fn from_files(files: HashMap<String, Vec<u8>>) -> Self {
+        let entries = files
+            .into_iter()
+            .map(|(sha1, bytes)| {
+                let size = bytes.len();
+                let location = ResourceLocation {
+                    archive: Rc::from(bytes),
+                    data_start: 0,
+                    compressed_size: size,
+                    size,
+                    compression: CompressionMethod::Stored,
+                };
+                (sha1, location)
+            })
+            .collect();
+
+        Self {
+            entries: Rc::new(entries),
+        }
+    }
+
+    fn index(
+        bytes: Rc<[u8]>,
+        archive: &mut ZipArchive<Cursor<&[u8]>>,
+        key_of: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, String> {
+        let mut entries = HashMap::new();
+
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+            if file.is_dir() {
+                continue;
+            }
+            let Some(key) = key_of(file.name()) else {
+                continue;
+            };
+
+            entries.insert(
+                key,
+                ResourceLocation {
+                    archive: bytes.clone(),
+                    data_start: file.data_start() as usize,
+                    compressed_size: file.compressed_size() as usize,
+                    size: file.size() as usize,
+                    compression: file.compression(),
+                },
+            );
+        }
+
+        Ok(Self {
+            entries: Rc::new(entries),
+        })
+    }
+
+    /// Decode the resource with the given sha1
+    pub fn get(&self, sha1: &str) -> Option<Vec<u8>> {
+        let location = self.entries.get(sha1)?;
+        let end = location.data_start.checked_add(location.compressed_size)?;
+        let data = location.archive.get(location.data_start..end)?;
+
+        match location.compression {
+            CompressionMethod::Stored => Some(data.to_vec()),
+            CompressionMethod::Deflated => {
+                let mut bytes = Vec::with_capacity(location.size);
+                match DeflateDecoder::new(data).read_to_end(&mut bytes) {
+                    Ok(_) => Some(bytes),
+                    Err(e) => {
+                        log::warn!("Failed to inflate resource {}: {}", sha1, e);
+                        None
+                    }
+                }
+            }
+            other => {
+                log::warn!("Unsupported compression {:?} for {}", other, sha1);
+                None
+            }
+        }
+    }
+
+    pub fn contains(&self, sha1: &str) -> bool {
+        self.entries.contains_key(sha1)
+    }
+
+    /// Uncompressed size of the resource in bytes
+    pub fn size(&self, sha1: &str) -> Option<usize> {
+        self.entries.get(sha1).map(|location| location.size)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Merge the resources of another store, e.g. from a nested archive
+    pub fn extend(&mut self, other: ResourceStore) {
+        if other.is_empty() {
+            return;
+        }
+
+        let entries = Rc::make_mut(&mut self.entries);
+        for (sha1, location) in other.entries.iter() {
+            entries.insert(sha1.clone(), location.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn build_archive(compression: CompressionMethod) -> Vec<u8> {
+        let mut buf = Vec::new();
+        {
+            let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+            let options = FileOptions::default().compression_method(compression);
+
+            zip.start_file("trace.trace", options).unwrap();
+            zip.write_all(b"{}").unwrap();
+
+            zip.start_file("resources/abc123.jpeg", options).unwrap();
+            zip.write_all(&[0xFF, 0xD8, 0xFF, 0xE0].repeat(64)).unwrap();
+
+            zip.start_file("resources/def456", options).unwrap();
+            zip.write_all(b"hello resource").unwrap();
+
+            zip.finish().unwrap();
+        }
+        buf
+    }
+
+    fn load_store(bytes: &[u8]) -> ResourceStore {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        ResourceStore::from_archive(Rc::from(bytes), &mut archive).unwrap()
+    }
+
+    #[test]
+    fn test_get_stored_resource() {
+        let bytes = build_archive(CompressionMethod::Stored);
+        let store = load_store(&bytes);
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get("def456").unwrap(), b"hello resource");
+        assert!(store.get("trace.trace").is_none());
+    }
+
+    #[test]
+    fn test_get_deflated_resource() {
+        let bytes = build_archive(CompressionMethod::Deflated);
+        let store = load_store(&bytes);
+
+        let image = store.get("abc123.jpeg").unwrap();
+        assert_eq!(image.len(), 256);
+        assert_eq!(&image[..2], &[0xFF, 0xD8]);
+        assert_eq!(store.size("abc123.jpeg"), Some(256));
+    }
+
+    #[test]
+    fn test_named_entries() {
+        let bytes = build_archive(CompressionMethod::Deflated);
+        let mut archive = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        let names = HashSet::from(["resources/def456".to_string()]);
+        let store =
+            ResourceStore::from_entries(Rc::from(bytes.as_slice()), &mut archive, &names).unwrap();
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get("resources/def456").unwrap(), b"hello resource");
+        assert!(!store.contains("resources/abc123.jpeg"));
+    }
+
+    #[test]
+    fn test_missing_resource() {
+        let store = ResourceStore::new();
+        assert!(store.is_empty());
+        assert!(!store.contains("abc123"));
+        assert!(store.get("abc123").is_none());
+    }
+
+    #[test]
+    fn test_extend_merges_archives() {
+        let first = build_archive(CompressionMethod::Stored);
+        let mut store = load_store(&first);
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+            zip.start_file("resources/other", FileOptions::default())
+                .unwrap();
+            zip.write_all(b"other").unwrap();
+            zip.finish().unwrap();
+        }
+
+        store.extend(load_store(&buf));
+
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.get("other").unwrap(), b"other");
+        assert_eq!(store.get("def456").unwrap(), b"hello resource");
+    }
+}
@@ -0,0 +1,176 @@
+//! Playwright route mocks from recorded responses
+//! Each selected response becomes a `page.route()` handler fulfilling the
+//! request with the recorded status, content type and body, so a failing
+//! test can be replayed without the backend it talked to
+
+use crate::models::NetworkEntry;
+use base64::{engine::general_purpose, Engine as _};
+
+/// Export route-mocking TypeScript for the given responses, in recorded order
+/// `body` looks up a response body by its sha1
+pub fn export_route_mocks(
+    resources: &[&NetworkEntry],
+    body: impl Fn(&str) -> Option<Vec<u8>>,
+) -> String {
+    let mut output = String::new();
+    output.push_str("import type { Page } from '@playwright/test';\n\n");
+    output.push_str("// Responses recorded in the trace\n");
+    output.push_str("export async function mockRecordedRoutes(page: Page) {\n");
+
+    // Handlers registered later run first, so register in reverse: earlier
+    // responses to the same request are served once each, the last one
+    // answers every repetition after that
+    for (idx, resource) in resources.iter().enumerate().rev() {
+        let repeated = resources[idx + 1..]
+            .iter()
+            .any(|later| same_request(later, resource));
+
+        if idx < resources.len() - 1 {
+            output.push('\n');
+        }
+        write_route(&mut output, resource, repeated, &body);
+    }
+
+    output.push_str("}\n");
+    output
+}
+
+fn same_request(a: &NetworkEntry, b: &NetworkEntry) -> bool {
+    a.url() == b.url() && a.method() == b.method()
+}
+
+fn write_route(
+    output: &mut String,
+    resource: &NetworkEntry,
+    once: bool,
+    body: &impl Fn(&str) -> Option<Vec<u8>>,
+) {
+    let url = js_string(resource.url());
+    output.push_str(&format!(
+        "  await page.route(url => url.href === {}, async route => {{\n",
+        url
+    ));
+
+    let method = resource.method();
+    if method != "GET" {
+        output.push_str(&format!(
+            "    if (route.request().method() !== {}) return route.fallback();\n",
+            js_string(method)
+        ));
+    }
+
+    output.push_str("    await route.fulfill({\n");
+    output.push_str(&format!(
+        "      status: {},\n",
+        resource
+            .status()
+            .filter(|status| *status > 0)
+            .unwrap_or(200)
+    ));
+    if let Some(content_type) = resource.content_type().filter(|t| !t.is_empty()) {
+        output.push_str(&format!(
+            "      contentType: {},\n",
+            js_string(content_type)
+        ));
+    }
+    match resource.body_sha1().and_then(body) {
+        Some(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => output.push_str(&format!("      body: {},\n", js_string(&text))),
+            Err(e) => output.push_str(&format!(
+                "      body: Buffer.from({}, 'base64'),\n",
+                js_string(&general_purpose::STANDARD.encode(e.as_bytes()))
+            )),
+        },
+        None if resource.body_sha1().is_some() => {
+            output.push_str("      // Body was not found in the trace\n");
+        }
+        None => {}
+    }
+    output.push_str("    });\n");
+
+    if once {
+        output.push_str("  }, { times: 1 });\n");
+    } else {
+        output.push_str("  });\n");
+    }
+}
+
+/// Quoted string literal; JSON strings are valid TypeScript
+fn js_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "''".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NetworkContent, NetworkRequest, NetworkResponse};
+
+    fn resource(url: &str, method: &str, status: i32, sha1: Option<&str>) -> NetworkEntry {
+        NetworkEntry {
+            request: NetworkRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            },
+            response: NetworkResponse {
+                status,
+                content: NetworkContent {
+                    mime_type: Some("application/json".to_string()),
+                    sha1: sha1.map(str::to_string),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn body(sha1: &str) -> Option<Vec<u8>> {
+        match sha1 {
+            "users" => Some(br#"{"users":[]}"#.to_vec()),
+            "binary" => Some(vec![0xff, 0x00]),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_route_mock_fulfills_recorded_response() {
+        let users = resource("https://api.test/users?page=1", "GET", 200, Some("users"));
+        let code = export_route_mocks(&[&users], body);
+
+        assert!(code.contains("export async function mockRecordedRoutes(page: Page) {"));
+        assert!(code.contains(
+            r#"await page.route(url => url.href === "https://api.test/users?page=1", async route => {"#
+        ));
+        assert!(code.contains("      status: 200,\n"));
+        assert!(code.contains(r#"      contentType: "application/json","#));
+        assert!(code.contains(r#"      body: "{\"users\":[]}","#));
+        assert!(!code.contains("fallback"));
+        assert!(!code.contains("times"));
+    }
+
+    #[test]
+    fn test_route_mock_method_and_binary_body() {
+        let upload = resource("https://api.test/upload", "POST", 500, Some("binary"));
+        let code = export_route_mocks(&[&upload], body);
+
+        assert!(
+            code.contains(r#"if (route.request().method() !== "POST") return route.fallback();"#)
+        );
+        assert!(code.contains(r#"body: Buffer.from("/wA=", 'base64'),"#));
+    }
+
+    #[test]
+    fn test_route_mock_repeated_requests_served_in_order() {
+        let first = resource("https://api.test/status", "GET", 503, None);
+        let second = resource("https://api.test/status", "GET", 200, None);
+        let code = export_route_mocks(&[&first, &second], body);
+
+        // The last response is registered first and stays
+        let last = code.find("status: 200").unwrap();
+        let once = code.find("status: 503").unwrap();
+        assert!(last < once);
+        assert_eq!(code.matches("{ times: 1 }").count(), 1);
+        assert!(code[once..].contains("{ times: 1 }"));
+    }
+}
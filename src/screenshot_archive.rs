@@ -0,0 +1,163 @@
+//! Zip of screenshots for bulk download
+//! The images are re-packed client-side, stored uncompressed since PNG and
+//! JPEG data does not shrink any further
+
+use crate::models::{TestAttachment, TestCase};
+use crate::resource_store::ResourceStore;
+use crate::test_case_loader::decode_data_url;
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Screenshots and comparison images of a test, without failed captures
+pub fn test_case_images(test_case: &TestCase) -> Vec<&TestAttachment> {
+    let comparisons = test_case
+        .image_diffs
+        .iter()
+        .flat_map(|diff| [&diff.expected, &diff.actual, &diff.diff])
+        .flatten();
+
+    test_case
+        .screenshots
+        .iter()
+        .chain(comparisons)
+        .filter(|image| !image.capture_failed)
+        .collect()
+}
+
+/// Bytes of an attachment, from its data URL or, before the card was
+/// expanded, from the archive
+pub fn attachment_bytes(attachment: &TestAttachment, store: &ResourceStore) -> Option<Vec<u8>> {
+    if !attachment.data_url.is_empty() {
+        return decode_data_url(&attachment.data_url);
+    }
+    store.get(attachment.archive_entry.as_deref()?)
+}
+
+/// Zip images under the given paths; images that cannot be read are skipped
+/// and a path used twice keeps its first image
+pub fn build_screenshot_zip<'a>(
+    images: impl IntoIterator<Item = (String, &'a TestAttachment)>,
+    store: &ResourceStore,
+) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    let mut written = HashSet::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+        for (path, image) in images {
+            if written.contains(&path) {
+                continue;
+            }
+            let Some(bytes) = attachment_bytes(image, store) else {
+                log::warn!("Skipping unreadable screenshot {}", path);
+                continue;
+            };
+
+            zip.start_file(path.as_str(), options)
+                .map_err(|e| e.to_string())?;
+            zip.write_all(&bytes).map_err(|e| e.to_string())?;
+            written.insert(path);
+        }
+
+        zip.finish().map_err(|e| e.to_string())?;
+    }
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ImageDiff;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn image(name: &str, data_url: &str) -> TestAttachment {
+        TestAttachment {
+            name: name.to_string(),
+            mime_type: "image/png".to_string(),
+            data_url: data_url.to_string(),
+            size_bytes: None,
+            capture_failed: false,
+            archive_entry: None,
+        }
+    }
+
+    #[test]
+    fn test_build_screenshot_zip() {
+        // "PNG" and "JPG" in base64
+        let first = image("test-failed-1.png", "data:image/png;base64,UE5H");
+        let second = image("test-failed-2.png", "data:image/png;base64,SlBH");
+        let unreadable = image("missing.png", "");
+
+        let bytes = build_screenshot_zip(
+            [
+                ("login/test-failed-1.png".to_string(), &first),
+                ("login/test-failed-1.png".to_string(), &second),
+                ("login/missing.png".to_string(), &unreadable),
+                ("checkout/test-failed-2.png".to_string(), &second),
+            ],
+            &ResourceStore::new(),
+        )
+        .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut content = String::new();
+        archive
+            .by_name("login/test-failed-1.png")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "PNG");
+        assert!(archive.by_name("checkout/test-failed-2.png").is_ok());
+    }
+
+    #[test]
+    fn test_images_include_comparisons() {
+        let mut failed = image("broken.png", "");
+        failed.capture_failed = true;
+
+        let test_case = TestCase {
+            id: "home".to_string(),
+            name: "home".to_string(),
+            status: crate::models::TestStatus::Failed,
+            markdown_content: None,
+            screenshots: vec![image("test-failed-1.png", ""), failed],
+            video: None,
+            trace_file: None,
+            text_attachments: Vec::new(),
+            duration_ms: None,
+            error_message: None,
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
+            suite_path: Vec::new(),
+            image_diffs: vec![ImageDiff {
+                name: "homepage".to_string(),
+                expected: Some(image("homepage-expected.png", "")),
+                actual: Some(image("homepage-actual.png", "")),
+                diff: None,
+            }],
+            trace_contexts: Vec::new(),
+            trace_overview: None,
+        };
+
+        let names: Vec<&str> = test_case_images(&test_case)
+            .iter()
+            .map(|image| image.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "test-failed-1.png",
+                "homepage-expected.png",
+                "homepage-actual.png"
+            ]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Traces and test results open side by side
+//! Dropped files and folders load as a batch: it replaces the open items or
+//! joins them, and test result archives of the batch open together as runs
+//! of one suite once every load of the batch has finished
+
+use crate::models::{TestCaseCollection, TraceModel};
+use crate::trace_links::TraceTarget;
+
+/// Trace or test results open in the session
+#[derive(Clone, PartialEq)]
+pub enum SessionContent {
+    Trace {
+        model: TraceModel,
+        /// Test the trace was opened from, when opened from a test card
+        origin: Option<TraceOrigin>,
+    },
+    TestCases {
+        test_cases: TestCaseCollection,
+        /// Every loaded run of the suite, oldest first
+        runs: Vec<TestCaseCollection>,
+    },
+}
+
+/// Test card a trace was opened from
+#[derive(Clone, PartialEq)]
+pub struct TraceOrigin {
+    /// Session item listing the test
+    pub item_id: usize,
+    pub test_name: String,
+    pub target: TraceTarget,
+}
+
+/// Archive listed in the session sidebar
+#[derive(Clone, PartialEq)]
+pub struct SessionItem {
+    /// Stays the same when other items close; keys the main view
+    pub id: usize,
+    /// Files the content was loaded from
+    pub name: String,
+    pub content: SessionContent,
+}
+
+#[derive(Default)]
+pub struct Session {
+    pub items: Vec<SessionItem>,
+    /// Index of the item shown in the main view
+    pub active_item: usize,
+    next_item_id: usize,
+    /// Files and folders in the batch being loaded
+    batch_size: usize,
+    /// Loads of the batch that have not finished yet
+    pending_loads: usize,
+    /// Test result archives of the batch loaded so far, keyed by file name
+    loaded_runs: Vec<(String, TestCaseCollection)>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start loading `loads` files or folders, replacing the open items
+    /// unless `adding`
+    pub fn begin_batch(&mut self, loads: usize, adding: bool) {
+        if !adding {
+            self.items.clear();
+            self.active_item = 0;
+        }
+        self.batch_size = loads.max(1);
+        self.pending_loads = self.batch_size;
+        self.loaded_runs.clear();
+    }
+
+    /// Files and folders in the batch being loaded, at least one
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.max(1)
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.pending_loads > 0
+    }
+
+    /// Stop waiting for the loads of the batch
+    pub fn cancel_batch(&mut self) {
+        self.pending_loads = 0;
+        self.loaded_runs.clear();
+    }
+
+    /// Open a trace of the batch; returns whether the batch is complete
+    pub fn trace_loaded(&mut self, name: String, model: TraceModel) -> bool {
+        if !self.is_loading() {
+            return false;
+        }
+
+        self.open(
+            name,
+            SessionContent::Trace {
+                model,
+                origin: None,
+            },
+        );
+        self.finish_load()
+    }
+
+    /// Keep test results of the batch until every load has finished;
+    /// returns whether the batch is complete
+    pub fn test_cases_loaded(&mut self, name: String, test_cases: TestCaseCollection) -> bool {
+        if !self.is_loading() {
+            return false;
+        }
+
+        self.loaded_runs.push((name, test_cases));
+        self.finish_load()
+    }
+
+    /// Show content next to the open items
+    pub fn open(&mut self, name: String, content: SessionContent) {
+        self.items.push(SessionItem {
+            id: self.next_item_id,
+            name,
+            content,
+        });
+        self.next_item_id += 1;
+        self.active_item = self.items.len() - 1;
+    }
+
+    /// Close an item; returns whether it was open
+    pub fn close(&mut self, index: usize) -> bool {
+        if index >= self.items.len() {
+            return false;
+        }
+
+        self.items.remove(index);
+        if index < self.active_item
+            || (self.active_item == self.items.len() && self.active_item > 0)
+        {
+            self.active_item -= 1;
+        }
+        true
+    }
+
+    pub fn active(&self) -> Option<&SessionItem> {
+        self.items.get(self.active_item)
+    }
+
+    fn finish_load(&mut self) -> bool {
+        self.pending_loads -= 1;
+        if self.is_loading() {
+            return false;
+        }
+
+        if !self.loaded_runs.is_empty() {
+            // Archive names usually carry the run date, so they sort chronologically
+            self.loaded_runs.sort_by(|a, b| a.0.cmp(&b.0));
+            let (names, runs): (Vec<String>, Vec<TestCaseCollection>) =
+                self.loaded_runs.drain(..).unzip();
+            let test_cases = runs.last().cloned().unwrap_or_default();
+
+            self.open(
+                names.join(", "),
+                SessionContent::TestCases { test_cases, runs },
+            );
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(session: &Session) -> Vec<&str> {
+        session
+            .items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect()
+    }
+
+    /// Test results told apart by their only warning
+    fn test_cases(run: &str) -> TestCaseCollection {
+        TestCaseCollection {
+            warnings: vec![run.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_add_traces_to_session() {
+        let mut session = Session::new();
+        session.begin_batch(1, false);
+        assert!(session.trace_loaded("first.zip".to_string(), TraceModel::new()));
+
+        session.begin_batch(2, true);
+        assert!(!session.trace_loaded("second.zip".to_string(), TraceModel::new()));
+        assert!(session.trace_loaded("third.zip".to_string(), TraceModel::new()));
+
+        assert_eq!(names(&session), ["first.zip", "second.zip", "third.zip"]);
+        assert_eq!(session.active_item, 2);
+    }
+
+    #[test]
+    fn test_replace_session() {
+        let mut session = Session::new();
+        session.begin_batch(1, false);
+        session.trace_loaded("first.zip".to_string(), TraceModel::new());
+
+        session.begin_batch(2, false);
+        session.trace_loaded("second.zip".to_string(), TraceModel::new());
+        session.trace_loaded("third.zip".to_string(), TraceModel::new());
+
+        assert_eq!(names(&session), ["second.zip", "third.zip"]);
+    }
+
+    #[test]
+    fn test_mixed_batch_keeps_test_results() {
+        let mut session = Session::new();
+        session.begin_batch(3, false);
+        assert!(!session.test_cases_loaded("run-2.zip".to_string(), test_cases("b")));
+        assert!(!session.trace_loaded("trace.zip".to_string(), TraceModel::new()));
+        assert!(session.test_cases_loaded("run-1.zip".to_string(), test_cases("a")));
+
+        assert_eq!(names(&session), ["trace.zip", "run-1.zip, run-2.zip"]);
+        let Some(SessionContent::TestCases { test_cases, runs }) =
+            session.active().map(|item| &item.content)
+        else {
+            panic!("test results are not active");
+        };
+        assert_eq!(runs.len(), 2);
+        assert_eq!(test_cases.warnings, ["b"]);
+    }
+
+    #[test]
+    fn test_loads_after_cancel_are_ignored() {
+        let mut session = Session::new();
+        session.begin_batch(2, false);
+        session.cancel_batch();
+
+        assert!(!session.trace_loaded("late.zip".to_string(), TraceModel::new()));
+        assert!(session.items.is_empty());
+    }
+
+    #[test]
+    fn test_close() {
+        let mut session = Session::new();
+        session.begin_batch(3, false);
+        for name in ["a", "b", "c"] {
+            session.trace_loaded(name.to_string(), TraceModel::new());
+        }
+
+        assert!(session.close(2));
+        assert_eq!(session.active_item, 1);
+        assert!(session.close(0));
+        assert_eq!(session.active_item, 0);
+        assert_eq!(names(&session), ["b"]);
+        assert!(!session.close(5));
+    }
+}
@@ -0,0 +1,162 @@
+//! Slack mrkdwn serialization of reports
+//! Slack has no headings, tables or code languages, and long messages fold
+//! away, so headings become bold lines, tables bulleted rows and code blocks
+//! are cut after a few lines. `&`, `<` and `>` are escaped everywhere since
+//! Slack reads them as markup even inside code
+
+use crate::models::TraceModel;
+use crate::report::{build_trace_report, Block, ExportOptions, Report};
+
+/// Lines of a code block kept in Slack messages
+const MAX_CODE_LINES: usize = 12;
+
+/// Export a trace model as a Slack message; stacks are cut to their first
+/// app frame
+pub fn export_to_slack(model: &TraceModel, options: &ExportOptions) -> String {
+    let options = ExportOptions {
+        short_stacks: true,
+        ..options.clone()
+    };
+    render_slack(&build_trace_report(model, &options))
+}
+
+/// Serialize a report as Slack mrkdwn
+pub fn render_slack(report: &Report) -> String {
+    let mut output = String::new();
+
+    for block in &report.blocks {
+        render_block(&mut output, block);
+    }
+
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+    output
+}
+
+fn render_block(output: &mut String, block: &Block) {
+    match block {
+        Block::Heading { text, .. } => {
+            end_paragraph(output);
+            output.push_str(&format!("*{}*\n", escape(text)));
+        }
+        Block::Fields(fields) => {
+            for (name, value) in fields {
+                output.push_str(&format!("• *{}*: {}\n", escape(name), escape(value)));
+            }
+            output.push('\n');
+        }
+        Block::Properties(properties) => {
+            let properties: Vec<String> = properties
+                .iter()
+                .map(|(name, value)| format!("*{}*: {}", escape(name), escape(value)))
+                .collect();
+            output.push_str(&properties.join(" · "));
+            output.push('\n');
+        }
+        Block::Label(text) | Block::Collapsible(text) => {
+            output.push_str(&format!("*{}*:\n", escape(text)));
+        }
+        Block::Paragraph(text) => {
+            output.push_str(&escape(text));
+            output.push_str("\n\n");
+        }
+        Block::Note(text) => {
+            output.push_str(&format!("_{}_\n\n", escape(text)));
+        }
+        Block::List(items) => {
+            for item in items {
+                output.push_str(&format!("• {}\n", escape(item)));
+            }
+            output.push('\n');
+        }
+        Block::Table { rows, .. } => {
+            for row in rows {
+                output.push_str(&format!("• {}\n", escape(&row.join(" · "))));
+            }
+            output.push('\n');
+        }
+        Block::Code { text, .. } => {
+            let lines: Vec<&str> = text.trim_end().lines().collect();
+            output.push_str("```\n");
+            for line in lines.iter().take(MAX_CODE_LINES) {
+                output.push_str(&escape(line));
+                output.push('\n');
+            }
+            if lines.len() > MAX_CODE_LINES {
+                output.push_str(&format!("… {} more lines\n", lines.len() - MAX_CODE_LINES));
+            }
+            output.push_str("```\n\n");
+        }
+        // Slack only shows images it can fetch
+        Block::Image { alt, src } if src.starts_with("http") => {
+            output.push_str(&format!("<{}|{}>\n\n", src, escape(alt)));
+        }
+        // Slack messages have no anchors to link to
+        Block::Contents(entries) => {
+            for (_, title) in entries {
+                output.push_str(&format!("• {}\n", escape(title)));
+            }
+            output.push('\n');
+        }
+        Block::Image { .. } | Block::Metadata(_) | Block::Anchor { .. } => {}
+        Block::Rule => end_paragraph(output),
+    }
+}
+
+/// Blank line after the text so far, unless there already is one
+fn end_paragraph(output: &mut String) {
+    if !output.is_empty() && !output.ends_with("\n\n") {
+        output.push('\n');
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_slack() {
+        let report = Report {
+            blocks: vec![
+                Block::Heading {
+                    level: 2,
+                    text: "1. click ⚠️ FAILED".to_string(),
+                },
+                Block::Properties(vec![
+                    ("Duration".to_string(), "5ms".to_string()),
+                    ("Action".to_string(), "Click <button>".to_string()),
+                ]),
+                Block::Table {
+                    headers: vec!["Status".to_string(), "URL".to_string()],
+                    rows: vec![vec!["500".to_string(), "https://app.test/api".to_string()]],
+                },
+                Block::Code {
+                    language: Some("json".to_string()),
+                    text: (1..=20).map(|i| format!("line {}\n", i)).collect(),
+                },
+                Block::Image {
+                    alt: "Before".to_string(),
+                    src: "data:image/jpeg;base64,/9j/".to_string(),
+                },
+                Block::Rule,
+            ],
+        };
+
+        let slack = render_slack(&report);
+
+        assert!(slack.starts_with("*1. click ⚠️ FAILED*\n"));
+        assert!(slack.contains("*Duration*: 5ms · *Action*: Click &lt;button&gt;\n"));
+        assert!(slack.contains("• 500 · https://app.test/api\n"));
+        assert!(slack.contains("```\nline 1\n"));
+        assert!(slack.contains("line 12\n… 8 more lines\n```"));
+        assert!(!slack.contains("line 13"));
+        assert!(!slack.contains("data:image"));
+        assert!(!slack.contains('#'));
+    }
+}
@@ -0,0 +1,329 @@
+//! Counts and duration statistics of a context
+//! Computed once when the trace is loaded, so panels and exporters do not
+//! scan every action again; test steps are left out of durations since they
+//! span the actions they group and would count the same time twice
+
+use crate::models::{ActionEntry, ContextEntry};
+use crate::steps::is_step;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Slowest actions kept in the stats
+pub const SLOWEST_COUNT: usize = 10;
+
+/// Upper bounds of the histogram buckets in milliseconds; the last bucket is open
+const BUCKET_BOUNDS: [f64; 6] = [10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextStats {
+    /// Actions including steps
+    pub action_count: usize,
+    pub failed_count: usize,
+    pub unfinished_count: usize,
+    /// Time spent in finished actions other than steps
+    pub total_ms: f64,
+    /// Part of `total_ms` spent in actions that failed
+    pub failed_ms: f64,
+    /// Errors reported outside actions, e.g. uncaught page errors
+    pub error_count: usize,
+    pub network: NetworkTotals,
+    /// Time spent per API method, longest first
+    pub method_totals: Vec<MethodTotal>,
+    pub histogram: Vec<HistogramBucket>,
+    /// Slowest finished actions, slowest first
+    pub slowest: Vec<SlowAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkTotals {
+    pub requests: usize,
+    pub failed: usize,
+    /// Bytes received, from transfer sizes or else body sizes
+    pub transferred_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodTotal {
+    /// API method such as `Frame.click`
+    pub method: String,
+    pub count: usize,
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBucket {
+    /// Range such as `100–500ms`
+    pub label: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowAction {
+    pub call_id: String,
+    /// Short description such as `locator.click (#pay)`
+    pub label: String,
+    pub duration_ms: f64,
+}
+
+impl ContextStats {
+    pub fn is_empty(&self) -> bool {
+        self.method_totals.is_empty()
+    }
+}
+
+/// Stats of a context whose actions, errors and resources are loaded
+pub fn compute_stats(context: &ContextEntry) -> ContextStats {
+    let actions = &context.actions;
+    let timed = timed_actions(actions);
+
+    let mut totals: HashMap<String, MethodTotal> = HashMap::new();
+    for (action, duration) in &timed {
+        let method = api_method(action);
+        let total = totals.entry(method.clone()).or_insert(MethodTotal {
+            method,
+            count: 0,
+            total_ms: 0.0,
+        });
+        total.count += 1;
+        total.total_ms += duration;
+    }
+    let mut method_totals: Vec<MethodTotal> = totals.into_values().collect();
+    method_totals.sort_by(|a, b| {
+        b.total_ms
+            .total_cmp(&a.total_ms)
+            .then_with(|| a.method.cmp(&b.method))
+    });
+
+    let mut histogram: Vec<HistogramBucket> = bucket_labels()
+        .into_iter()
+        .map(|label| HistogramBucket { label, count: 0 })
+        .collect();
+    for (_, duration) in &timed {
+        let index = BUCKET_BOUNDS
+            .iter()
+            .position(|bound| duration < bound)
+            .unwrap_or(BUCKET_BOUNDS.len());
+        histogram[index].count += 1;
+    }
+
+    let slowest = slowest_actions(actions, SLOWEST_COUNT)
+        .into_iter()
+        .map(|(action, duration)| SlowAction {
+            call_id: action.call_id.clone(),
+            label: action.summary_label(),
+            duration_ms: duration,
+        })
+        .collect();
+
+    let network = NetworkTotals {
+        requests: context.resources.len(),
+        failed: context.resources.iter().filter(|r| r.is_failed()).count(),
+        transferred_bytes: context
+            .resources
+            .iter()
+            .filter_map(|resource| resource.response.transfer_size.or(resource.body_size()))
+            .filter(|size| *size > 0)
+            .map(|size| size as u64)
+            .sum(),
+    };
+
+    ContextStats {
+        action_count: actions.len(),
+        failed_count: actions.iter().filter(|a| a.error.is_some()).count(),
+        unfinished_count: actions.iter().filter(|a| a.did_not_finish()).count(),
+        total_ms: timed.iter().map(|(_, duration)| duration).sum(),
+        failed_ms: timed
+            .iter()
+            .filter(|(action, _)| action.error.is_some())
+            .map(|(_, duration)| duration)
+            .sum(),
+        error_count: context.errors.len(),
+        network,
+        method_totals,
+        histogram,
+        slowest,
+    }
+}
+
+/// The `count` slowest finished actions with their durations, slowest first
+pub fn slowest_actions(actions: &[ActionEntry], count: usize) -> Vec<(&ActionEntry, f64)> {
+    let mut timed = timed_actions(actions);
+    // Stable, so equally slow actions keep their trace order
+    timed.sort_by(|a, b| b.1.total_cmp(&a.1));
+    timed.truncate(count);
+    timed
+}
+
+/// Finished actions other than steps, with their durations
+fn timed_actions(actions: &[ActionEntry]) -> Vec<(&ActionEntry, f64)> {
+    actions
+        .iter()
+        .filter(|action| !is_step(action))
+        .filter_map(|action| Some((action, action.duration_ms()?)))
+        .collect()
+}
+
+/// `Class.method` of an action, falling back to its type
+fn api_method(action: &ActionEntry) -> String {
+    match (&action.class, &action.method) {
+        (Some(class), Some(method)) => format!("{}.{}", class, method),
+        (None, Some(method)) => method.clone(),
+        _ => action.action_type.clone(),
+    }
+}
+
+fn bucket_labels() -> Vec<String> {
+    let format = |ms: f64| {
+        if ms >= 1000.0 {
+            format!("{}s", ms / 1000.0)
+        } else {
+            format!("{}ms", ms)
+        }
+    };
+
+    let mut labels = vec![format!("<{}", format(BUCKET_BOUNDS[0]))];
+    labels.extend(
+        BUCKET_BOUNDS
+            .windows(2)
+            .map(|pair| format!("{}–{}", format(pair[0]), format(pair[1]))),
+    );
+    labels.push(format!(
+        "≥{}",
+        format(BUCKET_BOUNDS[BUCKET_BOUNDS.len() - 1])
+    ));
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NetworkContent, NetworkEntry, NetworkResponse, SerializedError};
+    use std::collections::HashMap;
+
+    fn action(call_id: &str, class: &str, method: &str, start: f64, end: f64) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: call_id.to_string(),
+            start_time: start,
+            end_time: end,
+            title: None,
+            class: Some(class.to_string()),
+            method: Some(method.to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: None,
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        }
+    }
+
+    fn resource(status: i32, transfer_size: Option<i64>, body_size: i64) -> NetworkEntry {
+        NetworkEntry {
+            response: NetworkResponse {
+                status,
+                transfer_size,
+                content: NetworkContent {
+                    size: body_size,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn context_with(actions: Vec<ActionEntry>, resources: Vec<NetworkEntry>) -> ContextEntry {
+        ContextEntry {
+            id: String::new(),
+            start_time: 0.0,
+            end_time: 10000.0,
+            browser_name: "chromium".to_string(),
+            platform: None,
+            playwright_version: None,
+            wall_time: 0.0,
+            monotonic_time: 0.0,
+            title: None,
+            pages: vec![],
+            actions,
+            resources,
+            events: vec![],
+            errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let mut failed = action("call@3", "Frame", "click", 1300.0, 1370.0);
+        failed.error = Some(SerializedError {
+            message: Some("Timeout".to_string()),
+            stack: None,
+        });
+        let context = context_with(
+            vec![
+                action("step@1", "Test", "test.step", 0.0, 10000.0),
+                action("call@1", "Frame", "goto", 0.0, 1200.0),
+                action("call@2", "Frame", "click", 1200.0, 1230.0),
+                failed,
+                action("call@4", "Frame", "fill", 1400.0, 0.0),
+            ],
+            vec![
+                resource(200, Some(1500), 1500),
+                resource(404, None, 300),
+                resource(-1, None, 0),
+            ],
+        );
+
+        let stats = compute_stats(&context);
+
+        assert_eq!(
+            (
+                stats.action_count,
+                stats.failed_count,
+                stats.unfinished_count
+            ),
+            (5, 1, 1)
+        );
+        assert_eq!((stats.total_ms, stats.failed_ms), (1300.0, 70.0));
+        assert_eq!(
+            stats.network,
+            NetworkTotals {
+                requests: 3,
+                failed: 2,
+                transferred_bytes: 1800,
+            }
+        );
+
+        let totals: Vec<(&str, usize, f64)> = stats
+            .method_totals
+            .iter()
+            .map(|total| (total.method.as_str(), total.count, total.total_ms))
+            .collect();
+        assert_eq!(
+            totals,
+            [("Frame.goto", 1, 1200.0), ("Frame.click", 2, 100.0)]
+        );
+
+        let counts: Vec<usize> = stats.histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [0, 1, 1, 0, 0, 1, 0]);
+        assert_eq!(stats.histogram[0].label, "<10ms");
+        assert_eq!(stats.histogram[5].label, "1s–5s");
+        assert_eq!(stats.histogram[6].label, "≥5s");
+
+        let slowest: Vec<&str> = stats.slowest.iter().map(|a| a.call_id.as_str()).collect();
+        assert_eq!(slowest, ["call@1", "call@3", "call@2"]);
+    }
+}
@@ -0,0 +1,407 @@
+//! Grouping of actions under the test steps that issued them
+//! Mirrors the structure of the test source: `test.step` blocks and hooks
+//! become groups containing the actions executed inside them
+
+use crate::models::ActionEntry;
+use std::collections::HashMap;
+
+/// Methods recorded by the test runner for blocks that group other actions
+const STEP_METHODS: &[&str] = &["test.step", "hook"];
+
+/// Upper bound on ancestor lookups, guarding against cyclic parent ids
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepNode<'a> {
+    Action(&'a ActionEntry),
+    Step {
+        step: &'a ActionEntry,
+        children: Vec<StepNode<'a>>,
+    },
+}
+
+impl<'a> StepNode<'a> {
+    /// The action backing this node
+    pub fn action(&self) -> &'a ActionEntry {
+        match self {
+            StepNode::Action(action) => action,
+            StepNode::Step { step, .. } => step,
+        }
+    }
+
+    /// Whether this node or anything nested under it failed
+    pub fn has_error(&self) -> bool {
+        match self {
+            StepNode::Action(action) => action.error.is_some(),
+            StepNode::Step { step, children } => {
+                step.error.is_some() || children.iter().any(|c| c.has_error())
+            }
+        }
+    }
+
+    /// Page of the node, or of the first action nested under it that has one
+    pub fn page_id(&self) -> Option<&'a str> {
+        match self {
+            StepNode::Action(action) => action.page_id.as_deref(),
+            StepNode::Step { step, children } => step
+                .page_id
+                .as_deref()
+                .or_else(|| children.iter().find_map(StepNode::page_id)),
+        }
+    }
+}
+
+/// Nodes whose action matches, with their steps
+/// A step is kept when anything under it matches, showing only the matches;
+/// a matching step with no matching children keeps all of them
+pub fn filter_nodes<'a>(
+    nodes: &[StepNode<'a>],
+    matches: &impl Fn(&ActionEntry) -> bool,
+) -> Vec<StepNode<'a>> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            StepNode::Action(action) => matches(action).then_some(node.clone()),
+            StepNode::Step { step, children } => {
+                let kept = filter_nodes(children, matches);
+                if !kept.is_empty() {
+                    Some(StepNode::Step {
+                        step,
+                        children: kept,
+                    })
+                } else {
+                    matches(step).then_some(node.clone())
+                }
+            }
+        })
+        .collect()
+}
+
+/// Top-level nodes that ran on one page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageGroup<'a> {
+    /// `None` for nodes that never touched a page, such as runner steps
+    pub page_id: Option<&'a str>,
+    pub nodes: Vec<StepNode<'a>>,
+}
+
+/// Group top-level nodes by page, pages in order of first appearance
+/// A step spanning several pages is kept whole under the first of them
+pub fn group_nodes_by_page(nodes: Vec<StepNode<'_>>) -> Vec<PageGroup<'_>> {
+    let mut groups: Vec<PageGroup> = Vec::new();
+    for node in nodes {
+        let page_id = node.page_id();
+        match groups.iter_mut().find(|group| group.page_id == page_id) {
+            Some(group) => group.nodes.push(node),
+            None => groups.push(PageGroup {
+                page_id,
+                nodes: vec![node],
+            }),
+        }
+    }
+    groups
+}
+
+/// Whether the action is a step that other actions are grouped under
+pub fn is_step(action: &ActionEntry) -> bool {
+    action.class.as_deref() == Some("Test")
+        && action
+            .method
+            .as_deref()
+            .is_some_and(|method| STEP_METHODS.contains(&method))
+}
+
+/// Whether any of the actions is a step
+pub fn has_steps(actions: &[ActionEntry]) -> bool {
+    actions.iter().any(is_step)
+}
+
+/// Build the step tree for a list of actions
+/// Each action is placed under its nearest step ancestor, found through
+/// `parent_id` or, for library calls, the `step_id` of the runner step.
+/// Input order is preserved among siblings.
+pub fn group_actions_by_step(actions: &[ActionEntry]) -> Vec<StepNode<'_>> {
+    let by_id: HashMap<&str, &ActionEntry> =
+        actions.iter().map(|a| (a.call_id.as_str(), a)).collect();
+
+    let mut children_of: HashMap<Option<&str>, Vec<&ActionEntry>> = HashMap::new();
+    for action in actions {
+        let owner = nearest_step(action, &by_id).map(|step| step.call_id.as_str());
+        children_of.entry(owner).or_default().push(action);
+    }
+
+    let mut nodes = build_nodes(None, &mut children_of);
+
+    // Steps whose parents form a cycle are never reached from the root
+    let mut orphans: Vec<&ActionEntry> = children_of.into_values().flatten().collect();
+    orphans.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    nodes.extend(orphans.into_iter().map(StepNode::Action));
+
+    nodes
+}
+
+fn build_nodes<'a>(
+    owner: Option<&'a str>,
+    children_of: &mut HashMap<Option<&'a str>, Vec<&'a ActionEntry>>,
+) -> Vec<StepNode<'a>> {
+    let Some(actions) = children_of.remove(&owner) else {
+        return Vec::new();
+    };
+
+    actions
+        .into_iter()
+        .map(|action| {
+            if is_step(action) {
+                StepNode::Step {
+                    step: action,
+                    children: build_nodes(Some(action.call_id.as_str()), children_of),
+                }
+            } else {
+                StepNode::Action(action)
+            }
+        })
+        .collect()
+}
+
+fn nearest_step<'a>(
+    action: &ActionEntry,
+    by_id: &HashMap<&str, &'a ActionEntry>,
+) -> Option<&'a ActionEntry> {
+    let mut current = parent_of(action, by_id);
+
+    for _ in 0..MAX_DEPTH {
+        let candidate = current?;
+        if is_step(candidate) {
+            return Some(candidate);
+        }
+        current = parent_of(candidate, by_id);
+    }
+
+    None
+}
+
+fn parent_of<'a>(
+    action: &ActionEntry,
+    by_id: &HashMap<&str, &'a ActionEntry>,
+) -> Option<&'a ActionEntry> {
+    let parent = action.parent_id.as_deref().and_then(|id| by_id.get(id));
+
+    // Library calls point at the runner step through their step id
+    let step = action
+        .step_id
+        .as_deref()
+        .filter(|id| *id != action.call_id)
+        .and_then(|id| by_id.get(id));
+
+    parent.or(step).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SerializedError;
+    use std::collections::HashMap;
+
+    fn action(call_id: &str, method: &str, parent_id: Option<&str>) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: call_id.to_string(),
+            start_time: 0.0,
+            end_time: 0.0,
+            title: Some(call_id.to_string()),
+            class: Some("Test".to_string()),
+            method: Some(method.to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: parent_id.map(str::to_string),
+            step_id: None,
+            error: None,
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: None,
+            stack: vec![],
+        }
+    }
+
+    #[test]
+    fn test_flat_without_steps() {
+        let actions = vec![
+            action("pw:api@1", "pw:api", None),
+            action("expect@2", "expect", None),
+        ];
+
+        let nodes = group_actions_by_step(&actions);
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().all(|n| matches!(n, StepNode::Action(_))));
+        assert!(!has_steps(&actions));
+    }
+
+    #[test]
+    fn test_actions_nested_under_step() {
+        let actions = vec![
+            action("test.step@1", "test.step", None),
+            action("pw:api@2", "pw:api", Some("test.step@1")),
+            action("expect@3", "expect", Some("test.step@1")),
+            action("pw:api@4", "pw:api", None),
+        ];
+
+        let nodes = group_actions_by_step(&actions);
+        assert_eq!(nodes.len(), 2);
+
+        match &nodes[0] {
+            StepNode::Step { step, children } => {
+                assert_eq!(step.call_id, "test.step@1");
+                assert_eq!(children.len(), 2);
+                assert_eq!(children[0].action().call_id, "pw:api@2");
+            }
+            _ => panic!("Expected step node"),
+        }
+
+        assert_eq!(nodes[1].action().call_id, "pw:api@4");
+    }
+
+    #[test]
+    fn test_non_step_parents_are_skipped() {
+        // A fixture is not a step, so its children belong to the enclosing hook
+        let actions = vec![
+            action("hook@1", "hook", None),
+            action("fixture@2", "fixture", Some("hook@1")),
+            action("pw:api@3", "pw:api", Some("fixture@2")),
+        ];
+
+        let nodes = group_actions_by_step(&actions);
+        assert_eq!(nodes.len(), 1);
+
+        match &nodes[0] {
+            StepNode::Step { children, .. } => assert_eq!(children.len(), 2),
+            _ => panic!("Expected step node"),
+        }
+    }
+
+    #[test]
+    fn test_library_call_grouped_by_step_id() {
+        let mut call = action("call@5", "click", None);
+        call.class = Some("Frame".to_string());
+        call.step_id = Some("test.step@1".to_string());
+
+        let actions = vec![action("test.step@1", "test.step", None), call];
+
+        let nodes = group_actions_by_step(&actions);
+        assert_eq!(nodes.len(), 1);
+
+        match &nodes[0] {
+            StepNode::Step { children, .. } => {
+                assert_eq!(children[0].action().call_id, "call@5")
+            }
+            _ => panic!("Expected step node"),
+        }
+    }
+
+    #[test]
+    fn test_step_error_propagates() {
+        let mut failing = action("expect@2", "expect", Some("test.step@1"));
+        failing.error = Some(SerializedError {
+            message: Some("failed".to_string()),
+            stack: None,
+        });
+
+        let actions = vec![action("test.step@1", "test.step", None), failing];
+        let nodes = group_actions_by_step(&actions);
+
+        assert!(nodes[0].has_error());
+    }
+
+    #[test]
+    fn test_filter_nodes_keeps_matching_steps() {
+        let mut failing = action("expect@3", "expect", Some("test.step@1"));
+        failing.error = Some(SerializedError {
+            message: Some("failed".to_string()),
+            stack: None,
+        });
+        let actions = vec![
+            action("test.step@1", "test.step", None),
+            action("pw:api@2", "pw:api", Some("test.step@1")),
+            failing,
+            action("test.step@4", "test.step", None),
+            action("pw:api@5", "pw:api", Some("test.step@4")),
+            action("pw:api@6", "pw:api", None),
+        ];
+        let nodes = group_actions_by_step(&actions);
+
+        let failed = filter_nodes(&nodes, &|action| action.error.is_some());
+        assert_eq!(failed.len(), 1);
+        match &failed[0] {
+            StepNode::Step { step, children } => {
+                assert_eq!(step.call_id, "test.step@1");
+                assert_eq!(children.len(), 1);
+                assert_eq!(children[0].action().call_id, "expect@3");
+            }
+            _ => panic!("Expected step node"),
+        }
+
+        let step = filter_nodes(&nodes, &|action| action.call_id == "test.step@4");
+        match &step[..] {
+            [StepNode::Step { children, .. }] => assert_eq!(children.len(), 1),
+            _ => panic!("Expected the step with all its children"),
+        }
+    }
+
+    #[test]
+    fn test_group_nodes_by_page() {
+        let on_page = |call_id: &str, parent_id: Option<&str>, page_id: &str| {
+            let mut call = action(call_id, "click", parent_id);
+            call.class = Some("Frame".to_string());
+            call.page_id = Some(page_id.to_string());
+            call
+        };
+        let actions = vec![
+            on_page("call@1", None, "page@1"),
+            action("test.step@2", "test.step", None),
+            on_page("call@3", Some("test.step@2"), "page@2"),
+            on_page("call@4", Some("test.step@2"), "page@1"),
+            on_page("call@5", None, "page@1"),
+            action("expect@6", "expect", None),
+        ];
+
+        let groups = group_nodes_by_page(group_actions_by_step(&actions));
+        let summary: Vec<(Option<&str>, Vec<&str>)> = groups
+            .iter()
+            .map(|group| {
+                let ids = group.nodes.iter().map(|n| n.action().call_id.as_str());
+                (group.page_id, ids.collect())
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                (Some("page@1"), vec!["call@1", "call@5"]),
+                (Some("page@2"), vec!["test.step@2"]),
+                (None, vec!["expect@6"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cyclic_parents_terminate() {
+        let actions = vec![
+            action("a", "pw:api", Some("b")),
+            action("b", "pw:api", Some("a")),
+        ];
+
+        let nodes = group_actions_by_step(&actions);
+        assert_eq!(nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_cyclic_steps_are_kept() {
+        let actions = vec![
+            action("hook@1", "hook", Some("hook@2")),
+            action("hook@2", "hook", Some("hook@1")),
+        ];
+
+        let nodes = group_actions_by_step(&actions);
+        assert_eq!(nodes.len(), 2);
+    }
+}
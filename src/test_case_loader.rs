@@ -1,8 +1,22 @@
+use crate::analysis::base_test_id;
+use crate::error_context::parse_error_context;
+use crate::json_report_loader::{outcomes_by_output_folder, ReportOutcome};
 use crate::models::*;
+use crate::resource_store::ResourceStore;
+use crate::trace_links::context_links;
+use crate::trace_loader::load_trace_from_zip;
 use base64::{engine::general_purpose, Engine as _};
+use std::collections::{HashMap, HashSet};
 use std::io::{Cursor, Read};
+use std::rc::Rc;
 use zip::ZipArchive;
 
+/// Videos above this size are decoded only when the user asks for them
+pub const MAX_INLINE_VIDEO_BYTES: usize = 32 * 1024 * 1024;
+
+/// Bytes read at load time to recognize failed captures; see `is_capture_failed`
+const CAPTURE_HEADER_BYTES: u64 = 8;
+
 #[derive(Debug)]
 pub enum TestCaseLoadError {
     ZipError(String),
@@ -29,6 +43,7 @@ impl std::error::Error for TestCaseLoadError {}
 ///   - test-failed-1.png
 ///   - trace.zip
 ///   - video.webm
+/// - results.json (optional JSON reporter output with the real outcomes)
 pub fn load_test_cases_from_zip(bytes: &[u8]) -> Result<TestCaseCollection, TestCaseLoadError> {
     log::info!("Parsing test cases ZIP archive...");
 
@@ -39,8 +54,8 @@ pub fn load_test_cases_from_zip(bytes: &[u8]) -> Result<TestCaseCollection, Test
     log::info!("ZIP archive opened, {} entries found", archive.len());
 
     // Group files by test case folder
-    let mut test_case_folders: std::collections::HashMap<String, Vec<String>> =
-        std::collections::HashMap::new();
+    let mut test_case_folders: HashMap<String, Vec<String>> = HashMap::new();
+    let mut report_files = Vec::new();
 
     for i in 0..archive.len() {
         let file = archive
@@ -53,6 +68,12 @@ pub fn load_test_cases_from_zip(bytes: &[u8]) -> Result<TestCaseCollection, Test
             continue;
         }
 
+        // A JSON report next to the test folders knows the real outcomes
+        if !name.contains('/') && name.ends_with(".json") {
+            report_files.push(name);
+            continue;
+        }
+
         // Extract folder name
         if let Some(folder) = extract_folder_name(&name) {
             test_case_folders
@@ -65,6 +86,7 @@ pub fn load_test_cases_from_zip(bytes: &[u8]) -> Result<TestCaseCollection, Test
     log::info!("Found {} test case folders", test_case_folders.len());
 
     let mut test_cases = Vec::new();
+    let mut warnings = Vec::new();
 
     for (folder_name, files) in test_case_folders {
         log::info!("Processing test case folder: {}", folder_name);
@@ -73,14 +95,44 @@ pub fn load_test_cases_from_zip(bytes: &[u8]) -> Result<TestCaseCollection, Test
             Ok(test_case) => test_cases.push(test_case),
             Err(e) => {
                 log::warn!("Failed to load test case {}: {}", folder_name, e);
+                warnings.push(format!("Failed to load test case {}: {}", folder_name, e));
                 // Continue processing other test cases
             }
         }
     }
 
+    // Folders come out of a hash map; keep the list stable between loads
+    test_cases.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut report_outcomes = HashMap::new();
+    for name in &report_files {
+        let bytes = read_binary_file_from_archive(&mut archive, name)?;
+        report_outcomes.extend(outcomes_by_output_folder(&bytes));
+    }
+    apply_known_outcomes(&mut test_cases, &report_outcomes);
+
     log::info!("Loaded {} test cases", test_cases.len());
 
-    Ok(TestCaseCollection { test_cases })
+    warnings.extend(capture_failure_warnings(&test_cases));
+
+    // Only entry locations are kept; see `load_attachments`
+    let deferred: HashSet<String> = test_cases
+        .iter()
+        .flat_map(binary_attachments)
+        .filter_map(|attachment| attachment.archive_entry.clone())
+        .collect();
+    let attachment_store = if deferred.is_empty() {
+        ResourceStore::new()
+    } else {
+        ResourceStore::from_entries(Rc::from(bytes), &mut archive, &deferred)
+            .map_err(TestCaseLoadError::ZipError)?
+    };
+
+    Ok(TestCaseCollection {
+        test_cases,
+        warnings,
+        attachment_store,
+    })
 }
 
 fn extract_folder_name(path: &str) -> Option<&str> {
@@ -100,6 +152,8 @@ fn load_test_case_from_folder(
     let mut screenshots = Vec::new();
     let mut video = None;
     let mut trace_file = None;
+    let mut embedded_trace = None;
+    let mut text_attachments = Vec::new();
 
     for file_path in files {
         let file_name = file_path
@@ -115,36 +169,63 @@ fn load_test_case_from_folder(
             || file_name.ends_with(".jpg")
             || file_name.ends_with(".jpeg")
         {
-            // Load screenshot
-            let attachment = load_binary_file_as_attachment(archive, file_path)?;
-            screenshots.push(attachment);
+            // Index screenshot; it is encoded when its card is expanded
+            screenshots.push(index_binary_file(archive, file_path)?);
         } else if file_name.ends_with(".webm") || file_name.ends_with(".mp4") {
-            // Load video
-            video = Some(load_binary_file_as_attachment(archive, file_path)?);
+            // Index video
+            video = Some(index_binary_file(archive, file_path)?);
         } else if file_name.ends_with(".zip") && file_name.contains("trace") {
-            // Load trace file
-            trace_file = Some(load_binary_file_as_attachment(archive, file_path)?);
+            // Read trace file; its timeline gives the duration and its errors the outcome
+            let bytes = read_binary_file_from_archive(archive, file_path)?;
+            embedded_trace = load_trace_from_zip(&bytes)
+                .ok()
+                .filter(|model| !model.contexts.is_empty());
+            trace_file = Some(indexed_attachment(file_path, bytes.len(), &bytes));
+        } else if file_name.ends_with(".txt")
+            || file_name.ends_with(".log")
+            || file_name.ends_with(".json")
+        {
+            // Load text attachment, e.g. captured stdout or attached JSON data
+            text_attachments.push(load_text_attachment(archive, file_path)?);
         }
     }
 
-    // Determine test status based on folder name and presence of error-context.md
-    let status = if folder_name.to_lowercase().contains("fail")
-        || folder_name.to_lowercase().contains("error")
-        || markdown_content.is_some()
-    {
-        TestStatus::Failed
-    } else {
-        TestStatus::Passed
+    text_attachments.sort_by(|a, b| a.name.cmp(&b.name));
+    let image_diffs = extract_image_diffs(&mut screenshots);
+
+    let status = match &embedded_trace {
+        // error-context.md is only written for failures
+        Some(model) if trace_has_errors(model) || markdown_content.is_some() => TestStatus::Failed,
+        Some(_) => TestStatus::Passed,
+        // Without a trace, guess from the folder name
+        None if folder_name.to_lowercase().contains("fail")
+            || folder_name.to_lowercase().contains("error")
+            || markdown_content.is_some() =>
+        {
+            TestStatus::Failed
+        }
+        None => TestStatus::Passed,
     };
+    // Contexts without actions have no meaningful timeline
+    let duration_ms = embedded_trace
+        .as_ref()
+        .map(TraceModel::summary)
+        .filter(|summary| summary.action_count > 0)
+        .map(|summary| summary.duration_ms);
 
     // Extract error message from markdown if available
     let error_message = if status == TestStatus::Failed {
-        markdown_content
-            .as_ref()
-            .and_then(|md| extract_first_line(md))
+        markdown_content.as_ref().and_then(|md| {
+            // error-context.md starts with headings; the error has its own section
+            parse_error_context(md)
+                .error_line()
+                .map(str::to_string)
+                .or_else(|| extract_first_line(md))
+        })
     } else {
         None
     };
+    let has_video = video.is_some();
 
     Ok(TestCase {
         id: folder_name.to_string(),
@@ -154,8 +235,30 @@ fn load_test_case_from_folder(
         screenshots,
         video,
         trace_file,
-        duration_ms: None,
+        text_attachments,
+        duration_ms,
         error_message,
+        annotations: Vec::new(),
+        tags: Vec::new(),
+        project: project_from_folder_name(folder_name),
+        suite_path: spec_from_folder_name(folder_name).into_iter().collect(),
+        image_diffs,
+        trace_contexts: embedded_trace
+            .as_ref()
+            .map(context_links)
+            .unwrap_or_default(),
+        trace_overview: embedded_trace.as_ref().map(|model| TraceOverview {
+            summary: model.summary(),
+            video_chapters: if has_video {
+                model
+                    .contexts
+                    .iter()
+                    .flat_map(|context| context.video_chapters())
+                    .collect()
+            } else {
+                Vec::new()
+            },
+        }),
     })
 }
 
@@ -174,10 +277,10 @@ fn read_text_file_from_archive(
     Ok(content)
 }
 
-fn load_binary_file_as_attachment(
+fn read_binary_file_from_archive(
     archive: &mut ZipArchive<Cursor<&[u8]>>,
     name: &str,
-) -> Result<TestAttachment, TestCaseLoadError> {
+) -> Result<Vec<u8>, TestCaseLoadError> {
     let mut file = archive
         .by_name(name)
         .map_err(|e| TestCaseLoadError::ZipError(format!("Failed to read {}: {}", name, e)))?;
@@ -186,25 +289,272 @@ fn load_binary_file_as_attachment(
     file.read_to_end(&mut bytes)
         .map_err(|e| TestCaseLoadError::IoError(e.to_string()))?;
 
-    let size_bytes = bytes.len();
+    Ok(bytes)
+}
+
+/// Attachment for an archive entry without reading all of it
+/// Only the first bytes are inflated, to tell whether the capture failed
+fn index_binary_file(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<TestAttachment, TestCaseLoadError> {
+    let file = archive
+        .by_name(name)
+        .map_err(|e| TestCaseLoadError::ZipError(format!("Failed to read {}: {}", name, e)))?;
+    let size = file.size() as usize;
+
+    let mut header = Vec::new();
+    file.take(CAPTURE_HEADER_BYTES)
+        .read_to_end(&mut header)
+        .map_err(|e| TestCaseLoadError::IoError(e.to_string()))?;
+
+    Ok(indexed_attachment(name, size, &header))
+}
 
-    // Determine MIME type from extension
+fn indexed_attachment(name: &str, size: usize, header: &[u8]) -> TestAttachment {
     let mime_type = determine_mime_type(name);
 
-    // Encode as base64 data URL
-    let base64_data = general_purpose::STANDARD.encode(&bytes);
-    let data_url = format!("data:{};base64,{}", mime_type, base64_data);
+    TestAttachment {
+        name: name.split('/').next_back().unwrap_or(name).to_string(),
+        mime_type: mime_type.to_string(),
+        data_url: String::new(),
+        size_bytes: Some(size),
+        capture_failed: is_capture_failed(mime_type, header),
+        archive_entry: Some(name.to_string()),
+    }
+}
+
+/// Encode the indexed attachments of a test from the archive
+/// Attachments above `max_inline_bytes` stay in the archive; the card decodes
+/// those on request
+pub fn load_attachments(test_case: &TestCase, store: &ResourceStore) -> TestCase {
+    let mut test_case = test_case.clone();
+
+    let diffs = test_case
+        .image_diffs
+        .iter_mut()
+        .flat_map(|diff| [&mut diff.expected, &mut diff.actual, &mut diff.diff])
+        .flatten();
+    let attachments = test_case
+        .screenshots
+        .iter_mut()
+        .chain(diffs)
+        .chain(&mut test_case.video)
+        .chain(&mut test_case.trace_file);
+
+    for attachment in attachments {
+        let Some(entry) = attachment.archive_entry.as_deref() else {
+            continue;
+        };
+        if !attachment.data_url.is_empty()
+            || max_inline_bytes(&attachment.mime_type)
+                .is_some_and(|limit| attachment.size_bytes.unwrap_or(0) > limit)
+        {
+            continue;
+        }
+
+        match store.get(entry) {
+            Some(bytes) => attachment.data_url = data_url(&attachment.mime_type, &bytes),
+            None => log::warn!("Attachment {} is not in the archive", entry),
+        }
+    }
+
+    test_case
+}
+
+fn binary_attachments(test_case: &TestCase) -> impl Iterator<Item = &TestAttachment> {
+    let diffs = test_case
+        .image_diffs
+        .iter()
+        .flat_map(|diff| [&diff.expected, &diff.actual, &diff.diff])
+        .flatten();
+
+    test_case
+        .screenshots
+        .iter()
+        .chain(diffs)
+        .chain(&test_case.video)
+        .chain(&test_case.trace_file)
+}
+
+/// Largest attachment of the given type that is inlined as a data URL
+/// Screenshots are always inlined since galleries and diffs show them directly
+pub fn max_inline_bytes(mime_type: &str) -> Option<usize> {
+    mime_type
+        .starts_with("video/")
+        .then_some(MAX_INLINE_VIDEO_BYTES)
+}
+
+fn data_url(mime_type: &str, bytes: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        mime_type,
+        general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// Move screenshot comparison images out of `screenshots` into diffs
+/// A lone `-actual.png`, e.g. for a missing baseline, stays a screenshot
+pub fn extract_image_diffs(screenshots: &mut Vec<TestAttachment>) -> Vec<ImageDiff> {
+    let mut diffs: Vec<ImageDiff> = Vec::new();
+
+    for screenshot in screenshots.iter() {
+        let Some((name, _)) = split_diff_suffix(&screenshot.name) else {
+            continue;
+        };
+        if !diffs.iter().any(|diff| diff.name == name) {
+            diffs.push(ImageDiff {
+                name: name.to_string(),
+                expected: None,
+                actual: None,
+                diff: None,
+            });
+        }
+    }
+
+    for diff in &mut diffs {
+        let images: Vec<&TestAttachment> = screenshots
+            .iter()
+            .filter(|screenshot| {
+                split_diff_suffix(&screenshot.name).is_some_and(|(name, _)| name == diff.name)
+            })
+            .collect();
+        if images.len() < 2 {
+            continue;
+        }
+
+        for image in images {
+            match split_diff_suffix(&image.name) {
+                Some((_, "expected")) => diff.expected = Some(image.clone()),
+                Some((_, "actual")) => diff.actual = Some(image.clone()),
+                _ => diff.diff = Some(image.clone()),
+            }
+        }
+    }
+
+    diffs.retain(|diff| diff.expected.is_some() || diff.actual.is_some() || diff.diff.is_some());
+    screenshots.retain(|screenshot| {
+        split_diff_suffix(&screenshot.name)
+            .is_none_or(|(name, _)| !diffs.iter().any(|diff| diff.name == name))
+    });
+
+    diffs
+}
+
+/// Split `home-expected.png` into `("home", "expected")`
+fn split_diff_suffix(file_name: &str) -> Option<(&str, &str)> {
+    let stem = &file_name[..file_name.rfind('.')?];
+    ["expected", "actual", "diff"].iter().find_map(|kind| {
+        stem.strip_suffix(kind)?
+            .strip_suffix('-')
+            .map(|name| (name, *kind))
+    })
+}
+
+/// Failed actions or errors the test runner reported; uncaught page errors
+/// do not fail a test
+fn trace_has_errors(model: &TraceModel) -> bool {
+    model.contexts.iter().any(|context| {
+        context.errors.iter().any(|error| !error.page_error)
+            || context.actions.iter().any(|action| action.error.is_some())
+    })
+}
+
+/// Override guessed statuses with outcomes known from the whole archive
+/// Playwright only retries failed attempts, and a shipped `results.json`
+/// records every outcome and duration, keyed here by output folder
+fn apply_known_outcomes(
+    test_cases: &mut [TestCase],
+    report_outcomes: &HashMap<String, ReportOutcome>,
+) {
+    let ids: HashSet<String> = test_cases.iter().map(|tc| tc.id.clone()).collect();
+
+    for test_case in test_cases.iter_mut() {
+        let (base_id, retry) = base_test_id(&test_case.id);
+        if ids.contains(&format!("{}-retry{}", base_id, retry + 1)) {
+            test_case.status = TestStatus::Failed;
+        }
+
+        if let Some(outcome) = report_outcomes.get(&test_case.id) {
+            test_case.status = outcome.status.clone();
+            // The reporter times the whole test, hooks included
+            test_case.duration_ms = Some(outcome.duration_ms);
+        }
+    }
+}
+
+fn load_text_attachment(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<TextAttachment, TestCaseLoadError> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|e| TestCaseLoadError::ZipError(format!("Failed to read {}: {}", name, e)))?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| TestCaseLoadError::IoError(e.to_string()))?;
+
+    // Logs may contain invalid UTF-8 from truncated multi-byte output
+    let content = String::from_utf8_lossy(&bytes).into_owned();
 
     let file_name = name.split('/').next_back().unwrap_or(name).to_string();
 
-    Ok(TestAttachment {
+    let mime_type = if file_name.to_lowercase().ends_with(".json") {
+        "application/json"
+    } else {
+        "text/plain"
+    };
+
+    Ok(TextAttachment {
         name: file_name,
         mime_type: mime_type.to_string(),
-        data_url,
-        size_bytes: Some(size_bytes),
+        content,
     })
 }
 
+/// Decode the bytes of an attachment stored as a base64 data URL
+pub fn decode_data_url(data_url: &str) -> Option<Vec<u8>> {
+    let (_, data) = data_url.split_once(";base64,")?;
+    general_purpose::STANDARD.decode(data).ok()
+}
+
+/// Whether a screenshot or video is empty or lacks the signature of its format
+/// Only formats Playwright records are checked; anything else is trusted
+pub fn is_capture_failed(mime_type: &str, bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return true;
+    }
+
+    match mime_type {
+        "image/png" => !bytes.starts_with(b"\x89PNG"),
+        "image/jpeg" => !bytes.starts_with(&[0xFF, 0xD8, 0xFF]),
+        "video/webm" => !bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]),
+        "video/mp4" => bytes.get(4..8) != Some(b"ftyp"),
+        _ => false,
+    }
+}
+
+/// One load warning per screenshot or video that failed to capture
+pub fn capture_failure_warnings(test_cases: &[TestCase]) -> Vec<String> {
+    test_cases
+        .iter()
+        .flat_map(|test_case| {
+            test_case
+                .screenshots
+                .iter()
+                .chain(test_case.video.iter())
+                .filter(|attachment| attachment.capture_failed)
+                .map(move |attachment| {
+                    format!(
+                        "{}: {} is empty or corrupt",
+                        test_case.name, attachment.name
+                    )
+                })
+        })
+        .collect()
+}
+
 fn determine_mime_type(filename: &str) -> &str {
     let filename = filename.to_lowercase();
     if filename.ends_with(".png") {
@@ -222,6 +572,43 @@ fn determine_mime_type(filename: &str) -> &str {
     }
 }
 
+/// Output folders start with the spec file name without its extension,
+/// e.g. `counter-Counter-Example-...` for `counter.spec.ts`
+/// Describe blocks cannot be told apart from the test title, so only the
+/// spec is recovered
+fn spec_from_folder_name(folder_name: &str) -> Option<String> {
+    let (spec, rest) = folder_name.split_once('-')?;
+    (!spec.is_empty() && !rest.is_empty()).then(|| spec.to_string())
+}
+
+/// Project suffixes of Playwright's default configurations, longest first so
+/// `Mobile-Chrome` wins over `chrome`
+const KNOWN_PROJECTS: &[&str] = &[
+    "Microsoft-Edge",
+    "Mobile-Safari",
+    "Mobile-Chrome",
+    "Google-Chrome",
+    "chromium",
+    "firefox",
+    "webkit",
+    "msedge",
+    "chrome",
+];
+
+/// Playwright appends the project to result folders, e.g. `login-passes-chromium`
+fn project_from_folder_name(folder_name: &str) -> Option<String> {
+    let (base, _) = base_test_id(folder_name);
+    let base = base.to_lowercase();
+
+    KNOWN_PROJECTS
+        .iter()
+        .find(|project| {
+            base.strip_suffix(&project.to_lowercase())
+                .is_some_and(|rest| rest.ends_with('-'))
+        })
+        .map(|project| project.to_string())
+}
+
 fn format_test_name(folder_name: &str) -> String {
     // Convert folder name to readable test name
     // e.g., "test-case-1" -> "Test Case 1"
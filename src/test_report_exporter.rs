@@ -0,0 +1,453 @@
+//! Markdown summary of a whole test run
+//! Counterpart of `markdown_exporter` for test result archives: the run is
+//! walked into a `Report` and serialized with the same markdown renderer
+
+use crate::analysis::{group_retries, RetryGroup};
+use crate::ansi_parser::strip_ansi;
+use crate::markdown_exporter::render_markdown;
+use crate::models::{TestCase, TestCaseCollection, TestStatus};
+use crate::report::{Block, Report};
+use crate::triage::Triage;
+use std::collections::HashMap;
+
+/// Lines of an error kept in the failure excerpts
+const EXCERPT_LINES: usize = 20;
+
+/// Export a test run to markdown
+/// `triage` looks up the triage notes of a base test id
+pub fn export_test_run_to_markdown(
+    collection: &TestCaseCollection,
+    triage: impl Fn(&str) -> Option<Triage>,
+) -> String {
+    render_markdown(&build_test_run_report(collection, triage))
+}
+
+/// Build the report for a test run; retries are folded into one row per test
+pub fn build_test_run_report(
+    collection: &TestCaseCollection,
+    triage: impl Fn(&str) -> Option<Triage>,
+) -> Report {
+    let mut report = Report::new();
+    let groups = group_retries(&collection.test_cases);
+    let triage = triage_summaries(&groups, triage);
+
+    report.heading(1, "Playwright Test Report");
+
+    // Totals by final outcome
+    report.heading(2, "Summary");
+
+    let count = |status: TestStatus| {
+        groups
+            .iter()
+            .filter(|group| group.final_attempt().status == status)
+            .count()
+    };
+    let mut summary = vec![
+        ("Total Tests".to_string(), groups.len().to_string()),
+        ("Passed".to_string(), count(TestStatus::Passed).to_string()),
+        ("Failed".to_string(), count(TestStatus::Failed).to_string()),
+        (
+            "Skipped".to_string(),
+            count(TestStatus::Skipped).to_string(),
+        ),
+    ];
+
+    let flaky = groups
+        .iter()
+        .filter(|group| group.passed_on_retry())
+        .count();
+    if flaky > 0 {
+        summary.push(("Passed on Retry".to_string(), flaky.to_string()));
+    }
+
+    let durations: Vec<f64> = groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .attempts
+                .iter()
+                .filter_map(|attempt| attempt.duration_ms)
+        })
+        .collect();
+    if !durations.is_empty() {
+        summary.push((
+            "Total Duration".to_string(),
+            format!("{:.2}s", durations.iter().sum::<f64>() / 1000.0),
+        ));
+    }
+
+    report.push(Block::Fields(summary));
+
+    if !collection.warnings.is_empty() {
+        report.label("Load Warnings");
+        report.push(Block::List(collection.warnings.clone()));
+    }
+
+    if groups.is_empty() {
+        report.push(Block::Note("No test cases found.".to_string()));
+        return report;
+    }
+
+    // One row per test; the triage column only when someone triaged
+    let mut headers = vec![
+        "Test".to_string(),
+        "Status".to_string(),
+        "Duration".to_string(),
+        "Attempts".to_string(),
+    ];
+    let mut rows: Vec<Vec<String>> = groups.iter().map(test_row).collect();
+    if !triage.is_empty() {
+        headers.push("Triage".to_string());
+        for (row, group) in rows.iter_mut().zip(&groups) {
+            row.push(
+                triage
+                    .get(group.test_id.as_str())
+                    .cloned()
+                    .unwrap_or_default(),
+            );
+        }
+    }
+
+    report.heading(2, "Tests");
+    report.push(Block::Table { headers, rows });
+
+    // Failure details
+    let failed: Vec<&RetryGroup> = groups
+        .iter()
+        .filter(|group| group.final_attempt().status == TestStatus::Failed)
+        .collect();
+
+    if !failed.is_empty() {
+        report.heading(2, "Failures");
+
+        for group in failed {
+            build_failure(&mut report, group, triage.get(group.test_id.as_str()));
+        }
+    }
+
+    report
+}
+
+/// Terse digest of the failed tests, one line each, for chat messages or prompts
+pub fn export_failed_summary(
+    collection: &TestCaseCollection,
+    triage: impl Fn(&str) -> Option<Triage>,
+) -> String {
+    let groups = group_retries(&collection.test_cases);
+    let triage = triage_summaries(&groups, triage);
+    let failed: Vec<&RetryGroup> = groups
+        .iter()
+        .filter(|group| group.final_attempt().status == TestStatus::Failed)
+        .collect();
+
+    if failed.is_empty() {
+        return format!("**All {} tests passed**\n", groups.len());
+    }
+
+    let mut output = format!("**{} of {} tests failed**\n\n", failed.len(), groups.len());
+    for group in failed {
+        let name = &group.attempts[0].name;
+        let mut line = match &group.final_attempt().error_message {
+            Some(error) => format!("- **{}** — {}", name, strip_ansi(error)),
+            None => format!("- **{}**", name),
+        };
+        if let Some(summary) = triage.get(group.test_id.as_str()) {
+            line.push_str(&format!(" _({})_", summary));
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// One-line triage of each test that has any, by base test id
+fn triage_summaries(
+    groups: &[RetryGroup],
+    triage: impl Fn(&str) -> Option<Triage>,
+) -> HashMap<&str, String> {
+    groups
+        .iter()
+        .filter_map(|group| {
+            let summary = triage(&group.test_id)?.summary()?;
+            Some((group.test_id.as_str(), summary))
+        })
+        .collect()
+}
+
+fn test_row(group: &RetryGroup) -> Vec<String> {
+    let test_case = group.final_attempt();
+    vec![
+        group.attempts[0].name.clone(),
+        status_label(&test_case.status).to_string(),
+        test_case
+            .duration_ms
+            .map(|duration| format!("{:.0}ms", duration))
+            .unwrap_or_else(|| "–".to_string()),
+        group.attempts.len().to_string(),
+    ]
+}
+
+fn build_failure(report: &mut Report, group: &RetryGroup, triage: Option<&String>) {
+    let test_case = group.final_attempt();
+
+    report.heading(3, &group.attempts[0].name);
+
+    let mut properties = vec![("Test ID".to_string(), group.test_id.clone())];
+    if !test_case.suite_path.is_empty() {
+        properties.push(("Suite".to_string(), test_case.suite_path.join(" › ")));
+    }
+    if let Some(duration) = test_case.duration_ms {
+        properties.push(("Duration".to_string(), format!("{:.0}ms", duration)));
+    }
+    if group.attempts.len() > 1 {
+        properties.push(("Attempts".to_string(), group.attempts.len().to_string()));
+    }
+    if let Some(triage) = triage {
+        properties.push(("Triage".to_string(), triage.clone()));
+    }
+    report.push(Block::Properties(properties));
+
+    if let Some(excerpt) = error_excerpt(test_case) {
+        report.label("Error");
+        report.code(None, excerpt);
+    }
+
+    let attachments = attachment_links(test_case);
+    if !attachments.is_empty() {
+        report.label("Attachments");
+        report.push(Block::List(attachments));
+    }
+}
+
+/// First lines of the most detailed error available
+fn error_excerpt(test_case: &TestCase) -> Option<String> {
+    let error_file = test_case
+        .text_attachments
+        .iter()
+        .find(|attachment| attachment.name.starts_with("error"))
+        .map(|attachment| attachment.content.as_str());
+
+    let text = strip_ansi(
+        test_case
+            .markdown_content
+            .as_deref()
+            .or(error_file)
+            .or(test_case.error_message.as_deref())?,
+    );
+    let text = text.trim();
+
+    if text.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= EXCERPT_LINES {
+        return Some(text.to_string());
+    }
+
+    Some(format!(
+        "{}\n… {} more lines",
+        lines[..EXCERPT_LINES].join("\n"),
+        lines.len() - EXCERPT_LINES
+    ))
+}
+
+/// Links relative to the test results folder, where each test has its own folder
+fn attachment_links(test_case: &TestCase) -> Vec<String> {
+    let comparisons = test_case
+        .image_diffs
+        .iter()
+        .flat_map(|diff| [&diff.expected, &diff.actual, &diff.diff])
+        .flatten();
+    let binary = comparisons
+        .chain(&test_case.screenshots)
+        .chain(&test_case.video)
+        .chain(&test_case.trace_file)
+        .map(|attachment| attachment.name.as_str());
+    let text = test_case
+        .text_attachments
+        .iter()
+        .map(|attachment| attachment.name.as_str());
+
+    binary
+        .chain(text)
+        .map(|name| format!("[{}]({}/{})", name, test_case.id, name))
+        .collect()
+}
+
+fn status_label(status: &TestStatus) -> &'static str {
+    match status {
+        TestStatus::Passed => "✅ passed",
+        TestStatus::Failed => "❌ failed",
+        TestStatus::Skipped => "⏭️ skipped",
+        TestStatus::Pending => "⏳ pending",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TestAttachment, TextAttachment};
+    use crate::resource_store::ResourceStore;
+
+    fn test_case(id: &str, status: TestStatus) -> TestCase {
+        TestCase {
+            id: id.to_string(),
+            name: id.to_string(),
+            status,
+            markdown_content: None,
+            screenshots: vec![],
+            video: None,
+            trace_file: None,
+            text_attachments: vec![],
+            duration_ms: Some(1500.0),
+            error_message: None,
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
+            suite_path: Vec::new(),
+            image_diffs: Vec::new(),
+            trace_contexts: Vec::new(),
+            trace_overview: None,
+        }
+    }
+
+    fn collection(test_cases: Vec<TestCase>) -> TestCaseCollection {
+        TestCaseCollection {
+            test_cases,
+            warnings: Vec::new(),
+            attachment_store: ResourceStore::new(),
+        }
+    }
+
+    #[test]
+    fn test_summary_counts_final_outcomes() {
+        let markdown = export_test_run_to_markdown(
+            &collection(vec![
+                test_case("login", TestStatus::Passed),
+                test_case("checkout", TestStatus::Failed),
+                test_case("checkout-retry1", TestStatus::Passed),
+                test_case("search", TestStatus::Failed),
+            ]),
+            |_| None,
+        );
+
+        assert!(markdown.starts_with("# Playwright Test Report\n"));
+        assert!(markdown.contains("- **Total Tests**: 3\n"));
+        assert!(markdown.contains("- **Passed**: 2\n"));
+        assert!(markdown.contains("- **Failed**: 1\n"));
+        assert!(markdown.contains("- **Passed on Retry**: 1\n"));
+        assert!(markdown.contains("- **Total Duration**: 6.00s\n"));
+        assert!(markdown.contains("| checkout | ✅ passed | 1500ms | 2 |"));
+    }
+
+    #[test]
+    fn test_failure_excerpt_and_attachments() {
+        let mut failed = test_case("login-failed", TestStatus::Failed);
+        failed.markdown_content = Some(
+            (1..=25)
+                .map(|line| format!("line {}", line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        failed.screenshots.push(TestAttachment {
+            name: "test-failed-1.png".to_string(),
+            mime_type: "image/png".to_string(),
+            data_url: "data:image/png;base64,AAAA".to_string(),
+            size_bytes: Some(3),
+            capture_failed: false,
+            archive_entry: None,
+        });
+        failed.text_attachments.push(TextAttachment {
+            name: "stdout.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            content: "hello".to_string(),
+        });
+
+        let report = build_test_run_report(&collection(vec![failed]), |_| None);
+
+        assert!(report.blocks.contains(&Block::Heading {
+            level: 3,
+            text: "login-failed".to_string()
+        }));
+        assert!(report.blocks.iter().any(|block| matches!(
+            block,
+            Block::Code { text, .. }
+                if text.ends_with("line 20\n… 5 more lines") && !text.contains("line 21")
+        )));
+        assert!(report.blocks.contains(&Block::List(vec![
+            "[test-failed-1.png](login-failed/test-failed-1.png)".to_string(),
+            "[stdout.txt](login-failed/stdout.txt)".to_string(),
+        ])));
+    }
+
+    #[test]
+    fn test_empty_run() {
+        let report = build_test_run_report(&collection(vec![]), |_| None);
+
+        assert_eq!(
+            report.blocks.last(),
+            Some(&Block::Note("No test cases found.".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_failed_summary() {
+        let mut timeout = test_case("checkout", TestStatus::Failed);
+        timeout.error_message = Some("Test timeout of 30000ms exceeded.".to_string());
+
+        let summary = export_failed_summary(
+            &collection(vec![
+                test_case("login", TestStatus::Passed),
+                timeout,
+                test_case("search", TestStatus::Failed),
+            ]),
+            |_| None,
+        );
+
+        assert_eq!(
+            summary,
+            "**2 of 3 tests failed**\n\n\
+             - **checkout** — Test timeout of 30000ms exceeded.\n\
+             - **search**\n"
+        );
+        assert_eq!(
+            export_failed_summary(
+                &collection(vec![test_case("login", TestStatus::Passed)]),
+                |_| None
+            ),
+            "**All 1 tests passed**\n"
+        );
+    }
+
+    #[test]
+    fn test_triage_in_exports() {
+        use crate::triage::TriageStatus;
+
+        let triage = |test_id: &str| {
+            (test_id == "checkout").then(|| Triage {
+                status: Some(TriageStatus::KnownIssue),
+                note: "payment sandbox is down".to_string(),
+            })
+        };
+        let run = collection(vec![
+            test_case("checkout", TestStatus::Failed),
+            test_case("login", TestStatus::Passed),
+        ]);
+
+        let markdown = export_test_run_to_markdown(&run, triage);
+        assert!(markdown.contains("| Attempts | Triage |"));
+        assert!(markdown.contains(
+            "| checkout | ❌ failed | 1500ms | 1 | Known issue: payment sandbox is down |"
+        ));
+        assert!(markdown.contains("| login | ✅ passed | 1500ms | 1 |  |"));
+
+        assert_eq!(
+            export_failed_summary(&run, triage),
+            "**1 of 2 tests failed**\n\n\
+             - **checkout** _(Known issue: payment sandbox is down)_\n"
+        );
+        assert!(!export_test_run_to_markdown(&run, |_| None).contains("Triage"));
+    }
+}
@@ -0,0 +1,158 @@
+//! Grouping of tests by spec file and describe block
+//! Each test lands under the suites of its `suite_path`; tests without one
+//! stay at the root so archives lacking metadata still show a flat list
+
+use crate::analysis::RetryGroup;
+use crate::models::TestStatus;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SuiteNode<'a> {
+    /// Spec file or describe block title; empty for the root
+    pub title: String,
+    pub suites: Vec<SuiteNode<'a>>,
+    pub tests: Vec<&'a RetryGroup>,
+}
+
+/// Final outcomes of the tests in a suite, nested suites included
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StatusCounts {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl<'a> SuiteNode<'a> {
+    pub fn counts(&self) -> StatusCounts {
+        let mut counts = StatusCounts::default();
+
+        for test in &self.tests {
+            counts.total += 1;
+            match test.final_attempt().status {
+                TestStatus::Passed => counts.passed += 1,
+                TestStatus::Failed => counts.failed += 1,
+                TestStatus::Skipped => counts.skipped += 1,
+                TestStatus::Pending => {}
+            }
+        }
+
+        for suite in &self.suites {
+            let nested = suite.counts();
+            counts.total += nested.total;
+            counts.passed += nested.passed;
+            counts.failed += nested.failed;
+            counts.skipped += nested.skipped;
+        }
+
+        counts
+    }
+
+    fn child(&mut self, title: &str) -> &mut SuiteNode<'a> {
+        let index = match self.suites.iter().position(|suite| suite.title == title) {
+            Some(index) => index,
+            None => {
+                self.suites.push(SuiteNode {
+                    title: title.to_string(),
+                    ..Default::default()
+                });
+                self.suites.len() - 1
+            }
+        };
+        &mut self.suites[index]
+    }
+}
+
+/// Whether any test carries suite information worth grouping by
+pub fn has_suites(tests: &[&RetryGroup]) -> bool {
+    tests
+        .iter()
+        .any(|test| !test.final_attempt().suite_path.is_empty())
+}
+
+/// Arrange tests into nested suites, keeping the order they first appear in
+pub fn build_suite_tree<'a>(tests: &[&'a RetryGroup]) -> SuiteNode<'a> {
+    let mut root = SuiteNode::default();
+
+    for test in tests {
+        let mut node = &mut root;
+        for title in &test.final_attempt().suite_path {
+            node = node.child(title);
+        }
+        node.tests.push(test);
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::group_retries;
+    use crate::models::TestCase;
+
+    fn test_case(id: &str, status: TestStatus, suite_path: &[&str]) -> TestCase {
+        TestCase {
+            id: id.to_string(),
+            name: id.to_string(),
+            status,
+            markdown_content: None,
+            screenshots: vec![],
+            video: None,
+            trace_file: None,
+            text_attachments: vec![],
+            duration_ms: None,
+            error_message: None,
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            project: None,
+            suite_path: suite_path.iter().map(|title| title.to_string()).collect(),
+            image_diffs: Vec::new(),
+            trace_contexts: Vec::new(),
+            trace_overview: None,
+        }
+    }
+
+    #[test]
+    fn test_build_suite_tree() {
+        let groups = group_retries(&[
+            test_case("login", TestStatus::Passed, &["auth.spec.ts", "Login"]),
+            test_case("logout", TestStatus::Failed, &["auth.spec.ts"]),
+            test_case("signup", TestStatus::Skipped, &["auth.spec.ts", "Login"]),
+            test_case("cart", TestStatus::Passed, &["cart.spec.ts"]),
+            test_case("smoke", TestStatus::Passed, &[]),
+        ]);
+        let tests: Vec<_> = groups.iter().collect();
+
+        assert!(has_suites(&tests));
+        let root = build_suite_tree(&tests);
+
+        assert_eq!(root.tests.len(), 1);
+        let titles: Vec<_> = root.suites.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["auth.spec.ts", "cart.spec.ts"]);
+
+        let auth = &root.suites[0];
+        assert_eq!(auth.tests[0].test_id, "logout");
+        assert_eq!(auth.suites[0].title, "Login");
+        assert_eq!(auth.suites[0].tests.len(), 2);
+
+        assert_eq!(
+            auth.counts(),
+            StatusCounts {
+                total: 3,
+                passed: 1,
+                failed: 1,
+                skipped: 1
+            }
+        );
+        assert_eq!(root.counts().total, 5);
+    }
+
+    #[test]
+    fn test_flat_without_suites() {
+        let groups = group_retries(&[test_case("a", TestStatus::Passed, &[])]);
+        let tests: Vec<_> = groups.iter().collect();
+
+        assert!(!has_suites(&tests));
+        assert!(build_suite_tree(&tests).suites.is_empty());
+    }
+}
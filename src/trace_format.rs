@@ -0,0 +1,251 @@
+//! Trace format versions
+//! Playwright bumps the `version` of the `context-options` event whenever the
+//! shape of trace events changes. Events of older supported versions are
+//! rewritten to the current shape before they are deserialized, so the rest of
+//! the loader only deals with one format
+
+use crate::models::TraceEvent;
+use serde_json::{Map, Value};
+
+/// Oldest version that can be upgraded
+pub const MIN_SUPPORTED_VERSION: u32 = 3;
+/// Version the trace models follow
+pub const CURRENT_VERSION: u32 = 8;
+
+pub fn is_supported(version: u32) -> bool {
+    (MIN_SUPPORTED_VERSION..=CURRENT_VERSION).contains(&version)
+}
+
+#[derive(Debug)]
+pub enum EventError {
+    Malformed(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+/// Reads trace lines in the version declared by the `context-options` event;
+/// lines before it are read as the current version
+#[derive(Debug, Default)]
+pub struct EventReader {
+    version: Option<u32>,
+}
+
+impl EventReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events of one line in the current format; an `action` event of version
+    /// 3 expands into a `before` and an `after` event
+    pub fn read_line(&mut self, line: &str) -> Result<Vec<TraceEvent>, EventError> {
+        if self
+            .version
+            .is_none_or(|version| version == CURRENT_VERSION)
+        {
+            match serde_json::from_str::<TraceEvent>(line) {
+                Ok(TraceEvent::ContextOptions(options)) if options.version != CURRENT_VERSION => {
+                    // Shapes differ from here on; continue with the upgrade below
+                    self.declare(options.version)?;
+                }
+                Ok(event) => return Ok(vec![event]),
+                Err(e) if self.version.is_some() => return Err(EventError::Malformed(e)),
+                // Until the version is known the line may be in an older shape
+                Err(_) => {}
+            }
+        }
+
+        let value = serde_json::from_str::<Value>(line).map_err(EventError::Malformed)?;
+        if value.get("type").and_then(Value::as_str) == Some("context-options") {
+            let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+            self.declare(u32::try_from(version).unwrap_or(u32::MAX))?;
+        }
+
+        let version = self.version.unwrap_or(CURRENT_VERSION);
+        upgrade(value, version)
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<_, _>>()
+            .map_err(EventError::Malformed)
+    }
+
+    fn declare(&mut self, version: u32) -> Result<(), EventError> {
+        if !is_supported(version) {
+            return Err(EventError::UnsupportedVersion(version));
+        }
+        self.version = Some(version);
+        Ok(())
+    }
+}
+
+/// Rewrite an event of the given version to the current shape
+fn upgrade(mut event: Value, version: u32) -> Vec<Value> {
+    let Some(object) = event.as_object_mut() else {
+        return vec![event];
+    };
+    let event_type = object
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    match event_type.as_str() {
+        // Version 3 recorded a whole call as one event, split into before/after since
+        "action" if version < 4 => return split_action(object),
+        // Version 3 nested the event under `metadata`
+        "event" if version < 4 => {
+            if let Some(Value::Object(metadata)) = object.remove("metadata") {
+                rename(object, &metadata, "type", "class");
+                rename(object, &metadata, "method", "method");
+                rename(object, &metadata, "params", "params");
+                rename(object, &metadata, "startTime", "time");
+            }
+        }
+        // Traces before version 8 named the action title `apiName`
+        "before" if version < 8 => {
+            if let Some(api_name) = object.remove("apiName") {
+                object.entry("title").or_insert(api_name);
+            }
+        }
+        // Older traces have no monotonic clock reference
+        "context-options" => {
+            object.entry("monotonicTime").or_insert(Value::from(0.0));
+        }
+        _ => {}
+    }
+
+    vec![event]
+}
+
+fn split_action(action: &Map<String, Value>) -> Vec<Value> {
+    let Some(Value::Object(metadata)) = action.get("metadata") else {
+        return Vec::new();
+    };
+
+    let mut before = Map::new();
+    before.insert("type".to_string(), Value::from("before"));
+    rename(&mut before, metadata, "id", "callId");
+    rename(&mut before, metadata, "startTime", "startTime");
+    rename(&mut before, metadata, "type", "class");
+    rename(&mut before, metadata, "method", "method");
+    rename(&mut before, metadata, "params", "params");
+    rename(&mut before, metadata, "apiName", "title");
+    rename(&mut before, metadata, "pageId", "pageId");
+    rename(&mut before, metadata, "stack", "stack");
+
+    let mut after = Map::new();
+    after.insert("type".to_string(), Value::from("after"));
+    rename(&mut after, metadata, "id", "callId");
+    rename(&mut after, metadata, "endTime", "endTime");
+    rename(&mut after, metadata, "result", "result");
+
+    // `{ error: { message, stack } }`, or `{ value }` when a non-Error was thrown
+    if let Some(error) = metadata.get("error") {
+        let error = match (error.get("error"), error.get("value")) {
+            (Some(inner), _) => inner.clone(),
+            (None, Some(value)) => serde_json::json!({ "message": value.to_string() }),
+            (None, None) => error.clone(),
+        };
+        after.insert("error".to_string(), error);
+    }
+
+    vec![Value::Object(before), Value::Object(after)]
+}
+
+/// Copy `from[old]` into `to[new]` when present
+fn rename(to: &mut Map<String, Value>, from: &Map<String, Value>, old: &str, new: &str) {
+    if let Some(value) = from.get(old) {
+        to.insert(new.to_string(), value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(lines: &[&str]) -> Result<Vec<TraceEvent>, EventError> {
+        let mut reader = EventReader::new();
+        let mut events = Vec::new();
+        for line in lines {
+            events.extend(reader.read_line(line)?);
+        }
+        Ok(events)
+    }
+
+    #[test]
+    fn test_current_version_read_as_is() {
+        let events = read_all(&[
+            r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":5}"#,
+            r#"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","title":"Navigate"}"#,
+        ])
+        .unwrap();
+
+        assert_eq!(events.len(), 2);
+        match &events[1] {
+            TraceEvent::Before(before) => assert_eq!(before.title.as_deref(), Some("Navigate")),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_api_name_upgraded_to_title() {
+        let events = read_all(&[
+            r#"{"type":"context-options","version":6,"browserName":"firefox","wallTime":0}"#,
+            r#"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","apiName":"page.goto"}"#,
+        ])
+        .unwrap();
+
+        match &events[0] {
+            TraceEvent::ContextOptions(options) => assert_eq!(options.monotonic_time, 0.0),
+            other => panic!("unexpected event {:?}", other),
+        }
+        match &events[1] {
+            TraceEvent::Before(before) => assert_eq!(before.title.as_deref(), Some("page.goto")),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_version_3_action_split() {
+        let events = read_all(&[
+            r#"{"type":"context-options","version":3,"browserName":"webkit","wallTime":0}"#,
+            r##"{"type":"action","metadata":{"id":"call@4","type":"Frame","method":"click","apiName":"page.click","params":{"selector":"#ok"},"startTime":10,"endTime":25,"error":{"error":{"message":"Timeout","stack":"at x"}}}}"##,
+            r#"{"type":"event","metadata":{"type":"BrowserContext","method":"page","params":{},"startTime":12}}"#,
+        ])
+        .unwrap();
+
+        assert_eq!(events.len(), 4);
+        match (&events[1], &events[2]) {
+            (TraceEvent::Before(before), TraceEvent::After(after)) => {
+                assert_eq!(before.call_id, "call@4");
+                assert_eq!(before.class, "Frame");
+                assert_eq!(before.title.as_deref(), Some("page.click"));
+                assert_eq!(after.end_time, 25.0);
+                assert_eq!(
+                    after.error.as_ref().unwrap().message.as_deref(),
+                    Some("Timeout")
+                );
+            }
+            other => panic!("unexpected events {:?}", other),
+        }
+        match &events[3] {
+            TraceEvent::Event(event) => {
+                assert_eq!(event.class, "BrowserContext");
+                assert_eq!(event.time, Some(12.0));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_versions() {
+        for version in [2, 9] {
+            let line = format!(
+                r#"{{"type":"context-options","version":{},"browserName":"chromium","wallTime":0,"monotonicTime":0}}"#,
+                version
+            );
+            match read_all(&[&line]) {
+                Err(EventError::UnsupportedVersion(found)) => assert_eq!(found, version),
+                other => panic!("expected unsupported version, got {:?}", other),
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Links between test cases and the contexts of their traces
+//! The loader records the contexts of each test's `trace.zip` on the test, so
+//! a card can open the trace at a context or failed action, and the opened
+//! trace can lead back to the card it came from
+
+use crate::models::{ActionEntry, FailedActionLink, TestCase, TraceContextLink, TraceModel};
+use crate::resource_store::ResourceStore;
+use crate::test_case_loader::decode_data_url;
+
+/// Place in a test's trace to open
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceTarget {
+    pub test_case_id: String,
+    pub context_id: String,
+    /// Action to select; none opens the context without a selection
+    pub call_id: Option<String>,
+}
+
+/// Links to the contexts of a trace that recorded actions
+pub fn context_links(model: &TraceModel) -> Vec<TraceContextLink> {
+    model
+        .contexts
+        .iter()
+        .filter(|context| !context.actions.is_empty())
+        .map(|context| TraceContextLink {
+            context_id: context.id.clone(),
+            title: context.title.clone(),
+            browser_name: context.browser_name.clone(),
+            failed_actions: context
+                .actions
+                .iter()
+                .filter(|action| action.error.is_some())
+                .map(|action| FailedActionLink {
+                    call_id: action.call_id.clone(),
+                    label: action.summary_label(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Index of the target's context in the model and the action to select
+/// Falls back to the first context when the id is unknown, e.g. when the
+/// archive was re-exported with other trace file names
+pub fn locate<'a>(
+    model: &'a TraceModel,
+    context_id: &str,
+    call_id: Option<&str>,
+) -> Option<(usize, Option<&'a ActionEntry>)> {
+    let index = model
+        .contexts
+        .iter()
+        .position(|context| context.id == context_id)
+        .or_else(|| (!model.contexts.is_empty()).then_some(0))?;
+    let action = call_id.and_then(|call_id| {
+        model.contexts[index]
+            .actions
+            .iter()
+            .find(|action| action.call_id == call_id)
+    });
+
+    Some((index, action))
+}
+
+/// Bytes of a test's trace archive, inlined or still in the report archive
+pub fn trace_bytes(test_case: &TestCase, store: &ResourceStore) -> Option<Vec<u8>> {
+    let trace = test_case.trace_file.as_ref()?;
+
+    match trace.archive_entry.as_deref() {
+        Some(entry) if trace.data_url.is_empty() => store.get(entry),
+        _ => decode_data_url(&trace.data_url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ContextEntry, SerializedError};
+    use std::collections::HashMap;
+
+    fn action(call_id: &str, error: Option<&str>) -> ActionEntry {
+        ActionEntry {
+            action_type: "before".to_string(),
+            call_id: call_id.to_string(),
+            start_time: 100.0,
+            end_time: 200.0,
+            title: None,
+            class: Some("Frame".to_string()),
+            method: Some("click".to_string()),
+            params: HashMap::new(),
+            page_id: None,
+            parent_id: None,
+            step_id: None,
+            error: error.map(|message| SerializedError {
+                message: Some(message.to_string()),
+                stack: None,
+            }),
+            log: vec![],
+            frame_id: None,
+            input: None,
+            locator: Some("#pay".to_string()),
+            stack: vec![],
+        }
+    }
+
+    fn context(id: &str, actions: Vec<ActionEntry>) -> ContextEntry {
+        ContextEntry {
+            id: id.to_string(),
+            start_time: 0.0,
+            end_time: 1000.0,
+            browser_name: "chromium".to_string(),
+            platform: None,
+            playwright_version: None,
+            wall_time: 0.0,
+            monotonic_time: 0.0,
+            title: Some("checkout".to_string()),
+            pages: vec![],
+            actions,
+            resources: vec![],
+            events: vec![],
+            errors: vec![],
+            stdio: vec![],
+            page_events: vec![],
+            console: vec![],
+            stats: Default::default(),
+        }
+    }
+
+    fn model() -> TraceModel {
+        let mut model = TraceModel::new();
+        model.contexts = vec![
+            context("empty", vec![]),
+            context(
+                "0-trace",
+                vec![action("call@1", None), action("call@2", Some("Timeout"))],
+            ),
+        ];
+        model
+    }
+
+    #[test]
+    fn test_context_links() {
+        let links = context_links(&model());
+
+        assert_eq!(
+            links,
+            vec![TraceContextLink {
+                context_id: "0-trace".to_string(),
+                title: Some("checkout".to_string()),
+                browser_name: "chromium".to_string(),
+                failed_actions: vec![FailedActionLink {
+                    call_id: "call@2".to_string(),
+                    label: "Frame.click (#pay)".to_string(),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_locate() {
+        let model = model();
+
+        let (index, action) = locate(&model, "0-trace", Some("call@2")).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(action.map(|a| a.call_id.as_str()), Some("call@2"));
+
+        assert_eq!(locate(&model, "renamed", Some("call@2")), Some((0, None)));
+        assert_eq!(locate(&TraceModel::new(), "0-trace", None), None);
+    }
+}
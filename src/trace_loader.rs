@@ -1,8 +1,16 @@
 use crate::models::*;
+use crate::resource_store::{ResourceStore, RESOURCES_PREFIX};
+use crate::stats::compute_stats;
+use crate::trace_format::{EventError, EventReader, CURRENT_VERSION, MIN_SUPPORTED_VERSION};
+use base64::{engine::general_purpose, Engine as _};
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
+use std::rc::Rc;
 use zip::ZipArchive;
 
+/// Characters of a malformed line kept in its parse warning
+const SNIPPET_CHARS: usize = 120;
+
 #[derive(Debug)]
 pub enum LoadError {
     ZipError(String),
@@ -10,6 +18,8 @@ pub enum LoadError {
     #[allow(dead_code)]
     ParseError(String),
     MissingTraceFile,
+    /// Trace written in a format version this viewer cannot read
+    UnsupportedVersion(u32),
 }
 
 impl std::fmt::Display for LoadError {
@@ -19,6 +29,11 @@ impl std::fmt::Display for LoadError {
             LoadError::IoError(e) => write!(f, "IO error: {}", e),
             LoadError::ParseError(e) => write!(f, "Parse error: {}", e),
             LoadError::MissingTraceFile => write!(f, "No .trace file found in archive"),
+            LoadError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported trace version {} (supported versions: {}-{})",
+                version, MIN_SUPPORTED_VERSION, CURRENT_VERSION
+            ),
         }
     }
 }
@@ -37,24 +52,72 @@ pub fn load_trace_from_zip(bytes: &[u8]) -> Result<TraceModel, LoadError> {
     let is_report_archive = (0..archive.len()).any(|i| {
         archive
             .by_index(i)
-            .map(|f| {
-                let name = f.name();
-                name.starts_with("data/") && name.ends_with(".zip")
-            })
+            .map(|f| is_nested_archive(f.name()))
             .unwrap_or(false)
     });
 
     if is_report_archive {
         log::info!("Detected report archive format");
-        return load_report_archive(archive);
+        return load_report_archive(archive, |_| true);
     }
 
     // Regular trace archive processing
-    load_single_trace_archive(archive)
+    load_single_trace_archive(bytes, archive)
+}
+
+/// Trace archive nested in the `data/` folder of a report archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct NestedArchive {
+    pub name: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+}
+
+/// List the nested trace archives without parsing them
+/// Returns `None` when the archive is not a report archive
+pub fn list_report_archives(bytes: &[u8]) -> Result<Option<Vec<NestedArchive>>, LoadError> {
+    let cursor = Cursor::new(bytes);
+    let mut archive = ZipArchive::new(cursor).map_err(|e| LoadError::ZipError(e.to_string()))?;
+
+    let mut nested = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive
+            .by_index_raw(i)
+            .map_err(|e| LoadError::ZipError(e.to_string()))?;
+
+        if is_nested_archive(file.name()) {
+            nested.push(NestedArchive {
+                name: file.name().to_string(),
+                size: file.size(),
+            });
+        }
+    }
+
+    Ok((!nested.is_empty()).then_some(nested))
 }
 
-fn load_report_archive(mut archive: ZipArchive<Cursor<&[u8]>>) -> Result<TraceModel, LoadError> {
+/// Load only the selected nested archives of a report archive
+pub fn load_report_archive_selection(
+    bytes: &[u8],
+    selected: &[String],
+) -> Result<TraceModel, LoadError> {
+    let cursor = Cursor::new(bytes);
+    let archive = ZipArchive::new(cursor).map_err(|e| LoadError::ZipError(e.to_string()))?;
+
+    load_report_archive(archive, |name| selected.iter().any(|s| s == name))
+}
+
+fn is_nested_archive(name: &str) -> bool {
+    name.starts_with("data/") && name.ends_with(".zip")
+}
+
+fn load_report_archive(
+    mut archive: ZipArchive<Cursor<&[u8]>>,
+    include: impl Fn(&str) -> bool,
+) -> Result<TraceModel, LoadError> {
     let mut all_contexts = Vec::new();
+    let mut resource_store = ResourceStore::new();
+    let mut parse_warnings = Vec::new();
 
     // Find all ZIP files in the data/ folder
     let mut nested_zips = Vec::new();
@@ -64,7 +127,7 @@ fn load_report_archive(mut archive: ZipArchive<Cursor<&[u8]>>) -> Result<TraceMo
             .map_err(|e| LoadError::ZipError(e.to_string()))?;
         let name = file.name().to_string();
 
-        if name.starts_with("data/") && name.ends_with(".zip") {
+        if is_nested_archive(&name) && include(&name) {
             nested_zips.push((i, name));
         }
     }
@@ -91,7 +154,25 @@ fn load_report_archive(mut archive: ZipArchive<Cursor<&[u8]>>) -> Result<TraceMo
 
         // Recursively load the nested trace
         let trace_model = load_trace_from_zip(&nested_bytes)?;
-        all_contexts.extend(trace_model.contexts);
+        all_contexts.extend(
+            trace_model
+                .contexts
+                .into_iter()
+                .map(|context| ContextEntry {
+                    id: format!("{}/{}", name, context.id),
+                    ..context
+                }),
+        );
+        resource_store.extend(trace_model.resource_store);
+        parse_warnings.extend(
+            trace_model
+                .parse_warnings
+                .into_iter()
+                .map(|warning| ParseWarning {
+                    file: format!("{}/{}", name, warning.file),
+                    ..warning
+                }),
+        );
     }
 
     log::info!(
@@ -101,63 +182,186 @@ fn load_report_archive(mut archive: ZipArchive<Cursor<&[u8]>>) -> Result<TraceMo
 
     Ok(TraceModel {
         contexts: all_contexts,
+        resource_store,
+        parse_warnings,
     })
 }
 
 fn load_single_trace_archive(
+    bytes: &[u8],
     mut archive: ZipArchive<Cursor<&[u8]>>,
 ) -> Result<TraceModel, LoadError> {
-    // Find all .trace files
-    let mut trace_files = Vec::new();
-    let mut network_files = HashMap::new();
-    let mut resources = HashMap::new();
+    let names = (0..archive.len())
+        .map(|i| {
+            archive
+                .by_index_raw(i)
+                .map(|file| file.name().to_string())
+                .map_err(|e| LoadError::ZipError(e.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let files = group_trace_files(names.iter().map(String::as_str));
+
+    let (contexts, parse_warnings) = parse_contexts(files.traces, files.networks, |name| {
+        read_file_from_archive(&mut archive, name)
+    })?;
+
+    // Only entry locations are kept; resources are inflated when requested
+    let resource_store = if files.has_resources {
+        ResourceStore::from_archive(Rc::from(bytes), &mut archive).map_err(LoadError::ZipError)?
+    } else {
+        ResourceStore::new()
+    };
 
-    for i in 0..archive.len() {
-        let file = archive
-            .by_index(i)
-            .map_err(|e| LoadError::ZipError(e.to_string()))?;
-        let name = file.name().to_string();
+    log::info!("Indexed {} resource(s)", resource_store.len());
+
+    Ok(TraceModel {
+        contexts,
+        resource_store,
+        parse_warnings,
+    })
+}
 
-        if name.ends_with(".trace") {
-            let ordinal = name.trim_end_matches(".trace");
-            trace_files.push(ordinal.to_string());
-        } else if name.ends_with(".network") {
-            let ordinal = name.trim_end_matches(".network");
-            network_files.insert(ordinal.to_string(), i);
-        } else if name.starts_with("resources/") {
-            resources.insert(name.clone(), i);
+/// Whether a file is part of an uncompressed trace rather than an archive
+pub fn is_raw_trace_file(name: &str) -> bool {
+    name.ends_with(".trace") || name.ends_with(".network")
+}
+
+/// Load an unzipped trace folder, each file given as (path in the folder, bytes)
+/// Loose `.trace` and `.network` files load the same way, named by file name
+pub fn load_trace_from_files(files: Vec<(String, Vec<u8>)>) -> Result<TraceModel, LoadError> {
+    let grouped = group_trace_files(files.iter().map(|(name, _)| name.as_str()));
+
+    let mut contents: HashMap<String, Vec<u8>> = files.into_iter().collect();
+    let (contexts, parse_warnings) = parse_contexts(grouped.traces, grouped.networks, |name| {
+        let bytes = contents
+            .remove(name)
+            .ok_or_else(|| LoadError::IoError(format!("Missing {}", name)))?;
+        String::from_utf8(bytes).map_err(|e| LoadError::IoError(format!("{}: {}", name, e)))
+    })?;
+
+    let resource_store = ResourceStore::from_files(
+        contents
+            .into_iter()
+            .filter_map(|(name, bytes)| {
+                let sha1 = name.strip_prefix(RESOURCES_PREFIX)?;
+                (!sha1.is_empty()).then(|| (sha1.to_string(), bytes))
+            })
+            .collect(),
+    );
+
+    log::info!("Read {} resource(s)", resource_store.len());
+
+    Ok(TraceModel {
+        contexts,
+        resource_store,
+        parse_warnings,
+    })
+}
+
+/// Trace files of an archive or folder, grouped into the chunks of each context
+struct TraceFiles {
+    traces: Vec<(String, Vec<(u32, String)>)>,
+    networks: HashMap<String, Vec<(u32, String)>>,
+    has_resources: bool,
+}
+
+fn group_trace_files<'a>(names: impl Iterator<Item = &'a str>) -> TraceFiles {
+    let mut files = TraceFiles {
+        traces: Vec::new(),
+        networks: HashMap::new(),
+        has_resources: false,
+    };
+
+    for name in names {
+        if let Some(stem) = name.strip_suffix(".trace") {
+            let (ordinal, chunk) = split_chunk(stem);
+            match files.traces.iter_mut().find(|(o, _)| o == ordinal) {
+                Some((_, chunks)) => chunks.push((chunk, name.to_string())),
+                None => files
+                    .traces
+                    .push((ordinal.to_string(), vec![(chunk, name.to_string())])),
+            }
+        } else if let Some(stem) = name.strip_suffix(".network") {
+            let (ordinal, chunk) = split_chunk(stem);
+            files
+                .networks
+                .entry(ordinal.to_string())
+                .or_default()
+                .push((chunk, name.to_string()));
+        } else if name.starts_with(RESOURCES_PREFIX) {
+            files.has_resources = true;
         }
     }
 
+    files
+}
+
+/// Parse every context; `read` returns the text of a trace file
+fn parse_contexts(
+    trace_files: Vec<(String, Vec<(u32, String)>)>,
+    mut network_files: HashMap<String, Vec<(u32, String)>>,
+    mut read: impl FnMut(&str) -> Result<String, LoadError>,
+) -> Result<(Vec<ContextEntry>, Vec<ParseWarning>), LoadError> {
     if trace_files.is_empty() {
         return Err(LoadError::MissingTraceFile);
     }
 
-    log::info!("Found {} trace file(s)", trace_files.len());
+    log::info!("Found {} trace(s)", trace_files.len());
 
     let mut contexts = Vec::new();
+    let mut parse_warnings = Vec::new();
+
+    for (ordinal, trace_chunks) in trace_files {
+        log::info!(
+            "Processing trace: {} ({} chunk(s))",
+            ordinal,
+            trace_chunks.len()
+        );
+
+        let traces = read_chunks(trace_chunks, &mut read)?;
+        let networks = read_chunks(
+            network_files.remove(&ordinal).unwrap_or_default(),
+            &mut read,
+        )?;
+
+        // Parse the trace; malformed lines become warnings
+        let mut context = parse_trace(&traces, &networks, &mut parse_warnings)?;
+        context.id = ordinal;
+        contexts.push(context);
+    }
 
-    for ordinal in trace_files {
-        log::info!("Processing trace: {}", ordinal);
-
-        // Read the main trace file
-        let trace_name = format!("{}.trace", ordinal);
-        let trace_content = read_file_from_archive(&mut archive, &trace_name)?;
+    if !parse_warnings.is_empty() {
+        log::warn!("{} trace line(s) could not be parsed", parse_warnings.len());
+    }
 
-        // Read the network file if it exists
-        let network_name = format!("{}.network", ordinal);
-        let network_content = if archive.by_name(&network_name).is_ok() {
-            Some(read_file_from_archive(&mut archive, &network_name)?)
-        } else {
-            None
-        };
+    Ok((contexts, parse_warnings))
+}
 
-        // Parse the trace
-        let context = parse_trace(&trace_content, network_content)?;
-        contexts.push(context);
-    }
+/// Split a file stem into its ordinal and chunk number: long traces continue
+/// in `trace-1.trace`, `trace-2.trace`, … after `trace.trace`
+fn split_chunk(stem: &str) -> (&str, u32) {
+    stem.rsplit_once('-')
+        .and_then(|(ordinal, suffix)| {
+            let digits = suffix.strip_prefix("chunk").unwrap_or(suffix);
+            let chunk = digits.parse().ok()?;
+            (!ordinal.is_empty()).then_some((ordinal, chunk))
+        })
+        .unwrap_or((stem, 0))
+}
 
-    Ok(TraceModel { contexts })
+/// Read chunk files as (name, content) in chunk order
+fn read_chunks(
+    mut chunks: Vec<(u32, String)>,
+    read: &mut impl FnMut(&str) -> Result<String, LoadError>,
+) -> Result<Vec<(String, String)>, LoadError> {
+    chunks.sort_by_key(|(chunk, _)| *chunk);
+    chunks
+        .into_iter()
+        .map(|(_, name)| {
+            let content = read(&name)?;
+            Ok((name, content))
+        })
+        .collect()
 }
 
 fn read_file_from_archive(
@@ -175,45 +379,235 @@ fn read_file_from_archive(
     Ok(content)
 }
 
+/// Page with the given id, added when first seen
+fn page_entry<'a>(pages: &'a mut Vec<PageEntry>, page_id: &str) -> &'a mut PageEntry {
+    let index = match pages.iter().position(|page| page.page_id == page_id) {
+        Some(index) => index,
+        None => {
+            pages.push(PageEntry {
+                page_id: page_id.to_string(),
+                screencast_frames: Vec::new(),
+                frames: Vec::new(),
+                title: None,
+                viewport: None,
+            });
+            pages.len() - 1
+        }
+    };
+    &mut pages[index]
+}
+
+/// Frame of the page with the given id, added when first seen
+fn page_frame<'a>(page: &'a mut PageEntry, frame_id: &str) -> &'a mut FrameEntry {
+    let index = match page.frames.iter().position(|f| f.frame_id == frame_id) {
+        Some(index) => index,
+        None => {
+            page.frames.push(FrameEntry {
+                frame_id: frame_id.to_string(),
+                parent_frame_id: None,
+                url: None,
+                is_main_frame: false,
+                detached: false,
+            });
+            page.frames.len() - 1
+        }
+    };
+    &mut page.frames[index]
+}
+
+fn is_frame_event(event: &ObjectEvent) -> bool {
+    matches!(
+        event.method.as_str(),
+        "frameAttached" | "frameDetached" | "navigated" | "frameNavigated"
+    )
+}
+
+fn object_page_id(event: &ObjectEvent) -> Option<&str> {
+    event
+        .page_id
+        .as_deref()
+        .or_else(|| event.params.get("pageId").and_then(|id| id.as_str()))
+}
+
+/// Attach, detach or navigate a frame of the page
+fn apply_frame_event(page: &mut PageEntry, event: &ObjectEvent) {
+    let param = |key: &str| event.params.get(key).and_then(|value| value.as_str());
+    let Some(frame_id) = param("frameId") else {
+        return;
+    };
+
+    let frame = page_frame(page, frame_id);
+    match event.method.as_str() {
+        "frameAttached" => {
+            frame.parent_frame_id = param("parentFrameId").map(str::to_string);
+            frame.detached = false;
+        }
+        "frameDetached" => frame.detached = true,
+        _ => {
+            if let Some(url) = param("url") {
+                frame.url = Some(url.to_string());
+            }
+        }
+    }
+}
+
+fn stdio_entry(stream: StdioStream, output: &StdioTraceEvent) -> StdioEntry {
+    let text = match (&output.text, &output.base64) {
+        (Some(text), _) => text.clone(),
+        (None, Some(encoded)) => general_purpose::STANDARD
+            .decode(encoded)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default(),
+        (None, None) => String::new(),
+    };
+
+    StdioEntry {
+        stream,
+        timestamp: output.timestamp,
+        text,
+        call_id: None,
+    }
+}
+
+/// Stack frames in the `    at function (file:line:column)` form of V8 stacks
+fn format_stack(frames: &[StackFrame]) -> Option<String> {
+    if frames.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<String> = frames
+        .iter()
+        .map(|frame| {
+            let location = frame.location();
+            match &frame.function {
+                Some(function) if !function.is_empty() => {
+                    format!("    at {} ({})", function, location)
+                }
+                _ => format!("    at {}", location),
+            }
+        })
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
+/// Uncaught exception in a page; params hold a serialized error, or a value
+/// when something other than an `Error` was thrown
+fn page_error(params: &serde_json::Value) -> Option<ErrorEvent> {
+    let error = params.get("error")?;
+
+    if let Some(serialized) = error
+        .get("error")
+        .and_then(|error| serde_json::from_value::<SerializedError>(error.clone()).ok())
+    {
+        return Some(ErrorEvent {
+            message: serialized
+                .message
+                .unwrap_or_else(|| "Unknown error".to_string()),
+            stack: serialized.stack,
+            page_error: true,
+        });
+    }
+
+    let value = error.get("value")?;
+    Some(ErrorEvent {
+        message: value
+            .as_str()
+            .map_or_else(|| value.to_string(), str::to_string),
+        stack: None,
+        page_error: true,
+    })
+}
+
+/// Events of one line as (file name, line index); a malformed line is
+/// collected as a warning and yields no events
+fn read_events(
+    reader: &mut EventReader,
+    (file, index): (&str, usize),
+    line: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<TraceEvent>, LoadError> {
+    match reader.read_line(line) {
+        Ok(events) => Ok(events),
+        Err(EventError::Malformed(e)) => {
+            warnings.push(parse_warning(file, index, &e, line));
+            Ok(Vec::new())
+        }
+        Err(EventError::UnsupportedVersion(version)) => Err(LoadError::UnsupportedVersion(version)),
+    }
+}
+
+fn parse_warning(file: &str, index: usize, error: &serde_json::Error, line: &str) -> ParseWarning {
+    log::warn!("Failed to parse {} line {}: {}", file, index + 1, error);
+
+    let mut snippet: String = line.chars().take(SNIPPET_CHARS).collect();
+    if snippet.len() < line.len() {
+        snippet.push('…');
+    }
+
+    ParseWarning {
+        file: file.to_string(),
+        line: index + 1,
+        error: error.to_string(),
+        snippet,
+    }
+}
+
+/// Parse the `.trace` chunks of one context and its `.network` chunks into a
+/// single context, each chunk given as (name, content) in chunk order
 fn parse_trace(
-    trace_content: &str,
-    network_content: Option<String>,
+    traces: &[(String, String)],
+    networks: &[(String, String)],
+    warnings: &mut Vec<ParseWarning>,
 ) -> Result<ContextEntry, LoadError> {
     let mut actions_map: HashMap<String, ActionEntry> = HashMap::new();
-    let mut pages: HashMap<String, PageEntry> = HashMap::new();
+    // Kept in order of appearance, which is the order pages were opened in
+    let mut pages: Vec<PageEntry> = Vec::new();
     let mut events = Vec::new();
-    let errors = Vec::new();
+    let mut errors = Vec::new();
+    let mut stdio = Vec::new();
+    let mut page_events = Vec::new();
+    let mut console = Vec::new();
+    let mut action_frames: HashMap<String, String> = HashMap::new();
+    let mut reader = EventReader::new();
 
     let mut context = ContextEntry {
+        id: String::new(),
         start_time: f64::MAX,
         end_time: 0.0,
         browser_name: String::new(),
         platform: None,
         playwright_version: None,
         wall_time: 0.0,
+        monotonic_time: 0.0,
         title: None,
         pages: Vec::new(),
         actions: Vec::new(),
         resources: Vec::new(),
         events: Vec::new(),
         errors: Vec::new(),
+        stdio: Vec::new(),
+        page_events: Vec::new(),
+        console: Vec::new(),
+        stats: Default::default(),
     };
 
     // Parse main trace file (line-delimited JSON)
-    for line in trace_content.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+    for (trace_name, trace_content) in traces {
+        for (index, line) in trace_content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
 
-        match serde_json::from_str::<TraceEvent>(line) {
-            Ok(event) => {
+            for event in read_events(&mut reader, (trace_name, index), line, warnings)? {
                 match &event {
                     TraceEvent::ContextOptions(ctx_opts) => {
                         context.browser_name = ctx_opts.browser_name.clone();
                         context.platform = ctx_opts.platform.clone();
                         context.playwright_version = ctx_opts.playwright_version.clone();
                         context.wall_time = ctx_opts.wall_time;
+                        context.monotonic_time = ctx_opts.monotonic_time;
                         context.title = ctx_opts.title.clone();
                     }
                     TraceEvent::Before(before) => {
@@ -228,14 +622,27 @@ fn parse_trace(
                             params: before.params.clone(),
                             page_id: before.page_id.clone(),
                             parent_id: before.parent_id.clone(),
+                            step_id: before.step_id.clone(),
                             error: None,
                             log: Vec::new(),
+                            frame_id: None,
+                            input: None,
+                            locator: before
+                                .params
+                                .get("selector")
+                                .and_then(|selector| selector.as_str())
+                                .map(str::to_string),
+                            stack: before.stack.clone(),
                         };
 
                         if action.start_time < context.start_time {
                             context.start_time = action.start_time;
                         }
 
+                        if let Some(page_id) = &action.page_id {
+                            page_entry(&mut pages, page_id);
+                        }
+
                         actions_map.insert(before.call_id.clone(), action);
                     }
                     TraceEvent::After(after) => {
@@ -248,60 +655,130 @@ fn parse_trace(
                             }
                         }
                     }
+                    TraceEvent::Input(input) => {
+                        if let Some(action) = actions_map.get_mut(&input.call_id) {
+                            action.input = Some(ActionInput {
+                                point: input.point,
+                                snapshot: input.input_snapshot.clone(),
+                            });
+                        }
+                    }
                     TraceEvent::ScreencastFrame(frame) => {
-                        let page =
-                            pages
-                                .entry(frame.page_id.clone())
-                                .or_insert_with(|| PageEntry {
-                                    page_id: frame.page_id.clone(),
-                                    screencast_frames: Vec::new(),
-                                });
+                        let page = page_entry(&mut pages, &frame.page_id);
 
                         page.screencast_frames.push(ScreencastFrame {
                             sha1: frame.sha1.clone(),
                             timestamp: frame.timestamp,
                             width: frame.width,
                             height: frame.height,
-                            frame_swap_wall_time: None,
+                            frame_swap_wall_time: frame.frame_swap_wall_time,
                         });
                     }
+                    TraceEvent::Error(error) => errors.push(ErrorEvent {
+                        message: error.message.clone(),
+                        stack: format_stack(&error.stack),
+                        page_error: false,
+                    }),
+                    TraceEvent::Stdout(output) => {
+                        stdio.push(stdio_entry(StdioStream::Stdout, output))
+                    }
+                    TraceEvent::Stderr(output) => {
+                        stdio.push(stdio_entry(StdioStream::Stderr, output))
+                    }
+                    TraceEvent::Console(message) => console.push(ConsoleMessage::from(message)),
+                    TraceEvent::FrameSnapshot(frame_snapshot) => {
+                        let snapshot = &frame_snapshot.snapshot;
+                        let page = page_entry(&mut pages, &snapshot.page_id);
+                        let frame = page_frame(page, &snapshot.frame_id);
+                        frame.is_main_frame |= snapshot.is_main_frame;
+                        if snapshot.frame_url.is_some() {
+                            frame.url = snapshot.frame_url.clone();
+                        }
+                        if snapshot.is_main_frame {
+                            page.viewport = snapshot.viewport.or(page.viewport);
+                            // Later snapshots refer back to an unchanged title
+                            if snapshot.title.is_some() {
+                                page.title = snapshot.title.clone();
+                            }
+                        }
+
+                        // The first snapshot of an action is taken in its target frame
+                        if let Some(call_id) = &snapshot.call_id {
+                            action_frames
+                                .entry(call_id.clone())
+                                .or_insert_with(|| snapshot.frame_id.clone());
+                        }
+                    }
+                    TraceEvent::Event(object_event) if is_frame_event(object_event) => {
+                        if let Some(page_id) = object_page_id(object_event) {
+                            let page = page_entry(&mut pages, page_id);
+                            apply_frame_event(page, object_event);
+                        }
+                    }
+                    // A new page in the context
+                    TraceEvent::Event(object_event) if object_event.method == "page" => {
+                        if let Some(page_id) = object_page_id(object_event) {
+                            page_entry(&mut pages, page_id);
+                        }
+                    }
+                    TraceEvent::Event(object_event) if object_event.method == "pageError" => {
+                        errors.extend(page_error(&object_event.params));
+                    }
+                    TraceEvent::Event(object_event) => {
+                        page_events.extend(PageEvent::from_event(object_event));
+                    }
                     _ => {}
                 }
                 events.push(event);
             }
-            Err(e) => {
-                log::warn!("Failed to parse trace event: {} - Line: {}", e, line);
-            }
         }
     }
 
-    // Parse network file if present
-    if let Some(network) = network_content {
-        for line in network.lines() {
+    // Parse network files if present
+    for (network_name, network) in networks {
+        for (index, line) in network.lines().enumerate() {
             let line = line.trim();
             if line.is_empty() {
                 continue;
             }
 
             // Network events are also parsed as trace events
-            if let Ok(event) = serde_json::from_str::<TraceEvent>(line) {
+            for event in read_events(&mut reader, (network_name, index), line, warnings)? {
+                if let TraceEvent::ResourceSnapshot(resource) = &event {
+                    context.resources.push(resource.snapshot.clone());
+                }
                 events.push(event);
             }
         }
     }
 
+    for (call_id, frame_id) in action_frames {
+        if let Some(action) = actions_map.get_mut(&call_id) {
+            action.frame_id = Some(frame_id);
+        }
+    }
+
     // Convert maps to vectors
     context.actions = actions_map.into_values().collect();
 
     context
         .actions
-        .sort_by(|a, b| a.start_time.partial_cmp(&b.start_time).unwrap());
-
-    context.pages = pages.into_values().collect();
+        .sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
 
+    context.pages = pages;
     context.events = events;
     context.errors = errors;
 
+    for entry in &mut stdio {
+        entry.call_id = context
+            .nearest_action(entry.timestamp)
+            .map(|action| action.call_id.clone());
+    }
+    context.stdio = stdio;
+    context.page_events = page_events;
+    context.console = console;
+    context.stats = compute_stats(&context);
+
     log::info!(
         "Parsed {} actions, {} pages",
         context.actions.len(),
@@ -0,0 +1,124 @@
+//! Triage notes on test cases
+//! Notes belong to the reader, not the report: they are kept in the browser's
+//! localStorage keyed by the test id without retry suffix, so they survive
+//! reloads and apply to later runs of the same test
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the localStorage keys, followed by the test id
+const STORAGE_PREFIX: &str = "trace-viewer:triage:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriageStatus {
+    Investigating,
+    KnownIssue,
+    Fixed,
+}
+
+impl TriageStatus {
+    pub const ALL: [TriageStatus; 3] = [
+        TriageStatus::Investigating,
+        TriageStatus::KnownIssue,
+        TriageStatus::Fixed,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TriageStatus::Investigating => "Investigating",
+            TriageStatus::KnownIssue => "Known issue",
+            TriageStatus::Fixed => "Fixed",
+        }
+    }
+}
+
+/// Triage state of one test
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Triage {
+    #[serde(default)]
+    pub status: Option<TriageStatus>,
+    /// Free-text note
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Triage {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.note.trim().is_empty()
+    }
+
+    /// One-line form for exports, e.g. `Known issue: fails on CI only`
+    pub fn summary(&self) -> Option<String> {
+        let note = self.note.trim();
+        match (self.status, note.is_empty()) {
+            (None, true) => None,
+            (None, false) => Some(note.to_string()),
+            (Some(status), true) => Some(status.label().to_string()),
+            (Some(status), false) => Some(format!("{}: {}", status.label(), note)),
+        }
+    }
+}
+
+/// Stored triage of a test, if any
+pub fn load_triage(test_id: &str) -> Option<Triage> {
+    LocalStorage::get(storage_key(test_id)).ok()
+}
+
+/// Store the triage of a test; an empty triage removes the entry
+pub fn save_triage(test_id: &str, triage: &Triage) {
+    let key = storage_key(test_id);
+    if triage.is_empty() {
+        LocalStorage::delete(key);
+    } else if let Err(e) = LocalStorage::set(key, triage) {
+        log::warn!("Failed to save triage of {}: {}", test_id, e);
+    }
+}
+
+fn storage_key(test_id: &str) -> String {
+    format!("{}{}", STORAGE_PREFIX, test_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triage_summary() {
+        assert_eq!(Triage::default().summary(), None);
+        assert!(Triage {
+            status: None,
+            note: "  ".to_string()
+        }
+        .is_empty());
+
+        let known = Triage {
+            status: Some(TriageStatus::KnownIssue),
+            note: " fails on CI only ".to_string(),
+        };
+        assert_eq!(
+            known.summary().as_deref(),
+            Some("Known issue: fails on CI only")
+        );
+
+        let note_only = Triage {
+            status: None,
+            note: "flaky network".to_string(),
+        };
+        assert_eq!(note_only.summary().as_deref(), Some("flaky network"));
+    }
+
+    #[test]
+    fn test_triage_round_trip() {
+        let triage = Triage {
+            status: Some(TriageStatus::Investigating),
+            note: "looking into it".to_string(),
+        };
+        let json = serde_json::to_string(&triage).unwrap();
+
+        assert_eq!(serde_json::from_str::<Triage>(&json).unwrap(), triage);
+        assert_eq!(
+            serde_json::from_str::<Triage>("{}").unwrap(),
+            Triage::default()
+        );
+    }
+}
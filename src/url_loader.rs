@@ -0,0 +1,200 @@
+//! Loading traces and reports over HTTP
+//! The response body is streamed so the loading screen can follow the bytes
+//! received when the server reports a length. Browsers hide why a
+//! cross-origin request failed, so network errors are explained in terms of
+//! CORS, the usual cause when the URL itself is reachable
+
+use crate::file_reader::ReadGuard;
+use base64::{engine::general_purpose, Engine as _};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{Headers, ReadableStreamDefaultReader, RequestInit, RequestMode, Response};
+
+/// Name used when the URL path does not end in a file name
+const DEFAULT_FILE_NAME: &str = "trace.zip";
+
+/// Trace or report to fetch
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlLoad {
+    pub url: String,
+    /// Token for the `Authorization` header, as typed by the user
+    pub token: Option<String>,
+}
+
+impl UrlLoad {
+    pub fn file_name(&self) -> String {
+        file_name_from_url(&self.url)
+    }
+}
+
+/// Last path segment of a URL, without query or fragment
+pub fn file_name_from_url(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    path.split_once('/')
+        .and_then(|(_, path)| path.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or(DEFAULT_FILE_NAME)
+        .to_string()
+}
+
+/// `Authorization` header value for a token: values with a scheme are kept,
+/// `user:password` becomes basic auth and anything else a bearer token
+pub fn authorization_header(token: &str) -> Option<String> {
+    let token = token.trim();
+    if token.is_empty() {
+        None
+    } else if token.contains(' ') {
+        Some(token.to_string())
+    } else if token.contains(':') {
+        Some(format!("Basic {}", general_purpose::STANDARD.encode(token)))
+    } else {
+        Some(format!("Bearer {}", token))
+    }
+}
+
+/// Fetch the URL
+/// `on_progress` gets the fraction received when the length is known,
+/// `on_done` the body or the reason the request failed; neither is called once
+/// the guard is dropped
+pub fn fetch_bytes(
+    load: UrlLoad,
+    on_progress: impl Fn(f32) + 'static,
+    on_done: impl FnOnce(Result<Vec<u8>, String>) + 'static,
+) -> ReadGuard {
+    let (guard, cancelled) = ReadGuard::new();
+
+    spawn_local(async move {
+        let result = fetch(&load, &|fraction| {
+            if !cancelled.get() {
+                on_progress(fraction);
+            }
+        })
+        .await;
+
+        if !cancelled.get() {
+            on_done(result);
+        }
+    });
+
+    guard
+}
+
+async fn fetch(load: &UrlLoad, on_progress: &dyn Fn(f32)) -> Result<Vec<u8>, String> {
+    let window = web_sys::window().ok_or("No window to fetch from")?;
+
+    let init = RequestInit::new();
+    init.set_mode(RequestMode::Cors);
+    if let Some(value) = load.token.as_deref().and_then(authorization_header) {
+        let headers = Headers::new().map_err(js_error)?;
+        headers.set("Authorization", &value).map_err(js_error)?;
+        init.set_headers(&headers);
+    }
+
+    let response = JsFuture::from(window.fetch_with_str_and_init(&load.url, &init))
+        .await
+        .map_err(|e| network_error(&load.url, e))?;
+    let response: Response = response.unchecked_into();
+
+    if !response.ok() {
+        return Err(status_error(response.status(), &response.status_text()));
+    }
+
+    let length = response
+        .headers()
+        .get("Content-Length")
+        .ok()
+        .flatten()
+        .and_then(|length| length.parse::<usize>().ok());
+    let Some(body) = response.body() else {
+        return Ok(Vec::new());
+    };
+
+    let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+    let mut bytes = Vec::with_capacity(length.unwrap_or_default());
+    loop {
+        let chunk = JsFuture::from(reader.read())
+            .await
+            .map_err(|e| network_error(&load.url, e))?;
+        let done = js_sys::Reflect::get(&chunk, &JsValue::from_str("done"))
+            .map(|done| done.is_truthy())
+            .unwrap_or(true);
+        if done {
+            break;
+        }
+
+        if let Ok(value) = js_sys::Reflect::get(&chunk, &JsValue::from_str("value")) {
+            bytes.extend(js_sys::Uint8Array::new(&value).to_vec());
+        }
+        if let Some(length) = length.filter(|length| *length > 0) {
+            on_progress((bytes.len() as f32 / length as f32).min(1.0));
+        }
+    }
+
+    Ok(bytes)
+}
+
+fn status_error(status: u16, status_text: &str) -> String {
+    match status {
+        401 | 403 => format!(
+            "The server refused the request ({} {}). Check the authorization token.",
+            status, status_text
+        ),
+        404 => "Nothing was found at this URL (404).".to_string(),
+        _ => format!("The server answered {} {}", status, status_text),
+    }
+}
+
+/// Fetch rejects with an opaque `TypeError` for blocked cross-origin requests
+fn network_error(url: &str, error: JsValue) -> String {
+    format!(
+        "Could not download {} ({}). The server may not allow this page to read it \
+         (CORS): it has to answer with an Access-Control-Allow-Origin header, and \
+         allow the Authorization header when a token is set.",
+        url,
+        js_error(error)
+    )
+}
+
+fn js_error(error: JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .unwrap_or_else(|| format!("{:?}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_from_url() {
+        assert_eq!(
+            file_name_from_url("https://ci.test/artifacts/trace.zip?token=1#x"),
+            "trace.zip"
+        );
+        assert_eq!(
+            file_name_from_url("https://ci.test/runs/42/report.zip"),
+            "report.zip"
+        );
+        assert_eq!(file_name_from_url("https://ci.test/"), DEFAULT_FILE_NAME);
+        assert_eq!(file_name_from_url("https://ci.test"), DEFAULT_FILE_NAME);
+    }
+
+    #[test]
+    fn test_authorization_header() {
+        assert_eq!(authorization_header("  "), None);
+        assert_eq!(
+            authorization_header("abc123").as_deref(),
+            Some("Bearer abc123")
+        );
+        assert_eq!(
+            authorization_header("ci:secret").as_deref(),
+            Some("Basic Y2k6c2VjcmV0")
+        );
+        assert_eq!(
+            authorization_header("token ghp_x").as_deref(),
+            Some("token ghp_x")
+        );
+    }
+}
@@ -0,0 +1,5 @@
+// Installing the viewer as an app needs a service worker; traces are read
+// locally, so requests go straight to the network
+self.addEventListener('install', () => self.skipWaiting());
+self.addEventListener('activate', (event) => event.waitUntil(self.clients.claim()));
+self.addEventListener('fetch', () => {});
@@ -0,0 +1,201 @@
+use std::io::{Cursor, Write};
+use trace_viewer::blob_report_loader::{is_blob_report, load_test_cases_from_blob_report};
+use trace_viewer::models::{TestCase, TestStatus};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+fn project_event(project: &str, test_id: &str, title: &str) -> String {
+    serde_json::json!({
+        "method": "onProject",
+        "params": {
+            "project": {
+                "name": project,
+                "suites": [{
+                    "title": "checkout.spec.ts",
+                    "entries": [{
+                        "title": "Checkout",
+                        "entries": [{
+                            "testId": test_id,
+                            "title": title,
+                            "tags": ["smoke"],
+                            "annotations": [{ "type": "issue", "description": "#1234" }]
+                        }]
+                    }]
+                }]
+            }
+        }
+    })
+    .to_string()
+}
+
+fn test_begin(test_id: &str, result_id: &str, retry: usize) -> String {
+    serde_json::json!({
+        "method": "onTestBegin",
+        "params": { "testId": test_id, "result": { "id": result_id, "retry": retry } }
+    })
+    .to_string()
+}
+
+fn test_end(test_id: &str, result_id: &str, status: &str, error: Option<&str>) -> String {
+    let errors: Vec<_> = error
+        .map(
+            |message| serde_json::json!({ "message": message, "stack": "at checkout.spec.ts:7:3" }),
+        )
+        .into_iter()
+        .collect();
+
+    serde_json::json!({
+        "method": "onTestEnd",
+        "params": {
+            "test": { "testId": test_id },
+            "result": { "id": result_id, "duration": 1200, "status": status, "errors": errors }
+        }
+    })
+    .to_string()
+}
+
+fn build_blob(lines: &[String], resources: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        zip.start_file("report.jsonl", FileOptions::default())
+            .unwrap();
+        zip.write_all(lines.join("\n").as_bytes()).unwrap();
+
+        for (name, data) in resources {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+    buf
+}
+
+fn shard_one() -> Vec<u8> {
+    build_blob(
+        &[
+            r#"{"method":"onBlobReportMetadata","params":{"version":2,"name":"shard 1"}}"#
+                .to_string(),
+            project_event("chromium", "t1", "pays by card"),
+            test_begin("t1", "r1", 0),
+            r#"{"method":"onStdIO","params":{"type":"stdout","testId":"t1","resultId":"r1","data":"charging card\n","isBase64":false}}"#
+                .to_string(),
+            r#"{"method":"onAttach","params":{"testId":"t1","resultId":"r1","attachments":[{"name":"screenshot","contentType":"image/png","path":"resources/abc.png"}]}}"#
+                .to_string(),
+            test_end("t1", "r1", "failed", Some("Error: card declined\n\nCall log:")),
+            test_begin("t1", "r2", 1),
+            test_end("t1", "r2", "passed", None),
+        ],
+        &[("resources/abc.png", &[0x89, 0x50, 0x4E, 0x47])],
+    )
+}
+
+fn shard_two() -> Vec<u8> {
+    build_blob(
+        &[
+            project_event("chromium", "t2", "applies coupon"),
+            test_begin("t2", "r1", 0),
+            test_end(
+                "t2",
+                "r1",
+                "timedOut",
+                Some("Test timeout of 30000ms exceeded."),
+            ),
+        ],
+        &[],
+    )
+}
+
+fn find<'a>(test_cases: &'a [TestCase], id: &str) -> &'a TestCase {
+    test_cases
+        .iter()
+        .find(|tc| tc.id == id)
+        .unwrap_or_else(|| panic!("Test case {} not found", id))
+}
+
+#[test]
+fn test_load_blob_report() {
+    let bytes = shard_one();
+    assert!(is_blob_report(&bytes));
+
+    let test_cases = load_test_cases_from_blob_report(&bytes).unwrap().test_cases;
+    assert_eq!(test_cases.len(), 2);
+
+    let first = find(&test_cases, "t1");
+    assert_eq!(
+        first.name,
+        "checkout.spec.ts › Checkout › pays by card (chromium)"
+    );
+    assert_eq!(first.suite_path, vec!["checkout.spec.ts", "Checkout"]);
+    assert_eq!(first.status, TestStatus::Failed);
+    assert_eq!(first.duration_ms, Some(1200.0));
+    assert_eq!(first.error_message.as_deref(), Some("Error: card declined"));
+
+    let retry = find(&test_cases, "t1-retry1");
+    assert_eq!(retry.status, TestStatus::Passed);
+}
+
+#[test]
+fn test_blob_report_attachments() {
+    let test_cases = load_test_cases_from_blob_report(&shard_one())
+        .unwrap()
+        .test_cases;
+    let first = find(&test_cases, "t1");
+
+    assert_eq!(first.screenshots.len(), 1);
+    assert_eq!(first.screenshots[0].name, "abc.png");
+    assert_eq!(first.screenshots[0].size_bytes, Some(4));
+
+    let names: Vec<_> = first
+        .text_attachments
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["error.txt", "stdout"]);
+    assert!(first.text_attachments[0]
+        .content
+        .contains("checkout.spec.ts:7:3"));
+    assert_eq!(first.text_attachments[1].content, "charging card\n");
+}
+
+#[test]
+fn test_merge_sharded_blob_reports() {
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        for (name, shard) in [("report-2.zip", shard_two()), ("report-1.zip", shard_one())] {
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(&shard).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    assert!(is_blob_report(&buf));
+
+    let test_cases = load_test_cases_from_blob_report(&buf).unwrap().test_cases;
+    let ids: Vec<_> = test_cases.iter().map(|tc| tc.id.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "t1-retry1", "t2"]);
+    assert_eq!(find(&test_cases, "t2").status, TestStatus::Failed);
+}
+
+#[test]
+fn test_trace_archive_is_not_blob_report() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    assert!(!is_blob_report(trace_bytes));
+
+    let test_cases = include_bytes!("fixtures/test-cases.zip");
+    assert!(!is_blob_report(test_cases));
+}
+
+#[test]
+fn test_blob_report_tags_and_annotations() {
+    let test_cases = load_test_cases_from_blob_report(&shard_one())
+        .unwrap()
+        .test_cases;
+    let first = find(&test_cases, "t1");
+
+    // Tags get the `@` prefix shown in the Playwright UI
+    assert_eq!(first.tags, vec!["@smoke"]);
+    assert_eq!(first.annotations[0].annotation_type, "issue");
+    assert_eq!(first.annotations[0].description.as_deref(), Some("#1234"));
+}
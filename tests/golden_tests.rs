@@ -0,0 +1,166 @@
+//! Snapshot tests for exporters
+//! Outputs are compared against files in `tests/golden/`. After an intended
+//! format change, regenerate them with `UPDATE_GOLDEN=1 cargo test --test golden_tests`
+//! and review the diff.
+
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use trace_viewer::jira_exporter::export_to_jira;
+use trace_viewer::markdown_exporter::{
+    export_markdown_bundle, export_to_markdown, ExportOptions, MarkdownFlavor, BUNDLE_REPORT_NAME,
+};
+use trace_viewer::models::TraceModel;
+use trace_viewer::paths::detect_workspace_root;
+use trace_viewer::slack_exporter::export_to_slack;
+use trace_viewer::trace_loader::load_trace_from_zip;
+
+/// Remove fields that depend on where and when the export runs
+fn normalize(output: &str) -> String {
+    output
+        .replace("\r\n", "\n")
+        .split('\n')
+        .map(|line| match line.find("**Start Time**: ") {
+            Some(index) => format!("{}**Start Time**: <start-time>", &line[..index]),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    let actual = normalize(actual);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &actual).expect("Failed to write golden file");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing golden file {}; run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+
+    assert!(
+        normalize(&expected) == actual,
+        "Output differs from {}; run with UPDATE_GOLDEN=1 to update it\n\n{}",
+        path.display(),
+        actual
+    );
+}
+
+fn sample_trace() -> TraceModel {
+    load_trace_from_zip(include_bytes!("fixtures/sample-trace.zip")).unwrap()
+}
+
+#[test]
+fn test_markdown_export_golden() {
+    let markdown = export_to_markdown(&sample_trace(), &ExportOptions::default());
+    assert_golden("sample-trace.md", &markdown);
+}
+
+#[test]
+fn test_markdown_export_errors_only_golden() {
+    let options = ExportOptions {
+        errors_only: true,
+        ..Default::default()
+    };
+    let markdown = export_to_markdown(&sample_trace(), &options);
+    assert_golden("sample-trace-errors-only.md", &markdown);
+}
+
+#[test]
+fn test_markdown_export_short_paths_golden() {
+    let model = sample_trace();
+    let options = ExportOptions {
+        path_root: detect_workspace_root(&model),
+        ..Default::default()
+    };
+    let markdown = export_to_markdown(&model, &options);
+    assert_golden("sample-trace-short-paths.md", &markdown);
+}
+
+#[test]
+fn test_markdown_bundle_golden() {
+    let model = sample_trace();
+    let bundle = export_markdown_bundle(&model, &ExportOptions::default()).unwrap();
+    let mut archive = zip::ZipArchive::new(Cursor::new(bundle)).unwrap();
+
+    let mut markdown = String::new();
+    archive
+        .by_name(BUNDLE_REPORT_NAME)
+        .unwrap()
+        .read_to_string(&mut markdown)
+        .unwrap();
+    assert_golden("sample-trace-bundle.md", &markdown);
+
+    // Every linked screenshot ships with the report
+    let links: Vec<&str> = markdown
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("![")?
+                .split_once("](")?
+                .1
+                .strip_suffix(')')
+        })
+        .collect();
+    assert!(!links.is_empty());
+    for link in links {
+        let sha1 = link.strip_prefix("screenshots/").unwrap();
+        let mut bytes = Vec::new();
+        archive
+            .by_name(link)
+            .unwrap()
+            .read_to_end(&mut bytes)
+            .unwrap();
+        assert_eq!(Some(bytes), model.resource_store.get(sha1));
+    }
+}
+
+#[test]
+fn test_markdown_export_frontmatter_golden() {
+    let options = ExportOptions {
+        frontmatter: true,
+        trace_file: Some("sample-trace.zip".to_string()),
+        ..Default::default()
+    };
+    let markdown = export_to_markdown(&sample_trace(), &options);
+    assert_golden("sample-trace-frontmatter.md", &markdown);
+}
+
+#[test]
+fn test_markdown_export_github_golden() {
+    let options = ExportOptions {
+        flavor: MarkdownFlavor::GitHub,
+        ..Default::default()
+    };
+    let markdown = export_to_markdown(&sample_trace(), &options);
+    assert_golden("sample-trace-github.md", &markdown);
+}
+
+#[test]
+fn test_markdown_export_slack_golden() {
+    let slack = export_to_slack(&sample_trace(), &ExportOptions::default());
+    assert_golden("sample-trace-slack.md", &slack);
+}
+
+#[test]
+fn test_markdown_export_jira_golden() {
+    let jira = export_to_jira(&sample_trace(), &ExportOptions::default());
+    assert_golden("sample-trace-jira.txt", &jira);
+}
+
+#[test]
+fn test_markdown_export_contents_golden() {
+    let options = ExportOptions {
+        table_of_contents: true,
+        ..Default::default()
+    };
+    let markdown = export_to_markdown(&sample_trace(), &options);
+    assert_golden("sample-trace-contents.md", &markdown);
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use trace_viewer::json_report_loader::{is_json_report, load_test_cases_from_json_report};
+use trace_viewer::models::{TestCase, TestStatus};
+use trace_viewer::test_case_loader::TestCaseLoadError;
+
+fn load_fixture() -> Vec<TestCase> {
+    let bytes = fs::read("tests/fixtures/results.json").expect("Failed to read test file");
+    load_test_cases_from_json_report(&bytes)
+        .expect("Failed to load JSON report")
+        .test_cases
+}
+
+fn find<'a>(test_cases: &'a [TestCase], id: &str) -> &'a TestCase {
+    test_cases
+        .iter()
+        .find(|tc| tc.id == id)
+        .unwrap_or_else(|| panic!("Test case {} not found", id))
+}
+
+#[test]
+fn test_load_json_report() {
+    let test_cases = load_fixture();
+
+    // One entry per result, retries included
+    assert_eq!(test_cases.len(), 4);
+}
+
+#[test]
+fn test_json_report_names_and_ids() {
+    let test_cases = load_fixture();
+
+    let first = find(&test_cases, "a1b2c3-chromium");
+    assert_eq!(
+        first.name,
+        "login.spec.ts › Login › shows the dashboard (chromium)"
+    );
+
+    let retry = find(&test_cases, "a1b2c3-chromium-retry1");
+    assert!(retry.name.ends_with("retry 1"));
+    assert_eq!(retry.project.as_deref(), Some("chromium"));
+}
+
+#[test]
+fn test_json_report_status_and_duration() {
+    let test_cases = load_fixture();
+
+    let first = find(&test_cases, "a1b2c3-chromium");
+    assert_eq!(first.status, TestStatus::Failed);
+    assert_eq!(first.duration_ms, Some(1520.0));
+
+    assert_eq!(
+        find(&test_cases, "a1b2c3-chromium-retry1").status,
+        TestStatus::Passed
+    );
+    assert_eq!(
+        find(&test_cases, "d4e5f6-chromium").status,
+        TestStatus::Skipped
+    );
+
+    let timed_out = find(&test_cases, "g7h8i9-chromium");
+    assert_eq!(timed_out.status, TestStatus::Failed);
+    assert_eq!(
+        timed_out.error_message.as_deref(),
+        Some("Test timeout of 30000ms exceeded.")
+    );
+}
+
+#[test]
+fn test_json_report_errors_and_attachments() {
+    let test_cases = load_fixture();
+
+    let first = find(&test_cases, "a1b2c3-chromium");
+    assert!(first
+        .error_message
+        .as_deref()
+        .unwrap()
+        .contains("toBeVisible() failed"));
+
+    // Inline screenshots are kept, attachments stored as files are skipped
+    assert_eq!(first.screenshots.len(), 1);
+    assert!(first.screenshots[0]
+        .data_url
+        .starts_with("data:image/png;base64,"));
+    assert!(first.trace_file.is_none());
+
+    // The full error with stack is available as an attachment
+    assert_eq!(first.text_attachments[0].name, "error.txt");
+    assert!(first.text_attachments[0]
+        .content
+        .contains("login.spec.ts:9:5"));
+
+    let retry = find(&test_cases, "a1b2c3-chromium-retry1");
+    assert_eq!(retry.text_attachments[0].name, "stdout");
+    assert_eq!(retry.text_attachments[0].content, "logged in");
+}
+
+#[test]
+fn test_is_json_report() {
+    let bytes = fs::read("tests/fixtures/results.json").unwrap();
+    assert!(is_json_report(&bytes));
+
+    let zip = fs::read("tests/fixtures/test-cases.zip").unwrap();
+    assert!(!is_json_report(&zip));
+}
+
+#[test]
+fn test_invalid_json_report() {
+    let result = load_test_cases_from_json_report(b"{ not json");
+    assert!(matches!(result, Err(TestCaseLoadError::ParseError(_))));
+}
+
+#[test]
+fn test_json_report_tags_and_annotations() {
+    let test_cases = load_fixture();
+
+    assert_eq!(find(&test_cases, "a1b2c3-chromium").tags, vec!["@smoke"]);
+    assert!(find(&test_cases, "g7h8i9-chromium").tags.is_empty());
+
+    let skipped = find(&test_cases, "d4e5f6-chromium");
+    assert_eq!(skipped.tags, vec!["@mobile"]);
+    assert_eq!(skipped.annotations.len(), 1);
+    assert_eq!(skipped.annotations[0].annotation_type, "skip");
+    assert_eq!(
+        skipped.annotations[0].description.as_deref(),
+        Some("Cart is desktop only")
+    );
+}
+
+#[test]
+fn test_json_report_suite_path() {
+    let test_cases = load_fixture();
+
+    assert_eq!(
+        find(&test_cases, "a1b2c3-chromium").suite_path,
+        vec!["login.spec.ts", "Login"]
+    );
+    assert_eq!(
+        find(&test_cases, "g7h8i9-chromium").suite_path,
+        vec!["cart.spec.ts"]
+    );
+}
@@ -1,4 +1,3 @@
-use serde_json;
 use trace_viewer::models::*;
 
 #[test]
@@ -151,8 +150,13 @@ fn test_action_entry_serialization() {
         params: std::collections::HashMap::new(),
         page_id: Some("page@1".to_string()),
         parent_id: None,
+        step_id: None,
         error: None,
         log: vec![],
+        frame_id: None,
+        input: None,
+        locator: None,
+        stack: vec![],
     };
 
     let json = serde_json::to_string(&action).unwrap();
@@ -166,18 +170,24 @@ fn test_action_entry_serialization() {
 #[test]
 fn test_context_entry_with_pages() {
     let mut context = ContextEntry {
+        id: String::new(),
         start_time: 0.0,
         end_time: 5000.0,
         browser_name: "chromium".to_string(),
         platform: Some("linux".to_string()),
         playwright_version: Some("1.40.0".to_string()),
         wall_time: 1700000000000.0,
+        monotonic_time: 0.0,
         title: Some("Test".to_string()),
         pages: vec![],
         actions: vec![],
         resources: vec![],
         events: vec![],
         errors: vec![],
+        stdio: vec![],
+        page_events: vec![],
+        console: vec![],
+        stats: Default::default(),
     };
 
     let page = PageEntry {
@@ -189,6 +199,9 @@ fn test_context_entry_with_pages() {
             height: 720,
             frame_swap_wall_time: None,
         }],
+        frames: vec![],
+        title: None,
+        viewport: None,
     };
 
     context.pages.push(page);
@@ -226,19 +239,137 @@ fn test_log_entry() {
 }
 
 #[test]
-fn test_resource_snapshot() {
-    let resource = ResourceSnapshot {
-        url: "https://example.com/script.js".to_string(),
-        content_type: Some("application/javascript".to_string()),
-        sha1: Some("abc123".to_string()),
+fn test_network_entry_from_har() {
+    let json = r#"{
+        "pageref": "page@1",
+        "_frameref": "frame@2",
+        "startedDateTime": "2025-11-14T19:25:24.876Z",
+        "time": 6.5,
+        "_monotonicTime": 3603.2,
+        "request": {
+            "method": "POST",
+            "url": "https://example.com/api/cart",
+            "headers": [{"name": "Content-Type", "value": "application/json"}],
+            "postData": {"mimeType": "application/json", "text": "{\"id\":1}"},
+            "bodySize": 8
+        },
+        "response": {
+            "status": 500,
+            "statusText": "Internal Server Error",
+            "headers": [{"name": "content-type", "value": "application/json"}],
+            "content": {"size": 1024, "mimeType": "application/json", "_sha1": "abc123.json"},
+            "redirectURL": "",
+            "_transferSize": 1200
+        },
+        "timings": {"dns": 0.1, "connect": -1, "wait": 4.0, "receive": 2.4},
+        "serverIPAddress": "127.0.0.1"
+    }"#;
+
+    let entry: NetworkEntry = serde_json::from_str(json).unwrap();
+
+    assert_eq!(entry.page_id.as_deref(), Some("page@1"));
+    assert_eq!(entry.frame_id.as_deref(), Some("frame@2"));
+    assert_eq!(entry.start_time, Some(3603.2));
+    assert_eq!(entry.duration, Some(6.5));
+    assert_eq!(
+        (entry.method(), entry.url()),
+        ("POST", "https://example.com/api/cart")
+    );
+    assert_eq!(entry.status(), Some(500));
+    assert!(entry.is_failed());
+    assert_eq!(entry.content_type(), Some("application/json"));
+    assert_eq!(entry.body_sha1(), Some("abc123.json"));
+    assert_eq!(entry.body_size(), Some(1024));
+    assert_eq!(entry.response.transfer_size, Some(1200));
+    assert_eq!(
+        entry.request_header("content-type"),
+        Some("application/json")
+    );
+    assert_eq!(
+        entry.response_header("Content-Type"),
+        Some("application/json")
+    );
+    assert_eq!(
+        entry
+            .request
+            .post_data
+            .as_ref()
+            .and_then(|data| data.text.as_deref()),
+        Some("{\"id\":1}")
+    );
+    assert_eq!(entry.timings.wait, Some(4.0));
+    assert_eq!(entry.timings.ssl, None);
+
+    let json = serde_json::to_string(&entry).unwrap();
+    let deserialized: NetworkEntry = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, entry);
+}
+
+#[test]
+fn test_network_entry_without_response() {
+    let entry = NetworkEntry {
+        response: NetworkResponse {
+            status: -1,
+            failure_text: Some("net::ERR_CONNECTION_REFUSED".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
     };
 
-    let json = serde_json::to_string(&resource).unwrap();
-    let deserialized: ResourceSnapshot = serde_json::from_str(&json).unwrap();
+    assert!(entry.is_failed());
+    assert_eq!(entry.status(), None);
+    assert_eq!(entry.body_size(), None);
+}
+
+#[test]
+fn test_console_message_from_event() {
+    let json = r#"{
+        "type": "console",
+        "messageType": "warning",
+        "text": "Deprecated API",
+        "args": [{"preview": "Deprecated API", "value": "Deprecated API"}],
+        "location": {"url": "http://localhost:8080/index.js", "lineNumber": 33, "columnNumber": 16},
+        "time": 3650.268,
+        "pageId": "page@1"
+    }"#;
 
-    assert_eq!(deserialized.url, resource.url);
-    assert_eq!(deserialized.content_type, resource.content_type);
-    assert_eq!(deserialized.sha1, resource.sha1);
+    let TraceEvent::Console(event) = serde_json::from_str(json).unwrap() else {
+        panic!("expected a console event");
+    };
+    let message = ConsoleMessage::from(&event);
+
+    assert_eq!(message.severity, ConsoleSeverity::Warning);
+    assert_eq!(message.text, "Deprecated API");
+    assert_eq!(message.timestamp, 3650.268);
+    assert_eq!(message.page_id.as_deref(), Some("page@1"));
+    assert_eq!(
+        message.location.map(|location| location.label()).as_deref(),
+        Some("http://localhost:8080/index.js:33:16")
+    );
+
+    assert_eq!(
+        ConsoleSeverity::from_message_type("assert"),
+        ConsoleSeverity::Error
+    );
+    assert_eq!(
+        ConsoleSeverity::from_message_type("table"),
+        ConsoleSeverity::Log
+    );
+}
+
+#[test]
+fn test_document_title() {
+    let html = serde_json::json!([
+        "HTML", {"lang": "en"},
+        ["HEAD", {}, ["BASE", {"href": "http://localhost/"}], "\n", ["TITLE", {}, " Checkout "]],
+        ["BODY"]
+    ]);
+    assert_eq!(document_title(&html).as_deref(), Some("Checkout"));
+
+    // Unchanged nodes refer back to an earlier snapshot
+    let html = serde_json::json!(["HTML", {"lang": "en"}, [[1, 12]], ["BODY"]]);
+    assert_eq!(document_title(&html), None);
+    assert_eq!(document_title(&serde_json::json!([[1, 4]])), None);
 }
 
 #[test]
@@ -273,11 +404,415 @@ fn test_action_with_params() {
         params,
         page_id: Some("page@1".to_string()),
         parent_id: None,
+        step_id: None,
         error: None,
         log: vec![],
+        frame_id: None,
+        input: None,
+        locator: None,
+        stack: vec![],
     };
 
     assert_eq!(action.params.len(), 3);
     assert!(action.params.contains_key("url"));
     assert!(action.params.contains_key("timeout"));
 }
+
+fn frame(timestamp: f64) -> ScreencastFrame {
+    ScreencastFrame {
+        sha1: format!("frame-{}", timestamp),
+        timestamp,
+        width: 1280,
+        height: 720,
+        frame_swap_wall_time: None,
+    }
+}
+
+fn context_with_frames(frames: Vec<ScreencastFrame>) -> ContextEntry {
+    ContextEntry {
+        id: String::new(),
+        start_time: 0.0,
+        end_time: 5000.0,
+        browser_name: "chromium".to_string(),
+        platform: None,
+        playwright_version: None,
+        wall_time: 0.0,
+        monotonic_time: 0.0,
+        title: None,
+        pages: vec![PageEntry {
+            page_id: "page@1".to_string(),
+            screencast_frames: frames,
+            frames: vec![],
+            title: None,
+            viewport: None,
+        }],
+        actions: vec![],
+        resources: vec![],
+        events: vec![],
+        errors: vec![],
+        stdio: vec![],
+        page_events: vec![],
+        console: vec![],
+        stats: Default::default(),
+    }
+}
+
+fn timed_action(start_time: f64, end_time: f64, page_id: Option<&str>) -> ActionEntry {
+    ActionEntry {
+        action_type: "before".to_string(),
+        call_id: "call@1".to_string(),
+        start_time,
+        end_time,
+        title: None,
+        class: Some("Frame".to_string()),
+        method: Some("click".to_string()),
+        params: std::collections::HashMap::new(),
+        page_id: page_id.map(str::to_string),
+        parent_id: None,
+        step_id: None,
+        error: None,
+        log: vec![],
+        frame_id: None,
+        input: None,
+        locator: None,
+        stack: vec![],
+    }
+}
+
+#[test]
+fn test_action_screenshots_before_and_after() {
+    let context = context_with_frames(vec![
+        frame(100.0),
+        frame(900.0),
+        frame(1500.0),
+        frame(2500.0),
+    ]);
+    let action = timed_action(1000.0, 2000.0, Some("page@1"));
+
+    let (before, after) = context.action_screenshots(&action);
+
+    assert_eq!(before.map(|f| f.timestamp), Some(900.0));
+    assert_eq!(after.map(|f| f.timestamp), Some(2500.0));
+}
+
+#[test]
+fn test_action_screenshots_after_falls_back_to_last_frame() {
+    let context = context_with_frames(vec![frame(100.0), frame(1500.0)]);
+    let action = timed_action(1000.0, 2000.0, None);
+
+    let (before, after) = context.action_screenshots(&action);
+
+    assert_eq!(before.map(|f| f.timestamp), Some(100.0));
+    assert_eq!(after.map(|f| f.timestamp), Some(1500.0));
+}
+
+#[test]
+fn test_action_screenshots_other_page_ignored() {
+    let context = context_with_frames(vec![frame(100.0)]);
+    let action = timed_action(1000.0, 2000.0, Some("page@2"));
+
+    let (before, after) = context.action_screenshots(&action);
+
+    assert!(before.is_none());
+    assert!(after.is_none());
+}
+
+#[test]
+fn test_video_chapters_offset_from_first_frame() {
+    let mut context = context_with_frames(vec![frame(1500.0), frame(1000.0)]);
+    let mut failed = timed_action(3000.0, 3500.0, Some("page@1"));
+    failed.title = Some("Click button".to_string());
+    failed.error = Some(SerializedError {
+        message: Some("Timeout".to_string()),
+        stack: None,
+    });
+    context.actions = vec![
+        failed,
+        timed_action(1200.0, 1400.0, Some("page@1")),
+        timed_action(500.0, 600.0, None),
+    ];
+
+    let chapters = context.video_chapters();
+
+    assert_eq!(chapters.len(), 2);
+    assert_eq!(chapters[0].title, "click");
+    assert!((chapters[0].offset_seconds - 0.2).abs() < 1e-9);
+    assert!((chapters[0].duration_seconds - 0.2).abs() < 1e-9);
+    assert!(!chapters[0].failed);
+    assert_eq!(chapters[1].title, "Click button");
+    assert!((chapters[1].offset_seconds - 2.0).abs() < 1e-9);
+    assert!(chapters[1].failed);
+}
+
+#[test]
+fn test_trace_summary() {
+    let mut failing = timed_action(1200.0, 1800.0, Some("page@1"));
+    failing.title = Some("locator.click".to_string());
+    failing.locator = Some("#pay".to_string());
+    failing.error = Some(SerializedError {
+        message: Some("Timeout".to_string()),
+        stack: None,
+    });
+
+    let mut context = context_with_frames(vec![]);
+    context.actions = vec![timed_action(100.0, 900.0, Some("page@1")), failing];
+
+    let mut model = TraceModel::new();
+    model.contexts.push(context);
+
+    let summary = model.summary();
+    assert_eq!(summary.action_count, 2);
+    assert_eq!(summary.duration_ms, 5000.0);
+    assert_eq!(
+        summary.failed_action.as_deref(),
+        Some("locator.click (#pay)")
+    );
+}
+
+#[test]
+fn test_summary_label_without_title() {
+    assert_eq!(timed_action(0.0, 1.0, None).summary_label(), "Frame.click");
+}
+
+#[test]
+fn test_nearest_action_prefers_innermost_running() {
+    let mut context = context_with_frames(vec![]);
+    let mut step = timed_action(100.0, 2000.0, None);
+    step.call_id = "step@1".to_string();
+    let mut click = timed_action(500.0, 800.0, None);
+    click.call_id = "call@2".to_string();
+    let mut crashed = timed_action(3000.0, 0.0, None);
+    crashed.call_id = "call@3".to_string();
+    context.actions = vec![step, click, crashed];
+
+    let nearest = |timestamp| {
+        context
+            .nearest_action(timestamp)
+            .map(|a| a.call_id.as_str())
+    };
+
+    assert_eq!(nearest(600.0), Some("call@2"));
+    assert_eq!(nearest(1500.0), Some("step@1"));
+    assert_eq!(nearest(50.0), Some("step@1"));
+    assert_eq!(nearest(2400.0), Some("step@1"));
+    assert_eq!(nearest(2600.0), Some("call@3"));
+    assert_eq!(nearest(9000.0), Some("call@3"));
+    assert_eq!(context_with_frames(vec![]).nearest_action(10.0), None);
+}
+
+#[test]
+fn test_page_events_from_object_events() {
+    let event = |method: &str, params: serde_json::Value| ObjectEvent {
+        class: "Page".to_string(),
+        method: method.to_string(),
+        params,
+        time: Some(1200.0),
+        page_id: Some("page@1".to_string()),
+    };
+
+    let dialog = PageEvent::from_event(&event(
+        "dialog",
+        serde_json::json!({ "type": "confirm", "message": "Leave page?" }),
+    ))
+    .unwrap();
+    assert_eq!(dialog.label(), "confirm: Leave page?");
+    assert_eq!(dialog.page_id.as_deref(), Some("page@1"));
+
+    let download = PageEvent::from_event(&event(
+        "download",
+        serde_json::json!({ "url": "https://example.com/r.csv", "suggestedFilename": "report.csv" }),
+    ))
+    .unwrap();
+    assert_eq!(download.label(), "Download report.csv");
+
+    let popup = PageEvent::from_event(&event(
+        "popup",
+        serde_json::json!({ "page": { "pageId": "page@2" } }),
+    ))
+    .unwrap();
+    assert_eq!(
+        popup.kind,
+        PageEventKind::Popup {
+            page_id: Some("page@2".to_string())
+        }
+    );
+
+    let crash = PageEvent::from_event(&event("crash", serde_json::json!({}))).unwrap();
+    assert_eq!((crash.time, crash.kind_name()), (1200.0, "crash"));
+
+    assert!(PageEvent::from_event(&event("console", serde_json::json!({}))).is_none());
+}
+
+#[test]
+fn test_failures_during_action() {
+    let request = |url: &str, status: i32, start_time: f64| NetworkEntry {
+        start_time: Some(start_time),
+        request: NetworkRequest {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        },
+        response: NetworkResponse {
+            status,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let output = |stream: StdioStream, timestamp: f64| StdioEntry {
+        stream,
+        timestamp,
+        text: "boom\n".to_string(),
+        call_id: None,
+    };
+
+    let mut context = context_with_frames(vec![]);
+    context.resources = vec![
+        request("https://app.test/api/a", 500, 150.0),
+        request("https://app.test/api/b", 200, 160.0),
+        request("https://app.test/api/c", 404, 900.0),
+    ];
+    context.stdio = vec![
+        output(StdioStream::Stderr, 120.0),
+        output(StdioStream::Stdout, 130.0),
+        output(StdioStream::Stderr, 4000.0),
+    ];
+
+    let action = timed_action(100.0, 200.0, None);
+    assert_eq!(context.action_failed_requests(&action), [0]);
+    assert_eq!(context.action_stderr(&action), [0]);
+
+    // Without an after event the action runs until the trace ends
+    let unfinished = timed_action(100.0, 0.0, None);
+    assert_eq!(context.action_failed_requests(&unfinished), [0, 2]);
+    assert_eq!(context.action_stderr(&unfinished), [0, 2]);
+}
+
+#[test]
+fn test_action_console() {
+    let message = |severity: ConsoleSeverity, timestamp: f64, page_id: &str| ConsoleMessage {
+        severity,
+        text: "boom".to_string(),
+        location: None,
+        timestamp,
+        page_id: Some(page_id.to_string()),
+    };
+
+    let mut context = context_with_frames(vec![]);
+    context.console = vec![
+        message(ConsoleSeverity::Error, 120.0, "page@1"),
+        message(ConsoleSeverity::Log, 130.0, "page@1"),
+        message(ConsoleSeverity::Warning, 140.0, "page@2"),
+        message(ConsoleSeverity::Warning, 150.0, "page@1"),
+        message(ConsoleSeverity::Error, 90.0, "page@1"),
+        message(ConsoleSeverity::Error, 4000.0, "page@1"),
+    ];
+
+    let mut action = timed_action(100.0, 200.0, None);
+    action.page_id = Some("page@1".to_string());
+    assert_eq!(context.action_console(&action), [0, 3]);
+
+    // Actions without a page take messages from every page
+    action.page_id = None;
+    assert_eq!(context.action_console(&action), [0, 2, 3]);
+
+    let mut unfinished = timed_action(100.0, 0.0, None);
+    unfinished.page_id = Some("page@1".to_string());
+    assert_eq!(context.action_console(&unfinished), [0, 3, 5]);
+}
+
+#[test]
+fn test_action_category() {
+    let category = |method: &str| {
+        let mut action = timed_action(0.0, 10.0, None);
+        action.method = Some(method.to_string());
+        action.category()
+    };
+
+    assert_eq!(category("goto"), ActionCategory::Navigation);
+    assert_eq!(category("waitForURL"), ActionCategory::Navigation);
+    assert_eq!(category("dblclick"), ActionCategory::Click);
+    assert_eq!(category("pressSequentially"), ActionCategory::Fill);
+    assert_eq!(category("selectOption"), ActionCategory::Fill);
+    assert_eq!(category("expect"), ActionCategory::Expect);
+    assert_eq!(category("waitForLoadState"), ActionCategory::Wait);
+    assert_eq!(category("evaluate"), ActionCategory::Other);
+}
+
+#[test]
+fn test_action_target() {
+    let mut goto = timed_action(0.0, 10.0, None);
+    goto.params
+        .insert("url".to_string(), serde_json::json!("https://example.com/"));
+    assert_eq!(goto.target(), Some("https://example.com/"));
+
+    let mut click = timed_action(0.0, 10.0, None);
+    click.locator = Some("#pay".to_string());
+    assert_eq!(click.target(), Some("#pay"));
+
+    assert_eq!(timed_action(0.0, 10.0, None).target(), None);
+}
+
+#[test]
+fn test_action_typed_params() {
+    let with_params = |method: &str, params: serde_json::Value| {
+        let mut action = timed_action(0.0, 10.0, None);
+        action.method = Some(method.to_string());
+        action.params = serde_json::from_value(params).unwrap();
+        action.typed_params()
+    };
+
+    assert_eq!(
+        with_params(
+            "dblclick",
+            serde_json::json!({
+                "selector": "#pay",
+                "modifiers": ["Shift"],
+                "position": {"x": 4, "y": 2},
+                "strict": true,
+            })
+        ),
+        Some(ActionParams::Click(ClickParams {
+            selector: "#pay".to_string(),
+            button: None,
+            click_count: None,
+            modifiers: vec!["Shift".to_string()],
+            position: Some(InputPoint { x: 4.0, y: 2.0 }),
+            force: false,
+            trial: false,
+            timeout: None,
+        }))
+    );
+
+    let typed = with_params(
+        "type",
+        serde_json::json!({"selector": "#email", "text": "a@b.c"}),
+    );
+    assert!(matches!(typed, Some(ActionParams::Fill(fill)) if fill.value == "a@b.c"));
+
+    let expect = with_params(
+        "expect",
+        serde_json::json!({
+            "expression": "to.have.text",
+            "isNot": true,
+            "expectedText": [{"regexSource": "^Paid", "regexFlags": "i"}],
+        }),
+    );
+    let Some(ActionParams::Expect(expect)) = expect else {
+        panic!("expected expect params, got {:?}", expect);
+    };
+    assert!(expect.is_not);
+    assert_eq!(expect.selector, None);
+    assert_eq!(
+        expect.expected_text[0].regex_source.as_deref(),
+        Some("^Paid")
+    );
+
+    // Missing required fields and other methods stay untyped
+    assert_eq!(with_params("goto", serde_json::json!({"timeout": 0})), None);
+    assert_eq!(with_params("evaluate", serde_json::json!({})), None);
+
+    let params: std::collections::HashMap<String, serde_json::Value> =
+        serde_json::from_value(serde_json::json!({"url": "/", "waitUntil": "load"})).unwrap();
+    let goto = GotoParams::try_from(&params).unwrap();
+    assert_eq!(goto.wait_until.as_deref(), Some("load"));
+}
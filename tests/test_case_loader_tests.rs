@@ -1,7 +1,12 @@
 use base64::Engine;
 use std::fs;
 use trace_viewer::models::{TestCase, TestStatus};
-use trace_viewer::test_case_loader::{load_test_cases_from_zip, TestCaseLoadError};
+use trace_viewer::test_case_loader::{
+    decode_data_url, extract_image_diffs, is_capture_failed, load_attachments,
+    load_test_cases_from_zip, TestCaseLoadError,
+};
+use trace_viewer::trace_links::{locate, trace_bytes};
+use trace_viewer::trace_loader::load_trace_from_zip;
 
 #[test]
 fn test_load_test_cases_from_valid_zip() {
@@ -82,14 +87,11 @@ fn test_test_case_has_markdown_content() {
 
 #[test]
 fn test_test_case_has_screenshots() {
-    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
-    let test_cases = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
 
     // Find a test case with screenshots
-    let test_with_screenshots = test_cases
-        .test_cases
-        .iter()
-        .find(|tc| !tc.screenshots.is_empty());
+    let test_with_screenshots = test_cases.iter().find(|tc| !tc.screenshots.is_empty());
 
     assert!(
         test_with_screenshots.is_some(),
@@ -123,11 +125,11 @@ fn test_test_case_has_screenshots() {
 
 #[test]
 fn test_test_case_has_video() {
-    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
-    let test_cases = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
 
     // Find a test case with video
-    let test_with_video = test_cases.test_cases.iter().find(|tc| tc.video.is_some());
+    let test_with_video = test_cases.iter().find(|tc| tc.video.is_some());
 
     assert!(
         test_with_video.is_some(),
@@ -158,14 +160,11 @@ fn test_test_case_has_video() {
 
 #[test]
 fn test_test_case_has_trace_file() {
-    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
-    let test_cases = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
 
     // Find a test case with trace file
-    let test_with_trace = test_cases
-        .test_cases
-        .iter()
-        .find(|tc| tc.trace_file.is_some());
+    let test_with_trace = test_cases.iter().find(|tc| tc.trace_file.is_some());
 
     assert!(
         test_with_trace.is_some(),
@@ -190,6 +189,37 @@ fn test_test_case_has_trace_file() {
     );
 }
 
+#[test]
+fn test_test_case_links_trace_contexts() {
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).unwrap();
+
+    let test_case = collection
+        .test_cases
+        .iter()
+        .find(|tc| tc.trace_file.is_some())
+        .expect("no test case with a trace");
+    let link = test_case
+        .trace_contexts
+        .iter()
+        .find(|link| !link.failed_actions.is_empty())
+        .expect("no context with a failed action");
+
+    // The linked action can be found again in the trace it came from
+    let trace = trace_bytes(test_case, &collection.attachment_store).unwrap();
+    let model = load_trace_from_zip(&trace).unwrap();
+    let call_id = &link.failed_actions[0].call_id;
+    let (index, action) = locate(&model, &link.context_id, Some(call_id)).unwrap();
+
+    assert_eq!(model.contexts[index].id, link.context_id);
+    assert!(action.is_some_and(|action| action.error.is_some()));
+    assert!(collection
+        .test_cases
+        .iter()
+        .filter(|tc| tc.trace_file.is_none())
+        .all(|tc| tc.trace_contexts.is_empty()));
+}
+
 #[test]
 fn test_test_case_name_formatting() {
     let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
@@ -328,10 +358,10 @@ fn test_test_case_id_uniqueness() {
 
 #[test]
 fn test_screenshot_data_url_is_valid_base64() {
-    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
-    let test_cases = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
 
-    for test_case in &test_cases.test_cases {
+    for test_case in &test_cases {
         for screenshot in &test_case.screenshots {
             // Extract base64 part from data URL
             if let Some(base64_part) = screenshot.data_url.split("base64,").nth(1) {
@@ -350,10 +380,10 @@ fn test_screenshot_data_url_is_valid_base64() {
 
 #[test]
 fn test_video_data_url_is_valid_base64() {
-    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
-    let test_cases = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
 
-    for test_case in &test_cases.test_cases {
+    for test_case in &test_cases {
         if let Some(video) = &test_case.video {
             // Extract base64 part from data URL
             if let Some(base64_part) = video.data_url.split("base64,").nth(1) {
@@ -435,3 +465,437 @@ fn test_test_status_to_string() {
     assert_eq!(TestStatus::Skipped.to_string(), "skipped");
     assert_eq!(TestStatus::Pending.to_string(), "pending");
 }
+
+#[test]
+fn test_embedded_trace_video_chapters() {
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
+
+    let test_case = test_cases
+        .iter()
+        .find(|tc| tc.trace_file.is_some() && tc.video.is_some())
+        .expect("Expected a test case with a trace and a video");
+
+    let trace_bytes = decode_data_url(&test_case.trace_file.as_ref().unwrap().data_url)
+        .expect("Trace data URL should decode");
+    let model =
+        trace_viewer::trace_loader::load_trace_from_zip(&trace_bytes).expect("Trace should load");
+
+    let chapters: Vec<_> = model
+        .contexts
+        .iter()
+        .flat_map(|context| context.video_chapters())
+        .collect();
+
+    assert!(!chapters.is_empty(), "Expected chapters for page actions");
+    assert!(chapters
+        .iter()
+        .all(|chapter| chapter.offset_seconds >= 0.0 && chapter.duration_seconds >= 0.0));
+
+    // Kept at load time so the card does not parse the trace again
+    let overview = test_case.trace_overview.as_ref().unwrap();
+    assert_eq!(overview.video_chapters, chapters);
+    assert_eq!(overview.summary, model.summary());
+}
+
+#[test]
+fn test_duration_from_embedded_trace() {
+    // Attachments are encoded when a card is expanded
+    let test_cases = load_fixture_with_attachments();
+
+    for test_case in &test_cases {
+        match &test_case.trace_file {
+            Some(trace) => {
+                let trace_bytes = decode_data_url(&trace.data_url).unwrap();
+                let model = trace_viewer::trace_loader::load_trace_from_zip(&trace_bytes)
+                    .expect("Trace should load");
+                assert_eq!(test_case.duration_ms, Some(model.summary().duration_ms));
+            }
+            None => assert_eq!(test_case.duration_ms, None),
+        }
+    }
+}
+
+#[test]
+fn test_decode_data_url_rejects_plain_urls() {
+    assert_eq!(
+        decode_data_url("data:text/plain;base64,aGk="),
+        Some(b"hi".to_vec())
+    );
+    assert_eq!(decode_data_url("https://example.com/trace.zip"), None);
+}
+
+#[test]
+fn test_text_attachments_loaded() {
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        zip.start_file("login-chromium/stdout.txt", FileOptions::default())
+            .unwrap();
+        zip.write_all(b"\x1b[32mok\x1b[0m").unwrap();
+        zip.start_file("login-chromium/browser.log", FileOptions::default())
+            .unwrap();
+        zip.write_all(&[b'l', b'o', b'g', 0xFF]).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let test_cases = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+    let test_case = &test_cases.test_cases[0];
+
+    let names: Vec<_> = test_case
+        .text_attachments
+        .iter()
+        .map(|attachment| attachment.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["browser.log", "stdout.txt"]);
+
+    // Invalid UTF-8 is replaced rather than failing the whole test case
+    assert_eq!(test_case.text_attachments[0].content, "log\u{FFFD}");
+    assert_eq!(test_case.text_attachments[1].content, "\x1b[32mok\x1b[0m");
+    assert_eq!(test_case.text_attachments[1].mime_type, "text/plain");
+}
+
+#[test]
+fn test_json_attachments_loaded() {
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        zip.start_file("api-chromium/response.json", FileOptions::default())
+            .unwrap();
+        zip.write_all(br#"{"status":"ok","items":[1,2]}"#).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let test_cases = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+    let attachment = &test_cases.test_cases[0].text_attachments[0];
+
+    assert_eq!(attachment.name, "response.json");
+    assert_eq!(attachment.mime_type, "application/json");
+    assert!(serde_json::from_str::<serde_json::Value>(&attachment.content).is_ok());
+}
+
+#[test]
+fn test_zero_byte_attachments_marked_as_capture_failed() {
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        zip.start_file(
+            "checkout-chromium/test-failed-1.png",
+            FileOptions::default(),
+        )
+        .unwrap();
+        zip.start_file("checkout-chromium/video.webm", FileOptions::default())
+            .unwrap();
+        zip.write_all(b"<html>not a video</html>").unwrap();
+        zip.finish().unwrap();
+    }
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+    let test_case = &collection.test_cases[0];
+
+    assert!(test_case.screenshots[0].capture_failed);
+    assert_eq!(test_case.screenshots[0].size_bytes, Some(0));
+    assert!(test_case.video.as_ref().unwrap().capture_failed);
+
+    assert_eq!(collection.warnings.len(), 2);
+    assert!(collection.warnings[0].contains("test-failed-1.png"));
+}
+
+#[test]
+fn test_fixture_attachments_are_not_capture_failed() {
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+
+    assert!(collection.warnings.is_empty());
+}
+
+#[test]
+fn test_is_capture_failed() {
+    assert!(is_capture_failed("image/png", &[]));
+    assert!(is_capture_failed("image/png", b"GIF89a"));
+    assert!(!is_capture_failed("image/png", b"\x89PNG\r\n\x1a\n"));
+    assert!(!is_capture_failed("image/jpeg", &[0xFF, 0xD8, 0xFF, 0xE0]));
+    assert!(!is_capture_failed("video/mp4", b"\x00\x00\x00\x20ftypisom"));
+    assert!(!is_capture_failed("application/zip", b"PK"));
+}
+
+#[test]
+fn test_retry_folders_grouped() {
+    use trace_viewer::analysis::group_retries;
+
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+
+    let groups = group_retries(&collection.test_cases);
+    assert_eq!(collection.test_cases.len(), 9);
+    assert_eq!(groups.len(), 3);
+
+    for group in &groups {
+        assert_eq!(group.attempts.len(), 3);
+        assert_eq!(group.attempts[0].id, group.test_id);
+        assert!(group.final_attempt().id.ends_with("-retry2"));
+    }
+}
+
+#[test]
+fn test_spec_file_from_folder_name() {
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+
+    for test_case in &collection.test_cases {
+        assert_eq!(test_case.suite_path, vec!["counter"]);
+    }
+}
+
+#[test]
+fn test_project_from_folder_name() {
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+
+    // Retry suffixes come after the project
+    for test_case in &collection.test_cases {
+        assert_eq!(test_case.project.as_deref(), Some("chromium"));
+    }
+
+    let buf = build_zip(&[
+        ("login-Mobile-Chrome/stdout.txt", b"out"),
+        ("login-custom/stdout.txt", b"out"),
+    ]);
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+
+    assert_eq!(
+        collection.test_cases[0].project.as_deref(),
+        Some("Mobile-Chrome")
+    );
+    assert_eq!(collection.test_cases[1].project, None);
+}
+
+#[test]
+fn test_screenshot_comparison_detected() {
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        for name in [
+            "home-chromium/homepage-actual.png",
+            "home-chromium/homepage-diff.png",
+            "home-chromium/homepage-expected.png",
+            "home-chromium/test-failed-1.png",
+        ] {
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(b"\x89PNG\r\n\x1a\n").unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+    let test_case = &collection.test_cases[0];
+
+    assert_eq!(test_case.screenshots.len(), 1);
+    assert_eq!(test_case.screenshots[0].name, "test-failed-1.png");
+
+    assert_eq!(test_case.image_diffs.len(), 1);
+    let diff = &test_case.image_diffs[0];
+    assert_eq!(diff.name, "homepage");
+    assert_eq!(
+        diff.expected.as_ref().unwrap().name,
+        "homepage-expected.png"
+    );
+    assert_eq!(diff.actual.as_ref().unwrap().name, "homepage-actual.png");
+    assert_eq!(diff.diff.as_ref().unwrap().name, "homepage-diff.png");
+}
+
+#[test]
+fn test_lone_actual_screenshot_is_not_a_comparison() {
+    let attachment = |name: &str| trace_viewer::models::TestAttachment {
+        name: name.to_string(),
+        mime_type: "image/png".to_string(),
+        data_url: "data:image/png;base64,AAAA".to_string(),
+        size_bytes: Some(3),
+        capture_failed: false,
+        archive_entry: None,
+    };
+    let mut screenshots = vec![
+        attachment("missing-baseline-actual.png"),
+        attachment("diff.png"),
+    ];
+
+    let diffs = extract_image_diffs(&mut screenshots);
+
+    assert!(diffs.is_empty());
+    assert_eq!(screenshots.len(), 2);
+}
+
+#[test]
+fn test_attachments_indexed_not_encoded() {
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+
+    for test_case in &collection.test_cases {
+        for attachment in test_case.screenshots.iter().chain(&test_case.video) {
+            let entry = attachment.archive_entry.as_deref().unwrap();
+            assert!(attachment.data_url.is_empty());
+            assert!(entry.starts_with(&format!("{}/", test_case.id)));
+            assert_eq!(
+                collection.attachment_store.size(entry),
+                attachment.size_bytes
+            );
+        }
+    }
+}
+
+#[test]
+fn test_large_video_stays_in_archive() {
+    use trace_viewer::test_case_loader::MAX_INLINE_VIDEO_BYTES;
+
+    let large = vec![0u8; MAX_INLINE_VIDEO_BYTES + 1];
+    let buf = build_zip(&[
+        ("large-chromium/video.webm", &large),
+        ("small-chromium/video.webm", b"\x1a\x45\xdf\xa3"),
+    ]);
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+
+    let video = collection.test_cases[0].video.as_ref().unwrap();
+    assert!(video.is_deferred());
+    assert_eq!(video.size_bytes, Some(MAX_INLINE_VIDEO_BYTES + 1));
+    assert_eq!(
+        collection
+            .attachment_store
+            .size("large-chromium/video.webm"),
+        Some(MAX_INLINE_VIDEO_BYTES + 1)
+    );
+
+    // Still deferred once the card is expanded
+    let loaded = load_attachments(&collection.test_cases[0], &collection.attachment_store);
+    assert!(loaded.video.unwrap().is_deferred());
+
+    let loaded = load_attachments(&collection.test_cases[1], &collection.attachment_store);
+    let small = loaded.video.as_ref().unwrap();
+    assert!(!small.is_deferred());
+    assert!(small.data_url.starts_with("data:video/webm;base64,"));
+}
+
+/// Fixture test cases with their attachments read from the archive
+fn load_fixture_with_attachments() -> Vec<TestCase> {
+    let bytes = fs::read("tests/fixtures/test-cases.zip").expect("Failed to read test file");
+    let collection = load_test_cases_from_zip(&bytes).expect("Failed to load test cases");
+
+    collection
+        .test_cases
+        .iter()
+        .map(|test_case| load_attachments(test_case, &collection.attachment_store))
+        .collect()
+}
+
+fn build_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut buf));
+        for (name, content) in files {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+    buf
+}
+
+#[test]
+fn test_status_from_results_json() {
+    let report = fs::read("tests/fixtures/results.json").expect("Failed to read test file");
+    let buf = build_zip(&[
+        ("results.json", &report),
+        ("login-chromium/video.webm", b"\x1a\x45\xdf\xa3"),
+        ("unrelated-chromium/video.webm", b"\x1a\x45\xdf\xa3"),
+    ]);
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+
+    // The report is not a test folder
+    assert_eq!(collection.test_cases.len(), 2);
+    assert_eq!(collection.test_cases[0].id, "login-chromium");
+    assert_eq!(collection.test_cases[0].status, TestStatus::Failed);
+    assert_eq!(collection.test_cases[0].duration_ms, Some(1520.0));
+    assert_eq!(collection.test_cases[1].status, TestStatus::Passed);
+    assert_eq!(collection.test_cases[1].duration_ms, None);
+}
+
+#[test]
+fn test_retried_attempts_failed() {
+    let buf = build_zip(&[
+        ("checkout-chromium/stdout.txt", b"attempt 1"),
+        ("checkout-chromium-retry1/stdout.txt", b"attempt 2"),
+    ]);
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+
+    assert_eq!(collection.test_cases[0].status, TestStatus::Failed);
+    assert_eq!(collection.test_cases[1].status, TestStatus::Passed);
+}
+
+#[test]
+fn test_clean_trace_overrides_folder_name() {
+    let events = [
+        r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        r#"{"type":"before","callId":"call@1","startTime":100,"class":"Page","method":"goto"}"#,
+        r#"{"type":"after","callId":"call@1","endTime":350}"#,
+    ]
+    .join("\n");
+    let trace = build_zip(&[("trace.trace", events.as_bytes())]);
+    let buf = build_zip(&[("shows-error-page-chromium/trace.zip", &trace)]);
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+
+    // "error" in the folder name would otherwise read as a failure
+    assert_eq!(collection.test_cases[0].status, TestStatus::Passed);
+    assert_eq!(collection.test_cases[0].duration_ms, Some(250.0));
+}
+
+#[test]
+fn test_page_errors_do_not_fail_test() {
+    let trace_with = |extra: &str| {
+        let events = [
+            r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+            r#"{"type":"before","callId":"call@1","startTime":100,"class":"Page","method":"goto"}"#,
+            r#"{"type":"after","callId":"call@1","endTime":350}"#,
+            extra,
+        ]
+        .join("\n");
+        build_zip(&[("trace.trace", events.as_bytes())])
+    };
+    let page_error = trace_with(
+        r#"{"type":"event","time":200,"class":"BrowserContext","method":"pageError","params":{"error":{"value":"boom"}}}"#,
+    );
+    let runner_error =
+        trace_with(r#"{"type":"error","message":"Error: expect(received).toBe(expected)"}"#);
+    let buf = build_zip(&[
+        ("checkout-chromium/trace.zip", &page_error),
+        ("login-chromium/trace.zip", &runner_error),
+    ]);
+
+    let collection = load_test_cases_from_zip(&buf).expect("Failed to load test cases");
+
+    assert_eq!(collection.test_cases[0].id, "checkout-chromium");
+    assert_eq!(collection.test_cases[0].status, TestStatus::Passed);
+    assert_eq!(collection.test_cases[1].status, TestStatus::Failed);
+}
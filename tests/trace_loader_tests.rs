@@ -164,6 +164,82 @@ fn test_load_zip_without_trace_file() {
     assert!(matches!(result.unwrap_err(), LoadError::MissingTraceFile));
 }
 
+#[test]
+fn test_trace_action_stack() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    let navigate = model
+        .contexts
+        .iter()
+        .flat_map(|c| &c.actions)
+        .find(|a| a.title.as_deref() == Some("Navigate to \"/\""))
+        .expect("navigation step not found");
+
+    let frame = navigate.user_frame().expect("navigation step has no stack");
+    assert!(frame.file.ends_with("tests/pointer-tracking.spec.js"));
+    assert_eq!((frame.line, frame.column), (5, 16));
+}
+
+#[test]
+fn test_trace_console_messages() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    let messages: Vec<&ConsoleMessage> = model.contexts.iter().flat_map(|c| &c.console).collect();
+
+    assert_eq!(messages.len(), 2);
+    assert!(messages
+        .iter()
+        .all(|message| message.severity == ConsoleSeverity::Log && message.page_id.is_some()));
+    assert_eq!(
+        messages[1].text,
+        "Boid simulation initialized successfully!"
+    );
+    assert_eq!(
+        messages[1]
+            .location
+            .as_ref()
+            .map(ConsoleLocation::label)
+            .as_deref(),
+        Some("http://localhost:8080/index.js:33:16")
+    );
+}
+
+#[test]
+fn test_trace_page_metadata() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    let page = model
+        .contexts
+        .iter()
+        .flat_map(|c| &c.pages)
+        .find(|page| page.title.is_some())
+        .expect("no page with a title");
+
+    assert_eq!(page.title.as_deref(), Some("Boid Simulation - Rust + WASM"));
+    assert_eq!(page.url(), Some("http://localhost:8080/"));
+    assert_eq!(
+        page.viewport,
+        Some(Viewport {
+            width: 1280,
+            height: 720
+        })
+    );
+}
+
+#[test]
+fn test_trace_context_ids() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    let mut ids: Vec<&str> = model.contexts.iter().map(|c| c.id.as_str()).collect();
+    ids.sort();
+
+    assert_eq!(ids, ["0-trace", "test"]);
+}
+
 #[test]
 fn test_action_timing() {
     let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
@@ -284,6 +360,12 @@ fn test_load_report_archive() {
         expected_context_count,
         model.contexts.len()
     );
+
+    // Contexts of the two copies stay distinguishable
+    assert!(model
+        .contexts
+        .iter()
+        .any(|context| context.id == "data/trace2.zip/0-trace"));
 }
 
 #[test]
@@ -356,3 +438,529 @@ fn test_backward_compatibility_single_trace() {
     assert!(result.is_ok(), "Regular trace archive should still work");
     assert!(!result.unwrap().contexts.is_empty());
 }
+
+#[test]
+fn test_screencast_frames_in_resource_store() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    let frames: Vec<_> = model
+        .contexts
+        .iter()
+        .flat_map(|c| &c.pages)
+        .flat_map(|p| &p.screencast_frames)
+        .collect();
+
+    assert!(!frames.is_empty(), "No screencast frames parsed");
+
+    for frame in frames {
+        let image = model
+            .resource_store
+            .get(&frame.sha1)
+            .unwrap_or_else(|| panic!("Missing image for frame {}", frame.sha1));
+
+        // JPEG start-of-image marker
+        assert_eq!(&image[..2], &[0xFF, 0xD8]);
+        assert!(frame.frame_swap_wall_time.is_some());
+    }
+}
+
+#[test]
+fn test_resource_store_indexes_all_resources() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    // 6 screencast frames, an html page, a wasm module, a source file and one more
+    assert_eq!(model.resource_store.len(), 10);
+
+    let html = model
+        .resource_store
+        .get("64ca5888468d64ea40c83e18f608826c0760fc2b.html")
+        .expect("html resource");
+    assert_eq!(html.len(), 6364);
+}
+
+#[test]
+fn test_load_trace_from_unzipped_folder() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&trace_bytes[..])).unwrap();
+    let files: Vec<(String, Vec<u8>)> = (0..archive.len())
+        .map(|i| {
+            let mut file = archive.by_index(i).unwrap();
+            let mut bytes = Vec::new();
+            std::io::Read::read_to_end(&mut file, &mut bytes).unwrap();
+            (file.name().to_string(), bytes)
+        })
+        .filter(|(name, _)| !name.ends_with('/'))
+        .collect();
+
+    let from_folder = load_trace_from_files(files).unwrap();
+    let from_zip = load_trace_from_zip(trace_bytes).unwrap();
+
+    assert_eq!(from_folder.contexts, from_zip.contexts);
+    assert_eq!(from_folder.resource_store.len(), 10);
+    let html = from_folder
+        .resource_store
+        .get("64ca5888468d64ea40c83e18f608826c0760fc2b.html")
+        .expect("html resource");
+    assert_eq!(html.len(), 6364);
+
+    assert!(matches!(
+        load_trace_from_files(vec![("notes.txt".to_string(), vec![])]),
+        Err(LoadError::MissingTraceFile)
+    ));
+}
+
+#[test]
+fn test_network_resources_parsed() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    let resources: Vec<_> = model.contexts.iter().flat_map(|c| &c.resources).collect();
+    assert!(!resources.is_empty(), "No network resources parsed");
+
+    let document = resources
+        .iter()
+        .find(|r| r.url() == "http://localhost:8080/")
+        .expect("document request");
+
+    assert_eq!(document.method(), "GET");
+    assert_eq!(document.status(), Some(200));
+    assert_eq!(document.content_type(), Some("text/html"));
+    assert_eq!(document.body_size(), Some(6364));
+    assert_eq!(document.response.transfer_size, Some(6551));
+    assert!(document.duration.is_some_and(|ms| ms > 6.0 && ms < 6.2));
+    assert_eq!(
+        document.page_id.as_deref(),
+        Some("page@a01117c6a650c8b130d870b86c288b8a")
+    );
+    assert_eq!(document.request_header("host"), Some("localhost:8080"));
+    assert_eq!(document.response.status_text, "OK");
+    assert_eq!(document.timings.wait, Some(2.073));
+
+    // Response bodies are available from the resource store
+    let sha1 = document.body_sha1().expect("body sha1");
+    assert_eq!(model.resource_store.get(sha1).unwrap().len(), 6364);
+}
+
+#[test]
+fn test_detect_workspace_root() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    let model = load_trace_from_zip(trace_bytes).unwrap();
+
+    // Launch parameters point into the CI checkout
+    let root = trace_viewer::paths::detect_workspace_root(&model);
+    assert_eq!(
+        root.as_deref(),
+        Some("/home/runner/work/boid-rs/boid-rs/boid-wasm/www/")
+    );
+}
+
+#[test]
+fn test_report_archive_selection() {
+    let sample_trace = include_bytes!("fixtures/sample-trace.zip");
+
+    let mut report_buf = Vec::new();
+    {
+        let mut report_zip = ZipWriter::new(std::io::Cursor::new(&mut report_buf));
+
+        for name in ["data/chromium.zip", "data/firefox.zip", "data/webkit.zip"] {
+            report_zip.start_file(name, FileOptions::default()).unwrap();
+            report_zip.write_all(sample_trace).unwrap();
+        }
+
+        report_zip.finish().unwrap();
+    }
+
+    let archives = list_report_archives(&report_buf)
+        .unwrap()
+        .expect("Expected a report archive");
+    let names: Vec<_> = archives.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["data/chromium.zip", "data/firefox.zip", "data/webkit.zip"]
+    );
+    assert!(archives.iter().all(|a| a.size == sample_trace.len() as u64));
+
+    let model =
+        load_report_archive_selection(&report_buf, &["data/firefox.zip".to_string()]).unwrap();
+    let single_trace_model = load_trace_from_zip(sample_trace).unwrap();
+    assert_eq!(model.contexts.len(), single_trace_model.contexts.len());
+
+    let result = load_report_archive_selection(&report_buf, &[]);
+    assert!(matches!(result, Err(LoadError::MissingTraceFile)));
+}
+
+#[test]
+fn test_list_report_archives_plain_trace() {
+    let trace_bytes = include_bytes!("fixtures/sample-trace.zip");
+    assert_eq!(list_report_archives(trace_bytes).unwrap(), None);
+}
+
+#[test]
+fn test_malformed_lines_collected_as_warnings() {
+    use std::io::Write;
+
+    let trace = [
+        r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        r#"{"type":"before","callId":"call@1","startTime":10,"apiName":"page.goto","class":"Frame","method":"goto","params":{}}"#,
+        r#"{"type":"after","callId":"call@1","endTime":"#,
+        "",
+        r#"{"type":"before","callId":"call@2","startTime":"soon"}"#,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.start_file("0-trace.network", FileOptions::default())
+            .unwrap();
+        zip.write_all(b"not json").unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).expect("Malformed lines should not fail the load");
+
+    assert_eq!(model.contexts[0].actions.len(), 1);
+    let lines: Vec<(&str, usize)> = model
+        .parse_warnings
+        .iter()
+        .map(|warning| (warning.file.as_str(), warning.line))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            ("0-trace.trace", 3),
+            ("0-trace.trace", 5),
+            ("0-trace.network", 1)
+        ]
+    );
+    assert_eq!(model.parse_warnings[2].snippet, "not json");
+    assert!(!model.parse_warnings[0].error.is_empty());
+}
+
+#[test]
+fn test_error_events_become_context_errors() {
+    use std::io::Write;
+
+    let trace = [
+        r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        r#"{"type":"event","time":12,"class":"BrowserContext","method":"pageError","params":{"error":{"error":{"name":"TypeError","message":"x is undefined","stack":"TypeError: x is undefined\n    at app.js:3:7"}}}}"#,
+        r#"{"type":"event","time":13,"class":"BrowserContext","method":"pageError","params":{"error":{"value":"boom"}}}"#,
+        r#"{"type":"event","time":14,"class":"BrowserContext","method":"page","params":{"pageId":"page@1"}}"#,
+        r#"{"type":"error","message":"Error: expect(received).toBe(expected)","stack":[{"file":"/tests/login.spec.ts","line":12,"column":5,"function":"checkLogin"},{"file":"/tests/login.spec.ts","line":20,"column":3}]}"#,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+    assert!(model.parse_warnings.is_empty());
+
+    let errors = &model.contexts[0].errors;
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0].message, "x is undefined");
+    assert!(errors[0].stack.as_deref().unwrap().contains("app.js:3:7"));
+    assert_eq!(errors[1].message, "boom");
+    assert_eq!(errors[1].stack, None);
+    assert!(errors[0].page_error && errors[1].page_error);
+    assert_eq!(errors[2].message, "Error: expect(received).toBe(expected)");
+    assert!(!errors[2].page_error);
+    assert_eq!(
+        errors[2].stack.as_deref(),
+        Some("    at checkLogin (/tests/login.spec.ts:12:5)\n    at /tests/login.spec.ts:20:3")
+    );
+}
+
+#[test]
+fn test_trace_chunks_merged_into_one_context() {
+    use std::io::Write;
+
+    let files = [
+        (
+            "trace.trace",
+            [
+                r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+                r#"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","params":{}}"#,
+            ]
+            .join("\n"),
+        ),
+        (
+            "trace-1.trace",
+            [
+                r#"{"type":"after","callId":"call@1","endTime":40}"#,
+                r#"{"type":"before","callId":"call@2","startTime":50,"class":"Frame","method":"click","params":{}}"#,
+                r#"{"type":"after","callId":"call@2","endTime":70}"#,
+            ]
+            .join("\n"),
+        ),
+        (
+            "trace-1.network",
+            r#"{"type":"resource-snapshot","snapshot":{"request":{"url":"https://example.com/api","method":"GET"},"response":{"status":200,"content":{"size":2,"mimeType":"application/json"}},"_monotonicTime":55}}"#
+                .to_string(),
+        ),
+    ];
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        // Later chunk first, as archive order is not guaranteed
+        for (name, content) in files.iter().rev() {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+
+    assert_eq!(model.contexts.len(), 1);
+    let context = &model.contexts[0];
+    assert_eq!(context.browser_name, "chromium");
+    assert_eq!(context.actions.len(), 2);
+    assert_eq!(context.actions[0].end_time, 40.0);
+    assert_eq!(context.actions[1].end_time, 70.0);
+    assert_eq!(context.resources.len(), 1);
+}
+
+#[test]
+fn test_unsupported_trace_version() {
+    use std::io::Write;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(
+            br#"{"type":"context-options","version":42,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        )
+        .unwrap();
+        zip.finish().unwrap();
+    }
+
+    let error = load_trace_from_zip(&buf).unwrap_err();
+    assert!(matches!(error, LoadError::UnsupportedVersion(42)));
+    assert!(error.to_string().contains("Unsupported trace version 42"));
+}
+
+#[test]
+fn test_stdio_attributed_to_nearest_action() {
+    use std::io::Write;
+
+    let trace = [
+        r#"{"type":"context-options","version":8,"origin":"testRunner","browserName":"","wallTime":0,"monotonicTime":0}"#,
+        r#"{"type":"before","callId":"call@1","startTime":10,"class":"Test","method":"step","title":"Login","params":{}}"#,
+        r#"{"type":"stdout","timestamp":15,"text":"\u001b[32mlogged in\u001b[39m\n"}"#,
+        r#"{"type":"after","callId":"call@1","endTime":20}"#,
+        r#"{"type":"before","callId":"call@2","startTime":30,"class":"Test","method":"step","title":"Checkout","params":{}}"#,
+        r#"{"type":"after","callId":"call@2","endTime":40}"#,
+        r#"{"type":"stderr","timestamp":42,"base64":"d2FybmluZwo="}"#,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("test.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+    let stdio = &model.contexts[0].stdio;
+
+    assert_eq!(stdio.len(), 2);
+    assert_eq!(stdio[0].stream, StdioStream::Stdout);
+    assert_eq!(stdio[0].text, "\u{1b}[32mlogged in\u{1b}[39m\n");
+    assert_eq!(stdio[0].call_id.as_deref(), Some("call@1"));
+    assert_eq!(stdio[1].stream, StdioStream::Stderr);
+    assert_eq!(stdio[1].text, "warning\n");
+    assert_eq!(stdio[1].call_id.as_deref(), Some("call@2"));
+}
+
+#[test]
+fn test_frame_tree_and_action_frames() {
+    use std::io::Write;
+
+    let trace = [
+        r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        r#"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","params":{},"pageId":"page@1"}"#,
+        r#"{"type":"frame-snapshot","snapshot":{"callId":"call@1","snapshotName":"before@call@1","pageId":"page@1","frameId":"frame@main","frameUrl":"about:blank","html":[],"isMainFrame":true}}"#,
+        r#"{"type":"after","callId":"call@1","endTime":20}"#,
+        r#"{"type":"frame-snapshot","snapshot":{"callId":"call@1","snapshotName":"after@call@1","pageId":"page@1","frameId":"frame@main","frameUrl":"https://shop.test/","html":[],"isMainFrame":true}}"#,
+        r#"{"type":"event","time":25,"class":"Page","method":"frameAttached","pageId":"page@1","params":{"frameId":"frame@pay","parentFrameId":"frame@main"}}"#,
+        r#"{"type":"event","time":26,"class":"Page","method":"navigated","pageId":"page@1","params":{"frameId":"frame@pay","url":"https://pay.test/form"}}"#,
+        r#"{"type":"before","callId":"call@2","startTime":30,"class":"Frame","method":"fill","params":{},"pageId":"page@1"}"#,
+        r#"{"type":"frame-snapshot","snapshot":{"callId":"call@2","snapshotName":"before@call@2","pageId":"page@1","frameId":"frame@pay","frameUrl":"https://pay.test/form","html":[],"isMainFrame":false}}"#,
+        r#"{"type":"after","callId":"call@2","endTime":40}"#,
+        r#"{"type":"event","time":45,"class":"Page","method":"frameDetached","pageId":"page@1","params":{"frameId":"frame@pay"}}"#,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+    let context = &model.contexts[0];
+
+    let page = &context.pages[0];
+    assert_eq!(page.frames.len(), 2);
+    let main = page.frame("frame@main").unwrap();
+    assert!(main.is_main_frame);
+    assert_eq!(main.url.as_deref(), Some("https://shop.test/"));
+
+    let goto = &context.actions[0];
+    assert_eq!(goto.frame_id.as_deref(), Some("frame@main"));
+    assert_eq!(context.action_frame_path(goto).len(), 1);
+
+    let fill = &context.actions[1];
+    let path: Vec<&str> = context
+        .action_frame_path(fill)
+        .iter()
+        .map(|frame| frame.frame_id.as_str())
+        .collect();
+    assert_eq!(path, ["frame@main", "frame@pay"]);
+    assert!(page.frame("frame@pay").unwrap().detached);
+}
+
+#[test]
+fn test_pages_in_order_opened_with_urls() {
+    use std::io::Write;
+
+    let trace = [
+        r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        r#"{"type":"event","time":1,"class":"BrowserContext","method":"page","params":{"pageId":"page@b"}}"#,
+        r#"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","params":{},"pageId":"page@b"}"#,
+        r#"{"type":"frame-snapshot","snapshot":{"callId":"call@1","pageId":"page@b","frameId":"frame@1","frameUrl":"https://shop.test/","isMainFrame":true}}"#,
+        r#"{"type":"after","callId":"call@1","endTime":20}"#,
+        r#"{"type":"event","time":30,"class":"BrowserContext","method":"page","params":{"pageId":"page@a"}}"#,
+        r#"{"type":"screencast-frame","pageId":"page@a","sha1":"f1.jpeg","width":800,"height":600,"timestamp":35}"#,
+        r#"{"type":"screencast-frame","pageId":"page@b","sha1":"f2.jpeg","width":800,"height":600,"timestamp":36}"#,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+    let pages = &model.contexts[0].pages;
+
+    let ids: Vec<&str> = pages.iter().map(|page| page.page_id.as_str()).collect();
+    assert_eq!(ids, ["page@b", "page@a"]);
+    assert_eq!(pages[0].url(), Some("https://shop.test/"));
+    assert_eq!(pages[1].url(), None);
+    assert_eq!(pages[1].screencast_frames.len(), 1);
+}
+
+#[test]
+fn test_input_events_attached_to_actions() {
+    use std::io::Write;
+
+    let trace = [
+        r#"{"type":"context-options","version":8,"browserName":"chromium","wallTime":0,"monotonicTime":0}"#,
+        r##"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"click","params":{"selector":"#buy","modifiers":["Shift"],"clickCount":2}}"##,
+        r#"{"type":"input","callId":"call@1","inputSnapshot":"input@call@1","point":{"x":120.5,"y":48}}"#,
+        r#"{"type":"after","callId":"call@1","endTime":20}"#,
+        r##"{"type":"before","callId":"call@2","startTime":30,"class":"Frame","method":"press","params":{"selector":"#q","key":"Enter"}}"##,
+        r#"{"type":"after","callId":"call@2","endTime":40}"#,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+    let context = &model.contexts[0];
+
+    let click = context.actions[0].input_detail().unwrap();
+    assert_eq!(click.point, Some(InputPoint { x: 120.5, y: 48.0 }));
+    assert_eq!(click.snapshot.as_deref(), Some("input@call@1"));
+    assert_eq!(click.modifiers, ["Shift"]);
+    assert_eq!(click.click_count, Some(2));
+    assert_eq!(click.keys, None);
+
+    let press = context.actions[1].input_detail().unwrap();
+    assert_eq!(press.keys.as_deref(), Some("Enter"));
+    assert_eq!(press.point, None);
+}
+
+#[test]
+fn test_locator_parsed_from_params() {
+    use std::io::Write;
+    use zip::write::{FileOptions, ZipWriter};
+
+    let trace = [
+        r##"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","params":{"url":"https://example.com"}}"##,
+        r##"{"type":"before","callId":"call@2","startTime":20,"class":"Frame","method":"click","params":{"selector":"internal:role=button[name=\"Pay\"i]"}}"##,
+    ]
+    .join("\n");
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        zip.start_file("0-trace.trace", FileOptions::default())
+            .unwrap();
+        zip.write_all(trace.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    let model = load_trace_from_zip(&buf).unwrap();
+    let actions = &model.contexts[0].actions;
+
+    assert_eq!(actions[0].locator, None);
+    assert_eq!(
+        actions[1].locator.as_deref(),
+        Some(r#"internal:role=button[name="Pay"i]"#)
+    );
+}
+
+#[test]
+fn test_load_raw_trace_files() {
+    let trace = r#"{"type":"before","callId":"call@1","startTime":10,"class":"Frame","method":"goto","params":{"url":"https://example.com"}}"#;
+    let network = r#"{"type":"resource-snapshot","snapshot":{"request":{"url":"https://example.com/","method":"GET"},"response":{"status":200,"content":{"mimeType":"text/html"}}}}"#;
+
+    assert!(is_raw_trace_file("0-trace.trace"));
+    assert!(is_raw_trace_file("0-trace.network"));
+    assert!(!is_raw_trace_file("trace.zip"));
+
+    let model = load_trace_from_files(vec![
+        ("0-trace.trace".to_string(), trace.as_bytes().to_vec()),
+        ("0-trace.network".to_string(), network.as_bytes().to_vec()),
+    ])
+    .unwrap();
+
+    assert_eq!(model.contexts.len(), 1);
+    assert_eq!(model.contexts[0].actions[0].call_id, "call@1");
+    assert_eq!(model.contexts[0].resources[0].url(), "https://example.com/");
+    assert!(model.resource_store.is_empty());
+}